@@ -5,7 +5,8 @@ use crate::api::egl::{
 };
 use crate::CreationError::{self, OsError};
 use crate::{
-    Api, ContextError, GlAttributes, PixelFormat, PixelFormatRequirements, Rect,
+    Api, ContextError, GlAttributes, PixelFormat, PixelFormatRequirements,
+    Rect, StateMask,
 };
 
 use crate::platform::android::EventLoopExtAndroid;
@@ -140,6 +141,58 @@ impl Context {
         self.0.egl_context.make_current()
     }
 
+    #[inline]
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        if let Some(ref stopped) = self.0.stopped {
+            let stopped = stopped.lock();
+            if *stopped {
+                return Err(ContextError::ContextLost);
+            }
+        }
+
+        self.0
+            .egl_context
+            .make_current_draw_read(&draw.0.egl_context, &read.0.egl_context)
+    }
+
+    #[inline]
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        self.0.egl_context.bind_tex_image()
+    }
+
+    #[inline]
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        self.0.egl_context.release_tex_image()
+    }
+
+    #[inline]
+    pub fn copy_state_from(
+        &self,
+        _src: &Context,
+        _mask: StateMask,
+    ) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        self.0.egl_context.pbuffer_size()
+    }
+
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        self.0.egl_context.wait_gl()
+    }
+
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        self.0.egl_context.wait_native()
+    }
+
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
         if let Some(ref stopped) = self.0.stopped {
@@ -176,6 +229,40 @@ impl Context {
         self.0.egl_context.swap_buffers()
     }
 
+    #[inline]
+    pub fn swap_buffers_with_frame_id(
+        &self,
+    ) -> Result<crate::FrameId, ContextError> {
+        if let Some(ref stopped) = self.0.stopped {
+            let stopped = stopped.lock();
+            if *stopped {
+                return Err(ContextError::ContextLost);
+            }
+        }
+        self.0.egl_context.swap_buffers_with_frame_id()
+    }
+
+    #[inline]
+    pub fn presentation_times(
+        &self,
+        frame: crate::FrameId,
+    ) -> Result<crate::PresentationTimes, ContextError> {
+        self.0.egl_context.presentation_times(frame)
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        self.0.egl_context.buffer_age()
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        self.0.egl_context.set_damage_region(rects)
+    }
+
     #[inline]
     pub fn swap_buffers_with_damage(
         &self,
@@ -191,8 +278,29 @@ impl Context {
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
-        self.0.egl_context.swap_buffers_with_damage_supported()
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        self.0.egl_context.swap_damage_support()
+    }
+
+    #[inline]
+    pub fn set_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        self.0.egl_context.set_swap_interval(interval)
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> bool {
+        self.0.egl_context.supports_swap_interval(interval)
+    }
+
+    #[inline]
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
+        self.0.egl_context.get_swap_interval()
     }
 
     #[inline]
@@ -200,6 +308,26 @@ impl Context {
         self.0.egl_context.get_api()
     }
 
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        self.0.egl_context.priority()
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        self.0.egl_context.release_behavior()
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        self.0.egl_context.color_space()
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> Result<&crate::Extensions, ContextError> {
+        Ok(self.0.egl_context.extensions())
+    }
+
     #[inline]
     pub fn get_pixel_format(&self) -> PixelFormat {
         self.0.egl_context.get_pixel_format()
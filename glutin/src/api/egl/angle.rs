@@ -0,0 +1,280 @@
+//! Selection of the EGL library and of ANGLE's renderer on Windows.
+
+use super::EGL;
+use crate::CreationError;
+use glutin_egl_sys as ffi;
+use parking_lot::Mutex;
+use winapi::shared::ntdef::LUID;
+
+use std::os::raw;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const PLATFORM_ANGLE_ANGLE: ffi::egl::types::EGLenum = 0x3202;
+const PLATFORM_ANGLE_TYPE_ANGLE: raw::c_int = 0x3203;
+const PLATFORM_ANGLE_TYPE_DEFAULT_ANGLE: raw::c_int = 0x3206;
+const PLATFORM_ANGLE_TYPE_D3D9_ANGLE: raw::c_int = 0x3207;
+const PLATFORM_ANGLE_TYPE_D3D11_ANGLE: raw::c_int = 0x3208;
+const PLATFORM_ANGLE_TYPE_OPENGL_ANGLE: raw::c_int = 0x320D;
+const PLATFORM_ANGLE_TYPE_OPENGLES_ANGLE: raw::c_int = 0x320E;
+const PLATFORM_ANGLE_TYPE_VULKAN_ANGLE: raw::c_int = 0x3450;
+const PLATFORM_ANGLE_TYPE_METAL_ANGLE: raw::c_int = 0x3489;
+const PLATFORM_ANGLE_D3D_LUID_HIGH_ANGLE: raw::c_int = 0x34A0;
+const PLATFORM_ANGLE_D3D_LUID_LOW_ANGLE: raw::c_int = 0x34A1;
+
+lazy_static! {
+    static ref CONFIG: Mutex<EglDisplayBuilder> =
+        Mutex::new(EglDisplayBuilder::new());
+}
+
+// Whether the EGL library was loaded, after which the paths can't change.
+static LOADED: AtomicBool = AtomicBool::new(false);
+
+/// The renderer ANGLE translates OpenGL ES calls to, selected through
+/// `EGL_ANGLE_platform_angle`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnglePlatform {
+    /// Lets ANGLE pick, which is what happens without
+    /// `EGL_ANGLE_platform_angle`.
+    Default,
+    /// Direct3D 9. Requires `EGL_ANGLE_platform_angle_d3d`.
+    D3D9,
+    /// Direct3D 11. Requires `EGL_ANGLE_platform_angle_d3d`.
+    D3D11,
+    /// Desktop OpenGL. Requires `EGL_ANGLE_platform_angle_opengl`.
+    OpenGl,
+    /// OpenGL ES. Requires `EGL_ANGLE_platform_angle_opengl`.
+    OpenGlEs,
+    /// Vulkan. Requires `EGL_ANGLE_platform_angle_vulkan`.
+    Vulkan,
+    /// Metal. Requires `EGL_ANGLE_platform_angle_metal`, which is only
+    /// available on macOS, where glutin doesn't use EGL yet.
+    Metal,
+}
+
+impl AnglePlatform {
+    fn platform_type(self) -> raw::c_int {
+        match self {
+            AnglePlatform::Default => PLATFORM_ANGLE_TYPE_DEFAULT_ANGLE,
+            AnglePlatform::D3D9 => PLATFORM_ANGLE_TYPE_D3D9_ANGLE,
+            AnglePlatform::D3D11 => PLATFORM_ANGLE_TYPE_D3D11_ANGLE,
+            AnglePlatform::OpenGl => PLATFORM_ANGLE_TYPE_OPENGL_ANGLE,
+            AnglePlatform::OpenGlEs => PLATFORM_ANGLE_TYPE_OPENGLES_ANGLE,
+            AnglePlatform::Vulkan => PLATFORM_ANGLE_TYPE_VULKAN_ANGLE,
+            AnglePlatform::Metal => PLATFORM_ANGLE_TYPE_METAL_ANGLE,
+        }
+    }
+
+    fn extension(self) -> Option<&'static str> {
+        match self {
+            AnglePlatform::Default => None,
+            AnglePlatform::D3D9 | AnglePlatform::D3D11 => {
+                Some("EGL_ANGLE_platform_angle_d3d")
+            }
+            AnglePlatform::OpenGl | AnglePlatform::OpenGlEs => {
+                Some("EGL_ANGLE_platform_angle_opengl")
+            }
+            AnglePlatform::Vulkan => Some("EGL_ANGLE_platform_angle_vulkan"),
+            AnglePlatform::Metal => Some("EGL_ANGLE_platform_angle_metal"),
+        }
+    }
+}
+
+/// Configures which EGL library glutin loads on Windows and how it creates
+/// its displays.
+///
+/// The configuration is global and applies to every EGL context built after
+/// [`install`] is called.
+///
+/// # Example
+///
+/// ```no_run
+/// # use glutin::platform::windows::{AnglePlatform, EglDisplayBuilder};
+/// EglDisplayBuilder::new()
+///     .with_library_paths(&["angle/libEGL.dll"])
+///     .with_gles_library_path("angle/libGLESv2.dll")
+///     .with_angle_platforms(&[AnglePlatform::D3D11, AnglePlatform::Vulkan])
+///     .install()
+///     .unwrap();
+/// ```
+///
+/// [`install`]: struct.EglDisplayBuilder.html#method.install
+#[derive(Debug, Clone)]
+pub struct EglDisplayBuilder {
+    library_paths: Vec<String>,
+    gles_library_path: Option<String>,
+    angle_platforms: Vec<AnglePlatform>,
+    d3d_adapter_luid: Option<(raw::c_long, raw::c_ulong)>,
+}
+
+impl EglDisplayBuilder {
+    /// Creates the default configuration, which loads `libEGL.dll` from the
+    /// usual search path and uses `eglGetDisplay`.
+    pub fn new() -> Self {
+        EglDisplayBuilder {
+            library_paths: vec![
+                "libEGL.dll".to_string(),
+                "atioglxx.dll".to_string(),
+            ],
+            gles_library_path: None,
+            angle_platforms: vec![],
+            d3d_adapter_luid: None,
+        }
+    }
+
+    /// Sets the EGL libraries to try loading, in order. Use a full path to
+    /// make sure another EGL found earlier in the search path isn't used.
+    pub fn with_library_paths(mut self, paths: &[&str]) -> Self {
+        self.library_paths = paths.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    /// Loads the given OpenGL ES library before the EGL library, so that
+    /// ANGLE's `libEGL.dll` uses it instead of looking it up by name.
+    pub fn with_gles_library_path(mut self, path: &str) -> Self {
+        self.gles_library_path = Some(path.to_string());
+        self
+    }
+
+    /// Sets the ANGLE renderers to try, in order, through
+    /// `eglGetPlatformDisplayEXT`. The first one that initializes is used.
+    ///
+    /// Requires `EGL_ANGLE_platform_angle`. If the list is empty, which is the
+    /// default, `eglGetDisplay` is used instead.
+    pub fn with_angle_platforms(mut self, platforms: &[AnglePlatform]) -> Self {
+        self.angle_platforms = platforms.to_vec();
+        self
+    }
+
+    /// Makes ANGLE's Direct3D renderers use the adapter with the given LUID,
+    /// as found in `DXGI_ADAPTER_DESC::AdapterLuid`.
+    ///
+    /// Requires `EGL_ANGLE_platform_angle_d3d_luid`.
+    pub fn with_d3d_adapter_luid(mut self, luid: LUID) -> Self {
+        self.d3d_adapter_luid = Some((luid.HighPart, luid.LowPart));
+        self
+    }
+
+    /// Makes this the configuration used by glutin.
+    ///
+    /// Fails if the library paths were changed after the EGL library was
+    /// already loaded, that is after the first context was built.
+    pub fn install(self) -> Result<(), CreationError> {
+        let mut config = CONFIG.lock();
+        if LOADED.load(Ordering::SeqCst)
+            && (self.library_paths != config.library_paths
+                || self.gles_library_path != config.gles_library_path)
+        {
+            return Err(CreationError::NotSupported(
+                "The EGL library was already loaded".to_string(),
+            ));
+        }
+        *config = self;
+        Ok(())
+    }
+}
+
+/// Returns the EGL libraries to try loading, after loading the OpenGL ES
+/// library if one was set.
+pub fn library_paths() -> Vec<String> {
+    let config = CONFIG.lock();
+    LOADED.store(true, Ordering::SeqCst);
+
+    if let Some(ref path) = config.gles_library_path {
+        if let Ok(lib) = libloading::Library::new(path) {
+            // like the EGL library, it stays loaded until the process exits
+            std::mem::forget(lib);
+        }
+    }
+
+    config.library_paths.clone()
+}
+
+/// Creates and initializes a display with the first ANGLE platform that
+/// works. Returns `None` if no platforms were set.
+pub fn get_display(
+    native_display: ffi::EGLNativeDisplayType,
+    client_extensions: &[String],
+) -> Result<Option<ffi::egl::types::EGLDisplay>, CreationError> {
+    let config = CONFIG.lock().clone();
+    if config.angle_platforms.is_empty() {
+        return Ok(None);
+    }
+
+    let egl = EGL.as_ref().unwrap();
+    super::debug::register();
+    let has_extension = |e: &str| client_extensions.iter().any(|s| s == e);
+    if !has_extension("EGL_ANGLE_platform_angle")
+        || !egl.GetPlatformDisplayEXT.is_loaded()
+    {
+        return Err(CreationError::NotSupported(
+            "EGL_ANGLE_platform_angle not supported".to_string(),
+        ));
+    }
+
+    let mut failures = vec![];
+    for &platform in &config.angle_platforms {
+        if let Some(extension) = platform.extension() {
+            if !has_extension(extension) {
+                failures
+                    .push(format!("{:?} ({} missing)", platform, extension));
+                continue;
+            }
+        }
+
+        let mut attribs =
+            vec![PLATFORM_ANGLE_TYPE_ANGLE, platform.platform_type()];
+        if let Some((high, low)) = config.d3d_adapter_luid {
+            if platform == AnglePlatform::D3D9
+                || platform == AnglePlatform::D3D11
+            {
+                if !has_extension("EGL_ANGLE_platform_angle_d3d_luid") {
+                    failures.push(format!(
+                        "{:?} (EGL_ANGLE_platform_angle_d3d_luid missing)",
+                        platform
+                    ));
+                    continue;
+                }
+                attribs.push(PLATFORM_ANGLE_D3D_LUID_HIGH_ANGLE);
+                attribs.push(high as raw::c_int);
+                attribs.push(PLATFORM_ANGLE_D3D_LUID_LOW_ANGLE);
+                attribs.push(low as raw::c_int);
+            }
+        }
+        attribs.push(ffi::egl::NONE as raw::c_int);
+
+        let display = unsafe {
+            egl.GetPlatformDisplayEXT(
+                PLATFORM_ANGLE_ANGLE,
+                native_display as *mut _,
+                attribs.as_ptr(),
+            )
+        };
+        if display.is_null() {
+            failures.push(format!(
+                "{:?} (eglGetPlatformDisplayEXT failed: 0x{:x})",
+                platform,
+                unsafe { egl.GetError() }
+            ));
+            continue;
+        }
+
+        // ANGLE only fails to bring up the renderer on initialization
+        let (mut major, mut minor) = (0, 0);
+        if unsafe { egl.Initialize(display, &mut major, &mut minor) } == 0 {
+            failures.push(format!(
+                "{:?} (eglInitialize failed: 0x{:x})",
+                platform,
+                unsafe { egl.GetError() }
+            ));
+            continue;
+        }
+
+        return Ok(Some(display));
+    }
+
+    Err(CreationError::OsError(format!(
+        "Could not create an ANGLE display with any of the requested \
+         platforms: {}",
+        failures.join(", ")
+    )))
+}
@@ -0,0 +1,228 @@
+//! Driver messages reported through `EGL_KHR_debug`.
+
+use super::EGL;
+use crate::{ContextError, CreationError, EglErrorCode, ErrorCode};
+use glutin_egl_sys as ffi;
+use parking_lot::Mutex;
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw;
+use std::sync::Arc;
+
+const DEBUG_MSG_CRITICAL_KHR: u32 = 0x33B9;
+const DEBUG_MSG_ERROR_KHR: u32 = 0x33BA;
+const DEBUG_MSG_WARN_KHR: u32 = 0x33BB;
+const DEBUG_MSG_INFO_KHR: u32 = 0x33BC;
+
+type DebugProc = extern "system" fn(
+    ffi::egl::types::EGLenum,
+    *const raw::c_char,
+    ffi::egl::types::EGLint,
+    *const raw::c_void,
+    *const raw::c_void,
+    *const raw::c_char,
+);
+
+type DebugMessageControl = unsafe extern "system" fn(
+    Option<DebugProc>,
+    *const ffi::egl::types::EGLAttrib,
+)
+    -> ffi::egl::types::EGLint;
+
+type Callback = Arc<dyn Fn(EglDebugMessage) + Send + Sync>;
+
+lazy_static! {
+    static ref CALLBACK: Mutex<Option<Callback>> = Mutex::new(None);
+}
+
+thread_local! {
+    // The last error reported on this thread.
+    static LAST_ERROR: RefCell<Option<EglDebugMessage>> = RefCell::new(None);
+}
+
+/// The severity of a [`EglDebugMessage`].
+///
+/// [`EglDebugMessage`]: struct.EglDebugMessage.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EglDebugMessageType {
+    /// The command failed in a way that may have left the driver in an
+    /// unusable state.
+    Critical,
+    /// The command failed and generated an EGL error.
+    Error,
+    /// The command succeeded, but something may be wrong.
+    Warning,
+    /// Informational messages.
+    Info,
+}
+
+/// A message reported by the EGL driver through `EGL_KHR_debug`.
+#[derive(Debug, Clone)]
+pub struct EglDebugMessage {
+    /// The EGL error generated by the command, or `EGL_SUCCESS`.
+    pub error: u32,
+    /// The EGL command that generated the message, e.g. `eglCreateContext`.
+    pub command: String,
+    /// The severity of the message.
+    pub message_type: EglDebugMessageType,
+    /// The label attached to the calling thread with `eglLabelObjectKHR`,
+    /// or null.
+    pub thread_label: *const raw::c_void,
+    /// The label attached to the primary object of the command with
+    /// `eglLabelObjectKHR`, or null.
+    pub object_label: *const raw::c_void,
+    /// The message itself.
+    pub message: String,
+}
+
+/// See `platform::*::set_egl_debug_callback`.
+pub fn set_debug_callback<F>(callback: F)
+where
+    F: Fn(EglDebugMessage) + Send + Sync + 'static,
+{
+    *CALLBACK.lock() = Some(Arc::new(callback));
+}
+
+/// Registers `debug_proc` with the driver if a callback was set and the
+/// client extension is available. Called whenever a display is initialized.
+pub fn register() {
+    if CALLBACK.lock().is_none() {
+        return;
+    }
+
+    let egl = EGL.as_ref().unwrap();
+    unsafe {
+        let p =
+            egl.QueryString(ffi::egl::NO_DISPLAY, ffi::egl::EXTENSIONS as i32);
+        if p.is_null()
+            || !CStr::from_ptr(p)
+                .to_string_lossy()
+                .split(' ')
+                .any(|e| e == "EGL_KHR_debug")
+        {
+            return;
+        }
+
+        let name = CString::new("eglDebugMessageControlKHR").unwrap();
+        let addr = egl.GetProcAddress(name.as_ptr()) as *const raw::c_void;
+        if addr.is_null() {
+            return;
+        }
+        let debug_message_control: DebugMessageControl =
+            std::mem::transmute(addr);
+
+        // every message type is enabled, not just the default errors
+        let attribs = [
+            DEBUG_MSG_CRITICAL_KHR as ffi::egl::types::EGLAttrib,
+            ffi::egl::TRUE as ffi::egl::types::EGLAttrib,
+            DEBUG_MSG_ERROR_KHR as ffi::egl::types::EGLAttrib,
+            ffi::egl::TRUE as ffi::egl::types::EGLAttrib,
+            DEBUG_MSG_WARN_KHR as ffi::egl::types::EGLAttrib,
+            ffi::egl::TRUE as ffi::egl::types::EGLAttrib,
+            DEBUG_MSG_INFO_KHR as ffi::egl::types::EGLAttrib,
+            ffi::egl::TRUE as ffi::egl::types::EGLAttrib,
+            ffi::egl::NONE as ffi::egl::types::EGLAttrib,
+        ];
+        debug_message_control(Some(debug_proc as DebugProc), attribs.as_ptr());
+    }
+}
+
+/// Appends the last error the driver reported for `command` on this thread
+/// to `message`, if there is one.
+pub fn with_last_error(command: &str, message: String) -> String {
+    let last = LAST_ERROR.with(|last| last.borrow_mut().take());
+    match last {
+        Some(ref last) if last.command == command => {
+            format!("{} ({})", message, last.message)
+        }
+        _ => message,
+    }
+}
+
+/// Reads the error of the last failed EGL call on this thread, and formats
+/// `message` with it the way [`with_last_error`] does.
+///
+/// [`with_last_error`]: fn.with_last_error.html
+pub fn last_error(command: &str, message: &str) -> (ErrorCode, String) {
+    let egl = EGL.as_ref().unwrap();
+    let code = unsafe { egl.GetError() } as u32;
+    error(command, message, code)
+}
+
+/// Like [`last_error`], for an error that was already read with
+/// `eglGetError`.
+///
+/// [`last_error`]: fn.last_error.html
+pub fn error(command: &str, message: &str, code: u32) -> (ErrorCode, String) {
+    let message =
+        with_last_error(command, format!("{}: 0x{:x}", message, code));
+    (ErrorCode::Egl(EglErrorCode::from_raw(code)), message)
+}
+
+/// A [`CreationError`] for the last failed EGL call on this thread.
+///
+/// [`CreationError`]: ../../enum.CreationError.html
+pub fn creation_error(command: &str, message: &str) -> CreationError {
+    let (code, message) = last_error(command, message);
+    CreationError::ApiError(code, message)
+}
+
+/// A [`ContextError`] for the last failed EGL call on this thread.
+///
+/// [`ContextError`]: ../../enum.ContextError.html
+pub fn context_error(command: &str, message: &str) -> ContextError {
+    let (code, message) = last_error(command, message);
+    ContextError::ApiError(code, message)
+}
+
+extern "system" fn debug_proc(
+    error: ffi::egl::types::EGLenum,
+    command: *const raw::c_char,
+    message_type: ffi::egl::types::EGLint,
+    thread_label: *const raw::c_void,
+    object_label: *const raw::c_void,
+    message: *const raw::c_char,
+) {
+    let to_string = |s: *const raw::c_char| {
+        if s.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+        }
+    };
+
+    let message_type = message_type as u32;
+    let message_type = if message_type == DEBUG_MSG_CRITICAL_KHR {
+        EglDebugMessageType::Critical
+    } else if message_type == DEBUG_MSG_ERROR_KHR {
+        EglDebugMessageType::Error
+    } else if message_type == DEBUG_MSG_WARN_KHR {
+        EglDebugMessageType::Warning
+    } else {
+        EglDebugMessageType::Info
+    };
+
+    let message = EglDebugMessage {
+        error,
+        command: to_string(command),
+        message_type,
+        thread_label,
+        object_label,
+        message: to_string(message),
+    };
+
+    if error != ffi::egl::SUCCESS {
+        LAST_ERROR.with(|last| *last.borrow_mut() = Some(message.clone()));
+    }
+
+    // the lock isn't held while calling back, in case the callback itself
+    // calls into EGL
+    let callback = CALLBACK.lock().clone();
+    if let Some(callback) = callback {
+        // unwinding into the driver is undefined behavior
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            callback(message)
+        }));
+    }
+}
@@ -90,7 +90,9 @@ mod egl {
     impl Egl {
         pub fn new() -> Result<Self, ()> {
             #[cfg(target_os = "windows")]
-            let paths = vec!["libEGL.dll", "atioglxx.dll"];
+            let configured = super::angle::library_paths();
+            #[cfg(target_os = "windows")]
+            let paths = configured.iter().map(|p| p.as_str()).collect();
 
             #[cfg(not(target_os = "windows"))]
             let paths = vec!["libEGL.so.1", "libEGL.so"];
@@ -114,13 +116,24 @@ mod egl {
     }
 }
 
+#[cfg(target_os = "windows")]
+mod angle;
+mod debug;
 mod make_current_guard;
+mod timestamps;
 
+#[cfg(target_os = "windows")]
+pub use self::angle::{AnglePlatform, EglDisplayBuilder};
+pub use self::debug::{
+    set_debug_callback, EglDebugMessage, EglDebugMessageType,
+};
 pub use self::egl::Egl;
 use self::make_current_guard::MakeCurrentGuard;
 use crate::{
-    Api, ContextError, CreationError, GlAttributes, GlRequest, PixelFormat,
-    PixelFormatRequirements, Rect, ReleaseBehavior, Robustness,
+    Api, ColorSpace, ContextError, CreationError, DirectRendering, Extensions,
+    FrameId, GlAttributes, GlRequest, PixelFormat, PixelFormatRequirements,
+    PresentationTimes, Priority, Rect, ReleaseBehavior, Robustness,
+    SwapBehavior, SwapInterval,
 };
 
 use glutin_egl_sys as ffi;
@@ -139,6 +152,7 @@ use winit::dpi;
 use std::ffi::{CStr, CString};
 use std::ops::{Deref, DerefMut};
 use std::os::raw;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 
 impl Deref for Egl {
     type Target = ffi::egl::Egl;
@@ -170,8 +184,8 @@ pub enum NativeDisplay {
     Wayland(Option<ffi::EGLNativeDisplayType>),
     /// `EGL_DEFAULT_DISPLAY` is mandatory for Android.
     Android,
-    // TODO: should be `EGLDeviceEXT`
-    Device(ffi::EGLNativeDisplayType),
+    /// A device found with `EGL_EXT_device_enumeration`.
+    Device(ffi::egl::types::EGLDeviceEXT),
     /// Don't specify any display type. Useful on windows. `None` means
     /// `EGL_DEFAULT_DISPLAY`.
     Other(Option<ffi::EGLNativeDisplayType>),
@@ -184,14 +198,62 @@ pub struct Context {
     surface: Option<Mutex<ffi::egl::types::EGLSurface>>,
     api: Api,
     pixel_format: PixelFormat,
-    #[cfg(target_os = "android")]
+    // Whether `EGL_KHR_surfaceless_context` is supported.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    surfaceless_supported: bool,
+    // Whether `EGL_EXT_buffer_age` is supported.
+    buffer_age_supported: bool,
+    swap_damage: Option<SwapDamageExtension>,
+    // Whether `EGL_KHR_partial_update` is supported.
+    partial_update_supported: bool,
+    // Whether a damage region was set since the last swap.
+    damage_region_set: AtomicBool,
+    // The last swap interval set on the surface.
+    swap_interval: AtomicI32,
+    // The extensions supported by `display`.
+    extensions: Extensions,
+    // The config of the surface.
     config_id: ffi::egl::types::EGLConfig,
+    // Whether the context was created with `EGL_NO_CONFIG_KHR`.
+    no_config: bool,
+    // Whether the context was created with `EGL_CONTEXT_OPENGL_NO_ERROR_KHR`.
+    no_error: bool,
+    // Whether the context was created with `EGL_LOSE_CONTEXT_ON_RESET`.
+    lose_context_on_reset: bool,
+    release_behavior: ReleaseBehavior,
+    // Whether the context and its surface were created with
+    // `EGL_PROTECTED_CONTENT_EXT`.
+    protected_content: bool,
+    // Whether the surface is a pbuffer.
+    pbuffer: bool,
+    // Whether the surface is a pbuffer that can be bound as a texture.
+    textureable: bool,
+    // Whether `EGL_ANDROID_get_frame_timestamps` is supported.
+    frame_timestamps_supported: bool,
+    // Whether timestamps are being recorded for the surface.
+    timestamps_enabled: AtomicBool,
+    // The stream the surface produces frames for, if it is a stream producer
+    // surface.
+    stream: Option<ffi::egl::types::EGLStreamKHR>,
+    // The color space to recreate the surface with.
+    #[cfg(target_os = "android")]
+    color_space: Option<ColorSpace>,
+    // Whether to preserve the color buffer of recreated surfaces on swaps.
+    #[cfg(target_os = "android")]
+    preserved_swap: bool,
 }
 
 #[cfg(target_os = "android")]
 #[inline]
 fn get_native_display(native_display: &NativeDisplay) -> *const raw::c_void {
     let egl = EGL.as_ref().unwrap();
+    debug::register();
     unsafe { egl.GetDisplay(ffi::egl::DEFAULT_DISPLAY as *mut _) }
 }
 
@@ -204,8 +266,9 @@ fn get_egl_version(
         let mut minor: ffi::egl::types::EGLint = std::mem::zeroed();
 
         if egl.Initialize(display, &mut major, &mut minor) == 0 {
-            return Err(CreationError::OsError(
-                "eglInitialize failed".to_string(),
+            return Err(debug::creation_error(
+                "eglInitialize",
+                "eglInitialize failed",
             ));
         }
 
@@ -213,6 +276,212 @@ fn get_egl_version(
     }
 }
 
+/// Calls `eglGetDisplay` or equivalent.
+fn get_display(
+    native_display: &NativeDisplay,
+) -> Result<ffi::egl::types::EGLDisplay, CreationError> {
+    #[cfg(target_os = "windows")]
+    let display = match *native_display {
+        NativeDisplay::Other(display) => angle::get_display(
+            display.unwrap_or(ffi::egl::DEFAULT_DISPLAY as *const _),
+            &get_client_extensions(),
+        )?,
+        _ => None,
+    }
+    .unwrap_or_else(|| get_native_display(native_display));
+    #[cfg(not(target_os = "windows"))]
+    let display = get_native_display(native_display);
+
+    if display.is_null() {
+        return Err(CreationError::OsError(
+            "Could not create EGL display object".to_string(),
+        ));
+    }
+    Ok(display)
+}
+
+/// Queries the extensions of an initialized display.
+fn query_extensions(
+    display: ffi::egl::types::EGLDisplay,
+    egl_version: (ffi::egl::types::EGLint, ffi::egl::types::EGLint),
+) -> Extensions {
+    if egl_version < (1, 2) {
+        return Extensions::default();
+    }
+    query_display_string(display, ffi::egl::EXTENSIONS)
+        .map(|list| Extensions::parse(&list))
+        .unwrap_or_default()
+}
+
+fn query_display_string(
+    display: ffi::egl::types::EGLDisplay,
+    name: ffi::egl::types::EGLenum,
+) -> Option<String> {
+    let egl = EGL.as_ref().unwrap();
+    unsafe {
+        let p = egl.QueryString(display, name as i32);
+        if p.is_null() {
+            None
+        } else {
+            Some(CStr::from_ptr(p).to_string_lossy().into_owned())
+        }
+    }
+}
+
+/// Information about an EGL display, available without creating a context.
+#[derive(Debug, Clone)]
+pub struct EglDisplayInfo {
+    /// The EGL version returned by `eglInitialize`.
+    pub version: (i32, i32),
+    /// The `EGL_VENDOR` string.
+    pub vendor: Option<String>,
+    /// The `EGL_VERSION` string, which usually includes vendor-specific
+    /// information after the version.
+    pub version_string: Option<String>,
+    /// The `EGL_CLIENT_APIS` string, e.g. `OpenGL OpenGL_ES`.
+    pub client_apis: Option<String>,
+    /// The extensions supported by the display.
+    pub extensions: Extensions,
+    /// The name of the Mesa driver, if `EGL_MESA_query_driver` is supported.
+    pub driver_name: Option<String>,
+}
+
+impl EglDisplayInfo {
+    /// Returns true if the display supports the given extension.
+    #[inline]
+    pub fn has_extension(&self, extension: &str) -> bool {
+        self.extensions.contains(extension)
+    }
+
+    /// Initializes the display and queries its information.
+    fn query(
+        native_display: &NativeDisplay,
+    ) -> Result<EglDisplayInfo, CreationError> {
+        if EGL.is_none() {
+            return Err(CreationError::NotSupported(
+                "libEGL not present".to_string(),
+            ));
+        }
+        let egl = EGL.as_ref().unwrap();
+
+        let display = get_display(native_display)?;
+        let version = get_egl_version(display)?;
+        let extensions = query_extensions(display, version);
+
+        let driver_name = if extensions.contains("EGL_MESA_query_driver") {
+            type GetDisplayDriverName =
+                unsafe extern "system" fn(
+                    ffi::egl::types::EGLDisplay,
+                )
+                    -> *const raw::c_char;
+
+            let name = CString::new("eglGetDisplayDriverName").unwrap();
+            let addr = unsafe { egl.GetProcAddress(name.as_ptr()) };
+            if addr.is_null() {
+                None
+            } else {
+                unsafe {
+                    let get_display_driver_name: GetDisplayDriverName =
+                        std::mem::transmute(addr);
+                    let p = get_display_driver_name(display);
+                    if p.is_null() {
+                        None
+                    } else {
+                        Some(CStr::from_ptr(p).to_string_lossy().into_owned())
+                    }
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(EglDisplayInfo {
+            version,
+            vendor: query_display_string(display, ffi::egl::VENDOR),
+            version_string: query_display_string(display, ffi::egl::VERSION),
+            client_apis: if version >= (1, 2) {
+                query_display_string(display, ffi::egl::CLIENT_APIS)
+            } else {
+                None
+            },
+            extensions,
+            driver_name,
+        })
+    }
+}
+
+/// Returns information about the default EGL display, the one picked by
+/// `eglGetDisplay(EGL_DEFAULT_DISPLAY)`.
+///
+/// This initializes the display.
+pub fn default_display_info() -> Result<EglDisplayInfo, CreationError> {
+    EglDisplayInfo::query(&NativeDisplay::Other(None))
+}
+
+/// Returns the distinct sample counts of the configs of `native_display` for
+/// `surface_type` that match the requirements, apart from multisampling, and
+/// that `keep` accepts, like the config selection of context creation would.
+/// `0` stands for configs without multisampling.
+///
+/// This initializes the display.
+pub fn supported_sample_counts<F>(
+    native_display: &NativeDisplay,
+    surface_type: SurfaceType,
+    pf_reqs: &PixelFormatRequirements,
+    opengl: &GlAttributes<&Context>,
+    mut keep: F,
+) -> Result<Vec<u16>, CreationError>
+where
+    F: FnMut(ffi::egl::types::EGLDisplay, ffi::egl::types::EGLConfig) -> bool,
+{
+    if EGL.is_none() {
+        return Err(CreationError::NotSupported(
+            "libEGL not present".to_string(),
+        ));
+    }
+    let egl = EGL.as_ref().unwrap();
+
+    let display = get_display(native_display)?;
+    let egl_version = get_egl_version(display)?;
+    let extensions = query_extensions(display, egl_version);
+
+    let mut pf_reqs = pf_reqs.clone();
+    pf_reqs.multisampling = None;
+    pf_reqs.multisampling_exact = false;
+
+    unsafe {
+        let (version, api) = bind_and_get_api(opengl, egl_version)?;
+        let config_ids = choose_configs(
+            display,
+            &egl_version,
+            &extensions,
+            api,
+            version,
+            &pf_reqs,
+            surface_type,
+            opengl,
+        )?;
+
+        let mut counts = config_ids
+            .into_iter()
+            .filter(|&config| keep(display, config))
+            .map(|config| {
+                let mut value = 0;
+                egl.GetConfigAttrib(
+                    display,
+                    config,
+                    ffi::egl::SAMPLES as ffi::egl::types::EGLint,
+                    &mut value,
+                );
+                value as u16
+            })
+            .collect::<Vec<_>>();
+        counts.sort();
+        counts.dedup();
+        Ok(counts)
+    }
+}
+
 unsafe fn bind_and_get_api<'a>(
     opengl: &'a GlAttributes<&'a Context>,
     egl_version: (ffi::egl::types::EGLint, ffi::egl::types::EGLint),
@@ -271,12 +540,11 @@ unsafe fn bind_and_get_api<'a>(
     }
 }
 
+/// Queries the list of extensions without any display, if supported.
 #[cfg(not(target_os = "android"))]
-fn get_native_display(native_display: &NativeDisplay) -> *const raw::c_void {
+fn get_client_extensions() -> Vec<String> {
     let egl = EGL.as_ref().unwrap();
-    // the first step is to query the list of extensions without any display, if
-    // supported
-    let dp_extensions = unsafe {
+    unsafe {
         let p =
             egl.QueryString(ffi::egl::NO_DISPLAY, ffi::egl::EXTENSIONS as i32);
 
@@ -291,7 +559,16 @@ fn get_native_display(native_display: &NativeDisplay) -> *const raw::c_void {
                 .unwrap_or_else(|_| format!(""));
             list.split(' ').map(|e| e.to_string()).collect::<Vec<_>>()
         }
-    };
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn get_native_display(native_display: &NativeDisplay) -> *const raw::c_void {
+    let egl = EGL.as_ref().unwrap();
+    debug::register();
+    // the first step is to query the list of extensions without any display, if
+    // supported
+    let dp_extensions = get_client_extensions();
 
     let has_dp_extension =
         |e: &str| dp_extensions.iter().find(|s| s == &e).is_some();
@@ -400,23 +677,37 @@ fn get_native_display(native_display: &NativeDisplay) -> *const raw::c_void {
                 ffi::egl::DEFAULT_DISPLAY as *mut _,
                 std::ptr::null(),
             )
-        }
+        },
 
-        NativeDisplay::Device(display)
+        NativeDisplay::Device(device)
             if has_dp_extension("EGL_EXT_platform_device")
                 && egl.GetPlatformDisplay.is_loaded() =>
         unsafe {
             egl.GetPlatformDisplay(
                 ffi::egl::PLATFORM_DEVICE_EXT,
-                display as *mut _,
+                device as *mut _,
                 std::ptr::null(),
             )
-        }
+        },
+
+        NativeDisplay::Device(device)
+            if has_dp_extension("EGL_EXT_platform_device")
+                && egl.GetPlatformDisplayEXT.is_loaded() =>
+        unsafe {
+            egl.GetPlatformDisplayEXT(
+                ffi::egl::PLATFORM_DEVICE_EXT,
+                device as *mut _,
+                std::ptr::null(),
+            )
+        },
+
+        // A device can't be passed to `eglGetDisplay`, and falling back to the
+        // default display would silently pick another device.
+        NativeDisplay::Device(_) => std::ptr::null(),
 
         NativeDisplay::X11(Some(display))
         | NativeDisplay::Gbm(Some(display))
         | NativeDisplay::Wayland(Some(display))
-        | NativeDisplay::Device(display)
         | NativeDisplay::Other(Some(display)) => unsafe {
             egl.GetDisplay(display as *mut _)
         },
@@ -431,12 +722,197 @@ fn get_native_display(native_display: &NativeDisplay) -> *const raw::c_void {
     }
 }
 
+/// An EGL device, usually a GPU, found with `EGL_EXT_device_enumeration`.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Device {
+    device: ffi::egl::types::EGLDeviceEXT,
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+unsafe impl Send for Device {}
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+unsafe impl Sync for Device {}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+impl Device {
+    /// Returns all the devices EGL knows about.
+    pub fn enumerate() -> Result<Vec<Device>, CreationError> {
+        let egl = match *EGL {
+            Some(ref egl) => egl,
+            None => {
+                return Err(CreationError::NotSupported(
+                    "libEGL not present".to_string(),
+                ))
+            }
+        };
+
+        if !get_client_extensions()
+            .iter()
+            .any(|s| s == "EGL_EXT_device_enumeration")
+            || !egl.QueryDevicesEXT.is_loaded()
+        {
+            return Err(CreationError::NotSupported(
+                "EGL_EXT_device_enumeration not supported".to_string(),
+            ));
+        }
+
+        unsafe {
+            let mut num_devices = 0;
+            if egl.QueryDevicesEXT(0, std::ptr::null_mut(), &mut num_devices)
+                == 0
+            {
+                return Err(debug::creation_error(
+                    "eglQueryDevicesEXT",
+                    "eglQueryDevicesEXT failed",
+                ));
+            }
+
+            let mut devices = vec![std::ptr::null(); num_devices as usize];
+            if egl.QueryDevicesEXT(
+                num_devices,
+                devices.as_mut_ptr(),
+                &mut num_devices,
+            ) == 0
+            {
+                return Err(debug::creation_error(
+                    "eglQueryDevicesEXT",
+                    "eglQueryDevicesEXT failed",
+                ));
+            }
+            devices.truncate(num_devices as usize);
+
+            Ok(devices
+                .into_iter()
+                .map(|device| Device { device })
+                .collect())
+        }
+    }
+
+    /// Returns the extensions supported by the device.
+    pub fn extensions(&self) -> Vec<String> {
+        self.query_string(ffi::egl::EXTENSIONS)
+            .map(|list| list.split(' ').map(|e| e.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the path of the DRM device file, for example `/dev/dri/card0`,
+    /// if the device supports `EGL_EXT_device_drm`.
+    pub fn drm_device_file(&self) -> Option<String> {
+        if !self.extensions().iter().any(|s| s == "EGL_EXT_device_drm") {
+            return None;
+        }
+        self.query_string(ffi::egl::DRM_DEVICE_FILE_EXT)
+    }
+
+    /// Returns the vendor of the EGL implementation driving the device.
+    ///
+    /// This initializes the EGL display of the device.
+    pub fn vendor(&self) -> Option<String> {
+        let egl = EGL.as_ref().unwrap();
+        let display = get_native_display(&NativeDisplay::Device(self.device));
+        if display.is_null() {
+            return None;
+        }
+        get_egl_version(display).ok()?;
+
+        unsafe {
+            let p = egl.QueryString(display, ffi::egl::VENDOR as i32);
+            if p.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(p).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Returns information about the EGL display of the device.
+    ///
+    /// This initializes the EGL display of the device.
+    pub fn display_info(&self) -> Result<EglDisplayInfo, CreationError> {
+        EglDisplayInfo::query(&NativeDisplay::Device(self.device))
+    }
+
+    /// Returns the underlying `EGLDeviceEXT`.
+    #[inline]
+    pub fn raw_device(&self) -> ffi::egl::types::EGLDeviceEXT {
+        self.device
+    }
+
+    fn query_string(&self, name: ffi::egl::types::EGLenum) -> Option<String> {
+        let egl = EGL.as_ref().unwrap();
+        if !egl.QueryDeviceStringEXT.is_loaded() {
+            return None;
+        }
+
+        unsafe {
+            let p = egl.QueryDeviceStringEXT(self.device, name as i32);
+            if p.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(p).to_string_lossy().into_owned())
+            }
+        }
+    }
+}
+
+/// The extension used to swap buffers with damage.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum SwapDamageExtension {
+    Khr,
+    Ext,
+}
+
+impl SwapDamageExtension {
+    fn find(extensions: &Extensions) -> Option<Self> {
+        let egl = EGL.as_ref().unwrap();
+        let has_extension = |e: &str| extensions.contains(e);
+        if has_extension("EGL_KHR_swap_buffers_with_damage")
+            && egl.SwapBuffersWithDamageKHR.is_loaded()
+        {
+            Some(SwapDamageExtension::Khr)
+        } else if has_extension("EGL_EXT_swap_buffers_with_damage")
+            && egl.SwapBuffersWithDamageEXT.is_loaded()
+        {
+            Some(SwapDamageExtension::Ext)
+        } else {
+            None
+        }
+    }
+}
+
 #[allow(dead_code)] // Not all platforms use all
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum SurfaceType {
     PBuffer,
     Window,
     Surfaceless,
+    Pixmap,
+    Stream,
 }
 
 impl Context {
@@ -451,7 +927,7 @@ impl Context {
         opengl: &'a GlAttributes<&'a Context>,
         native_display: NativeDisplay,
         surface_type: SurfaceType,
-        config_selector: F,
+        mut config_selector: F,
     ) -> Result<ContextPrototype<'a>, CreationError>
     where
         F: FnMut(
@@ -460,48 +936,106 @@ impl Context {
         ) -> Result<ffi::egl::types::EGLConfig, ()>,
     {
         let egl = EGL.as_ref().unwrap();
-        // calling `eglGetDisplay` or equivalent
-        let display = get_native_display(&native_display);
 
-        if display.is_null() {
-            return Err(CreationError::OsError(
-                "Could not create EGL display object".to_string(),
-            ));
+        #[cfg(not(target_os = "android"))]
+        {
+            if let NativeDisplay::Device(_) = native_display {
+                if !get_client_extensions()
+                    .iter()
+                    .any(|s| s == "EGL_EXT_platform_device")
+                {
+                    return Err(CreationError::NotSupported(
+                        "EGL_EXT_platform_device not supported".to_string(),
+                    ));
+                }
+            }
         }
 
+        // calling `eglGetDisplay` or equivalent
+        let display = get_display(&native_display)?;
+
         let egl_version = get_egl_version(display)?;
 
         // the list of extensions supported by the client once initialized is
         // different from the list of extensions obtained earlier
-        let extensions = if egl_version >= (1, 2) {
-            let p = unsafe {
-                CStr::from_ptr(
-                    egl.QueryString(display, ffi::egl::EXTENSIONS as i32),
-                )
-            };
-            let list = String::from_utf8(p.to_bytes().to_vec())
-                .unwrap_or_else(|_| format!(""));
-            list.split(' ').map(|e| e.to_string()).collect::<Vec<_>>()
-        } else {
-            vec![]
-        };
+        let extensions = query_extensions(display, egl_version);
 
         // binding the right API and choosing the version
-        let (version, api) = unsafe { bind_and_get_api(&opengl, egl_version)? };
+        let (mut version, mut api) =
+            unsafe { bind_and_get_api(&opengl, egl_version)? };
 
-        let (config_id, pixel_format) = unsafe {
+        let mut config = unsafe {
             choose_fbconfig(
                 display,
                 &egl_version,
+                &extensions,
                 api,
                 version,
                 pf_reqs,
                 surface_type,
                 opengl,
-                config_selector,
-            )?
+                &mut config_selector,
+            )
         };
 
+        // When OpenGL was bound but no config can render it, fall back to
+        // OpenGL ES if the request allows it, rather than failing when
+        // creating the context.
+        if config.is_err() && api == Api::OpenGl {
+            let es_version = match opengl.version {
+                GlRequest::Latest => Some(None),
+                GlRequest::GlThenGles {
+                    opengles_version, ..
+                } => Some(Some(opengles_version)),
+                GlRequest::Specific(..) => None,
+            };
+            if let Some(es_version) = es_version {
+                let egl = EGL.as_ref().unwrap();
+                if unsafe { egl.BindAPI(ffi::egl::OPENGL_ES_API) } != 0 {
+                    api = Api::OpenGlEs;
+                    version = es_version;
+                    config = unsafe {
+                        choose_fbconfig(
+                            display,
+                            &egl_version,
+                            &extensions,
+                            api,
+                            version,
+                            pf_reqs,
+                            surface_type,
+                            opengl,
+                            &mut config_selector,
+                        )
+                    };
+                }
+            }
+        }
+        let (config_id, pixel_format) = config?;
+
+        if let Some(color_space) = pf_reqs.color_space {
+            check_color_space(color_space, &egl_version, &extensions)?;
+        }
+
+        if opengl.protected_content
+            && !extensions.contains("EGL_EXT_protected_content")
+        {
+            return Err(CreationError::NotSupported(
+                "EGL_EXT_protected_content not supported".to_string(),
+            ));
+        }
+
+        if opengl.direct_rendering == DirectRendering::RequireIndirect {
+            return Err(CreationError::NotSupported(
+                "Indirect rendering is not supported by EGL".to_string(),
+            ));
+        }
+
+        if opengl.reset_isolation {
+            return Err(CreationError::NotSupported(
+                "Reset isolation is not supported by EGL".to_string(),
+            ));
+        }
+
         Ok(ContextPrototype {
             opengl,
             display,
@@ -511,6 +1045,17 @@ impl Context {
             version,
             config_id,
             pixel_format,
+            color_space: pf_reqs.color_space,
+            release_behavior: pf_reqs.release_behavior,
+            texture_format: if pf_reqs.textureable
+                && surface_type == SurfaceType::PBuffer
+            {
+                Some(texture_format(pf_reqs))
+            } else {
+                None
+            },
+            preserved_swap: pf_reqs.preserved_swap,
+            largest_pbuffer: pf_reqs.largest_pbuffer,
         })
     }
 
@@ -522,10 +1067,18 @@ impl Context {
         if ret == Some(0) {
             match egl.GetError() as u32 {
                 ffi::egl::CONTEXT_LOST => Err(ContextError::ContextLost),
-                err => panic!(
-                    "make_current: eglMakeCurrent failed (eglGetError returned 0x{:x})",
-                    err
-                ),
+                // Mesa reports terminated displays as bad ones when binding
+                ffi::egl::NOT_INITIALIZED | ffi::egl::BAD_DISPLAY => {
+                    Err(ContextError::DisplayLost)
+                }
+                err => {
+                    let (code, message) = debug::error(
+                        "eglMakeCurrent",
+                        "eglMakeCurrent failed",
+                        err,
+                    );
+                    Err(ContextError::ApiError(code, message))
+                }
             }
         } else {
             Ok(())
@@ -533,21 +1086,113 @@ impl Context {
     }
 
     pub unsafe fn make_current(&self) -> Result<(), ContextError> {
-        let egl = EGL.as_ref().unwrap();
-        let surface = self
-            .surface
-            .as_ref()
-            .map(|s| *s.lock())
-            .unwrap_or(ffi::egl::NO_SURFACE);
-        let ret = egl.MakeCurrent(self.display, surface, surface, self.context);
-
-        self.check_make_current(Some(ret))
+        self.make_current_draw_read(self, self)
     }
 
-    pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
-        let egl = EGL.as_ref().unwrap();
+    /// Makes the context current without binding any surface, even if it has
+    /// one. Requires `EGL_KHR_surfaceless_context`.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub unsafe fn make_current_surfaceless(&self) -> Result<(), ContextError> {
+        if !self.surfaceless_supported {
+            return Err(ContextError::FunctionUnavailable);
+        }
 
-        let surface_eq =
+        let egl = EGL.as_ref().unwrap();
+        let ret = egl.MakeCurrent(
+            self.display,
+            ffi::egl::NO_SURFACE,
+            ffi::egl::NO_SURFACE,
+            self.context,
+        );
+
+        self.check_make_current(Some(ret))
+    }
+
+    /// Makes the context current with the surface of `other` instead of its
+    /// own. Unless the context was created without a config, both surfaces
+    /// must have the same config.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub unsafe fn make_current_with_surface_of(
+        &self,
+        other: &Context,
+    ) -> Result<(), ContextError> {
+        self.make_current_draw_read(other, other)
+    }
+
+    /// Makes the context current, drawing to the surface of `draw` and
+    /// reading from the surface of `read`. Either can be the context itself.
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        let draw = self.surface_of(draw)?;
+        let read = self.surface_of(read)?;
+
+        let egl = EGL.as_ref().unwrap();
+        let ret = egl.MakeCurrent(self.display, draw, read, self.context);
+
+        self.check_make_current(Some(ret))
+    }
+
+    // Returns the surface of `other`, after checking that it can be bound to
+    // this context.
+    fn surface_of(
+        &self,
+        other: &Context,
+    ) -> Result<ffi::egl::types::EGLSurface, ContextError> {
+        if std::ptr::eq(self, other) {
+            return Ok(self
+                .surface
+                .as_ref()
+                .map(|s| *s.lock())
+                .unwrap_or(ffi::egl::NO_SURFACE));
+        }
+
+        let surface = match other.surface {
+            Some(ref surface) => *surface.lock(),
+            None => return Err(ContextError::FunctionUnavailable),
+        };
+        if self.display != other.display {
+            return Err(ContextError::OsError(
+                "The surface belongs to another EGL display".to_string(),
+            ));
+        }
+        if !self.no_config && self.config_id != other.config_id {
+            return Err(ContextError::OsError(
+                "The surface was created with a different config; use \
+                 `with_no_config` to bind surfaces of any config"
+                    .to_string(),
+            ));
+        }
+        if self.protected_content && !other.protected_content {
+            return Err(ContextError::OsError(
+                "Protected contexts can only be made current with protected \
+                 surfaces; build the other context with \
+                 `with_protected_content`"
+                    .to_string(),
+            ));
+        }
+
+        Ok(surface)
+    }
+
+    pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
+        let egl = EGL.as_ref().unwrap();
+
+        let surface_eq =
             if let Some(surface) = self.surface.as_ref().map(|s| *s.lock()) {
                 egl.GetCurrentSurface(ffi::egl::DRAW as i32) == surface
                     || egl.GetCurrentSurface(ffi::egl::READ as i32) == surface
@@ -601,11 +1246,20 @@ impl Context {
         if *surface != ffi::egl::NO_SURFACE {
             return;
         }
+        let mut attrs = Vec::with_capacity(5);
+        if let Some(color_space) = self.color_space {
+            attrs.extend_from_slice(&color_space_attributes(color_space));
+        }
+        if self.protected_content {
+            attrs.push(ffi::egl::PROTECTED_CONTENT_EXT as raw::c_int);
+            attrs.push(ffi::egl::TRUE as raw::c_int);
+        }
+        attrs.push(ffi::egl::NONE as raw::c_int);
         *surface = egl.CreateWindowSurface(
             self.display,
             self.config_id,
             nwin,
-            std::ptr::null(),
+            attrs.as_ptr(),
         );
         if surface.is_null() {
             panic!(
@@ -613,6 +1267,13 @@ impl Context {
                 egl.GetError()
             )
         }
+        if self.preserved_swap {
+            if let Err(err) = preserve_swap(self.display, *surface) {
+                panic!("on_surface_created: {}", err);
+            }
+        }
+        // the new surface doesn't record timestamps until enabled again
+        self.timestamps_enabled.store(false, Ordering::Relaxed);
         let ret =
             egl.MakeCurrent(self.display, *surface, *surface, self.context);
         if ret == 0 {
@@ -647,102 +1308,1405 @@ impl Context {
             )
         }
 
-        egl.DestroySurface(self.display, *surface);
-        *surface = ffi::egl::NO_SURFACE;
+        egl.DestroySurface(self.display, *surface);
+        *surface = ffi::egl::NO_SURFACE;
+    }
+
+    #[inline]
+    pub fn get_proc_address(&self, addr: &str) -> *const core::ffi::c_void {
+        let egl = EGL.as_ref().unwrap();
+        let addr = CString::new(addr.as_bytes()).unwrap();
+        let addr = addr.as_ptr();
+        unsafe { egl.GetProcAddress(addr) as *const _ }
+    }
+
+    #[inline]
+    pub fn swap_buffers(&self) -> Result<(), ContextError> {
+        let egl = EGL.as_ref().unwrap();
+        let surface = self.surface.as_ref().unwrap().lock();
+        if *surface == ffi::egl::NO_SURFACE {
+            return Err(ContextError::ContextLost);
+        }
+
+        let ret = unsafe { egl.SwapBuffers(self.display, *surface) };
+        self.damage_region_set.store(false, Ordering::Relaxed);
+
+        if ret == 0 {
+            match unsafe { egl.GetError() } as u32 {
+                ffi::egl::CONTEXT_LOST => {
+                    return Err(ContextError::ContextLost)
+                }
+                ffi::egl::NOT_INITIALIZED => {
+                    return Err(ContextError::DisplayLost)
+                }
+                err => {
+                    let (code, message) = debug::error(
+                        "eglSwapBuffers",
+                        "eglSwapBuffers failed",
+                        err,
+                    );
+                    Err(ContextError::ApiError(code, message))
+                }
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Swaps the buffers like `swap_buffers`, and returns the id of the frame
+    /// that was swapped. Requires `EGL_ANDROID_get_frame_timestamps`.
+    pub fn swap_buffers_with_frame_id(&self) -> Result<FrameId, ContextError> {
+        if !self.frame_timestamps_supported {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let frame = {
+            let surface = self.surface.as_ref().unwrap().lock();
+            if *surface == ffi::egl::NO_SURFACE {
+                return Err(ContextError::ContextLost);
+            }
+
+            // timestamps are only recorded once enabled, so this is done on
+            // first use rather than for every surface
+            if !self.timestamps_enabled.swap(true, Ordering::Relaxed) {
+                if let Err(err) =
+                    unsafe { timestamps::enable(self.display, *surface) }
+                {
+                    self.timestamps_enabled.store(false, Ordering::Relaxed);
+                    return Err(err);
+                }
+            }
+            unsafe { timestamps::next_frame_id(self.display, *surface)? }
+        };
+
+        self.swap_buffers()?;
+        Ok(frame)
+    }
+
+    /// Returns the timestamps recorded for a frame swapped with
+    /// `swap_buffers_with_frame_id`.
+    pub fn presentation_times(
+        &self,
+        frame: FrameId,
+    ) -> Result<PresentationTimes, ContextError> {
+        if !self.frame_timestamps_supported {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let surface = self.surface.as_ref().unwrap().lock();
+        if *surface == ffi::egl::NO_SURFACE {
+            return Err(ContextError::ContextLost);
+        }
+        unsafe { timestamps::presentation_times(self.display, *surface, frame) }
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to query the buffer age"
+                    .to_string(),
+            ));
+        }
+        if !self.buffer_age_supported {
+            return Ok(0);
+        }
+
+        let egl = EGL.as_ref().unwrap();
+        let surface = match self.surface {
+            Some(ref surface) => *surface.lock(),
+            None => return Ok(0),
+        };
+        if surface == ffi::egl::NO_SURFACE {
+            return Ok(0);
+        }
+
+        let mut age = 0;
+        let ret = unsafe {
+            egl.QuerySurface(
+                self.display,
+                surface,
+                ffi::egl::BUFFER_AGE_EXT as i32,
+                &mut age,
+            )
+        };
+        if ret == 0 {
+            return Err(debug::context_error(
+                "eglQuerySurface",
+                "eglQuerySurface(EGL_BUFFER_AGE_EXT) failed",
+            ));
+        }
+        Ok(age as u32)
+    }
+
+    /// Returns the priority the driver actually granted, which may be lower
+    /// than the one requested, or `None` without `EGL_IMG_context_priority`.
+    pub fn priority(&self) -> Option<Priority> {
+        if !self.has_extension("EGL_IMG_context_priority") {
+            return None;
+        }
+
+        let egl = EGL.as_ref().unwrap();
+        let mut level = 0;
+        let ret = unsafe {
+            egl.QueryContext(
+                self.display,
+                self.context,
+                ffi::egl::CONTEXT_PRIORITY_LEVEL_IMG as raw::c_int,
+                &mut level,
+            )
+        };
+        if ret == 0 {
+            return None;
+        }
+
+        if level == ffi::egl::CONTEXT_PRIORITY_HIGH_IMG as raw::c_int {
+            Some(Priority::High)
+        } else if level == ffi::egl::CONTEXT_PRIORITY_MEDIUM_IMG as raw::c_int {
+            Some(Priority::Medium)
+        } else if level == ffi::egl::CONTEXT_PRIORITY_LOW_IMG as raw::c_int {
+            Some(Priority::Low)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the color space of the surface, or `None` if the context has
+    /// no surface or the implementation can't report it.
+    pub fn color_space(&self) -> Option<ColorSpace> {
+        let surface = match self.surface {
+            Some(ref surface) => *surface.lock(),
+            None => return None,
+        };
+        if surface == ffi::egl::NO_SURFACE {
+            return None;
+        }
+
+        let egl = EGL.as_ref().unwrap();
+        let mut value = 0;
+        let ret = unsafe {
+            egl.QuerySurface(
+                self.display,
+                surface,
+                ffi::egl::GL_COLORSPACE as raw::c_int,
+                &mut value,
+            )
+        };
+        if ret == 0 {
+            return None;
+        }
+
+        if value == ffi::egl::GL_COLORSPACE_LINEAR as raw::c_int {
+            Some(ColorSpace::Linear)
+        } else if value == ffi::egl::GL_COLORSPACE_SRGB as raw::c_int {
+            Some(ColorSpace::Srgb)
+        } else if value
+            == ffi::egl::GL_COLORSPACE_SCRGB_LINEAR_EXT as raw::c_int
+        {
+            Some(ColorSpace::ScRgbLinear)
+        } else if value == ffi::egl::GL_COLORSPACE_DISPLAY_P3_EXT as raw::c_int
+        {
+            Some(ColorSpace::DisplayP3)
+        } else {
+            None
+        }
+    }
+
+    /// Binds the color buffer of the pbuffer to the `GL_TEXTURE_2D` texture
+    /// bound in the current context. The pbuffer must be textureable.
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        let surface = self.texture_surface()?;
+        let egl = EGL.as_ref().unwrap();
+        let ret = unsafe {
+            egl.BindTexImage(
+                self.display,
+                surface,
+                ffi::egl::BACK_BUFFER as raw::c_int,
+            )
+        };
+        if ret == ffi::egl::FALSE {
+            return Err(debug::context_error(
+                "eglBindTexImage",
+                "eglBindTexImage failed",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Releases the color buffer bound with `bind_tex_image`.
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        let surface = self.texture_surface()?;
+        let egl = EGL.as_ref().unwrap();
+        let ret = unsafe {
+            egl.ReleaseTexImage(
+                self.display,
+                surface,
+                ffi::egl::BACK_BUFFER as raw::c_int,
+            )
+        };
+        if ret == ffi::egl::FALSE {
+            return Err(debug::context_error(
+                "eglReleaseTexImage",
+                "eglReleaseTexImage failed",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Waits for the rendering of the client API to complete before native
+    /// rendering to the surface.
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        self.wait("eglWaitClient", |egl| unsafe { egl.WaitClient() })
+    }
+
+    /// Waits for native rendering to the surface to complete before client
+    /// API rendering.
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        self.wait("eglWaitNative", |egl| unsafe {
+            egl.WaitNative(ffi::egl::CORE_NATIVE_ENGINE as raw::c_int)
+        })
+    }
+
+    fn wait<F>(&self, cmd: &str, f: F) -> Result<(), ContextError>
+    where
+        F: FnOnce(&ffi::egl::Egl) -> ffi::egl::types::EGLBoolean,
+    {
+        if !self.is_current() {
+            return Err(ContextError::OsError(format!(
+                "The context must be current to call `{}`",
+                cmd
+            )));
+        }
+
+        let egl = EGL.as_ref().unwrap();
+        if f(egl) == ffi::egl::FALSE {
+            return Err(debug::context_error(cmd, &format!("{} failed", cmd)));
+        }
+        Ok(())
+    }
+
+    /// Returns the size of the pbuffer, which may be smaller than requested
+    /// with `EGL_LARGEST_PBUFFER`.
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        let surface = match self.surface {
+            Some(ref surface) if self.pbuffer => *surface.lock(),
+            _ => return Err(ContextError::FunctionUnavailable),
+        };
+
+        let egl = EGL.as_ref().unwrap();
+        let query = |attrib| {
+            let mut value = 0;
+            let ret = unsafe {
+                egl.QuerySurface(
+                    self.display,
+                    surface,
+                    attrib as raw::c_int,
+                    &mut value,
+                )
+            };
+            if ret == ffi::egl::FALSE {
+                Err(debug::context_error(
+                    "eglQuerySurface",
+                    "eglQuerySurface failed",
+                ))
+            } else {
+                Ok(value as u32)
+            }
+        };
+        Ok(dpi::PhysicalSize::new(
+            query(ffi::egl::WIDTH)?,
+            query(ffi::egl::HEIGHT)?,
+        ))
+    }
+
+    fn texture_surface(
+        &self,
+    ) -> Result<ffi::egl::types::EGLSurface, ContextError> {
+        match self.surface {
+            Some(ref surface) if self.textureable => Ok(*surface.lock()),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    /// Switches the window surface between single and double buffering with
+    /// `EGL_KHR_mutable_render_buffer`. The switch takes effect on the next
+    /// `swap_buffers`.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub fn set_single_buffered(
+        &self,
+        single_buffered: bool,
+    ) -> Result<(), ContextError> {
+        if !self.has_extension("EGL_KHR_mutable_render_buffer") {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let egl = EGL.as_ref().unwrap();
+        let mut surface_type = 0;
+        let ret = unsafe {
+            egl.GetConfigAttrib(
+                self.display,
+                self.config_id,
+                ffi::egl::SURFACE_TYPE as raw::c_int,
+                &mut surface_type,
+            )
+        };
+        if ret == 0
+            || surface_type
+                & ffi::egl::MUTABLE_RENDER_BUFFER_BIT_KHR as raw::c_int
+                == 0
+        {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let surface = match self.surface {
+            Some(ref surface) => *surface.lock(),
+            None => return Err(ContextError::FunctionUnavailable),
+        };
+        if surface == ffi::egl::NO_SURFACE {
+            return Err(ContextError::ContextLost);
+        }
+
+        let buffer = if single_buffered {
+            ffi::egl::SINGLE_BUFFER
+        } else {
+            ffi::egl::BACK_BUFFER
+        };
+        let ret = unsafe {
+            egl.SurfaceAttrib(
+                self.display,
+                surface,
+                ffi::egl::RENDER_BUFFER as raw::c_int,
+                buffer as raw::c_int,
+            )
+        };
+        if ret == 0 {
+            return Err(debug::context_error(
+                "eglSurfaceAttrib",
+                "eglSurfaceAttrib failed",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the context currently renders to the front buffer.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub fn is_single_buffered(&self) -> bool {
+        let egl = EGL.as_ref().unwrap();
+        let mut buffer = 0;
+        let ret = unsafe {
+            egl.QueryContext(
+                self.display,
+                self.context,
+                ffi::egl::RENDER_BUFFER as raw::c_int,
+                &mut buffer,
+            )
+        };
+        ret != 0 && buffer == ffi::egl::SINGLE_BUFFER as raw::c_int
+    }
+
+    pub fn set_damage_region(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        if !self.partial_update_supported {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let egl = EGL.as_ref().unwrap();
+        let surface = self.surface.as_ref().unwrap().lock();
+        if *surface == ffi::egl::NO_SURFACE {
+            return Err(ContextError::ContextLost);
+        }
+
+        unsafe {
+            if egl.GetCurrentContext() != self.context
+                || egl.GetCurrentSurface(ffi::egl::DRAW as i32) != *surface
+            {
+                return Err(ContextError::OsError(
+                    "The context must be current to set the damage region"
+                        .to_string(),
+                ));
+            }
+        }
+
+        // Partial updates don't work with preserved buffers, which are left
+        // to the caller rather than switched to destroyed ones.
+        if unsafe { swap_behavior(self.display, *surface) }
+            == SwapBehavior::Preserved
+        {
+            return Err(ContextError::OsError(
+                "The damage region can't be set on a surface with preserved \
+                 swaps"
+                    .to_string(),
+            ));
+        }
+
+        if self.damage_region_set.swap(true, Ordering::Relaxed) {
+            return Err(ContextError::OsError(
+                "The damage region can only be set once per frame".to_string(),
+            ));
+        }
+
+        let mut ffirects: Vec<ffi::egl::types::EGLint> =
+            Vec::with_capacity(rects.len() * 4);
+
+        for rect in rects {
+            ffirects.push(rect.x as ffi::egl::types::EGLint);
+            ffirects.push(rect.y as ffi::egl::types::EGLint);
+            ffirects.push(rect.width as ffi::egl::types::EGLint);
+            ffirects.push(rect.height as ffi::egl::types::EGLint);
+        }
+
+        let ret = unsafe {
+            egl.SetDamageRegionKHR(
+                self.display,
+                *surface,
+                ffirects.as_mut_ptr(),
+                rects.len() as ffi::egl::types::EGLint,
+            )
+        };
+
+        if ret == ffi::egl::FALSE {
+            match unsafe { egl.GetError() } as u32 {
+                ffi::egl::CONTEXT_LOST => Err(ContextError::ContextLost),
+                ffi::egl::NOT_INITIALIZED => Err(ContextError::DisplayLost),
+                err => {
+                    let (code, message) = debug::error(
+                        "eglSetDamageRegionKHR",
+                        "eglSetDamageRegionKHR failed",
+                        err,
+                    );
+                    Err(ContextError::ApiError(code, message))
+                }
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    pub fn swap_buffers_with_damage(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        let egl = EGL.as_ref().unwrap();
+
+        let extension = match self.swap_damage {
+            Some(extension) => extension,
+            None => return Err(ContextError::FunctionUnavailable),
+        };
+
+        let surface = self.surface.as_ref().unwrap().lock();
+        if *surface == ffi::egl::NO_SURFACE {
+            return Err(ContextError::ContextLost);
+        }
+
+        let mut ffirects: Vec<ffi::egl::types::EGLint> =
+            Vec::with_capacity(rects.len() * 4);
+
+        for rect in rects {
+            ffirects.push(rect.x as ffi::egl::types::EGLint);
+            ffirects.push(rect.y as ffi::egl::types::EGLint);
+            ffirects.push(rect.width as ffi::egl::types::EGLint);
+            ffirects.push(rect.height as ffi::egl::types::EGLint);
+        }
+
+        self.damage_region_set.store(false, Ordering::Relaxed);
+        let ret = unsafe {
+            match extension {
+                SwapDamageExtension::Khr => egl.SwapBuffersWithDamageKHR(
+                    self.display,
+                    *surface,
+                    ffirects.as_mut_ptr(),
+                    rects.len() as ffi::egl::types::EGLint,
+                ),
+                SwapDamageExtension::Ext => egl.SwapBuffersWithDamageEXT(
+                    self.display,
+                    *surface,
+                    ffirects.as_mut_ptr(),
+                    rects.len() as ffi::egl::types::EGLint,
+                ),
+            }
+        };
+
+        if ret == ffi::egl::FALSE {
+            match unsafe { egl.GetError() } as u32 {
+                ffi::egl::CONTEXT_LOST => {
+                    return Err(ContextError::ContextLost)
+                }
+                ffi::egl::NOT_INITIALIZED => {
+                    return Err(ContextError::DisplayLost)
+                }
+                err => {
+                    let command = match extension {
+                        SwapDamageExtension::Khr => {
+                            "eglSwapBuffersWithDamageKHR"
+                        }
+                        SwapDamageExtension::Ext => {
+                            "eglSwapBuffersWithDamageEXT"
+                        }
+                    };
+                    let (code, message) = debug::error(
+                        command,
+                        &format!("{} failed", command),
+                        err,
+                    );
+                    Err(ContextError::ApiError(code, message))
+                }
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        match self.swap_damage {
+            Some(_) => crate::DamageSupport::Native,
+            None => crate::DamageSupport::None,
+        }
+    }
+
+    // EGL has no adaptive swap interval. Other intervals are clamped to the
+    // range supported by the config.
+    pub fn set_swap_interval(
+        &self,
+        interval: SwapInterval,
+    ) -> Result<SwapInterval, ContextError> {
+        let interval = match interval {
+            SwapInterval::DontWait => 0,
+            SwapInterval::Wait(n) => n.get() as raw::c_int,
+            SwapInterval::AdaptiveWait(_) => {
+                return Err(ContextError::FunctionUnavailable)
+            }
+        };
+        let (min, max) = self
+            .swap_interval_range()
+            .ok_or(ContextError::FunctionUnavailable)?;
+        let interval = interval.max(min).min(max);
+
+        let egl = EGL.as_ref().unwrap();
+        if unsafe { egl.SwapInterval(self.display, interval) } == 0 {
+            return Err(debug::context_error(
+                "eglSwapInterval",
+                "eglSwapInterval failed",
+            ));
+        }
+        self.swap_interval.store(interval, Ordering::Relaxed);
+
+        Ok(SwapInterval::from_raw(interval))
+    }
+
+    // EGL can't query the swap interval, so this is the last one set.
+    pub fn get_swap_interval(&self) -> Option<SwapInterval> {
+        self.surface.as_ref()?;
+        Some(SwapInterval::from_raw(
+            self.swap_interval.load(Ordering::Relaxed),
+        ))
+    }
+
+    pub fn supports_swap_interval(&self, interval: SwapInterval) -> bool {
+        let interval = match interval {
+            SwapInterval::DontWait => 0,
+            SwapInterval::Wait(n) => n.get() as raw::c_int,
+            SwapInterval::AdaptiveWait(_) => return false,
+        };
+        match self.swap_interval_range() {
+            Some((min, max)) => min <= interval && interval <= max,
+            None => false,
+        }
+    }
+
+    // Returns the `EGL_MIN_SWAP_INTERVAL` and `EGL_MAX_SWAP_INTERVAL` of the
+    // config, or `None` if there is no surface.
+    fn swap_interval_range(&self) -> Option<(raw::c_int, raw::c_int)> {
+        self.surface.as_ref()?;
+
+        let egl = EGL.as_ref().unwrap();
+        let (mut min, mut max) = (0, 0);
+        unsafe {
+            if egl.GetConfigAttrib(
+                self.display,
+                self.config_id,
+                ffi::egl::MIN_SWAP_INTERVAL as raw::c_int,
+                &mut min,
+            ) == 0
+                || egl.GetConfigAttrib(
+                    self.display,
+                    self.config_id,
+                    ffi::egl::MAX_SWAP_INTERVAL as raw::c_int,
+                    &mut max,
+                ) == 0
+            {
+                return None;
+            }
+        }
+
+        Some((min, max))
+    }
+
+    #[inline]
+    pub fn get_pixel_format(&self) -> PixelFormat {
+        let mut pixel_format = self.pixel_format.clone();
+        // `eglSurfaceAttrib` can change the swap behavior after creation, so
+        // it is queried rather than cached.
+        if let Some(ref surface) = self.surface {
+            let surface = *surface.lock();
+            if surface != ffi::egl::NO_SURFACE {
+                pixel_format.swap_behavior =
+                    unsafe { swap_behavior(self.display, surface) };
+            }
+        }
+        pixel_format
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> ReleaseBehavior {
+        self.release_behavior
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    #[inline]
+    fn has_extension(&self, extension: &str) -> bool {
+        self.extensions.contains(extension)
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+impl Context {
+    /// Whether fences can be created, using `EGL_KHR_fence_sync`.
+    #[inline]
+    pub fn fence_sync_supported(&self) -> bool {
+        self.has_extension("EGL_KHR_fence_sync")
+            && EGL.as_ref().unwrap().CreateSyncKHR.is_loaded()
+    }
+
+    /// Whether fences can be exported to and imported from file descriptors,
+    /// using `EGL_ANDROID_native_fence_sync`.
+    #[inline]
+    pub fn native_fence_fd_supported(&self) -> bool {
+        self.fence_sync_supported()
+            && self.has_extension("EGL_ANDROID_native_fence_sync")
+            && EGL.as_ref().unwrap().DupNativeFenceFDANDROID.is_loaded()
+    }
+
+    /// Inserts a fence into the command stream of the context, which must be
+    /// current. The fence is backed by a native fence if supported.
+    pub fn create_fence(&self) -> Result<Fence<'_>, ContextError> {
+        if !self.fence_sync_supported() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to create a fence".to_string(),
+            ));
+        }
+
+        let native = self.native_fence_fd_supported();
+        let sync_type = if native {
+            ffi::egl::SYNC_NATIVE_FENCE_ANDROID as ffi::egl::types::EGLenum
+        } else {
+            ffi::egl::SYNC_FENCE_KHR as ffi::egl::types::EGLenum
+        };
+        let fence = unsafe { self.create_sync(sync_type, &[])? };
+
+        // The native fence only gets a file descriptor once the commands
+        // before it are flushed.
+        if native {
+            unsafe {
+                let gl_flush_fn = self.get_proc_address("glFlush");
+                if gl_flush_fn.is_null() {
+                    return Err(ContextError::FunctionUnavailable);
+                }
+                let gl_flush_fn =
+                    std::mem::transmute::<_, extern "system" fn()>(gl_flush_fn);
+                gl_flush_fn();
+            }
+        }
+
+        Ok(fence)
+    }
+
+    /// Creates a fence from a native fence file descriptor, for example one
+    /// exported by another process. EGL takes ownership of `fd`.
+    pub fn import_fence_fd(
+        &self,
+        fd: std::os::unix::io::RawFd,
+    ) -> Result<Fence<'_>, ContextError> {
+        if !self.native_fence_fd_supported() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        unsafe {
+            self.create_sync(
+                ffi::egl::SYNC_NATIVE_FENCE_ANDROID as ffi::egl::types::EGLenum,
+                &[ffi::egl::SYNC_NATIVE_FENCE_FD_ANDROID as raw::c_int, fd],
+            )
+        }
+    }
+
+    /// Creates an [`EglImage`] from a level 0 `GL_TEXTURE_2D` texture of the
+    /// context, which must be current.
+    ///
+    /// [`EglImage`]: struct.EglImage.html
+    pub fn create_image_from_texture(
+        &self,
+        texture: u32,
+    ) -> Result<EglImage<'_>, ContextError> {
+        if !self.has_extension("EGL_KHR_gl_texture_2D_image") {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        unsafe {
+            self.create_image(
+                ffi::egl::GL_TEXTURE_2D_KHR as ffi::egl::types::EGLenum,
+                texture,
+                &[ffi::egl::GL_TEXTURE_LEVEL_KHR as raw::c_int, 0],
+            )
+        }
+    }
+
+    /// Creates an [`EglImage`] from a renderbuffer of the context, which must
+    /// be current.
+    ///
+    /// [`EglImage`]: struct.EglImage.html
+    pub fn create_image_from_renderbuffer(
+        &self,
+        renderbuffer: u32,
+    ) -> Result<EglImage<'_>, ContextError> {
+        if !self.has_extension("EGL_KHR_gl_renderbuffer_image") {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        unsafe {
+            self.create_image(
+                ffi::egl::GL_RENDERBUFFER_KHR as ffi::egl::types::EGLenum,
+                renderbuffer,
+                &[],
+            )
+        }
+    }
+
+    unsafe fn create_image(
+        &self,
+        target: ffi::egl::types::EGLenum,
+        buffer: u32,
+        attribs: &[raw::c_int],
+    ) -> Result<EglImage<'_>, ContextError> {
+        let egl = EGL.as_ref().unwrap();
+        if !self.has_extension("EGL_KHR_image_base")
+            || !egl.CreateImageKHR.is_loaded()
+        {
+            return Err(ContextError::FunctionUnavailable);
+        }
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to create an image".to_string(),
+            ));
+        }
+
+        let mut attrib_list = attribs.to_vec();
+        attrib_list.push(ffi::egl::NONE as raw::c_int);
+
+        let image = egl.CreateImageKHR(
+            self.display,
+            self.context,
+            target,
+            buffer as usize as ffi::egl::types::EGLClientBuffer,
+            attrib_list.as_ptr(),
+        );
+        if image.is_null() {
+            return Err(debug::context_error(
+                "eglCreateImageKHR",
+                "eglCreateImageKHR failed",
+            ));
+        }
+
+        Ok(self.wrap_image(image))
+    }
+
+    /// Creates an [`EglImage`] from a dma-buf, for example a video frame, so
+    /// that it can be bound to a texture without copying.
+    ///
+    /// [`EglImage`]: struct.EglImage.html
+    pub fn create_image_from_dma_buf(
+        &self,
+        planes: &[DmaBufPlane],
+        fourcc: u32,
+        width: u32,
+        height: u32,
+        modifier: Option<u64>,
+    ) -> Result<EglImage<'_>, ContextError> {
+        let egl = EGL.as_ref().unwrap();
+        if !self.has_extension("EGL_KHR_image_base")
+            || !self.has_extension("EGL_EXT_image_dma_buf_import")
+            || !egl.CreateImageKHR.is_loaded()
+        {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let modifiers_supported = self
+            .has_extension("EGL_EXT_image_dma_buf_import_modifiers")
+            && egl.QueryDmaBufFormatsEXT.is_loaded()
+            && egl.QueryDmaBufModifiersEXT.is_loaded();
+        let max_planes = if modifiers_supported { 4 } else { 3 };
+        if planes.is_empty() || planes.len() > max_planes {
+            return Err(ContextError::OsError(format!(
+                "Invalid number of dma-buf planes: {} (1 to {} supported)",
+                planes.len(),
+                max_planes
+            )));
+        }
+        if modifier.is_some() && !modifiers_supported {
+            return Err(ContextError::OsError(
+                "dma-buf modifiers are not supported \
+                 (EGL_EXT_image_dma_buf_import_modifiers is missing)"
+                    .to_string(),
+            ));
+        }
+
+        // Find out why the import would fail, if we can.
+        if modifiers_supported {
+            let formats = unsafe { self.query_dma_buf_formats() };
+            if !formats.contains(&(fourcc as raw::c_int)) {
+                return Err(ContextError::OsError(format!(
+                    "Unsupported dma-buf fourcc: 0x{:08x}",
+                    fourcc
+                )));
+            }
+            if let Some(modifier) = modifier {
+                let modifiers = unsafe { self.query_dma_buf_modifiers(fourcc) };
+                if !modifiers.contains(&modifier) {
+                    return Err(ContextError::OsError(format!(
+                        "Unsupported dma-buf modifier 0x{:016x} for fourcc \
+                         0x{:08x}",
+                        modifier, fourcc
+                    )));
+                }
+            }
+        }
+
+        let plane_attribs = [
+            (
+                ffi::egl::DMA_BUF_PLANE0_FD_EXT,
+                ffi::egl::DMA_BUF_PLANE0_OFFSET_EXT,
+                ffi::egl::DMA_BUF_PLANE0_PITCH_EXT,
+                ffi::egl::DMA_BUF_PLANE0_MODIFIER_LO_EXT,
+                ffi::egl::DMA_BUF_PLANE0_MODIFIER_HI_EXT,
+            ),
+            (
+                ffi::egl::DMA_BUF_PLANE1_FD_EXT,
+                ffi::egl::DMA_BUF_PLANE1_OFFSET_EXT,
+                ffi::egl::DMA_BUF_PLANE1_PITCH_EXT,
+                ffi::egl::DMA_BUF_PLANE1_MODIFIER_LO_EXT,
+                ffi::egl::DMA_BUF_PLANE1_MODIFIER_HI_EXT,
+            ),
+            (
+                ffi::egl::DMA_BUF_PLANE2_FD_EXT,
+                ffi::egl::DMA_BUF_PLANE2_OFFSET_EXT,
+                ffi::egl::DMA_BUF_PLANE2_PITCH_EXT,
+                ffi::egl::DMA_BUF_PLANE2_MODIFIER_LO_EXT,
+                ffi::egl::DMA_BUF_PLANE2_MODIFIER_HI_EXT,
+            ),
+            (
+                ffi::egl::DMA_BUF_PLANE3_FD_EXT,
+                ffi::egl::DMA_BUF_PLANE3_OFFSET_EXT,
+                ffi::egl::DMA_BUF_PLANE3_PITCH_EXT,
+                ffi::egl::DMA_BUF_PLANE3_MODIFIER_LO_EXT,
+                ffi::egl::DMA_BUF_PLANE3_MODIFIER_HI_EXT,
+            ),
+        ];
+
+        let mut attrib_list: Vec<raw::c_int> = vec![
+            ffi::egl::WIDTH as raw::c_int,
+            width as raw::c_int,
+            ffi::egl::HEIGHT as raw::c_int,
+            height as raw::c_int,
+            ffi::egl::LINUX_DRM_FOURCC_EXT as raw::c_int,
+            fourcc as raw::c_int,
+        ];
+        for (plane, &(fd, offset, pitch, modifier_lo, modifier_hi)) in
+            planes.iter().zip(plane_attribs.iter())
+        {
+            attrib_list.push(fd as raw::c_int);
+            attrib_list.push(plane.fd);
+            attrib_list.push(offset as raw::c_int);
+            attrib_list.push(plane.offset as raw::c_int);
+            attrib_list.push(pitch as raw::c_int);
+            attrib_list.push(plane.stride as raw::c_int);
+            if let Some(modifier) = modifier {
+                attrib_list.push(modifier_lo as raw::c_int);
+                attrib_list.push(modifier as u32 as raw::c_int);
+                attrib_list.push(modifier_hi as raw::c_int);
+                attrib_list.push((modifier >> 32) as u32 as raw::c_int);
+            }
+        }
+        attrib_list.push(ffi::egl::NONE as raw::c_int);
+
+        let image = unsafe {
+            egl.CreateImageKHR(
+                self.display,
+                ffi::egl::NO_CONTEXT,
+                ffi::egl::LINUX_DMA_BUF_EXT as ffi::egl::types::EGLenum,
+                std::ptr::null(),
+                attrib_list.as_ptr(),
+            )
+        };
+        if image.is_null() {
+            return Err(debug::context_error(
+                "eglCreateImageKHR",
+                "eglCreateImageKHR failed to import the dma-buf",
+            ));
+        }
+
+        Ok(self.wrap_image(image))
+    }
+
+    unsafe fn query_dma_buf_formats(&self) -> Vec<raw::c_int> {
+        let egl = EGL.as_ref().unwrap();
+        let mut num_formats = 0;
+        egl.QueryDmaBufFormatsEXT(
+            self.display,
+            0,
+            std::ptr::null_mut(),
+            &mut num_formats,
+        );
+        let mut formats = vec![0; num_formats as usize];
+        egl.QueryDmaBufFormatsEXT(
+            self.display,
+            num_formats,
+            formats.as_mut_ptr(),
+            &mut num_formats,
+        );
+        formats.truncate(num_formats as usize);
+        formats
+    }
+
+    unsafe fn query_dma_buf_modifiers(&self, fourcc: u32) -> Vec<u64> {
+        let egl = EGL.as_ref().unwrap();
+        let mut num_modifiers = 0;
+        egl.QueryDmaBufModifiersEXT(
+            self.display,
+            fourcc as raw::c_int,
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            &mut num_modifiers,
+        );
+        let mut modifiers = vec![0; num_modifiers as usize];
+        egl.QueryDmaBufModifiersEXT(
+            self.display,
+            fourcc as raw::c_int,
+            num_modifiers,
+            modifiers.as_mut_ptr(),
+            std::ptr::null_mut(),
+            &mut num_modifiers,
+        );
+        modifiers.truncate(num_modifiers as usize);
+        modifiers
+    }
+
+    fn wrap_image(&self, image: ffi::egl::types::EGLImageKHR) -> EglImage<'_> {
+        let egl = EGL.as_ref().unwrap();
+        EglImage {
+            display: self.display,
+            image,
+            dma_buf_export_supported: self
+                .has_extension("EGL_MESA_image_dma_buf_export")
+                && egl.ExportDMABUFImageMESA.is_loaded(),
+            _context: std::marker::PhantomData,
+        }
+    }
+
+    unsafe fn create_sync(
+        &self,
+        sync_type: ffi::egl::types::EGLenum,
+        attribs: &[raw::c_int],
+    ) -> Result<Fence<'_>, ContextError> {
+        let egl = EGL.as_ref().unwrap();
+        let mut attrib_list = attribs.to_vec();
+        attrib_list.push(ffi::egl::NONE as raw::c_int);
+
+        let sync =
+            egl.CreateSyncKHR(self.display, sync_type, attrib_list.as_ptr());
+        if sync.is_null() {
+            return Err(debug::context_error(
+                "eglCreateSyncKHR",
+                "eglCreateSyncKHR failed",
+            ));
+        }
+
+        Ok(Fence {
+            display: self.display,
+            sync,
+            native: sync_type
+                == ffi::egl::SYNC_NATIVE_FENCE_ANDROID
+                    as ffi::egl::types::EGLenum,
+            wait_sync_supported: self.has_extension("EGL_KHR_wait_sync")
+                && egl.WaitSyncKHR.is_loaded(),
+            _context: std::marker::PhantomData,
+        })
+    }
+}
+
+/// A fence in the command stream of an EGL context, which is signaled once
+/// the GPU has executed every command before it.
+///
+/// The fence borrows the context, as it must be destroyed before the display
+/// of the context may be terminated.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+#[derive(Debug)]
+pub struct Fence<'a> {
+    display: ffi::egl::types::EGLDisplay,
+    sync: ffi::egl::types::EGLSyncKHR,
+    native: bool,
+    wait_sync_supported: bool,
+    _context: std::marker::PhantomData<&'a Context>,
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+unsafe impl<'a> Send for Fence<'a> {}
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+unsafe impl<'a> Sync for Fence<'a> {}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+impl<'a> Fence<'a> {
+    /// Blocks the calling thread until the fence is signaled or `timeout`
+    /// elapses. `None` waits forever.
+    ///
+    /// Returns whether the fence was signaled.
+    pub fn client_wait(
+        &self,
+        timeout: Option<std::time::Duration>,
+    ) -> Result<bool, ContextError> {
+        let egl = EGL.as_ref().unwrap();
+        let timeout = timeout
+            .map(|timeout| {
+                timeout.as_secs() * 1_000_000_000
+                    + timeout.subsec_nanos() as u64
+            })
+            .unwrap_or(u64::max_value());
+
+        let ret = unsafe {
+            egl.ClientWaitSyncKHR(
+                self.display,
+                self.sync,
+                ffi::egl::SYNC_FLUSH_COMMANDS_BIT as raw::c_int,
+                timeout,
+            )
+        };
+
+        if ret == ffi::egl::CONDITION_SATISFIED as raw::c_int {
+            Ok(true)
+        } else if ret == ffi::egl::TIMEOUT_EXPIRED as raw::c_int {
+            Ok(false)
+        } else {
+            Err(debug::context_error(
+                "eglClientWaitSyncKHR",
+                "eglClientWaitSyncKHR failed",
+            ))
+        }
+    }
+
+    /// Makes the GPU wait for the fence before executing any further commands
+    /// of the current context, without blocking the calling thread. Requires
+    /// `EGL_KHR_wait_sync`.
+    pub fn server_wait(&self) -> Result<(), ContextError> {
+        if !self.wait_sync_supported {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let egl = EGL.as_ref().unwrap();
+        let ret = unsafe { egl.WaitSyncKHR(self.display, self.sync, 0) };
+        if ret == ffi::egl::FALSE as raw::c_int {
+            return Err(debug::context_error(
+                "eglWaitSyncKHR",
+                "eglWaitSyncKHR failed",
+            ));
+        }
+        Ok(())
     }
 
+    /// Returns whether the fence is backed by a native fence, and so can be
+    /// exported with [`export_fd`].
+    ///
+    /// [`export_fd`]: #method.export_fd
     #[inline]
-    pub fn get_proc_address(&self, addr: &str) -> *const core::ffi::c_void {
-        let egl = EGL.as_ref().unwrap();
-        let addr = CString::new(addr.as_bytes()).unwrap();
-        let addr = addr.as_ptr();
-        unsafe { egl.GetProcAddress(addr) as *const _ }
+    pub fn is_native(&self) -> bool {
+        self.native
     }
 
-    #[inline]
-    pub fn swap_buffers(&self) -> Result<(), ContextError> {
-        let egl = EGL.as_ref().unwrap();
-        let surface = self.surface.as_ref().unwrap().lock();
-        if *surface == ffi::egl::NO_SURFACE {
-            return Err(ContextError::ContextLost);
+    /// Returns a new file descriptor for the native fence, which the caller
+    /// owns. It can be passed to another process and imported there.
+    pub fn export_fd(&self) -> Result<std::os::unix::io::RawFd, ContextError> {
+        if !self.native {
+            return Err(ContextError::FunctionUnavailable);
         }
 
-        let ret = unsafe { egl.SwapBuffers(self.display, *surface) };
+        // EGL_NO_NATIVE_FENCE_FD_ANDROID
+        const NO_NATIVE_FENCE_FD: raw::c_int = -1;
 
-        if ret == 0 {
-            match unsafe { egl.GetError() } as u32 {
-                ffi::egl::CONTEXT_LOST => {
-                    return Err(ContextError::ContextLost)
-                }
-                err => panic!(
-                    "swap_buffers: eglSwapBuffers failed (eglGetError returned 0x{:x})",
-                    err
-                ),
-            }
-        } else {
-            Ok(())
+        let egl = EGL.as_ref().unwrap();
+        let fd =
+            unsafe { egl.DupNativeFenceFDANDROID(self.display, self.sync) };
+        if fd == NO_NATIVE_FENCE_FD {
+            return Err(debug::context_error(
+                "eglDupNativeFenceFDANDROID",
+                "eglDupNativeFenceFDANDROID failed",
+            ));
         }
+        Ok(fd)
     }
+}
 
-    #[inline]
-    pub fn swap_buffers_with_damage(
-        &self,
-        rects: &[Rect],
-    ) -> Result<(), ContextError> {
-        let egl = EGL.as_ref().unwrap();
+/// An EGL image made from a texture or renderbuffer, which can be exported as
+/// a dma-buf.
+///
+/// The image borrows the context, as it must be destroyed before the display
+/// of the context may be terminated.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+#[derive(Debug)]
+pub struct EglImage<'a> {
+    display: ffi::egl::types::EGLDisplay,
+    image: ffi::egl::types::EGLImageKHR,
+    dma_buf_export_supported: bool,
+    _context: std::marker::PhantomData<&'a Context>,
+}
 
-        if !egl.SwapBuffersWithDamageKHR.is_loaded() {
-            return Err(ContextError::FunctionUnavailable);
-        }
+/// A dma-buf exported from an [`EglImage`].
+///
+/// The file descriptors are owned by the caller, who must close them.
+///
+/// [`EglImage`]: struct.EglImage.html
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmaBuf {
+    /// The DRM fourcc code of the format of the image.
+    pub fourcc: u32,
+    /// The DRM format modifier of the image.
+    pub modifier: u64,
+    /// The planes of the image.
+    pub planes: Vec<DmaBufPlane>,
+}
 
-        let surface = self.surface.as_ref().unwrap().lock();
-        if *surface == ffi::egl::NO_SURFACE {
-            return Err(ContextError::ContextLost);
-        }
+/// A plane of a [`DmaBuf`].
+///
+/// [`DmaBuf`]: struct.DmaBuf.html
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DmaBufPlane {
+    /// The file descriptor of the plane.
+    pub fd: std::os::unix::io::RawFd,
+    /// The number of bytes between the start of two consecutive rows.
+    pub stride: u32,
+    /// The offset of the plane in the buffer, in bytes.
+    pub offset: u32,
+}
 
-        let mut ffirects: Vec<ffi::egl::types::EGLint> =
-            Vec::with_capacity(rects.len() * 4);
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+unsafe impl<'a> Send for EglImage<'a> {}
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+unsafe impl<'a> Sync for EglImage<'a> {}
 
-        for rect in rects {
-            ffirects.push(rect.x as ffi::egl::types::EGLint);
-            ffirects.push(rect.y as ffi::egl::types::EGLint);
-            ffirects.push(rect.width as ffi::egl::types::EGLint);
-            ffirects.push(rect.height as ffi::egl::types::EGLint);
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+impl<'a> EglImage<'a> {
+    /// Returns the underlying `EGLImageKHR`, for example to pass it to
+    /// `glEGLImageTargetTexture2DOES`, which can be loaded with the
+    /// `get_proc_address` of the context.
+    #[inline]
+    pub fn raw_image(&self) -> ffi::egl::types::EGLImageKHR {
+        self.image
+    }
+
+    /// Exports the image as a dma-buf. Requires
+    /// `EGL_MESA_image_dma_buf_export`.
+    ///
+    /// Every call returns new file descriptors, which are not closed when the
+    /// image is dropped.
+    pub fn export_dma_buf(&self) -> Result<DmaBuf, ContextError> {
+        if !self.dma_buf_export_supported {
+            return Err(ContextError::FunctionUnavailable);
         }
 
-        let ret = unsafe {
-            egl.SwapBuffersWithDamageKHR(
+        let egl = EGL.as_ref().unwrap();
+        unsafe {
+            let mut fourcc = 0;
+            let mut num_planes = 0;
+            if egl.ExportDMABUFImageQueryMESA(
                 self.display,
-                *surface,
-                ffirects.as_mut_ptr(),
-                rects.len() as ffi::egl::types::EGLint,
-            )
-        };
+                self.image,
+                &mut fourcc,
+                &mut num_planes,
+                std::ptr::null_mut(),
+            ) == ffi::egl::FALSE
+            {
+                return Err(debug::context_error(
+                    "eglExportDMABUFImageQueryMESA",
+                    "eglExportDMABUFImageQueryMESA failed",
+                ));
+            }
 
-        if ret == ffi::egl::FALSE {
-            match unsafe { egl.GetError() } as u32 {
-                ffi::egl::CONTEXT_LOST => {
-                    return Err(ContextError::ContextLost)
-                }
-                err => panic!(
-                    "swap_buffers: eglSwapBuffers failed (eglGetError returned 0x{:x})",
-                    err
-                ),
+            // There is one modifier per plane, but they are all the same.
+            let num_planes = num_planes as usize;
+            let mut modifiers = vec![0; num_planes];
+            if egl.ExportDMABUFImageQueryMESA(
+                self.display,
+                self.image,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                modifiers.as_mut_ptr(),
+            ) == ffi::egl::FALSE
+            {
+                return Err(debug::context_error(
+                    "eglExportDMABUFImageQueryMESA",
+                    "eglExportDMABUFImageQueryMESA failed",
+                ));
             }
-        } else {
-            Ok(())
+
+            let mut fds = vec![-1; num_planes];
+            let mut strides = vec![0; num_planes];
+            let mut offsets = vec![0; num_planes];
+            if egl.ExportDMABUFImageMESA(
+                self.display,
+                self.image,
+                fds.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                offsets.as_mut_ptr(),
+            ) == ffi::egl::FALSE
+            {
+                return Err(debug::context_error(
+                    "eglExportDMABUFImageMESA",
+                    "eglExportDMABUFImageMESA failed",
+                ));
+            }
+
+            Ok(DmaBuf {
+                fourcc: fourcc as u32,
+                modifier: modifiers.first().cloned().unwrap_or(0) as u64,
+                planes: (0..num_planes)
+                    .map(|i| DmaBufPlane {
+                        fd: fds[i],
+                        stride: strides[i] as u32,
+                        offset: offsets[i] as u32,
+                    })
+                    .collect(),
+            })
         }
     }
+}
 
-    #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+impl<'a> Drop for EglImage<'a> {
+    fn drop(&mut self) {
         let egl = EGL.as_ref().unwrap();
-        egl.SwapBuffersWithDamageKHR.is_loaded()
+        unsafe {
+            egl.DestroyImageKHR(self.display, self.image);
+        }
     }
+}
 
-    #[inline]
-    pub fn get_pixel_format(&self) -> PixelFormat {
-        self.pixel_format.clone()
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+impl<'a> Drop for Fence<'a> {
+    fn drop(&mut self) {
+        let egl = EGL.as_ref().unwrap();
+        unsafe {
+            egl.DestroySyncKHR(self.display, self.sync);
+        }
     }
 }
 
@@ -762,22 +2726,33 @@ impl Drop for Context {
             // Ok, so we got to call `glFinish` before destroying the context
             // to ensure it actually gets destroyed. This requires making the
             // this context current.
-            let mut guard = MakeCurrentGuard::new(
+            //
+            // If the display was terminated behind our back, nothing can be
+            // made current anymore, so just release what's left.
+            let _guard = match MakeCurrentGuard::new(
                 self.display,
                 surface,
                 surface,
                 self.context,
-            )
-            .map_err(|err| ContextError::OsError(err))
-            .unwrap();
-
-            guard.if_any_same_then_invalidate(surface, surface, self.context);
+            ) {
+                Ok(mut guard) => {
+                    guard.if_any_same_then_invalidate(
+                        surface,
+                        surface,
+                        self.context,
+                    );
 
-            let gl_finish_fn = self.get_proc_address("glFinish");
-            assert!(gl_finish_fn != std::ptr::null());
-            let gl_finish_fn =
-                std::mem::transmute::<_, extern "system" fn()>(gl_finish_fn);
-            gl_finish_fn();
+                    let gl_finish_fn = self.get_proc_address("glFinish");
+                    assert!(gl_finish_fn != std::ptr::null());
+                    let gl_finish_fn = std::mem::transmute::<
+                        _,
+                        extern "system" fn(),
+                    >(gl_finish_fn);
+                    gl_finish_fn();
+                    Some(guard)
+                }
+                Err(_) => None,
+            };
 
             egl.DestroyContext(self.display, self.context);
             self.context = ffi::egl::NO_CONTEXT;
@@ -786,6 +2761,9 @@ impl Drop for Context {
                 let mut surface = surface.lock();
                 *surface = ffi::egl::NO_SURFACE;
             }
+            if let Some(stream) = self.stream.take() {
+                egl.DestroyStreamKHR(self.display, stream);
+            }
 
             // In a reasonable world, we could uncomment the line bellow.
             //
@@ -849,11 +2827,16 @@ pub struct ContextPrototype<'a> {
     opengl: &'a GlAttributes<&'a Context>,
     display: ffi::egl::types::EGLDisplay,
     egl_version: (ffi::egl::types::EGLint, ffi::egl::types::EGLint),
-    extensions: Vec<String>,
+    extensions: Extensions,
     api: Api,
     version: Option<(u8, u8)>,
     config_id: ffi::egl::types::EGLConfig,
     pixel_format: PixelFormat,
+    color_space: Option<ColorSpace>,
+    release_behavior: ReleaseBehavior,
+    texture_format: Option<ffi::egl::types::EGLenum>,
+    preserved_swap: bool,
+    largest_pbuffer: bool,
 }
 
 #[cfg(any(
@@ -899,28 +2882,60 @@ impl<'a> ContextPrototype<'a> {
     }
 
     pub fn finish(
-        self,
+        mut self,
         nwin: ffi::EGLNativeWindowType,
     ) -> Result<Context, CreationError> {
         let egl = EGL.as_ref().unwrap();
+        let mut attrs = Vec::with_capacity(5);
+        if let Some(color_space) = self.color_space {
+            attrs.extend_from_slice(&color_space_attributes(color_space));
+        }
+        if self.opengl.protected_content {
+            attrs.push(ffi::egl::PROTECTED_CONTENT_EXT as raw::c_int);
+            attrs.push(ffi::egl::TRUE as raw::c_int);
+        }
+        attrs.push(ffi::egl::NONE as raw::c_int);
+
         let surface = unsafe {
             let surface = egl.CreateWindowSurface(
                 self.display,
                 self.config_id,
                 nwin,
-                std::ptr::null(),
+                attrs.as_ptr(),
             );
             if surface.is_null() {
-                return Err(CreationError::OsError(
-                    "eglCreateWindowSurface failed".to_string(),
-                ));
+                return Err(
+                    self.surface_creation_error("eglCreateWindowSurface")
+                );
             }
             surface
         };
 
+        if self.color_space == Some(ColorSpace::Srgb) {
+            self.pixel_format.srgb = true;
+        }
         self.finish_impl(Some(surface))
     }
 
+    // Turns a failure to create a surface into a more precise error if it
+    // was caused by a color space the config can't render to.
+    fn surface_creation_error(&self, command: &str) -> CreationError {
+        let err = unsafe { EGL.as_ref().unwrap().GetError() } as u32;
+        match self.color_space {
+            Some(color_space) if err == ffi::egl::BAD_MATCH => {
+                CreationError::NotSupported(format!(
+                    "The chosen EGL config doesn't support the {:?} color space",
+                    color_space
+                ))
+            }
+            _ => {
+                let (code, message) =
+                    debug::error(command, &format!("{} failed", command), err);
+                CreationError::ApiError(code, message)
+            }
+        }
+    }
+
     #[cfg(any(
         target_os = "linux",
         target_os = "dragonfly",
@@ -931,12 +2946,7 @@ impl<'a> ContextPrototype<'a> {
     pub fn finish_surfaceless(self) -> Result<Context, CreationError> {
         // FIXME: Also check for the GL_OES_surfaceless_context *CONTEXT*
         // extension
-        if self
-            .extensions
-            .iter()
-            .find(|s| s == &"EGL_KHR_surfaceless_context")
-            .is_none()
-        {
+        if !self.extensions.contains("EGL_KHR_surfaceless_context") {
             Err(CreationError::NotSupported(
                 "EGL surfaceless not supported".to_string(),
             ))
@@ -955,51 +2965,402 @@ impl<'a> ContextPrototype<'a> {
         target_os = "openbsd",
     ))]
     pub fn finish_pbuffer(
-        self,
+        mut self,
         size: dpi::PhysicalSize<u32>,
     ) -> Result<Context, CreationError> {
         let size: (u32, u32) = size.into();
 
-        let egl = EGL.as_ref().unwrap();
-        let tex_fmt = if self.pixel_format.alpha_bits > 0 {
-            ffi::egl::TEXTURE_RGBA
-        } else {
-            ffi::egl::TEXTURE_RGB
-        };
-        let attrs = &[
+        let egl = EGL.as_ref().unwrap();
+        let mut attrs = vec![
+            ffi::egl::WIDTH as raw::c_int,
+            size.0 as raw::c_int,
+            ffi::egl::HEIGHT as raw::c_int,
+            size.1 as raw::c_int,
+        ];
+        if self.largest_pbuffer {
+            attrs.push(ffi::egl::LARGEST_PBUFFER as raw::c_int);
+            attrs.push(ffi::egl::TRUE as raw::c_int);
+        } else {
+            self.check_pbuffer_size(size)?;
+        }
+        if let Some(texture_format) = self.texture_format {
+            attrs.push(ffi::egl::TEXTURE_FORMAT as raw::c_int);
+            attrs.push(texture_format as raw::c_int);
+            attrs.push(ffi::egl::TEXTURE_TARGET as raw::c_int);
+            attrs.push(ffi::egl::TEXTURE_2D as raw::c_int);
+        }
+        if let Some(color_space) = self.color_space {
+            attrs.extend_from_slice(&color_space_attributes(color_space));
+        }
+        if self.opengl.protected_content {
+            attrs.push(ffi::egl::PROTECTED_CONTENT_EXT as raw::c_int);
+            attrs.push(ffi::egl::TRUE as raw::c_int);
+        }
+        attrs.push(ffi::egl::NONE as raw::c_int);
+
+        let surface = unsafe {
+            let surface = egl.CreatePbufferSurface(
+                self.display,
+                self.config_id,
+                attrs.as_ptr(),
+            );
+            if surface.is_null() || surface == ffi::egl::NO_SURFACE {
+                return Err(
+                    self.surface_creation_error("eglCreatePbufferSurface")
+                );
+            }
+            surface
+        };
+
+        if self.color_space == Some(ColorSpace::Srgb) {
+            self.pixel_format.srgb = true;
+        }
+        let textureable = self.texture_format.is_some();
+        let mut context = self.finish_impl(Some(surface))?;
+        context.pbuffer = true;
+        context.textureable = textureable;
+        Ok(context)
+    }
+
+    // Fails with a readable error instead of `EGL_BAD_ALLOC` if the pbuffer
+    // is larger than what the config supports.
+    fn check_pbuffer_size(
+        &self,
+        size: (u32, u32),
+    ) -> Result<(), CreationError> {
+        let egl = EGL.as_ref().unwrap();
+        let attrib = |attrib| {
+            let mut value = 0;
+            let ret = unsafe {
+                egl.GetConfigAttrib(
+                    self.display,
+                    self.config_id,
+                    attrib as raw::c_int,
+                    &mut value,
+                )
+            };
+            if ret == ffi::egl::FALSE {
+                Err(debug::creation_error(
+                    "eglGetConfigAttrib",
+                    "eglGetConfigAttrib failed",
+                ))
+            } else {
+                Ok(value as u64)
+            }
+        };
+        crate::check_pbuffer_size(
+            size,
+            attrib(ffi::egl::MAX_PBUFFER_WIDTH)?,
+            attrib(ffi::egl::MAX_PBUFFER_HEIGHT)?,
+            attrib(ffi::egl::MAX_PBUFFER_PIXELS)?,
+        )
+    }
+
+    /// Creates a surface for a native X11 pixmap, with
+    /// `eglCreatePlatformPixmapSurface` if the display was created for the
+    /// X11 platform.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub fn finish_pixmap(
+        mut self,
+        pixmap: raw::c_ulong,
+    ) -> Result<Context, CreationError> {
+        let egl = EGL.as_ref().unwrap();
+        let mut attrs = Vec::with_capacity(5);
+        if let Some(color_space) = self.color_space {
+            attrs.extend_from_slice(&color_space_attributes(color_space));
+        }
+        if self.opengl.protected_content {
+            attrs.push(ffi::egl::PROTECTED_CONTENT_EXT as raw::c_int);
+            attrs.push(ffi::egl::TRUE as raw::c_int);
+        }
+        attrs.push(ffi::egl::NONE as raw::c_int);
+
+        // the platform functions take a pointer to the `Pixmap`, while
+        // `eglCreatePixmapSurface` takes the `Pixmap` itself
+        let client_extensions = get_client_extensions();
+        let has_client_extension =
+            |e: &str| client_extensions.iter().any(|s| s == e);
+        let mut native_pixmap = pixmap;
+        let (command, surface) = unsafe {
+            if has_client_extension("EGL_KHR_platform_x11")
+                && egl.GetPlatformDisplay.is_loaded()
+                && egl.CreatePlatformPixmapSurface.is_loaded()
+            {
+                let attrs: Vec<ffi::egl::types::EGLAttrib> =
+                    attrs.iter().map(|&a| a as _).collect();
+                (
+                    "eglCreatePlatformPixmapSurface",
+                    egl.CreatePlatformPixmapSurface(
+                        self.display,
+                        self.config_id,
+                        &mut native_pixmap as *mut _ as *mut _,
+                        attrs.as_ptr(),
+                    ),
+                )
+            } else if has_client_extension("EGL_EXT_platform_x11")
+                && egl.GetPlatformDisplayEXT.is_loaded()
+                && egl.CreatePlatformPixmapSurfaceEXT.is_loaded()
+            {
+                (
+                    "eglCreatePlatformPixmapSurfaceEXT",
+                    egl.CreatePlatformPixmapSurfaceEXT(
+                        self.display,
+                        self.config_id,
+                        &mut native_pixmap as *mut _ as *mut _,
+                        attrs.as_ptr(),
+                    ),
+                )
+            } else {
+                (
+                    "eglCreatePixmapSurface",
+                    egl.CreatePixmapSurface(
+                        self.display,
+                        self.config_id,
+                        pixmap as ffi::EGLNativePixmapType,
+                        attrs.as_ptr(),
+                    ),
+                )
+            }
+        };
+        if surface.is_null() || surface == ffi::egl::NO_SURFACE {
+            return Err(self.surface_creation_error(command));
+        }
+
+        // pixmaps are single-buffered
+        self.pixel_format.double_buffer = false;
+        if self.color_space == Some(ColorSpace::Srgb) {
+            self.pixel_format.srgb = true;
+        }
+        self.finish_impl(Some(surface))
+    }
+
+    /// Creates a stream producer surface whose frames are presented on an
+    /// output layer of the display, found with `EGL_EXT_output_drm`. If
+    /// `plane` is `Some`, the layer of that DRM plane is used, otherwise the
+    /// first layer.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub fn finish_stream(
+        mut self,
+        plane: Option<u32>,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<Context, CreationError> {
+        let egl = EGL.as_ref().unwrap();
+        for extension in &[
+            "EGL_EXT_output_base",
+            "EGL_EXT_output_drm",
+            "EGL_KHR_stream",
+            "EGL_KHR_stream_producer_eglsurface",
+            "EGL_EXT_stream_consumer_egloutput",
+        ] {
+            if !self.extensions.contains(extension) {
+                return Err(CreationError::NotSupported(format!(
+                    "EGLStream output requires {}",
+                    extension
+                )));
+            }
+        }
+        if !egl.GetOutputLayersEXT.is_loaded()
+            || !egl.CreateStreamKHR.is_loaded()
+            || !egl.DestroyStreamKHR.is_loaded()
+            || !egl.StreamConsumerOutputEXT.is_loaded()
+            || !egl.CreateStreamProducerSurfaceKHR.is_loaded()
+        {
+            return Err(CreationError::NotSupported(
+                "EGLStream output functions not available".to_string(),
+            ));
+        }
+
+        let mut layer_attrs = Vec::with_capacity(3);
+        if let Some(plane) = plane {
+            layer_attrs
+                .push(ffi::egl::DRM_PLANE_EXT as ffi::egl::types::EGLAttrib);
+            layer_attrs.push(plane as ffi::egl::types::EGLAttrib);
+        }
+        layer_attrs.push(ffi::egl::NONE as ffi::egl::types::EGLAttrib);
+
+        let mut layer = std::ptr::null();
+        let mut num_layers = 0;
+        if unsafe {
+            egl.GetOutputLayersEXT(
+                self.display,
+                layer_attrs.as_ptr(),
+                &mut layer,
+                1,
+                &mut num_layers,
+            )
+        } == ffi::egl::FALSE
+        {
+            return Err(debug::creation_error(
+                "eglGetOutputLayersEXT",
+                "eglGetOutputLayersEXT failed",
+            ));
+        }
+        if num_layers == 0 {
+            return Err(CreationError::OsError(match plane {
+                Some(plane) => {
+                    format!("No EGL output layer found for DRM plane {}", plane)
+                }
+                None => "No EGL output layers found".to_string(),
+            }));
+        }
+
+        let stream_attrs = [ffi::egl::NONE as raw::c_int];
+        let stream =
+            unsafe { egl.CreateStreamKHR(self.display, stream_attrs.as_ptr()) };
+        if stream.is_null() {
+            return Err(debug::creation_error(
+                "eglCreateStreamKHR",
+                "eglCreateStreamKHR failed",
+            ));
+        }
+
+        let size: (u32, u32) = size.into();
+        let mut surface_attrs = vec![
             ffi::egl::WIDTH as raw::c_int,
             size.0 as raw::c_int,
             ffi::egl::HEIGHT as raw::c_int,
             size.1 as raw::c_int,
-            ffi::egl::NONE as raw::c_int,
         ];
-
+        if let Some(color_space) = self.color_space {
+            surface_attrs
+                .extend_from_slice(&color_space_attributes(color_space));
+        }
+        if self.opengl.protected_content {
+            surface_attrs.push(ffi::egl::PROTECTED_CONTENT_EXT as raw::c_int);
+            surface_attrs.push(ffi::egl::TRUE as raw::c_int);
+        }
+        surface_attrs.push(ffi::egl::NONE as raw::c_int);
         let surface = unsafe {
-            let surface = egl.CreatePbufferSurface(
+            if egl.StreamConsumerOutputEXT(self.display, stream, layer)
+                == ffi::egl::FALSE
+            {
+                let err = debug::creation_error(
+                    "eglStreamConsumerOutputEXT",
+                    "eglStreamConsumerOutputEXT failed",
+                );
+                egl.DestroyStreamKHR(self.display, stream);
+                return Err(err);
+            }
+
+            let surface = egl.CreateStreamProducerSurfaceKHR(
                 self.display,
                 self.config_id,
-                attrs.as_ptr(),
+                stream,
+                surface_attrs.as_ptr(),
             );
             if surface.is_null() || surface == ffi::egl::NO_SURFACE {
-                return Err(CreationError::OsError(
-                    "eglCreatePbufferSurface failed".to_string(),
-                ));
+                let err = self.surface_creation_error(
+                    "eglCreateStreamProducerSurfaceKHR",
+                );
+                egl.DestroyStreamKHR(self.display, stream);
+                return Err(err);
             }
             surface
         };
 
-        self.finish_impl(Some(surface))
+        if self.color_space == Some(ColorSpace::Srgb) {
+            self.pixel_format.srgb = true;
+        }
+        let display = self.display;
+        match self.finish_impl(Some(surface)) {
+            Ok(mut context) => {
+                context.stream = Some(stream);
+                Ok(context)
+            }
+            Err(err) => {
+                unsafe {
+                    egl.DestroySurface(display, surface);
+                    egl.DestroyStreamKHR(display, stream);
+                }
+                Err(err)
+            }
+        }
     }
 
     fn finish_impl(
         self,
         surface: Option<ffi::egl::types::EGLSurface>,
     ) -> Result<Context, CreationError> {
+        let mut pixel_format = self.pixel_format;
+        if let Some(surface) = surface {
+            if self.preserved_swap {
+                unsafe { preserve_swap(self.display, surface) }?;
+            }
+            pixel_format.swap_behavior =
+                unsafe { swap_behavior(self.display, surface) };
+        }
+
         let share = match self.opengl.sharing {
             Some(ctx) => ctx.context,
             None => std::ptr::null(),
         };
 
+        // `create_context` only honors `Robustness::NoError` with these
+        // extensions.
+        let no_error = self.opengl.robustness == Robustness::NoError
+            && (self.egl_version >= (1, 5)
+                || self.extensions.contains("EGL_KHR_create_context"))
+            && self.extensions.contains("EGL_KHR_create_context_no_error");
+        if no_error && self.opengl.debug {
+            return Err(CreationError::NotSupported(
+                "No-error contexts can't be debug contexts".to_string(),
+            ));
+        }
+        if self.release_behavior == ReleaseBehavior::None
+            && !self.extensions.contains("EGL_KHR_context_flush_control")
+        {
+            return Err(CreationError::ReleaseBehaviorNotSupported);
+        }
+        if let Some(ctx) = self.opengl.sharing {
+            if ctx.no_error != no_error {
+                return Err(CreationError::NotSupported(
+                    "Shared contexts must agree on the no-error flag"
+                        .to_string(),
+                ));
+            }
+        }
+
+        // all the contexts of a share group must have the same reset
+        // notification strategy
+        let lose_context_on_reset =
+            self.opengl.robustness.loses_context_on_reset()
+                && (self.egl_version >= (1, 5)
+                    || (self.extensions.contains("EGL_KHR_create_context")
+                        && self
+                            .extensions
+                            .contains("EGL_EXT_create_context_robustness")));
+        if let Some(ctx) = self.opengl.sharing {
+            if ctx.lose_context_on_reset != lose_context_on_reset {
+                return Err(CreationError::NotSupported(
+                    "Shared contexts must have the same reset notification \
+                     strategy"
+                        .to_string(),
+                ));
+            }
+        }
+
+        // With `EGL_KHR_no_config_context` the context isn't tied to the
+        // config of its surface, so it can be bound to surfaces of any config.
+        let no_config = self.opengl.no_config
+            && self.extensions.contains("EGL_KHR_no_config_context");
+        let context_config = if no_config {
+            std::ptr::null()
+        } else {
+            self.config_id
+        };
+
         let context = unsafe {
             if let Some(version) = self.version {
                 create_context(
@@ -1008,9 +3369,13 @@ impl<'a> ContextPrototype<'a> {
                     &self.extensions,
                     self.api,
                     version,
-                    self.config_id,
+                    context_config,
                     self.opengl.debug,
+                    self.opengl.forward_compatible,
                     self.opengl.robustness,
+                    self.opengl.priority,
+                    self.release_behavior,
+                    self.opengl.protected_content,
                     share,
                 )?
             } else if self.api == Api::OpenGlEs {
@@ -1020,9 +3385,13 @@ impl<'a> ContextPrototype<'a> {
                     &self.extensions,
                     self.api,
                     (2, 0),
-                    self.config_id,
+                    context_config,
                     self.opengl.debug,
+                    self.opengl.forward_compatible,
                     self.opengl.robustness,
+                    self.opengl.priority,
+                    self.release_behavior,
+                    self.opengl.protected_content,
                     share,
                 ) {
                     ctx
@@ -1032,9 +3401,13 @@ impl<'a> ContextPrototype<'a> {
                     &self.extensions,
                     self.api,
                     (1, 0),
-                    self.config_id,
+                    context_config,
                     self.opengl.debug,
+                    self.opengl.forward_compatible,
                     self.opengl.robustness,
+                    self.opengl.priority,
+                    self.release_behavior,
+                    self.opengl.protected_content,
                     share,
                 ) {
                     ctx
@@ -1048,9 +3421,13 @@ impl<'a> ContextPrototype<'a> {
                     &self.extensions,
                     self.api,
                     (3, 2),
-                    self.config_id,
+                    context_config,
                     self.opengl.debug,
+                    self.opengl.forward_compatible,
                     self.opengl.robustness,
+                    self.opengl.priority,
+                    self.release_behavior,
+                    self.opengl.protected_content,
                     share,
                 ) {
                     ctx
@@ -1060,9 +3437,13 @@ impl<'a> ContextPrototype<'a> {
                     &self.extensions,
                     self.api,
                     (3, 1),
-                    self.config_id,
+                    context_config,
                     self.opengl.debug,
+                    self.opengl.forward_compatible,
                     self.opengl.robustness,
+                    self.opengl.priority,
+                    self.release_behavior,
+                    self.opengl.protected_content,
                     share,
                 ) {
                     ctx
@@ -1072,9 +3453,13 @@ impl<'a> ContextPrototype<'a> {
                     &self.extensions,
                     self.api,
                     (1, 0),
-                    self.config_id,
+                    context_config,
                     self.opengl.debug,
+                    self.opengl.forward_compatible,
                     self.opengl.robustness,
+                    self.opengl.priority,
+                    self.release_behavior,
+                    self.opengl.protected_content,
                     share,
                 ) {
                     ctx
@@ -1112,9 +3497,44 @@ impl<'a> ContextPrototype<'a> {
             context,
             surface: surface.map(|s| Mutex::new(s)),
             api: self.api,
-            pixel_format: self.pixel_format,
-            #[cfg(target_os = "android")]
+            pixel_format,
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+            ))]
+            surfaceless_supported: self
+                .extensions
+                .contains("EGL_KHR_surfaceless_context"),
+            buffer_age_supported: self
+                .extensions
+                .contains("EGL_EXT_buffer_age"),
+            swap_damage: SwapDamageExtension::find(&self.extensions),
+            partial_update_supported: self
+                .extensions
+                .contains("EGL_KHR_partial_update")
+                && EGL.as_ref().unwrap().SetDamageRegionKHR.is_loaded(),
+            damage_region_set: AtomicBool::new(false),
+            swap_interval: AtomicI32::new(self.opengl.vsync as i32),
+            extensions: self.extensions.clone(),
             config_id: self.config_id,
+            no_config,
+            no_error,
+            lose_context_on_reset,
+            release_behavior: self.release_behavior,
+            protected_content: self.opengl.protected_content,
+            pbuffer: false,
+            textureable: false,
+            frame_timestamps_supported: surface.is_some()
+                && self.extensions.contains("EGL_ANDROID_get_frame_timestamps"),
+            timestamps_enabled: AtomicBool::new(false),
+            stream: None,
+            #[cfg(target_os = "android")]
+            color_space: self.color_space,
+            #[cfg(target_os = "android")]
+            preserved_swap: self.preserved_swap,
         })
     }
 }
@@ -1122,6 +3542,7 @@ impl<'a> ContextPrototype<'a> {
 unsafe fn choose_fbconfig<F>(
     display: ffi::egl::types::EGLDisplay,
     egl_version: &(ffi::egl::types::EGLint, ffi::egl::types::EGLint),
+    extensions: &Extensions,
     api: Api,
     version: Option<(u8, u8)>,
     pf_reqs: &PixelFormatRequirements,
@@ -1137,6 +3558,112 @@ where
 {
     let egl = EGL.as_ref().unwrap();
 
+    let config_ids = choose_configs(
+        display,
+        egl_version,
+        extensions,
+        api,
+        version,
+        pf_reqs,
+        surface_type,
+        opengl,
+    )?;
+
+    let config_id = config_selector(config_ids, display)
+        .map_err(|_| CreationError::NoAvailablePixelFormat)?;
+
+    // analyzing each config
+    macro_rules! attrib {
+        ($egl:expr, $display:expr, $config:expr, $attr:expr) => {{
+            let mut value = std::mem::zeroed();
+            let res = $egl.GetConfigAttrib(
+                $display,
+                $config,
+                $attr as ffi::egl::types::EGLint,
+                &mut value,
+            );
+            if res == 0 {
+                return Err(CreationError::OsError(
+                    "eglGetConfigAttrib failed".to_string(),
+                ));
+            }
+            value
+        }};
+    };
+
+    let min_swap_interval: ffi::egl::types::EGLint =
+        attrib!(egl, display, config_id, ffi::egl::MIN_SWAP_INTERVAL);
+    let max_swap_interval: ffi::egl::types::EGLint =
+        attrib!(egl, display, config_id, ffi::egl::MAX_SWAP_INTERVAL);
+
+    let desc = PixelFormat {
+        hardware_accelerated: attrib!(
+            egl,
+            display,
+            config_id,
+            ffi::egl::CONFIG_CAVEAT
+        ) != ffi::egl::SLOW_CONFIG as i32,
+        color_bits: attrib!(egl, display, config_id, ffi::egl::RED_SIZE) as u8
+            + attrib!(egl, display, config_id, ffi::egl::BLUE_SIZE) as u8
+            + attrib!(egl, display, config_id, ffi::egl::GREEN_SIZE) as u8,
+        rgb_bits: Some((
+            attrib!(egl, display, config_id, ffi::egl::RED_SIZE) as u8,
+            attrib!(egl, display, config_id, ffi::egl::GREEN_SIZE) as u8,
+            attrib!(egl, display, config_id, ffi::egl::BLUE_SIZE) as u8,
+        )),
+        alpha_bits: attrib!(egl, display, config_id, ffi::egl::ALPHA_SIZE)
+            as u8,
+        depth_bits: attrib!(egl, display, config_id, ffi::egl::DEPTH_SIZE)
+            as u8,
+        stencil_bits: attrib!(egl, display, config_id, ffi::egl::STENCIL_SIZE)
+            as u8,
+        stereoscopy: false,
+        double_buffer: true,
+        multisampling: match attrib!(egl, display, config_id, ffi::egl::SAMPLES)
+        {
+            0 | 1 => None,
+            a => Some(a as u16),
+        },
+        coverage_samples: None,
+        srgb: false, // TODO: use EGL_KHR_gl_colorspace to know that
+        float_color_buffer: extensions.contains("EGL_EXT_pixel_format_float")
+            && attrib!(
+                egl,
+                display,
+                config_id,
+                ffi::egl::COLOR_COMPONENT_TYPE_EXT
+            ) == ffi::egl::COLOR_COMPONENT_TYPE_FLOAT_EXT as i32,
+        // only known once a surface exists
+        swap_behavior: SwapBehavior::Undefined,
+        swap_interval_range: Some((
+            min_swap_interval.max(0) as u32,
+            max_swap_interval.max(0) as u32,
+        )),
+    };
+
+    Ok((config_id, desc))
+}
+
+/// Returns the configs matching the requirements, in the order of
+/// `eglChooseConfig`.
+unsafe fn choose_configs(
+    display: ffi::egl::types::EGLDisplay,
+    egl_version: &(ffi::egl::types::EGLint, ffi::egl::types::EGLint),
+    extensions: &Extensions,
+    api: Api,
+    version: Option<(u8, u8)>,
+    pf_reqs: &PixelFormatRequirements,
+    surface_type: SurfaceType,
+    opengl: &GlAttributes<&Context>,
+) -> Result<Vec<ffi::egl::types::EGLConfig>, CreationError> {
+    let egl = EGL.as_ref().unwrap();
+
+    if pf_reqs.preserved_swap && egl_version < &(1, 4) {
+        return Err(CreationError::NotSupported(
+            "Preserved swaps require EGL 1.4".to_string(),
+        ));
+    }
+
     let descriptor = {
         let mut out: Vec<raw::c_int> = Vec::with_capacity(37);
 
@@ -1145,11 +3672,32 @@ where
             out.push(ffi::egl::RGB_BUFFER as raw::c_int);
         }
 
+        if pf_reqs.textureable && surface_type == SurfaceType::PBuffer {
+            let binding = match texture_format(pf_reqs) {
+                ffi::egl::TEXTURE_RGBA => ffi::egl::BIND_TO_TEXTURE_RGBA,
+                _ => ffi::egl::BIND_TO_TEXTURE_RGB,
+            };
+            out.push(binding as raw::c_int);
+            out.push(ffi::egl::TRUE as raw::c_int);
+        }
+
         out.push(ffi::egl::SURFACE_TYPE as raw::c_int);
         let surface_type = match surface_type {
             SurfaceType::Window => ffi::egl::WINDOW_BIT,
             SurfaceType::PBuffer => ffi::egl::PBUFFER_BIT,
             SurfaceType::Surfaceless => 0,
+            SurfaceType::Pixmap => ffi::egl::PIXMAP_BIT,
+            SurfaceType::Stream => ffi::egl::STREAM_BIT_KHR,
+        };
+        // `EGL_SWAP_BEHAVIOR_PRESERVED_BIT` only applies to window and pbuffer
+        // surfaces.
+        let surface_type = match surface_type {
+            ffi::egl::WINDOW_BIT | ffi::egl::PBUFFER_BIT
+                if pf_reqs.preserved_swap =>
+            {
+                surface_type | ffi::egl::SWAP_BEHAVIOR_PRESERVED_BIT
+            }
+            _ => surface_type,
         };
         out.push(surface_type as raw::c_int);
 
@@ -1158,19 +3706,19 @@ where
                 if egl_version < &(1, 3) {
                     return Err(CreationError::NoAvailablePixelFormat);
                 }
+                // `EGL_OPENGL_ES3_BIT` is only defined by EGL 1.5 and
+                // `EGL_KHR_create_context`, older drivers reject it
+                let bit = if egl_version >= &(1, 5)
+                    || extensions.contains("EGL_KHR_create_context")
+                {
+                    ffi::egl::OPENGL_ES3_BIT
+                } else {
+                    ffi::egl::OPENGL_ES2_BIT
+                };
                 out.push(ffi::egl::RENDERABLE_TYPE as raw::c_int);
-                out.push(ffi::egl::OPENGL_ES3_BIT as raw::c_int);
-                out.push(ffi::egl::CONFORMANT as raw::c_int);
-                out.push(ffi::egl::OPENGL_ES3_BIT as raw::c_int);
-            }
-            (Api::OpenGlEs, Some((2, _))) => {
-                if egl_version < &(1, 3) {
-                    return Err(CreationError::NoAvailablePixelFormat);
-                }
-                out.push(ffi::egl::RENDERABLE_TYPE as raw::c_int);
-                out.push(ffi::egl::OPENGL_ES2_BIT as raw::c_int);
+                out.push(bit as raw::c_int);
                 out.push(ffi::egl::CONFORMANT as raw::c_int);
-                out.push(ffi::egl::OPENGL_ES2_BIT as raw::c_int);
+                out.push(bit as raw::c_int);
             }
             (Api::OpenGlEs, Some((1, _))) => {
                 if egl_version >= &(1, 3) {
@@ -1180,7 +3728,18 @@ where
                     out.push(ffi::egl::OPENGL_ES_BIT as raw::c_int);
                 }
             }
-            (Api::OpenGlEs, _) => unimplemented!(),
+            // OpenGL ES 2, which is also what is tried first when no version
+            // was requested
+            (Api::OpenGlEs, _) => {
+                if egl_version >= &(1, 3) {
+                    out.push(ffi::egl::RENDERABLE_TYPE as raw::c_int);
+                    out.push(ffi::egl::OPENGL_ES2_BIT as raw::c_int);
+                    out.push(ffi::egl::CONFORMANT as raw::c_int);
+                    out.push(ffi::egl::OPENGL_ES2_BIT as raw::c_int);
+                } else if version.is_some() {
+                    return Err(CreationError::NoAvailablePixelFormat);
+                }
+            }
             (Api::OpenGl, _) => {
                 if egl_version < &(1, 3) {
                     return Err(CreationError::NoAvailablePixelFormat);
@@ -1202,6 +3761,17 @@ where
             });
         }
 
+        if pf_reqs.float_color_buffer {
+            if !extensions.contains("EGL_EXT_pixel_format_float") {
+                return Err(CreationError::NotSupported(
+                    "float color buffers require EGL_EXT_pixel_format_float"
+                        .to_string(),
+                ));
+            }
+            out.push(ffi::egl::COLOR_COMPONENT_TYPE_EXT as raw::c_int);
+            out.push(ffi::egl::COLOR_COMPONENT_TYPE_FLOAT_EXT as raw::c_int);
+        }
+
         if let Some(color) = pf_reqs.color_bits {
             out.push(ffi::egl::RED_SIZE as raw::c_int);
             out.push((color / 3) as raw::c_int);
@@ -1239,8 +3809,10 @@ where
             out.push(multisampling as raw::c_int);
         }
 
-        if pf_reqs.stereoscopy {
-            return Err(CreationError::NoAvailablePixelFormat);
+        if pf_reqs.stereoscopy == Some(true) {
+            return Err(CreationError::NotSupported(
+                "Stereoscopy is not supported by EGL".to_string(),
+            ));
         }
 
         if let Some(xid) = pf_reqs.x11_visual_xid {
@@ -1248,16 +3820,20 @@ where
             out.push(xid as raw::c_int);
         }
 
-        // FIXME: srgb is not taken into account
-
-        match pf_reqs.release_behavior {
-            ReleaseBehavior::Flush => (),
-            ReleaseBehavior::None => {
-                // TODO: with EGL you need to manually set the behavior
-                unimplemented!()
-            }
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd",
+        ))]
+        if let Some(pixmap) = pf_reqs.x11_pixmap {
+            out.push(ffi::egl::MATCH_NATIVE_PIXMAP as raw::c_int);
+            out.push(pixmap as raw::c_int);
         }
 
+        // FIXME: srgb is not taken into account
+
         out.push(ffi::egl::NONE as raw::c_int);
         out
     };
@@ -1326,6 +3902,29 @@ where
                 return false;
             }
 
+            match pf_reqs.max_swap_interval {
+                Some(interval) if max_swap_interval < interval as i32 => {
+                    return false
+                }
+                _ => (),
+            }
+
+            // drivers treat `EGL_SAMPLES` as a minimum
+            if let (Some(samples), true) =
+                (pf_reqs.multisampling, pf_reqs.multisampling_exact)
+            {
+                let mut value = 0;
+                egl.GetConfigAttrib(
+                    display,
+                    config,
+                    ffi::egl::SAMPLES as ffi::egl::types::EGLint,
+                    &mut value,
+                );
+                if value != samples as ffi::egl::types::EGLint {
+                    return false;
+                }
+            }
+
             true
         })
         .collect::<Vec<_>>();
@@ -1334,66 +3933,116 @@ where
         return Err(CreationError::NoAvailablePixelFormat);
     }
 
-    let config_id = config_selector(config_ids, display)
-        .map_err(|_| CreationError::NoAvailablePixelFormat)?;
+    Ok(config_ids)
+}
 
-    // analyzing each config
-    macro_rules! attrib {
-        ($egl:expr, $display:expr, $config:expr, $attr:expr) => {{
-            let mut value = std::mem::zeroed();
-            let res = $egl.GetConfigAttrib(
-                $display,
-                $config,
-                $attr as ffi::egl::types::EGLint,
-                &mut value,
-            );
-            if res == 0 {
-                return Err(CreationError::OsError(
-                    "eglGetConfigAttrib failed".to_string(),
-                ));
+fn check_color_space(
+    color_space: ColorSpace,
+    egl_version: &(ffi::egl::types::EGLint, ffi::egl::types::EGLint),
+    extensions: &Extensions,
+) -> Result<(), CreationError> {
+    let extension = match color_space {
+        ColorSpace::Linear | ColorSpace::Srgb => {
+            if egl_version >= &(1, 5) {
+                return Ok(());
             }
-            value
-        }};
+            "EGL_KHR_gl_colorspace"
+        }
+        ColorSpace::ScRgbLinear => "EGL_EXT_gl_colorspace_scrgb_linear",
+        ColorSpace::DisplayP3 => "EGL_EXT_gl_colorspace_display_p3",
     };
 
-    let desc = PixelFormat {
-        hardware_accelerated: attrib!(
-            egl,
-            display,
-            config_id,
-            ffi::egl::CONFIG_CAVEAT
-        ) != ffi::egl::SLOW_CONFIG as i32,
-        color_bits: attrib!(egl, display, config_id, ffi::egl::RED_SIZE) as u8
-            + attrib!(egl, display, config_id, ffi::egl::BLUE_SIZE) as u8
-            + attrib!(egl, display, config_id, ffi::egl::GREEN_SIZE) as u8,
-        alpha_bits: attrib!(egl, display, config_id, ffi::egl::ALPHA_SIZE)
-            as u8,
-        depth_bits: attrib!(egl, display, config_id, ffi::egl::DEPTH_SIZE)
-            as u8,
-        stencil_bits: attrib!(egl, display, config_id, ffi::egl::STENCIL_SIZE)
-            as u8,
-        stereoscopy: false,
-        double_buffer: true,
-        multisampling: match attrib!(egl, display, config_id, ffi::egl::SAMPLES)
-        {
-            0 | 1 => None,
-            a => Some(a as u16),
-        },
-        srgb: false, // TODO: use EGL_KHR_gl_colorspace to know that
-    };
+    if extensions.contains(extension) {
+        Ok(())
+    } else {
+        Err(CreationError::NotSupported(format!(
+            "The {:?} color space requires {}",
+            color_space, extension
+        )))
+    }
+}
 
-    Ok((config_id, desc))
+// Asks for the color buffer of `surface` to be preserved on swaps. The config
+// of the surface must have `EGL_SWAP_BEHAVIOR_PRESERVED_BIT`.
+unsafe fn preserve_swap(
+    display: ffi::egl::types::EGLDisplay,
+    surface: ffi::egl::types::EGLSurface,
+) -> Result<(), CreationError> {
+    let egl = EGL.as_ref().unwrap();
+    if egl.SurfaceAttrib(
+        display,
+        surface,
+        ffi::egl::SWAP_BEHAVIOR as raw::c_int,
+        ffi::egl::BUFFER_PRESERVED as raw::c_int,
+    ) == ffi::egl::FALSE
+    {
+        return Err(debug::creation_error(
+            "eglSurfaceAttrib",
+            "eglSurfaceAttrib(EGL_SWAP_BEHAVIOR) failed",
+        ));
+    }
+    Ok(())
+}
+
+unsafe fn swap_behavior(
+    display: ffi::egl::types::EGLDisplay,
+    surface: ffi::egl::types::EGLSurface,
+) -> SwapBehavior {
+    let egl = EGL.as_ref().unwrap();
+    let mut value = 0;
+    if egl.QuerySurface(
+        display,
+        surface,
+        ffi::egl::SWAP_BEHAVIOR as raw::c_int,
+        &mut value,
+    ) != ffi::egl::FALSE
+        && value == ffi::egl::BUFFER_PRESERVED as raw::c_int
+    {
+        SwapBehavior::Preserved
+    } else {
+        SwapBehavior::Undefined
+    }
+}
+
+// The format a textureable pbuffer is bound as, which has an alpha channel
+// if one was requested.
+fn texture_format(
+    pf_reqs: &PixelFormatRequirements,
+) -> ffi::egl::types::EGLenum {
+    if pf_reqs.alpha_bits.unwrap_or(0) > 0 {
+        ffi::egl::TEXTURE_RGBA
+    } else {
+        ffi::egl::TEXTURE_RGB
+    }
+}
+
+fn color_space_attributes(color_space: ColorSpace) -> [raw::c_int; 2] {
+    let value = match color_space {
+        ColorSpace::Linear => ffi::egl::GL_COLORSPACE_LINEAR as raw::c_int,
+        ColorSpace::Srgb => ffi::egl::GL_COLORSPACE_SRGB as raw::c_int,
+        ColorSpace::ScRgbLinear => {
+            ffi::egl::GL_COLORSPACE_SCRGB_LINEAR_EXT as raw::c_int
+        }
+        ColorSpace::DisplayP3 => {
+            ffi::egl::GL_COLORSPACE_DISPLAY_P3_EXT as raw::c_int
+        }
+    };
+    [ffi::egl::GL_COLORSPACE as raw::c_int, value]
 }
 
 unsafe fn create_context(
     display: ffi::egl::types::EGLDisplay,
     egl_version: &(ffi::egl::types::EGLint, ffi::egl::types::EGLint),
-    extensions: &[String],
+    extensions: &Extensions,
     api: Api,
     version: (u8, u8),
     config_id: ffi::egl::types::EGLConfig,
     gl_debug: bool,
+    gl_forward_compatible: bool,
     gl_robustness: Robustness,
+    gl_priority: Option<Priority>,
+    release_behavior: ReleaseBehavior,
+    protected_content: bool,
     share: ffi::EGLContext,
 ) -> Result<ffi::egl::types::EGLContext, CreationError> {
     let egl = EGL.as_ref().unwrap();
@@ -1401,12 +4050,7 @@ unsafe fn create_context(
     let mut context_attributes = Vec::with_capacity(10);
     let mut flags = 0;
 
-    if egl_version >= &(1, 5)
-        || extensions
-            .iter()
-            .find(|s| s == &"EGL_KHR_create_context")
-            .is_some()
-    {
+    if egl_version >= &(1, 5) || extensions.contains("EGL_KHR_create_context") {
         context_attributes.push(ffi::egl::CONTEXT_MAJOR_VERSION as i32);
         context_attributes.push(version.0 as i32);
         context_attributes.push(ffi::egl::CONTEXT_MINOR_VERSION as i32);
@@ -1414,20 +4058,13 @@ unsafe fn create_context(
 
         // handling robustness
         let supports_robustness = egl_version >= &(1, 5)
-            || extensions
-                .iter()
-                .find(|s| s == &"EGL_EXT_create_context_robustness")
-                .is_some();
+            || extensions.contains("EGL_EXT_create_context_robustness");
 
         match gl_robustness {
             Robustness::NotRobust => (),
 
             Robustness::NoError => {
-                if extensions
-                    .iter()
-                    .find(|s| s == &"EGL_KHR_create_context_no_error")
-                    .is_some()
-                {
+                if extensions.contains("EGL_KHR_create_context_no_error") {
                     context_attributes.push(
                         ffi::egl::CONTEXT_OPENGL_NO_ERROR_KHR as raw::c_int,
                     );
@@ -1492,6 +4129,18 @@ unsafe fn create_context(
             }
         }
 
+        if gl_forward_compatible && api == Api::OpenGl {
+            if egl_version >= &(1, 5) {
+                context_attributes
+                    .push(ffi::egl::CONTEXT_OPENGL_FORWARD_COMPATIBLE as i32);
+                context_attributes.push(ffi::egl::TRUE as i32);
+            } else {
+                flags = flags
+                    | ffi::egl::CONTEXT_OPENGL_FORWARD_COMPATIBLE_BIT_KHR
+                        as raw::c_int;
+            }
+        }
+
         if gl_debug {
             if egl_version >= &(1, 5) {
                 context_attributes.push(ffi::egl::CONTEXT_OPENGL_DEBUG as i32);
@@ -1528,6 +4177,33 @@ unsafe fn create_context(
         context_attributes.push(version.0 as i32);
     }
 
+    // The priority is only a hint, so it is dropped rather than failing
+    // creation when the extension is missing.
+    if let Some(priority) = gl_priority {
+        if extensions.contains("EGL_IMG_context_priority") {
+            let level = match priority {
+                Priority::High => ffi::egl::CONTEXT_PRIORITY_HIGH_IMG,
+                Priority::Medium => ffi::egl::CONTEXT_PRIORITY_MEDIUM_IMG,
+                Priority::Low => ffi::egl::CONTEXT_PRIORITY_LOW_IMG,
+            };
+            context_attributes
+                .push(ffi::egl::CONTEXT_PRIORITY_LEVEL_IMG as raw::c_int);
+            context_attributes.push(level as raw::c_int);
+        }
+    }
+
+    if release_behavior == ReleaseBehavior::None {
+        context_attributes
+            .push(ffi::egl::CONTEXT_RELEASE_BEHAVIOR_KHR as raw::c_int);
+        context_attributes
+            .push(ffi::egl::CONTEXT_RELEASE_BEHAVIOR_NONE_KHR as raw::c_int);
+    }
+
+    if protected_content {
+        context_attributes.push(ffi::egl::PROTECTED_CONTENT_EXT as raw::c_int);
+        context_attributes.push(ffi::egl::TRUE as raw::c_int);
+    }
+
     context_attributes.push(ffi::egl::NONE as i32);
 
     let context = egl.CreateContext(
@@ -1542,7 +4218,14 @@ unsafe fn create_context(
             ffi::egl::BAD_MATCH | ffi::egl::BAD_ATTRIBUTE => {
                 return Err(CreationError::OpenGlVersionNotSupported);
             }
-            e => panic!("create_context: eglCreateContext failed: 0x{:x}", e),
+            err => {
+                let (code, message) = debug::error(
+                    "eglCreateContext",
+                    "eglCreateContext failed",
+                    err,
+                );
+                return Err(CreationError::ApiError(code, message));
+            }
         }
     }
 
@@ -0,0 +1,211 @@
+//! Presentation feedback through `EGL_ANDROID_get_frame_timestamps`.
+
+use super::EGL;
+use crate::{ContextError, FrameId, PresentationTimes};
+use glutin_egl_sys as ffi;
+
+use std::ffi::CString;
+use std::os::raw;
+use std::time::Duration;
+
+const TIMESTAMPS_ANDROID: ffi::egl::types::EGLint = 0x3430;
+const COMPOSITE_INTERVAL_ANDROID: ffi::egl::types::EGLint = 0x3432;
+const REQUESTED_PRESENT_TIME_ANDROID: ffi::egl::types::EGLint = 0x3434;
+const RENDERING_COMPLETE_TIME_ANDROID: ffi::egl::types::EGLint = 0x3435;
+const DISPLAY_PRESENT_TIME_ANDROID: ffi::egl::types::EGLint = 0x343A;
+
+// Negative timestamps are `EGL_TIMESTAMP_PENDING_ANDROID` and
+// `EGL_TIMESTAMP_INVALID_ANDROID`.
+type Nsecs = i64;
+
+type GetNextFrameId = unsafe extern "system" fn(
+    ffi::egl::types::EGLDisplay,
+    ffi::egl::types::EGLSurface,
+    *mut u64,
+) -> ffi::egl::types::EGLBoolean;
+
+type GetFrameTimestamps =
+    unsafe extern "system" fn(
+        ffi::egl::types::EGLDisplay,
+        ffi::egl::types::EGLSurface,
+        u64,
+        ffi::egl::types::EGLint,
+        *const ffi::egl::types::EGLint,
+        *mut Nsecs,
+    ) -> ffi::egl::types::EGLBoolean;
+
+type GetFrameTimestampSupported =
+    unsafe extern "system" fn(
+        ffi::egl::types::EGLDisplay,
+        ffi::egl::types::EGLSurface,
+        ffi::egl::types::EGLint,
+    ) -> ffi::egl::types::EGLBoolean;
+
+type GetCompositorTiming =
+    unsafe extern "system" fn(
+        ffi::egl::types::EGLDisplay,
+        ffi::egl::types::EGLSurface,
+        ffi::egl::types::EGLint,
+        *const ffi::egl::types::EGLint,
+        *mut Nsecs,
+    ) -> ffi::egl::types::EGLBoolean;
+
+// `eglGetProcAddress` doesn't depend on the display, so the functions are
+// resolved once for the whole process.
+struct Functions {
+    get_next_frame_id: Option<GetNextFrameId>,
+    get_frame_timestamps: Option<GetFrameTimestamps>,
+    get_frame_timestamp_supported: Option<GetFrameTimestampSupported>,
+    get_compositor_timing: Option<GetCompositorTiming>,
+}
+
+lazy_static! {
+    static ref FUNCTIONS: Functions = unsafe {
+        Functions {
+            get_next_frame_id: load("eglGetNextFrameIdANDROID")
+                .map(|addr| std::mem::transmute(addr)),
+            get_frame_timestamps: load("eglGetFrameTimestampsANDROID")
+                .map(|addr| std::mem::transmute(addr)),
+            get_frame_timestamp_supported: load(
+                "eglGetFrameTimestampSupportedANDROID",
+            )
+            .map(|addr| std::mem::transmute(addr)),
+            get_compositor_timing: load("eglGetCompositorTimingANDROID")
+                .map(|addr| std::mem::transmute(addr)),
+        }
+    };
+}
+
+unsafe fn load(name: &str) -> Option<*const raw::c_void> {
+    let egl = EGL.as_ref().unwrap();
+    let name = CString::new(name).unwrap();
+    let addr = egl.GetProcAddress(name.as_ptr()) as *const raw::c_void;
+    if addr.is_null() {
+        None
+    } else {
+        Some(addr)
+    }
+}
+
+/// Starts recording timestamps for the frames swapped on `surface`.
+pub unsafe fn enable(
+    display: ffi::egl::types::EGLDisplay,
+    surface: ffi::egl::types::EGLSurface,
+) -> Result<(), ContextError> {
+    let egl = EGL.as_ref().unwrap();
+    if egl.SurfaceAttrib(
+        display,
+        surface,
+        TIMESTAMPS_ANDROID,
+        ffi::egl::TRUE as ffi::egl::types::EGLint,
+    ) == ffi::egl::FALSE
+    {
+        return Err(super::debug::context_error(
+            "eglSurfaceAttrib",
+            "eglSurfaceAttrib(EGL_TIMESTAMPS_ANDROID) failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the id the next frame swapped on `surface` will have.
+pub unsafe fn next_frame_id(
+    display: ffi::egl::types::EGLDisplay,
+    surface: ffi::egl::types::EGLSurface,
+) -> Result<FrameId, ContextError> {
+    let get_next_frame_id = FUNCTIONS
+        .get_next_frame_id
+        .ok_or(ContextError::FunctionUnavailable)?;
+
+    let mut id = 0;
+    if get_next_frame_id(display, surface, &mut id) == ffi::egl::FALSE {
+        return Err(super::debug::context_error(
+            "eglGetNextFrameIdANDROID",
+            "eglGetNextFrameIdANDROID failed",
+        ));
+    }
+    Ok(FrameId(id))
+}
+
+/// Queries the timestamps of a frame swapped on `surface`. Timestamps the
+/// driver doesn't support, or hasn't recorded yet, are left out.
+pub unsafe fn presentation_times(
+    display: ffi::egl::types::EGLDisplay,
+    surface: ffi::egl::types::EGLSurface,
+    frame: FrameId,
+) -> Result<PresentationTimes, ContextError> {
+    let (get_frame_timestamps, get_frame_timestamp_supported) = match (
+        FUNCTIONS.get_frame_timestamps,
+        FUNCTIONS.get_frame_timestamp_supported,
+    ) {
+        (Some(timestamps), Some(supported)) => (timestamps, supported),
+        _ => return Err(ContextError::FunctionUnavailable),
+    };
+
+    // asking for an unsupported timestamp fails the whole query
+    let names: Vec<_> = [
+        REQUESTED_PRESENT_TIME_ANDROID,
+        RENDERING_COMPLETE_TIME_ANDROID,
+        DISPLAY_PRESENT_TIME_ANDROID,
+    ]
+    .iter()
+    .cloned()
+    .filter(|&name| {
+        get_frame_timestamp_supported(display, surface, name) != ffi::egl::FALSE
+    })
+    .collect();
+
+    let mut values = vec![-1; names.len()];
+    if get_frame_timestamps(
+        display,
+        surface,
+        frame.0,
+        names.len() as ffi::egl::types::EGLint,
+        names.as_ptr(),
+        values.as_mut_ptr(),
+    ) == ffi::egl::FALSE
+    {
+        return Err(super::debug::context_error(
+            "eglGetFrameTimestampsANDROID",
+            "eglGetFrameTimestampsANDROID failed",
+        ));
+    }
+
+    let value = |name| {
+        names
+            .iter()
+            .position(|&n| n == name)
+            .and_then(|i| to_duration(values[i]))
+    };
+    Ok(PresentationTimes {
+        requested_present: value(REQUESTED_PRESENT_TIME_ANDROID),
+        rendering_complete: value(RENDERING_COMPLETE_TIME_ANDROID),
+        display_present: value(DISPLAY_PRESENT_TIME_ANDROID),
+        composite_interval: composite_interval(display, surface),
+    })
+}
+
+// The compositor timing is optional, so failures are ignored.
+unsafe fn composite_interval(
+    display: ffi::egl::types::EGLDisplay,
+    surface: ffi::egl::types::EGLSurface,
+) -> Option<Duration> {
+    let get_compositor_timing = FUNCTIONS.get_compositor_timing?;
+
+    let name = COMPOSITE_INTERVAL_ANDROID;
+    let mut value = -1;
+    if get_compositor_timing(display, surface, 1, &name, &mut value)
+        == ffi::egl::FALSE
+    {
+        return None;
+    }
+    to_duration(value)
+}
+
+fn to_duration(nsecs: Nsecs) -> Option<Duration> {
+    if nsecs < 0 {
+        None
+    } else {
+        Some(Duration::from_nanos(nsecs as u64))
+    }
+}
@@ -1,3 +1,4 @@
+use super::x_error_trap::XErrorTrap;
 use crate::platform::unix::x11::XConnection;
 use glutin_glx_sys as ffi;
 
@@ -38,12 +39,14 @@ impl MakeCurrentGuard {
                 }),
             };
 
+            let trap = XErrorTrap::new(&xconn.xlib, xconn.display);
             let res =
                 glx.MakeCurrent(xconn.display as *mut _, drawable, context);
 
-            if res == 0 {
-                let err = xconn.check_errors();
-                Err(format!("`glXMakeCurrent` failed: {:?}", err))
+            if let Some((_, msg)) = trap.check("glXMakeCurrent") {
+                Err(msg)
+            } else if res == 0 {
+                Err("`glXMakeCurrent` failed".to_string())
             } else {
                 Ok(ret)
             }
@@ -7,6 +7,7 @@
 ))]
 
 mod make_current_guard;
+mod x_error_trap;
 mod glx {
     use crate::api::dlloader::{SymTrait, SymWrapper};
     use glutin_glx_sys as ffi;
@@ -53,26 +54,59 @@ mod glx {
             &mut self.0
         }
     }
+
+    /// The GLX extension functions. `glXGetProcAddress` doesn't depend on the
+    /// current context, so they are resolved once for the whole process.
+    pub struct GlxExtra(ffi::glx_extra::Glx);
+
+    /// Because `*const raw::c_void` doesn't implement `Sync`.
+    unsafe impl Sync for GlxExtra {}
+
+    impl GlxExtra {
+        pub fn new(glx: &Glx) -> Self {
+            GlxExtra(ffi::glx_extra::Glx::load_with(|proc_name| {
+                let c_str = std::ffi::CString::new(proc_name).unwrap();
+                unsafe {
+                    glx.GetProcAddress(c_str.as_ptr() as *const u8) as *const _
+                }
+            }))
+        }
+    }
+
+    impl Deref for GlxExtra {
+        type Target = ffi::glx_extra::Glx;
+
+        fn deref(&self) -> &ffi::glx_extra::Glx {
+            &self.0
+        }
+    }
 }
 
 pub use self::glx::Glx;
+use self::glx::GlxExtra;
 use self::make_current_guard::MakeCurrentGuard;
+use self::x_error_trap::XErrorTrap;
 use crate::{
-    Api, ContextError, CreationError, GlAttributes, GlProfile, GlRequest,
-    PixelFormat, PixelFormatRequirements, ReleaseBehavior, Robustness,
+    Api, ContextError, CreationError, DirectRendering, ErrorCode, Extensions,
+    FrameId, GlAttributes, GlProfile, GlRequest, PixelFormat,
+    PixelFormatRequirements, PresentationTimes, ReleaseBehavior, Robustness,
+    StateMask, SwapBehavior, SwapGroupLimits, SwapInterval,
 };
 
 use crate::platform::unix::x11::XConnection;
-use crate::platform_impl::x11_utils::SurfaceType;
+use crate::platform_impl::x11_utils::{self, SurfaceType};
 use glutin_glx_sys as ffi;
 use winit::dpi;
 
 use std::ffi::{CStr, CString};
 use std::os::raw;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 lazy_static! {
     pub static ref GLX: Option<Glx> = Glx::new().ok();
+    static ref GLX_EXTRA: Option<GlxExtra> = GLX.as_ref().map(GlxExtra::new);
 }
 
 #[derive(Debug)]
@@ -81,6 +115,304 @@ pub struct Context {
     drawable: ffi::Window,
     context: ffi::GLXContext,
     pixel_format: PixelFormat,
+    // Whether `GLX_EXT_buffer_age` is supported.
+    buffer_age_supported: bool,
+    // The GLX extensions supported by the screen.
+    extensions: Extensions,
+    // Whether the context was created with
+    // `GLX_CONTEXT_OPENGL_NO_ERROR_ARB`.
+    no_error: bool,
+    // Whether the context was created with `GLX_LOSE_CONTEXT_ON_RESET_ARB`.
+    lose_context_on_reset: bool,
+    release_behavior: ReleaseBehavior,
+    // Whether `drawable` is a GLXPixmap owned by the context.
+    pixmap: bool,
+    // The X window the context renders to, which `drawable` is a GLXWindow
+    // of if `glx_window` is true.
+    x11_window: Option<ffi::Window>,
+    // Whether `drawable` is a GLXWindow owned by the context.
+    glx_window: bool,
+    // Whether the visual was chosen with `glXChooseVisual`.
+    legacy_visual: bool,
+    // Whether the server supports `glXMakeContextCurrent`, which requires
+    // GLX 1.3.
+    make_context_current_supported: bool,
+    // The visual of the config, and its depth.
+    visual_id: ffi::VisualID,
+    depth: raw::c_int,
+    screen_id: raw::c_int,
+    // The swap group `drawable` joined, or 0.
+    swap_group: AtomicU32,
+}
+
+/// The GLX extension used to change the swap interval of a context, which
+/// decides what [`set_swap_interval`] and [`get_swap_interval`] can do.
+///
+/// The extensions are preferred in the order of the variants.
+///
+/// [`set_swap_interval`]:
+/// ../../struct.ContextWrapper.html#method.set_swap_interval
+/// [`get_swap_interval`]:
+/// ../../struct.ContextWrapper.html#method.get_swap_interval
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GlxSwapControl {
+    /// `GLX_EXT_swap_control`, which sets the interval of the drawable and
+    /// can be queried. `tear` is whether `GLX_EXT_swap_control_tear` is
+    /// supported too, which allows adaptive vsync.
+    Ext { tear: bool },
+    /// `GLX_MESA_swap_control`, which sets the interval of the drawable of
+    /// the current context and can be queried.
+    Mesa,
+    /// `GLX_SGI_swap_control`, which sets the interval of the drawable of the
+    /// current context, can't be queried and can't disable vsync.
+    Sgi,
+}
+
+impl GlxSwapControl {
+    fn find(extensions: &Extensions) -> Option<Self> {
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        if extensions.contains("GLX_EXT_swap_control")
+            && extra_functions.SwapIntervalEXT.is_loaded()
+        {
+            Some(GlxSwapControl::Ext {
+                tear: extensions.contains("GLX_EXT_swap_control_tear"),
+            })
+        } else if extensions.contains("GLX_MESA_swap_control")
+            && extra_functions.SwapIntervalMESA.is_loaded()
+        {
+            Some(GlxSwapControl::Mesa)
+        } else if extensions.contains("GLX_SGI_swap_control")
+            && extra_functions.SwapIntervalSGI.is_loaded()
+        {
+            Some(GlxSwapControl::Sgi)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether [`SwapInterval::DontWait`] can be set.
+    ///
+    /// [`SwapInterval::DontWait`]:
+    /// ../../enum.SwapInterval.html#variant.DontWait
+    pub fn supports_dont_wait(&self) -> bool {
+        *self != GlxSwapControl::Sgi
+    }
+
+    /// Returns whether [`SwapInterval::AdaptiveWait`] can be set.
+    ///
+    /// [`SwapInterval::AdaptiveWait`]:
+    /// ../../enum.SwapInterval.html#variant.AdaptiveWait
+    pub fn supports_adaptive_wait(&self) -> bool {
+        *self == GlxSwapControl::Ext { tear: true }
+    }
+
+    /// Returns whether the current interval can be queried.
+    pub fn is_queryable(&self) -> bool {
+        match *self {
+            GlxSwapControl::Ext { .. } => true,
+            GlxSwapControl::Mesa => {
+                GLX_EXTRA.as_ref().unwrap().GetSwapIntervalMESA.is_loaded()
+            }
+            GlxSwapControl::Sgi => false,
+        }
+    }
+
+    /// Returns whether the interval is set on a drawable rather than through
+    /// the current context. Otherwise glutin makes the context current to
+    /// change it.
+    pub fn is_per_drawable(&self) -> bool {
+        match *self {
+            GlxSwapControl::Ext { .. } => true,
+            GlxSwapControl::Mesa | GlxSwapControl::Sgi => false,
+        }
+    }
+}
+
+/// The counters of a drawable, as reported by `GLX_OML_sync_control`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GlxSyncValues {
+    /// The unadjusted system time at which `msc` last changed. With Mesa,
+    /// this is an offset from the epoch of `CLOCK_MONOTONIC`, which is the
+    /// clock [`Instant`] uses on Linux.
+    ///
+    /// [`Instant`]: https://doc.rust-lang.org/std/time/struct.Instant.html
+    pub ust: Duration,
+    /// The media stream counter, which is incremented on every vertical
+    /// retrace of the display.
+    pub msc: u64,
+    /// The swap buffer counter, which is incremented every time a swap of
+    /// the drawable completes.
+    pub sbc: u64,
+}
+
+impl GlxSyncValues {
+    fn new(ust: i64, msc: i64, sbc: i64) -> Self {
+        // the UST is in microseconds
+        GlxSyncValues {
+            ust: Duration::from_micros(ust as u64),
+            msc: msc as u64,
+            sbc: sbc as u64,
+        }
+    }
+}
+
+/// A renderer of an X screen, as reported by `GLX_MESA_query_renderer`
+/// without creating a context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlxRenderer {
+    /// The PCI vendor id of the GPU, or `0xffffffff` if unknown.
+    pub vendor_id: u32,
+    /// The PCI device id of the GPU, or `0xffffffff` if unknown.
+    pub device_id: u32,
+    /// The name of the vendor, like the `GL_VENDOR` string.
+    pub vendor: String,
+    /// The name of the device, like the `GL_RENDERER` string.
+    pub device: String,
+    /// The major, minor and patch version of the driver.
+    pub version: (u32, u32, u32),
+    /// Whether the renderer is hardware accelerated, which is `false` for
+    /// software renderers like llvmpipe.
+    pub accelerated: bool,
+    /// The amount of video memory in megabytes.
+    pub video_memory: u32,
+    /// Whether the GPU shares its memory with the CPU.
+    pub unified_memory_architecture: bool,
+}
+
+impl GlxRenderer {
+    /// Queries the renderer `index` of the screen `screen_id`.
+    ///
+    /// Returns [`CreationError::NotSupported`] if GLX or
+    /// `GLX_MESA_query_renderer` isn't available, which is the case for
+    /// drivers other than Mesa, and [`CreationError::OsError`] if there is no
+    /// such renderer.
+    ///
+    /// [`CreationError::NotSupported`]:
+    /// ../../enum.CreationError.html#variant.NotSupported
+    /// [`CreationError::OsError`]:
+    /// ../../enum.CreationError.html#variant.OsError
+    pub fn query(
+        xconn: &Arc<XConnection>,
+        screen_id: raw::c_int,
+        index: u32,
+    ) -> Result<Self, CreationError> {
+        if !query_renderer_supported(xconn, screen_id)? {
+            return Err(CreationError::NotSupported(
+                "GLX_MESA_query_renderer is not supported".to_string(),
+            ));
+        }
+        unsafe { query_renderer(xconn, screen_id, index) }.ok_or_else(|| {
+            CreationError::OsError(format!(
+                "The screen has no renderer {}",
+                index
+            ))
+        })
+    }
+
+    /// Queries all the renderers of the screen `screen_id`, which there can be
+    /// several of on systems with multiple GPUs. Their order doesn't tell
+    /// which one a context will use.
+    ///
+    /// Fails like [`query`].
+    ///
+    /// [`query`]: #method.query
+    pub fn enumerate(
+        xconn: &Arc<XConnection>,
+        screen_id: raw::c_int,
+    ) -> Result<Vec<Self>, CreationError> {
+        if !query_renderer_supported(xconn, screen_id)? {
+            return Err(CreationError::NotSupported(
+                "GLX_MESA_query_renderer is not supported".to_string(),
+            ));
+        }
+        let mut renderers = Vec::new();
+        while let Some(renderer) =
+            unsafe { query_renderer(xconn, screen_id, renderers.len() as u32) }
+        {
+            renderers.push(renderer);
+        }
+        Ok(renderers)
+    }
+}
+
+fn query_renderer_supported(
+    xconn: &Arc<XConnection>,
+    screen_id: raw::c_int,
+) -> Result<bool, CreationError> {
+    if GLX.is_none() {
+        return Err(CreationError::NotSupported(
+            "GLX is not available".to_string(),
+        ));
+    }
+    let extra_functions = GLX_EXTRA.as_ref().unwrap();
+    Ok(
+        load_extensions(xconn, screen_id)?.contains("GLX_MESA_query_renderer")
+            && extra_functions.QueryRendererIntegerMESA.is_loaded()
+            && extra_functions.QueryRendererStringMESA.is_loaded(),
+    )
+}
+
+// Returns `None` if the screen has no renderer `index`.
+unsafe fn query_renderer(
+    xconn: &Arc<XConnection>,
+    screen_id: raw::c_int,
+    index: u32,
+) -> Option<GlxRenderer> {
+    let extra_functions = GLX_EXTRA.as_ref().unwrap();
+    let integer = |attribute: u32, values: &mut [u32]| {
+        extra_functions.QueryRendererIntegerMESA(
+            xconn.display as *mut _,
+            screen_id,
+            index as raw::c_int,
+            attribute as raw::c_int,
+            values.as_mut_ptr(),
+        ) != 0
+    };
+    let string = |attribute: u32| {
+        let string = extra_functions.QueryRendererStringMESA(
+            xconn.display as *mut _,
+            screen_id,
+            index as raw::c_int,
+            attribute as raw::c_int,
+        );
+        if string.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(string).to_string_lossy().into_owned()
+        }
+    };
+
+    let mut vendor_id = [0];
+    if !integer(ffi::glx_extra::RENDERER_VENDOR_ID_MESA, &mut vendor_id) {
+        return None;
+    }
+    let mut device_id = [0];
+    integer(ffi::glx_extra::RENDERER_DEVICE_ID_MESA, &mut device_id);
+    let mut version = [0; 3];
+    integer(ffi::glx_extra::RENDERER_VERSION_MESA, &mut version);
+    let mut accelerated = [0];
+    integer(ffi::glx_extra::RENDERER_ACCELERATED_MESA, &mut accelerated);
+    let mut video_memory = [0];
+    integer(
+        ffi::glx_extra::RENDERER_VIDEO_MEMORY_MESA,
+        &mut video_memory,
+    );
+    let mut unified_memory_architecture = [0];
+    integer(
+        ffi::glx_extra::RENDERER_UNIFIED_MEMORY_ARCHITECTURE_MESA,
+        &mut unified_memory_architecture,
+    );
+
+    Some(GlxRenderer {
+        vendor_id: vendor_id[0],
+        device_id: device_id[0],
+        vendor: string(ffi::glx_extra::RENDERER_VENDOR_ID_MESA),
+        device: string(ffi::glx_extra::RENDERER_DEVICE_ID_MESA),
+        version: (version[0], version[1], version[2]),
+        accelerated: accelerated[0] != 0,
+        video_memory: video_memory[0],
+        unified_memory_architecture: unified_memory_architecture[0] != 0,
+    })
 }
 
 impl Context {
@@ -107,21 +439,119 @@ impl Context {
             glx.QueryVersion(xconn.display as *mut _, &mut major, &mut minor);
         }
 
+        if opengl.priority.is_some() {
+            return Err(CreationError::NotSupported(
+                "Context priorities are not supported by GLX".to_string(),
+            ));
+        }
+
+        if opengl.protected_content {
+            return Err(CreationError::NotSupported(
+                "Protected content is not supported by GLX".to_string(),
+            ));
+        }
+
+        if pf_reqs.color_space.is_some() {
+            return Err(CreationError::NotSupported(
+                "Color spaces are not supported by GLX".to_string(),
+            ));
+        }
+
+        if pf_reqs.textureable {
+            return Err(CreationError::NotSupported(
+                "Textureable pbuffers are not supported by GLX".to_string(),
+            ));
+        }
+
         // loading the list of extensions
         let extensions = load_extensions(&xconn, screen_id)?;
 
-        // finding the pixel format we want
-        let (fb_config, pixel_format, visual_infos) = unsafe {
-            choose_fbconfig(
-                &extensions,
-                &xconn,
-                screen_id,
-                pf_reqs,
-                surface_type,
-                transparent,
-            )?
+        // finding the pixel format we want, fbconfigs require GLX 1.3
+        let fbconfigs_supported = (major, minor) >= (1, 3);
+        let result = if fbconfigs_supported {
+            let choose = |pf_reqs| unsafe {
+                choose_fbconfig(
+                    &extensions,
+                    &xconn,
+                    screen_id,
+                    pf_reqs,
+                    surface_type,
+                    transparent,
+                )
+            };
+            match choose(pf_reqs) {
+                // remote and virtual X servers often have no sRGB configs
+                Err(CreationError::NoAvailablePixelFormat)
+                    if pf_reqs.srgb && !pf_reqs.srgb_required =>
+                {
+                    let mut pf_reqs = pf_reqs.clone();
+                    pf_reqs.srgb = false;
+                    choose(&pf_reqs)
+                }
+                result => result,
+            }
+        } else {
+            Err(CreationError::NoAvailablePixelFormat)
+        };
+        let (fb_config, mut pixel_format, visual_infos, legacy) = match result {
+            Ok((fb_config, pixel_format, visual_infos)) => {
+                (fb_config, pixel_format, visual_infos, false)
+            }
+            Err(CreationError::NoAvailablePixelFormat)
+                if !fbconfigs_supported || pf_reqs.legacy_glx_visuals =>
+            {
+                if surface_type != SurfaceType::Window {
+                    return Err(CreationError::NotSupported(
+                        "Legacy GLX visuals only support windows".to_string(),
+                    ));
+                }
+                let (pixel_format, visual_infos) = unsafe {
+                    choose_legacy_visual(
+                        &extensions,
+                        &xconn,
+                        screen_id,
+                        pf_reqs,
+                    )
+                }
+                .map_err(|err| match err {
+                    CreationError::NoAvailablePixelFormat => {
+                        pf_reqs.no_config_error()
+                    }
+                    err => err,
+                })?;
+                (std::ptr::null(), pixel_format, visual_infos, true)
+            }
+            Err(CreationError::NoAvailablePixelFormat) => {
+                return Err(pf_reqs.no_config_error());
+            }
+            Err(err) => return Err(err),
         };
 
+        // the config caveat doesn't tell software renderers like llvmpipe
+        // apart, the renderer does. Which renderer the context will get isn't
+        // known before it's created, so this only helps when all the
+        // renderers of the screen agree.
+        if query_renderer_supported(&xconn, screen_id)? {
+            let renderers = GlxRenderer::enumerate(&xconn, screen_id)?;
+            let accelerated = renderers.first().map(|r| r.accelerated);
+            if let Some(accelerated) = accelerated {
+                if renderers.iter().all(|r| r.accelerated == accelerated) {
+                    // `Some(true)` is the default, which must not rule out
+                    // llvmpipe on Xvfb or VMs
+                    match pf_reqs.hardware_accelerated {
+                        Some(required)
+                            if pf_reqs.hardware_acceleration_explicit
+                                && required != accelerated =>
+                        {
+                            return Err(CreationError::NoAvailablePixelFormat);
+                        }
+                        _ => (),
+                    }
+                    pixel_format.hardware_accelerated = accelerated;
+                }
+            }
+        }
+
         Ok(ContextPrototype {
             extensions,
             xconn,
@@ -129,19 +559,22 @@ impl Context {
             fb_config,
             visual_infos: unsafe { std::mem::transmute(visual_infos) },
             pixel_format,
+            release_behavior: pf_reqs.release_behavior,
+            legacy,
+            largest_pbuffer: pf_reqs.largest_pbuffer,
+            make_context_current_supported: fbconfigs_supported,
         })
     }
 
-    unsafe fn check_make_current(
-        &self,
-        ret: Option<i32>,
+    fn check_make_current(
+        trap: &XErrorTrap,
+        cmd: &str,
+        ret: i32,
     ) -> Result<(), ContextError> {
-        if ret == Some(0) {
-            let err = self.xconn.check_errors();
-            Err(ContextError::OsError(format!(
-                "`glXMakeCurrent` failed: {:?}",
-                err
-            )))
+        if let Some(err) = trap.context_error(cmd) {
+            Err(err)
+        } else if ret == 0 {
+            Err(ContextError::OsError(format!("`{}` failed", cmd)))
         } else {
             Ok(())
         }
@@ -149,13 +582,97 @@ impl Context {
 
     #[inline]
     pub unsafe fn make_current(&self) -> Result<(), ContextError> {
+        self.make_current_draw_read(self, self)
+    }
+
+    /// Makes the context current, drawing to the window of `draw` and reading
+    /// from the window of `read`. Either can be the context itself.
+    ///
+    /// Distinct windows require `glXMakeContextCurrent` and so GLX 1.3, and
+    /// aren't supported by contexts of legacy visuals. The same window is made
+    /// current with `glXMakeCurrent`.
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        if self.xconn.display != draw.xconn.display
+            || self.xconn.display != read.xconn.display
+        {
+            return Err(ContextError::OsError(
+                "The drawable belongs to another X display".to_string(),
+            ));
+        }
+
         let glx = GLX.as_ref().unwrap();
-        let res = glx.MakeCurrent(
-            self.xconn.display as *mut _,
-            self.drawable,
-            self.context,
-        );
-        self.check_make_current(Some(res))
+        let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
+        let (cmd, res) = if draw.drawable == read.drawable {
+            let res = glx.MakeCurrent(
+                self.xconn.display as *mut _,
+                draw.drawable,
+                self.context,
+            );
+            ("glXMakeCurrent", res)
+        } else if self.make_context_current_supported && !self.legacy_visual {
+            let res = glx.MakeContextCurrent(
+                self.xconn.display as *mut _,
+                draw.drawable,
+                read.drawable,
+                self.context,
+            );
+            ("glXMakeContextCurrent", res)
+        } else {
+            return Err(ContextError::FunctionUnavailable);
+        };
+        match Self::check_make_current(&trap, cmd, res) {
+            Err(ContextError::ApiError(ErrorCode::X11(ffi::BadMatch), msg)) => {
+                let mismatch = self
+                    .config_mismatch(draw)
+                    .or_else(|| self.config_mismatch(read));
+                Err(match mismatch {
+                    Some(mismatch) => ContextError::ConfigMismatch(format!(
+                        "{}: {}",
+                        msg, mismatch
+                    )),
+                    None => ContextError::ApiError(
+                        ErrorCode::X11(ffi::BadMatch),
+                        msg,
+                    ),
+                })
+            }
+            res => res,
+        }
+    }
+
+    // Explains a `BadMatch` from making the context current on the window of
+    // `other`, which is usually created with another visual than the one of
+    // the config, for example a 32-bit visual chosen for a compositor and a
+    // 24-bit config.
+    fn config_mismatch(&self, other: &Context) -> Option<String> {
+        let window = other.x11_window?;
+        let (visual_id, depth) = unsafe {
+            let mut attrs = std::mem::zeroed();
+            if (self.xconn.xlib.XGetWindowAttributes)(
+                self.xconn.display,
+                window,
+                &mut attrs,
+            ) == 0
+            {
+                return None;
+            }
+            (
+                (self.xconn.xlib.XVisualIDFromVisual)(attrs.visual),
+                attrs.depth,
+            )
+        };
+        if visual_id == self.visual_id && depth == self.depth {
+            return None;
+        }
+        Some(format!(
+            "the window has visual {:#x} of depth {}, but the config of the \
+             context has visual {:#x} of depth {}",
+            visual_id, depth, self.visual_id, self.depth
+        ))
     }
 
     #[inline]
@@ -164,14 +681,15 @@ impl Context {
         if self.drawable == glx.GetCurrentDrawable()
             || self.context == glx.GetCurrentContext()
         {
+            let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
             let res = glx.MakeCurrent(
                 self.xconn.display as *mut _,
                 0,
                 std::ptr::null(),
             );
-            self.check_make_current(Some(res))
+            Self::check_make_current(&trap, "glXMakeCurrent", res)
         } else {
-            self.check_make_current(None)
+            Ok(())
         }
     }
 
@@ -186,6 +704,87 @@ impl Context {
         crate::Api::OpenGl
     }
 
+    #[inline]
+    pub fn drawable(&self) -> ffi::glx::types::GLXDrawable {
+        self.drawable
+    }
+
+    #[inline]
+    pub fn x11_window(&self) -> Option<ffi::Window> {
+        self.x11_window
+    }
+
+    #[inline]
+    pub fn visual_id(&self) -> ffi::VisualID {
+        self.visual_id
+    }
+
+    /// Copies the state selected by `mask` from `src` with `glXCopyContext`.
+    /// The context must not be current.
+    pub fn copy_state_from(
+        &self,
+        src: &Context,
+        mask: StateMask,
+    ) -> Result<(), ContextError> {
+        if self.xconn.display != src.xconn.display {
+            return Err(ContextError::FunctionUnavailable);
+        }
+        if self.is_current() {
+            return Err(ContextError::OsError(
+                "The destination of `glXCopyContext` can't be current"
+                    .to_string(),
+            ));
+        }
+
+        let glx = GLX.as_ref().unwrap();
+        let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
+        unsafe {
+            glx.CopyContext(
+                self.xconn.display as *mut _,
+                src.context,
+                self.context,
+                mask.0 as raw::c_ulong,
+            );
+        }
+        match trap.context_error("glXCopyContext") {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the size of the drawable, which for a pbuffer may be smaller
+    /// than requested with `GLX_LARGEST_PBUFFER`.
+    pub fn drawable_size(&self) -> dpi::PhysicalSize<u32> {
+        let glx = GLX.as_ref().unwrap();
+        let (mut width, mut height) = (0, 0);
+        unsafe {
+            glx.QueryDrawable(
+                self.xconn.display as *mut _,
+                self.drawable,
+                ffi::glx::WIDTH as raw::c_int,
+                &mut width,
+            );
+            glx.QueryDrawable(
+                self.xconn.display as *mut _,
+                self.drawable,
+                ffi::glx::HEIGHT as raw::c_int,
+                &mut height,
+            );
+        }
+        dpi::PhysicalSize::new(width, height)
+    }
+
+    #[inline]
+    pub fn is_direct(&self) -> bool {
+        let glx = GLX.as_ref().unwrap();
+        unsafe { glx.IsDirect(self.xconn.display as *mut _, self.context) != 0 }
+    }
+
+    #[inline]
+    pub fn uses_legacy_visual(&self) -> bool {
+        self.legacy_visual
+    }
+
     #[inline]
     pub unsafe fn raw_handle(&self) -> ffi::GLXContext {
         self.context
@@ -199,8 +798,47 @@ impl Context {
         unsafe { glx.GetProcAddress(addr as *const _) as *const _ }
     }
 
+    /// Waits for OpenGL rendering to complete before X rendering to the
+    /// drawable.
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        self.wait("glXWaitGL", |glx| unsafe { glx.WaitGL() })
+    }
+
+    /// Waits for X rendering to the drawable to complete before OpenGL
+    /// rendering.
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        self.wait("glXWaitX", |glx| unsafe { glx.WaitX() })
+    }
+
+    fn wait<F>(&self, cmd: &str, f: F) -> Result<(), ContextError>
+    where
+        F: FnOnce(&ffi::glx::Glx),
+    {
+        if !self.is_current() {
+            return Err(ContextError::OsError(format!(
+                "The context must be current to call `{}`",
+                cmd
+            )));
+        }
+
+        f(GLX.as_ref().unwrap());
+        if let Err(err) = self.xconn.check_errors() {
+            Err(ContextError::OsError(format!(
+                "`{}` failed: {:?}",
+                cmd, err
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
     #[inline]
     pub fn swap_buffers(&self) -> Result<(), ContextError> {
+        // swapping has no effect on pixmaps, which are single-buffered
+        if self.pixmap {
+            return Ok(());
+        }
+
         let glx = GLX.as_ref().unwrap();
         unsafe {
             glx.SwapBuffers(self.xconn.display as *mut _, self.drawable);
@@ -215,10 +853,498 @@ impl Context {
         }
     }
 
+    // Whether `GLX_OML_sync_control` is supported.
+    fn sync_control_supported(&self) -> bool {
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        self.extensions.contains("GLX_OML_sync_control")
+            && extra_functions.GetSyncValuesOML.is_loaded()
+            && extra_functions.SwapBuffersMscOML.is_loaded()
+            && extra_functions.WaitForMscOML.is_loaded()
+    }
+
+    fn video_sync_supported(&self) -> bool {
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        self.extensions.contains("GLX_SGI_video_sync")
+            && extra_functions.GetVideoSyncSGI.is_loaded()
+            && extra_functions.WaitVideoSyncSGI.is_loaded()
+    }
+
+    // `GLX_SGI_video_sync` only works with a current, direct context.
+    fn check_video_sync(&self) -> Result<(), ContextError> {
+        if !self.video_sync_supported() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to use `GLX_SGI_video_sync`"
+                    .to_string(),
+            ));
+        }
+        if !self.is_direct() {
+            return Err(ContextError::OsError(
+                "`GLX_SGI_video_sync` is not supported by indirect contexts"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn video_sync_counter(&self) -> Result<u32, ContextError> {
+        self.check_video_sync()?;
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let mut count = 0;
+        if unsafe { extra_functions.GetVideoSyncSGI(&mut count) } != 0 {
+            return Err(ContextError::OsError(
+                "`glXGetVideoSyncSGI` failed".to_string(),
+            ));
+        }
+        Ok(count)
+    }
+
+    pub fn wait_video_sync(
+        &self,
+        divisor: u32,
+        remainder: u32,
+    ) -> Result<u32, ContextError> {
+        if divisor == 0 || remainder >= divisor || divisor > i32::MAX as u32 {
+            return Err(ContextError::OsError(format!(
+                "Invalid video sync divisor {} and remainder {}",
+                divisor, remainder
+            )));
+        }
+        self.check_video_sync()?;
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let mut count = 0;
+        let ret = unsafe {
+            extra_functions.WaitVideoSyncSGI(
+                divisor as raw::c_int,
+                remainder as raw::c_int,
+                &mut count,
+            )
+        };
+        if ret != 0 {
+            return Err(ContextError::OsError(
+                "`glXWaitVideoSyncSGI` failed".to_string(),
+            ));
+        }
+        Ok(count)
+    }
+
+    pub fn sync_values(&self) -> Result<GlxSyncValues, ContextError> {
+        if !self.sync_control_supported() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let (mut ust, mut msc, mut sbc) = (0, 0, 0);
+        let ret = unsafe {
+            extra_functions.GetSyncValuesOML(
+                self.xconn.display as *mut _,
+                self.drawable,
+                &mut ust,
+                &mut msc,
+                &mut sbc,
+            )
+        };
+        if ret == 0 {
+            return Err(ContextError::OsError(
+                "`glXGetSyncValuesOML` failed".to_string(),
+            ));
+        }
+        Ok(GlxSyncValues::new(ust, msc, sbc))
+    }
+
+    pub fn swap_buffers_msc(
+        &self,
+        target_msc: u64,
+        divisor: u64,
+        remainder: u64,
+    ) -> Result<u64, ContextError> {
+        if !self.sync_control_supported() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let sbc = unsafe {
+            extra_functions.SwapBuffersMscOML(
+                self.xconn.display as *mut _,
+                self.drawable,
+                target_msc as i64,
+                divisor as i64,
+                remainder as i64,
+            )
+        };
+        if let Err(err) = self.xconn.check_errors() {
+            return Err(ContextError::OsError(format!(
+                "`glXSwapBuffersMscOML` failed: {:?}",
+                err
+            )));
+        }
+        if sbc < 0 {
+            return Err(ContextError::OsError(
+                "`glXSwapBuffersMscOML` failed".to_string(),
+            ));
+        }
+        Ok(sbc as u64)
+    }
+
+    /// Swaps the buffers with `glXSwapBuffersMscOML`, which returns the swap
+    /// buffer counter the swap will have. That counter is the frame id.
+    pub fn swap_buffers_with_frame_id(&self) -> Result<FrameId, ContextError> {
+        // with Mesa, a target of 0 honors the swap interval like
+        // `glXSwapBuffers`
+        self.swap_buffers_msc(0, 0, 0).map(FrameId)
+    }
+
+    /// Returns when a frame swapped with `swap_buffers_with_frame_id` reached
+    /// the display, which is only known while it is the latest completed
+    /// swap.
+    pub fn presentation_times(
+        &self,
+        frame: FrameId,
+    ) -> Result<PresentationTimes, ContextError> {
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        if !extra_functions.WaitForSbcOML.is_loaded() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let mut times = PresentationTimes {
+            requested_present: None,
+            rendering_complete: None,
+            display_present: None,
+            composite_interval: self.msc_rate(),
+        };
+        let values = self.sync_values()?;
+        if values.sbc < frame.0 {
+            return Ok(times);
+        }
+        if values.sbc > frame.0 {
+            return Err(ContextError::OsError(
+                "The frame is older than the latest completed swap".to_string(),
+            ));
+        }
+
+        // the swap has completed, so this returns at once with its time
+        let (mut ust, mut msc, mut sbc) = (0, 0, 0);
+        let ret = unsafe {
+            extra_functions.WaitForSbcOML(
+                self.xconn.display as *mut _,
+                self.drawable,
+                frame.0 as i64,
+                &mut ust,
+                &mut msc,
+                &mut sbc,
+            )
+        };
+        if ret == 0 {
+            return Err(ContextError::OsError(
+                "`glXWaitForSbcOML` failed".to_string(),
+            ));
+        }
+        times.display_present = Some(GlxSyncValues::new(ust, msc, sbc).ust);
+        Ok(times)
+    }
+
+    // The refresh period of the display, from `glXGetMscRateOML`.
+    fn msc_rate(&self) -> Option<Duration> {
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        if !extra_functions.GetMscRateOML.is_loaded() {
+            return None;
+        }
+        let (mut numerator, mut denominator) = (0, 0);
+        let ret = unsafe {
+            extra_functions.GetMscRateOML(
+                self.xconn.display as *mut _,
+                self.drawable,
+                &mut numerator,
+                &mut denominator,
+            )
+        };
+        if ret == 0 || numerator <= 0 || denominator <= 0 {
+            return None;
+        }
+        Some(Duration::from_nanos(
+            1_000_000_000 * denominator as u64 / numerator as u64,
+        ))
+    }
+
+    pub fn wait_for_msc(
+        &self,
+        target_msc: u64,
+        divisor: u64,
+        remainder: u64,
+    ) -> Result<GlxSyncValues, ContextError> {
+        if !self.sync_control_supported() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let (mut ust, mut msc, mut sbc) = (0, 0, 0);
+        let ret = unsafe {
+            extra_functions.WaitForMscOML(
+                self.xconn.display as *mut _,
+                self.drawable,
+                target_msc as i64,
+                divisor as i64,
+                remainder as i64,
+                &mut ust,
+                &mut msc,
+                &mut sbc,
+            )
+        };
+        if ret == 0 {
+            return Err(ContextError::OsError(
+                "`glXWaitForMscOML` failed".to_string(),
+            ));
+        }
+        Ok(GlxSyncValues::new(ust, msc, sbc))
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to query the buffer age"
+                    .to_string(),
+            ));
+        }
+        if !self.buffer_age_supported {
+            return Ok(0);
+        }
+
+        let glx = GLX.as_ref().unwrap();
+        // querying a drawable that isn't the current draw drawable of the
+        // thread raises `GLXBadDrawable`
+        if unsafe { glx.GetCurrentDrawable() } != self.drawable {
+            return Ok(0);
+        }
+
+        let mut age = 0;
+        unsafe {
+            glx.QueryDrawable(
+                self.xconn.display as *mut _,
+                self.drawable,
+                ffi::glx_extra::BACK_BUFFER_AGE_EXT as i32,
+                &mut age,
+            );
+        }
+        Ok(age)
+    }
+
+    pub fn set_swap_interval(
+        &self,
+        interval: SwapInterval,
+    ) -> Result<SwapInterval, ContextError> {
+        if !self.supports_swap_interval(interval) {
+            return Err(ContextError::FunctionUnavailable);
+        }
+        let swap_control = self
+            .swap_control()
+            .ok_or(ContextError::FunctionUnavailable)?;
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+
+        // A negative interval requests adaptive vsync.
+        let interval = match interval {
+            SwapInterval::DontWait => 0,
+            SwapInterval::Wait(n) => n.get() as raw::c_int,
+            SwapInterval::AdaptiveWait(n) => -(n.get() as raw::c_int),
+        };
+
+        match swap_control {
+            GlxSwapControl::Ext { .. } => unsafe {
+                extra_functions.SwapIntervalEXT(
+                    self.xconn.display as *mut _,
+                    self.drawable,
+                    interval,
+                );
+            },
+            GlxSwapControl::Mesa | GlxSwapControl::Sgi => {
+                // these change the drawable of the current context
+                let _guard = MakeCurrentGuard::new(
+                    &self.xconn,
+                    self.drawable,
+                    self.context,
+                )
+                .map_err(ContextError::OsError)?;
+                unsafe {
+                    if swap_control == GlxSwapControl::Mesa {
+                        extra_functions.SwapIntervalMESA(interval as u32);
+                    } else {
+                        extra_functions.SwapIntervalSGI(interval);
+                    }
+                }
+            }
+        }
+
+        self.xconn
+            .check_errors()
+            .map(|()| SwapInterval::from_raw(interval))
+            .map_err(|err| {
+                ContextError::OsError(format!(
+                    "Couldn't set the swap interval: {:?}",
+                    err
+                ))
+            })
+    }
+
+    pub fn get_swap_interval(&self) -> Option<SwapInterval> {
+        let glx = GLX.as_ref().unwrap();
+
+        let swap_control = self.swap_control()?;
+        if !swap_control.is_queryable() {
+            return None;
+        }
+
+        if let GlxSwapControl::Ext { tear } = swap_control {
+            let (mut interval, mut late_swaps_tear) = (0, 0);
+            unsafe {
+                glx.QueryDrawable(
+                    self.xconn.display as *mut _,
+                    self.drawable,
+                    ffi::glx_extra::SWAP_INTERVAL_EXT as i32,
+                    &mut interval,
+                );
+                // `GLX_SWAP_INTERVAL_EXT` is never negative, adaptive vsync
+                // is reported separately.
+                if tear {
+                    glx.QueryDrawable(
+                        self.xconn.display as *mut _,
+                        self.drawable,
+                        ffi::glx_extra::LATE_SWAPS_TEAR_EXT as i32,
+                        &mut late_swaps_tear,
+                    );
+                }
+            }
+            let interval = interval as i32;
+            Some(SwapInterval::from_raw(if late_swaps_tear != 0 {
+                -interval
+            } else {
+                interval
+            }))
+        } else {
+            let extra_functions = GLX_EXTRA.as_ref().unwrap();
+            let interval = unsafe { extra_functions.GetSwapIntervalMESA() };
+            Some(SwapInterval::from_raw(interval))
+        }
+    }
+
+    pub fn supports_swap_interval(&self, interval: SwapInterval) -> bool {
+        match (self.swap_control(), interval) {
+            (None, _) => false,
+            (Some(swap_control), SwapInterval::DontWait) => {
+                swap_control.supports_dont_wait()
+            }
+            (Some(_), SwapInterval::Wait(_)) => true,
+            (Some(swap_control), SwapInterval::AdaptiveWait(_)) => {
+                swap_control.supports_adaptive_wait()
+            }
+        }
+    }
+
+    /// Returns the extension used to change the swap interval, if any.
+    #[inline]
+    pub fn swap_control(&self) -> Option<GlxSwapControl> {
+        GlxSwapControl::find(&self.extensions)
+    }
+
+    pub fn query_max_swap_groups(
+        &self,
+    ) -> Result<SwapGroupLimits, ContextError> {
+        if !self.extensions.contains("GLX_NV_swap_group") {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let (mut max_groups, mut max_barriers) = (0, 0);
+        let res = unsafe {
+            extra_functions.QueryMaxSwapGroupsNV(
+                self.xconn.display as *mut _,
+                self.screen_id,
+                &mut max_groups,
+                &mut max_barriers,
+            )
+        };
+        if res == 0 {
+            return Err(ContextError::OsError(
+                "`glXQueryMaxSwapGroupsNV` failed".to_string(),
+            ));
+        }
+        Ok(SwapGroupLimits {
+            max_groups,
+            max_barriers,
+        })
+    }
+
+    pub fn join_swap_group(&self, group: u32) -> Result<(), ContextError> {
+        self.query_max_swap_groups()?.check_group(group)?;
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
+        let res = unsafe {
+            extra_functions.JoinSwapGroupNV(
+                self.xconn.display as *mut _,
+                self.drawable,
+                group,
+            )
+        };
+        if let Some(err) = trap.context_error("glXJoinSwapGroupNV") {
+            return Err(err);
+        }
+        if res == 0 {
+            return Err(ContextError::OsError(
+                "`glXJoinSwapGroupNV` failed".to_string(),
+            ));
+        }
+        self.swap_group.store(group, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn bind_swap_barrier(
+        &self,
+        group: u32,
+        barrier: u32,
+    ) -> Result<(), ContextError> {
+        let limits = self.query_max_swap_groups()?;
+        limits.check_group(group)?;
+        limits.check_barrier(barrier)?;
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+        let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
+        let res = unsafe {
+            extra_functions.BindSwapBarrierNV(
+                self.xconn.display as *mut _,
+                group,
+                barrier,
+            )
+        };
+        if let Some(err) = trap.context_error("glXBindSwapBarrierNV") {
+            return Err(err);
+        }
+        if res == 0 {
+            return Err(ContextError::OsError(
+                "`glXBindSwapBarrierNV` failed".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     #[inline]
     pub fn get_pixel_format(&self) -> PixelFormat {
         self.pixel_format.clone()
     }
+
+    #[inline]
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> ReleaseBehavior {
+        self.release_behavior
+    }
 }
 
 unsafe impl Send for Context {}
@@ -245,19 +1371,40 @@ impl Drop for Context {
             }
             std::mem::drop(guard);
 
+            if *self.swap_group.get_mut() != 0 {
+                GLX_EXTRA.as_ref().unwrap().JoinSwapGroupNV(
+                    self.xconn.display as *mut _,
+                    self.drawable,
+                    0,
+                );
+            }
+
             glx.DestroyContext(self.xconn.display as *mut _, self.context);
+            if self.pixmap {
+                glx.DestroyPixmap(self.xconn.display as *mut _, self.drawable);
+            }
+            // before the X window, which is dropped after the context
+            if self.glx_window {
+                glx.DestroyWindow(self.xconn.display as *mut _, self.drawable);
+            }
         }
     }
 }
 
 #[derive(Debug)]
 pub struct ContextPrototype<'a> {
-    extensions: String,
+    extensions: Extensions,
     xconn: Arc<XConnection>,
     opengl: &'a GlAttributes<&'a Context>,
     fb_config: ffi::glx::types::GLXFBConfig,
     visual_infos: ffi::XVisualInfo,
     pixel_format: PixelFormat,
+    release_behavior: ReleaseBehavior,
+    // Whether the visual was chosen with `glXChooseVisual` rather than from
+    // an fbconfig, in which case `fb_config` is null.
+    legacy: bool,
+    largest_pbuffer: bool,
+    make_context_current_supported: bool,
 }
 
 impl<'a> ContextPrototype<'a> {
@@ -267,22 +1414,102 @@ impl<'a> ContextPrototype<'a> {
     }
 
     // creating GL context
-    fn create_context(
-        &self,
-    ) -> Result<(ffi::glx_extra::Glx, ffi::GLXContext), CreationError> {
-        let glx = GLX.as_ref().unwrap();
+    // Whether `create_context` honors `Robustness::NoError`.
+    fn no_error(&self) -> bool {
+        !self.legacy
+            && self.opengl.robustness == Robustness::NoError
+            && self.extensions.contains("GLX_ARB_create_context")
+            && self.extensions.contains("GLX_ARB_create_context_no_error")
+    }
+
+    // Whether `create_context` sets `GLX_LOSE_CONTEXT_ON_RESET_ARB`.
+    fn lose_context_on_reset(&self) -> bool {
+        !self.legacy
+            && self.opengl.robustness.loses_context_on_reset()
+            && self.extensions.contains("GLX_ARB_create_context")
+            && self
+                .extensions
+                .contains("GLX_ARB_create_context_robustness")
+    }
+
+    // Whether to ask for a direct context.
+    fn direct(&self) -> bool {
+        self.opengl.direct_rendering != DirectRendering::RequireIndirect
+    }
+
+    fn create_context(&self) -> Result<ffi::GLXContext, CreationError> {
+        let context = self.create_raw_context()?;
+        if self.opengl.direct_rendering == DirectRendering::RequireDirect {
+            let glx = GLX.as_ref().unwrap();
+            unsafe {
+                if glx.IsDirect(self.xconn.display as *mut _, context) == 0 {
+                    glx.DestroyContext(self.xconn.display as *mut _, context);
+                    return Err(CreationError::DirectRenderingNotSupported);
+                }
+            }
+        }
+        Ok(context)
+    }
+
+    fn create_raw_context(&self) -> Result<ffi::GLXContext, CreationError> {
         let share = match self.opengl.sharing {
             Some(ctx) => ctx.context,
             None => std::ptr::null(),
         };
 
-        // loading the extra GLX functions
-        let extra_functions = ffi::glx_extra::Glx::load_with(|proc_name| {
-            let c_str = CString::new(proc_name).unwrap();
-            unsafe {
-                glx.GetProcAddress(c_str.as_ptr() as *const u8) as *const _
+        if self.legacy {
+            return self.create_legacy_context(share);
+        }
+
+        if self.release_behavior == ReleaseBehavior::None
+            && !(self.extensions.contains("GLX_ARB_create_context")
+                && self.extensions.contains("GLX_ARB_context_flush_control"))
+        {
+            return Err(CreationError::ReleaseBehaviorNotSupported);
+        }
+
+        let no_error = self.no_error();
+        if no_error && self.opengl.debug {
+            return Err(CreationError::NotSupported(
+                "No-error contexts can't be debug contexts".to_string(),
+            ));
+        }
+        if let Some(ctx) = self.opengl.sharing {
+            if ctx.no_error != no_error {
+                return Err(CreationError::NotSupported(
+                    "Shared contexts must agree on the no-error flag"
+                        .to_string(),
+                ));
             }
-        });
+        }
+
+        // all the contexts of a share group must have the same reset
+        // notification strategy
+        let lose_context_on_reset = self.lose_context_on_reset();
+        if let Some(ctx) = self.opengl.sharing {
+            if ctx.lose_context_on_reset != lose_context_on_reset {
+                return Err(CreationError::NotSupported(
+                    "Shared contexts must have the same reset notification \
+                     strategy"
+                        .to_string(),
+                ));
+            }
+        }
+        if self.opengl.reset_isolation
+            && !(lose_context_on_reset
+                && (self
+                    .extensions
+                    .contains("GLX_ARB_robustness_application_isolation")
+                    || self
+                        .extensions
+                        .contains("GLX_ARB_robustness_share_group_isolation")))
+        {
+            return Err(CreationError::NotSupported(
+                "Reset isolation is not supported by GLX".to_string(),
+            ));
+        }
+
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
 
         let context = match self.opengl.version {
             GlRequest::Latest => {
@@ -305,13 +1532,17 @@ impl<'a> ContextPrototype<'a> {
                     // the latest supported version but the one requested
                     for opengl_version in opengl_versions.iter() {
                         match create_context(
-                            &extra_functions,
+                            extra_functions,
                             &self.extensions,
                             &self.xconn.xlib,
                             *opengl_version,
                             self.opengl.profile,
                             self.opengl.debug,
+                            self.opengl.forward_compatible,
                             self.opengl.robustness,
+                            self.opengl.reset_isolation,
+                            self.release_behavior,
+                            self.direct(),
                             share,
                             self.xconn.display,
                             self.fb_config,
@@ -325,13 +1556,17 @@ impl<'a> ContextPrototype<'a> {
                         }
                     }
                     ctx = create_context(
-                        &extra_functions,
+                        extra_functions,
                         &self.extensions,
                         &self.xconn.xlib,
                         (1, 0),
                         self.opengl.profile,
                         self.opengl.debug,
+                        self.opengl.forward_compatible,
                         self.opengl.robustness,
+                        self.opengl.reset_isolation,
+                        self.release_behavior,
+                        self.direct(),
                         share,
                         self.xconn.display,
                         self.fb_config,
@@ -342,13 +1577,17 @@ impl<'a> ContextPrototype<'a> {
                 ctx
             }
             GlRequest::Specific(Api::OpenGl, (major, minor)) => create_context(
-                &extra_functions,
+                extra_functions,
                 &self.extensions,
                 &self.xconn.xlib,
                 (major, minor),
                 self.opengl.profile,
                 self.opengl.debug,
+                self.opengl.forward_compatible,
                 self.opengl.robustness,
+                self.opengl.reset_isolation,
+                self.release_behavior,
+                self.direct(),
                 share,
                 self.xconn.display,
                 self.fb_config,
@@ -359,13 +1598,17 @@ impl<'a> ContextPrototype<'a> {
                 opengl_version: (major, minor),
                 ..
             } => create_context(
-                &extra_functions,
+                extra_functions,
                 &self.extensions,
                 &self.xconn.xlib,
                 (major, minor),
                 self.opengl.profile,
                 self.opengl.debug,
+                self.opengl.forward_compatible,
                 self.opengl.robustness,
+                self.opengl.reset_isolation,
+                self.release_behavior,
+                self.direct(),
                 share,
                 self.xconn.display,
                 self.fb_config,
@@ -373,7 +1616,54 @@ impl<'a> ContextPrototype<'a> {
             )?,
         };
 
-        Ok((extra_functions, context))
+        Ok(context)
+    }
+
+    // Legacy visuals can't be passed to `glXCreateContextAttribsARB`, so
+    // only what `glXCreateContext` does can be honored.
+    fn create_legacy_context(
+        &self,
+        share: ffi::GLXContext,
+    ) -> Result<ffi::GLXContext, CreationError> {
+        if self.release_behavior == ReleaseBehavior::None {
+            return Err(CreationError::ReleaseBehaviorNotSupported);
+        }
+        match self.opengl.robustness {
+            Robustness::RobustNoResetNotification
+            | Robustness::RobustLoseContextOnReset => {
+                return Err(CreationError::RobustnessNotSupported);
+            }
+            _ => (),
+        }
+        if self.opengl.profile == Some(GlProfile::Core) {
+            return Err(CreationError::OpenGlVersionNotSupported);
+        }
+
+        let glx = GLX.as_ref().unwrap();
+        let visual_infos: *const ffi::XVisualInfo = &self.visual_infos;
+        let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
+        let context = unsafe {
+            glx.CreateContext(
+                self.xconn.display as *mut _,
+                visual_infos as *mut _,
+                share,
+                self.direct() as raw::c_int,
+            )
+        };
+        if let Some(err) = trap.creation_error("glXCreateContext") {
+            if !context.is_null() {
+                unsafe {
+                    glx.DestroyContext(self.xconn.display as *mut _, context);
+                }
+            }
+            return Err(err);
+        }
+        if context.is_null() {
+            return Err(CreationError::OsError(
+                "`glXCreateContext` failed".to_string(),
+            ));
+        }
+        Ok(context)
     }
 
     pub fn finish_pbuffer(
@@ -382,16 +1672,24 @@ impl<'a> ContextPrototype<'a> {
     ) -> Result<Context, CreationError> {
         let glx = GLX.as_ref().unwrap();
         let size: (u32, u32) = size.into();
-        let (_extra_functions, context) = self.create_context()?;
 
-        let attributes: Vec<raw::c_int> = vec![
+        let mut attributes: Vec<raw::c_int> = vec![
             ffi::glx::PBUFFER_WIDTH as raw::c_int,
             size.0 as raw::c_int,
             ffi::glx::PBUFFER_HEIGHT as raw::c_int,
             size.1 as raw::c_int,
-            0,
         ];
+        if self.largest_pbuffer {
+            attributes.push(ffi::glx::LARGEST_PBUFFER as raw::c_int);
+            attributes.push(1);
+        } else {
+            self.check_pbuffer_size(size)?;
+        }
+        attributes.push(0);
 
+        let context = self.create_context()?;
+
+        let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
         let pbuffer = unsafe {
             glx.CreatePbuffer(
                 self.xconn.display as *mut _,
@@ -399,95 +1697,250 @@ impl<'a> ContextPrototype<'a> {
                 attributes.as_ptr(),
             )
         };
+        if let Some(err) = trap.creation_error("glXCreatePbuffer") {
+            unsafe {
+                glx.DestroyContext(self.xconn.display as *mut _, context);
+            }
+            return Err(err);
+        }
+        drop(trap);
 
+        let no_error = self.no_error();
+        let lose_context_on_reset = self.lose_context_on_reset();
         Ok(Context {
             xconn: self.xconn,
             drawable: pbuffer,
             context,
             pixel_format: self.pixel_format,
+            // pbuffers aren't swapped
+            buffer_age_supported: false,
+            extensions: self.extensions,
+            no_error,
+            lose_context_on_reset,
+            release_behavior: self.release_behavior,
+            pixmap: false,
+            x11_window: None,
+            glx_window: false,
+            legacy_visual: self.legacy,
+            make_context_current_supported: self.make_context_current_supported,
+            visual_id: self.visual_infos.visualid,
+            depth: self.visual_infos.depth,
+            screen_id: self.visual_infos.screen,
+            swap_group: AtomicU32::new(0),
         })
     }
 
-    pub fn finish(self, window: ffi::Window) -> Result<Context, CreationError> {
+    // Fails with a readable error instead of `BadAlloc` if the pbuffer is
+    // larger than what the fbconfig supports.
+    fn check_pbuffer_size(
+        &self,
+        size: (u32, u32),
+    ) -> Result<(), CreationError> {
         let glx = GLX.as_ref().unwrap();
-        let (extra_functions, context) = self.create_context()?;
-
-        // vsync
-        let swap_mode = if self.opengl.vsync { 1 } else { 0 };
-
-        let _guard = MakeCurrentGuard::new(&self.xconn, window, context)
-            .map_err(|err| CreationError::OsError(err))?;
-
-        if check_ext(&self.extensions, "GLX_EXT_swap_control")
-            && extra_functions.SwapIntervalEXT.is_loaded()
-        {
-            // this should be the most common extension
+        let attrib = |attrib| {
+            let mut value = 0;
             unsafe {
-                extra_functions.SwapIntervalEXT(
+                glx.GetFBConfigAttrib(
                     self.xconn.display as *mut _,
-                    window,
-                    swap_mode,
+                    self.fb_config,
+                    attrib as raw::c_int,
+                    &mut value,
                 );
             }
+            value as u64
+        };
+        crate::check_pbuffer_size(
+            size,
+            attrib(ffi::glx::MAX_PBUFFER_WIDTH),
+            attrib(ffi::glx::MAX_PBUFFER_HEIGHT),
+            attrib(ffi::glx::MAX_PBUFFER_PIXELS),
+        )
+    }
 
-            let mut swap = unsafe { std::mem::zeroed() };
+    pub fn finish_pixmap(
+        self,
+        pixmap: ffi::Pixmap,
+    ) -> Result<Context, CreationError> {
+        let glx = GLX.as_ref().unwrap();
+        let context = self.create_context()?;
+
+        let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
+        let glx_pixmap = unsafe {
+            glx.CreatePixmap(
+                self.xconn.display as *mut _,
+                self.fb_config,
+                pixmap,
+                std::ptr::null(),
+            )
+        };
+        if let Some((code, msg)) = trap.check("glXCreatePixmap") {
             unsafe {
-                glx.QueryDrawable(
+                glx.DestroyContext(self.xconn.display as *mut _, context);
+            }
+            return Err(CreationError::ApiError(
+                code,
+                format!("{}, the pixmap may not match the config", msg),
+            ));
+        }
+        drop(trap);
+
+        let no_error = self.no_error();
+        let lose_context_on_reset = self.lose_context_on_reset();
+        Ok(Context {
+            xconn: self.xconn,
+            drawable: glx_pixmap,
+            context,
+            pixel_format: self.pixel_format,
+            buffer_age_supported: false,
+            extensions: self.extensions,
+            no_error,
+            lose_context_on_reset,
+            release_behavior: self.release_behavior,
+            pixmap: true,
+            x11_window: None,
+            glx_window: false,
+            legacy_visual: self.legacy,
+            make_context_current_supported: self.make_context_current_supported,
+            visual_id: self.visual_infos.visualid,
+            depth: self.visual_infos.depth,
+            screen_id: self.visual_infos.screen,
+            swap_group: AtomicU32::new(0),
+        })
+    }
+
+    pub fn finish(self, window: ffi::Window) -> Result<Context, CreationError> {
+        let glx = GLX.as_ref().unwrap();
+
+        // `glXMakeCurrent` fails with a bare BadMatch otherwise
+        match x11_utils::get_window_screen(&self.xconn, window) {
+            Some(screen_id) if screen_id != self.visual_infos.screen => {
+                return Err(CreationError::OsError(format!(
+                    "The window is on X screen {}, but the config is on X \
+                     screen {}",
+                    screen_id, self.visual_infos.screen
+                )));
+            }
+            _ => (),
+        }
+
+        let context = self.create_context()?;
+        let extra_functions = GLX_EXTRA.as_ref().unwrap();
+
+        // Some drivers only honor the attributes of the fbconfig, like sRGB
+        // and multisampling, when rendering to a GLXWindow created over it.
+        // Legacy visuals have no fbconfig. A GLXWindow tracks the size of its
+        // X window, so it doesn't need to be resized.
+        let (drawable, glx_window) = if self.legacy {
+            (window, false)
+        } else {
+            let trap = XErrorTrap::new(&self.xconn.xlib, self.xconn.display);
+            let glx_window = unsafe {
+                glx.CreateWindow(
                     self.xconn.display as *mut _,
+                    self.fb_config,
                     window,
-                    ffi::glx_extra::SWAP_INTERVAL_EXT as i32,
-                    &mut swap,
-                );
+                    std::ptr::null(),
+                )
+            };
+            match trap.check("glXCreateWindow") {
+                // an X window can only have one GLXWindow, so it fails if
+                // another context already renders to it
+                Some((_, msg)) => {
+                    warn!("{}, rendering to the X window instead", msg);
+                    (window, false)
+                }
+                None => (glx_window, true),
             }
+        };
+
+        // vsync
+        let swap_mode = if self.opengl.vsync { 1 } else { 0 };
+
+        let _guard = MakeCurrentGuard::new(&self.xconn, drawable, context)
+            .map_err(|err| CreationError::OsError(err))?;
+
+        match GlxSwapControl::find(&self.extensions) {
+            // this should be the most common extension
+            Some(GlxSwapControl::Ext { .. }) => {
+                unsafe {
+                    extra_functions.SwapIntervalEXT(
+                        self.xconn.display as *mut _,
+                        drawable,
+                        swap_mode,
+                    );
+                }
 
-            if swap != swap_mode as u32 {
-                return Err(CreationError::OsError(format!(
+                let mut swap = unsafe { std::mem::zeroed() };
+                unsafe {
+                    glx.QueryDrawable(
+                        self.xconn.display as *mut _,
+                        drawable,
+                        ffi::glx_extra::SWAP_INTERVAL_EXT as i32,
+                        &mut swap,
+                    );
+                }
+
+                if swap != swap_mode as u32 {
+                    return Err(CreationError::OsError(format!(
                     "Couldn't setup vsync: expected interval `{}` but got `{}`",
                     swap_mode, swap
                 )));
+                }
             }
-        } else if check_ext(&self.extensions, "GLX_MESA_swap_control")
-            && extra_functions.SwapIntervalMESA.is_loaded()
-        {
-            unsafe {
+            Some(GlxSwapControl::Mesa) => unsafe {
                 extra_functions.SwapIntervalMESA(swap_mode as u32);
-            }
-        } else if check_ext(&self.extensions, "GLX_SGI_swap_control")
-            && extra_functions.SwapIntervalSGI.is_loaded()
-        {
-            unsafe {
+            },
+            // `glXSwapIntervalSGI` can't disable vsync, so it is left on
+            Some(GlxSwapControl::Sgi) if swap_mode == 0 => (),
+            Some(GlxSwapControl::Sgi) => unsafe {
                 extra_functions.SwapIntervalSGI(swap_mode);
+            },
+            None => {
+                return Err(CreationError::OsError(
+                    "Couldn't find any available vsync extension".to_string(),
+                ));
             }
-        } else {
-            return Err(CreationError::OsError(
-                "Couldn't find any available vsync extension".to_string(),
-            ));
         }
 
+        let no_error = self.no_error();
+        let lose_context_on_reset = self.lose_context_on_reset();
         Ok(Context {
             xconn: self.xconn,
-            drawable: window,
+            drawable,
             context,
             pixel_format: self.pixel_format,
+            buffer_age_supported: self
+                .extensions
+                .contains("GLX_EXT_buffer_age"),
+            extensions: self.extensions,
+            no_error,
+            lose_context_on_reset,
+            release_behavior: self.release_behavior,
+            pixmap: false,
+            x11_window: Some(window),
+            glx_window,
+            legacy_visual: self.legacy,
+            make_context_current_supported: self.make_context_current_supported,
+            visual_id: self.visual_infos.visualid,
+            depth: self.visual_infos.depth,
+            screen_id: self.visual_infos.screen,
+            swap_group: AtomicU32::new(0),
         })
     }
 }
 
-extern "C" fn x_error_callback(
-    _dpy: *mut ffi::Display,
-    _err: *mut ffi::XErrorEvent,
-) -> i32 {
-    0
-}
-
 fn create_context(
     extra_functions: &ffi::glx_extra::Glx,
-    extensions: &str,
+    extensions: &Extensions,
     xlib: &ffi::Xlib,
     version: (u8, u8),
     profile: Option<GlProfile>,
     debug: bool,
+    forward_compatible: bool,
     robustness: Robustness,
+    reset_isolation: bool,
+    release_behavior: ReleaseBehavior,
+    direct: bool,
     share: ffi::GLXContext,
     display: *mut ffi::Display,
     fb_config: ffi::glx::types::GLXFBConfig,
@@ -495,8 +1948,8 @@ fn create_context(
 ) -> Result<ffi::GLXContext, CreationError> {
     let glx = GLX.as_ref().unwrap();
     unsafe {
-        let old_callback = (xlib.XSetErrorHandler)(Some(x_error_callback));
-        let context = if check_ext(extensions, "GLX_ARB_create_context") {
+        let trap = XErrorTrap::new(xlib, display);
+        let (cmd, context) = if extensions.contains("GLX_ARB_create_context") {
             let mut attributes = Vec::with_capacity(9);
 
             attributes
@@ -526,7 +1979,7 @@ fn create_context(
                 let mut flags = 0;
 
                 // robustness
-                if check_ext(extensions, "GLX_ARB_create_context_robustness") {
+                if extensions.contains("GLX_ARB_create_context_robustness") {
                     match robustness {
                         Robustness::RobustNoResetNotification
                         | Robustness::TryRobustNoResetNotification => {
@@ -553,6 +2006,11 @@ fn create_context(
                             flags = flags
                                 | ffi::glx_extra::CONTEXT_ROBUST_ACCESS_BIT_ARB
                                     as raw::c_int;
+                            if reset_isolation {
+                                flags = flags
+                                    | ffi::glx_extra::CONTEXT_RESET_ISOLATION_BIT_ARB
+                                        as raw::c_int;
+                            }
                         }
                         Robustness::NotRobust => (),
                         Robustness::NoError => (),
@@ -572,185 +2030,302 @@ fn create_context(
                         | ffi::glx_extra::CONTEXT_DEBUG_BIT_ARB as raw::c_int;
                 }
 
+                if forward_compatible {
+                    flags = flags
+                        | ffi::glx_extra::CONTEXT_FORWARD_COMPATIBLE_BIT_ARB
+                            as raw::c_int;
+                }
+
                 flags
             };
 
+            if release_behavior == ReleaseBehavior::None {
+                attributes.push(
+                    ffi::glx_extra::CONTEXT_RELEASE_BEHAVIOR_ARB as raw::c_int,
+                );
+                attributes.push(
+                    ffi::glx_extra::CONTEXT_RELEASE_BEHAVIOR_NONE_ARB
+                        as raw::c_int,
+                );
+            }
+
+            if robustness == Robustness::NoError
+                && extensions.contains("GLX_ARB_create_context_no_error")
+            {
+                attributes.push(
+                    ffi::glx_extra::CONTEXT_OPENGL_NO_ERROR_ARB as raw::c_int,
+                );
+                attributes.push(1);
+            }
+
             attributes.push(ffi::glx_extra::CONTEXT_FLAGS_ARB as raw::c_int);
             attributes.push(flags);
 
             attributes.push(0);
 
-            extra_functions.CreateContextAttribsARB(
+            let context = extra_functions.CreateContextAttribsARB(
                 display as *mut _,
                 fb_config,
                 share,
-                1,
+                direct as raw::c_int,
                 attributes.as_ptr(),
-            )
+            );
+            ("glXCreateContextAttribsARB", context)
         } else {
             let visual_infos: *const ffi::XVisualInfo = visual_infos;
-            glx.CreateContext(
+            let context = glx.CreateContext(
                 display as *mut _,
                 visual_infos as *mut _,
                 share,
-                1,
-            )
+                direct as raw::c_int,
+            );
+            ("glXCreateContext", context)
         };
 
-        (xlib.XSetErrorHandler)(old_callback);
-
+        // TODO: return `OpenGlVersionNotSupported` for `GLXBadFBConfig`
+        if let Some(err) = trap.creation_error(cmd) {
+            if !context.is_null() {
+                glx.DestroyContext(display as *mut _, context);
+            }
+            return Err(err);
+        }
         if context.is_null() {
-            // TODO: check for errors and return `OpenGlVersionNotSupported`
-            return Err(CreationError::OsError(
-                "GL context creation failed".to_string(),
-            ));
+            return Err(CreationError::OsError(format!("`{}` failed", cmd)));
         }
 
         Ok(context)
     }
 }
 
-/// Enumerates all available FBConfigs
-unsafe fn choose_fbconfig(
-    extensions: &str,
+/// Returns the distinct `GLX_SAMPLES` of the window configs of the screen
+/// `screen_id` that match the requirements, apart from multisampling. `0`
+/// stands for configs without multisampling.
+pub fn supported_sample_counts(
     xconn: &Arc<XConnection>,
     screen_id: raw::c_int,
     pf_reqs: &PixelFormatRequirements,
-    surface_type: SurfaceType,
-    transparent: Option<bool>,
-) -> Result<
-    (ffi::glx::types::GLXFBConfig, PixelFormat, ffi::XVisualInfo),
-    CreationError,
-> {
+) -> Result<Vec<u16>, CreationError> {
+    if GLX.is_none() {
+        return Err(CreationError::NotSupported(
+            "GLX is not available".to_string(),
+        ));
+    }
     let glx = GLX.as_ref().unwrap();
+    let extensions = load_extensions(xconn, screen_id)?;
 
-    let descriptor = {
-        let mut out: Vec<raw::c_int> = Vec::with_capacity(37);
+    let mut pf_reqs = pf_reqs.clone();
+    pf_reqs.multisampling = None;
+    pf_reqs.multisampling_exact = false;
 
-        out.push(ffi::glx::X_RENDERABLE as raw::c_int);
-        out.push(1);
+    unsafe {
+        let descriptor = fbconfig_attributes(
+            &extensions,
+            xconn,
+            &pf_reqs,
+            SurfaceType::Window,
+        )?;
+        let mut num_configs = 0;
+        let configs = glx.ChooseFBConfig(
+            xconn.display as *mut _,
+            screen_id,
+            descriptor.as_ptr(),
+            &mut num_configs,
+        );
+        if configs.is_null() {
+            return Ok(Vec::new());
+        }
 
-        if let Some(xid) = pf_reqs.x11_visual_xid {
-            // getting the visual infos
-            let fvi = crate::platform_impl::x11_utils::get_visual_info_from_xid(
-                &xconn, xid,
-            );
+        let mut counts = (0..num_configs as isize)
+            .map(|i| {
+                let mut value = 0;
+                glx.GetFBConfigAttrib(
+                    xconn.display as *mut _,
+                    *configs.offset(i),
+                    ffi::glx::SAMPLES as raw::c_int,
+                    &mut value,
+                );
+                value as u16
+            })
+            .collect::<Vec<_>>();
+        (xconn.xlib.XFree)(configs as *mut _);
+        counts.sort();
+        counts.dedup();
+        Ok(counts)
+    }
+}
+
+/// The `glXChooseFBConfig` attributes for the requirements.
+unsafe fn fbconfig_attributes(
+    extensions: &Extensions,
+    xconn: &Arc<XConnection>,
+    pf_reqs: &PixelFormatRequirements,
+    surface_type: SurfaceType,
+) -> Result<Vec<raw::c_int>, CreationError> {
+    // without the extension, ordinary multisampling is used instead
+    let coverage_sampling = extensions.contains("GLX_NV_multisample_coverage");
+
+    let mut out: Vec<raw::c_int> = Vec::with_capacity(37);
+
+    out.push(ffi::glx::X_RENDERABLE as raw::c_int);
+    out.push(1);
+
+    if let Some(xid) = pf_reqs.x11_visual_xid {
+        // getting the visual infos
+        let fvi = crate::platform_impl::x11_utils::get_visual_info_from_xid(
+            &xconn, xid,
+        );
+
+        out.push(ffi::glx::X_VISUAL_TYPE as raw::c_int);
+        out.push(fvi.class as raw::c_int);
 
-            out.push(ffi::glx::X_VISUAL_TYPE as raw::c_int);
-            out.push(fvi.class as raw::c_int);
+        out.push(ffi::glx::VISUAL_ID as raw::c_int);
+        out.push(xid as raw::c_int);
+    } else {
+        out.push(ffi::glx::X_VISUAL_TYPE as raw::c_int);
+        out.push(ffi::glx::TRUE_COLOR as raw::c_int);
+    }
 
-            out.push(ffi::glx::VISUAL_ID as raw::c_int);
-            out.push(xid as raw::c_int);
+    out.push(ffi::glx::DRAWABLE_TYPE as raw::c_int);
+    let surface_type = match surface_type {
+        SurfaceType::Window => ffi::glx::WINDOW_BIT,
+        SurfaceType::PBuffer => ffi::glx::PBUFFER_BIT,
+        SurfaceType::Surfaceless => ffi::glx::DONT_CARE, /* TODO: Properly support */
+        SurfaceType::Pixmap => ffi::glx::PIXMAP_BIT,
+        SurfaceType::Stream => {
+            return Err(CreationError::NotSupported(
+                "EGLStreams are not supported by GLX".to_string(),
+            ));
+        }
+    };
+    out.push(surface_type as raw::c_int);
+
+    // TODO: Use RGB/RGB_FLOAT_BIT_ARB if they don't want alpha bits,
+    // fallback to it if they don't care
+    out.push(ffi::glx::RENDER_TYPE as raw::c_int);
+    if pf_reqs.float_color_buffer {
+        if extensions.contains("GLX_ARB_fbconfig_float") {
+            out.push(ffi::glx_extra::RGBA_FLOAT_BIT_ARB as raw::c_int);
         } else {
-            out.push(ffi::glx::X_VISUAL_TYPE as raw::c_int);
-            out.push(ffi::glx::TRUE_COLOR as raw::c_int);
+            return Err(CreationError::NoAvailablePixelFormat);
         }
+    } else {
+        out.push(ffi::glx::RGBA_BIT as raw::c_int);
+    }
 
-        out.push(ffi::glx::DRAWABLE_TYPE as raw::c_int);
-        let surface_type = match surface_type {
-            SurfaceType::Window => ffi::glx::WINDOW_BIT,
-            SurfaceType::PBuffer => ffi::glx::PBUFFER_BIT,
-            SurfaceType::Surfaceless => ffi::glx::DONT_CARE, /* TODO: Properly support */
-        };
-        out.push(surface_type as raw::c_int);
-
-        // TODO: Use RGB/RGB_FLOAT_BIT_ARB if they don't want alpha bits,
-        // fallback to it if they don't care
-        out.push(ffi::glx::RENDER_TYPE as raw::c_int);
-        if pf_reqs.float_color_buffer {
-            if check_ext(extensions, "GLX_ARB_fbconfig_float") {
-                out.push(ffi::glx_extra::RGBA_FLOAT_BIT_ARB as raw::c_int);
-            } else {
-                return Err(CreationError::NoAvailablePixelFormat);
+    if let Some(color) = pf_reqs.color_bits {
+        out.push(ffi::glx::RED_SIZE as raw::c_int);
+        out.push((color / 3) as raw::c_int);
+        out.push(ffi::glx::GREEN_SIZE as raw::c_int);
+        out.push(
+            (color / 3 + if color % 3 != 0 { 1 } else { 0 }) as raw::c_int,
+        );
+        out.push(ffi::glx::BLUE_SIZE as raw::c_int);
+        out.push(
+            (color / 3 + if color % 3 == 2 { 1 } else { 0 }) as raw::c_int,
+        );
+    }
+
+    if let Some(alpha) = pf_reqs.alpha_bits {
+        out.push(ffi::glx::ALPHA_SIZE as raw::c_int);
+        out.push(alpha as raw::c_int);
+    }
+
+    if let Some(depth) = pf_reqs.depth_bits {
+        out.push(ffi::glx::DEPTH_SIZE as raw::c_int);
+        out.push(depth as raw::c_int);
+    }
+
+    if let Some(stencil) = pf_reqs.stencil_bits {
+        out.push(ffi::glx::STENCIL_SIZE as raw::c_int);
+        out.push(stencil as raw::c_int);
+    }
+
+    let double_buffer = pf_reqs.double_buffer.unwrap_or(true);
+    out.push(ffi::glx::DOUBLEBUFFER as raw::c_int);
+    out.push(if double_buffer { 1 } else { 0 });
+
+    if let Some(multisampling) = pf_reqs.multisampling {
+        if extensions.contains("GLX_ARB_multisample") {
+            out.push(ffi::glx_extra::SAMPLE_BUFFERS_ARB as raw::c_int);
+            out.push(if multisampling == 0 { 0 } else { 1 });
+            match pf_reqs.coverage_samples {
+                // `GLX_COVERAGE_SAMPLES_NV` is `GLX_SAMPLES_ARB`
+                Some(coverage_samples)
+                    if multisampling != 0 && coverage_sampling =>
+                {
+                    out.push(ffi::glx_extra::COVERAGE_SAMPLES_NV as raw::c_int);
+                    out.push(coverage_samples as raw::c_int);
+                    out.push(ffi::glx_extra::COLOR_SAMPLES_NV as raw::c_int);
+                    out.push(multisampling as raw::c_int);
+                }
+                _ => {
+                    out.push(ffi::glx_extra::SAMPLES_ARB as raw::c_int);
+                    out.push(multisampling as raw::c_int);
+                }
             }
         } else {
-            out.push(ffi::glx::RGBA_BIT as raw::c_int);
+            return Err(CreationError::NoAvailablePixelFormat);
         }
+    }
 
-        if let Some(color) = pf_reqs.color_bits {
-            out.push(ffi::glx::RED_SIZE as raw::c_int);
-            out.push((color / 3) as raw::c_int);
-            out.push(ffi::glx::GREEN_SIZE as raw::c_int);
+    out.push(ffi::glx::STEREO as raw::c_int);
+    out.push(match pf_reqs.stereoscopy {
+        Some(true) => 1,
+        Some(false) => 0,
+        None => ffi::glx::DONT_CARE as raw::c_int,
+    });
+
+    if pf_reqs.preserved_swap {
+        if extensions.contains("GLX_OML_swap_method") {
+            out.push(ffi::glx_extra::SWAP_METHOD_OML as raw::c_int);
+            out.push(ffi::glx_extra::SWAP_COPY_OML as raw::c_int);
+        } else {
+            return Err(CreationError::NoAvailablePixelFormat);
+        }
+    }
+
+    if pf_reqs.srgb {
+        if extensions.contains("GLX_ARB_framebuffer_sRGB") {
             out.push(
-                (color / 3 + if color % 3 != 0 { 1 } else { 0 }) as raw::c_int,
+                ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB as raw::c_int,
             );
-            out.push(ffi::glx::BLUE_SIZE as raw::c_int);
+            out.push(1);
+        } else if extensions.contains("GLX_EXT_framebuffer_sRGB") {
             out.push(
-                (color / 3 + if color % 3 == 2 { 1 } else { 0 }) as raw::c_int,
+                ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_EXT as raw::c_int,
             );
+            out.push(1);
+        } else {
+            return Err(CreationError::NoAvailablePixelFormat);
         }
+    }
 
-        if let Some(alpha) = pf_reqs.alpha_bits {
-            out.push(ffi::glx::ALPHA_SIZE as raw::c_int);
-            out.push(alpha as raw::c_int);
-        }
-
-        if let Some(depth) = pf_reqs.depth_bits {
-            out.push(ffi::glx::DEPTH_SIZE as raw::c_int);
-            out.push(depth as raw::c_int);
-        }
-
-        if let Some(stencil) = pf_reqs.stencil_bits {
-            out.push(ffi::glx::STENCIL_SIZE as raw::c_int);
-            out.push(stencil as raw::c_int);
-        }
-
-        let double_buffer = pf_reqs.double_buffer.unwrap_or(true);
-        out.push(ffi::glx::DOUBLEBUFFER as raw::c_int);
-        out.push(if double_buffer { 1 } else { 0 });
-
-        if let Some(multisampling) = pf_reqs.multisampling {
-            if check_ext(extensions, "GLX_ARB_multisample") {
-                out.push(ffi::glx_extra::SAMPLE_BUFFERS_ARB as raw::c_int);
-                out.push(if multisampling == 0 { 0 } else { 1 });
-                out.push(ffi::glx_extra::SAMPLES_ARB as raw::c_int);
-                out.push(multisampling as raw::c_int);
-            } else {
-                return Err(CreationError::NoAvailablePixelFormat);
-            }
-        }
-
-        out.push(ffi::glx::STEREO as raw::c_int);
-        out.push(if pf_reqs.stereoscopy { 1 } else { 0 });
+    out.push(ffi::glx::CONFIG_CAVEAT as raw::c_int);
+    out.push(ffi::glx::DONT_CARE as raw::c_int);
 
-        if pf_reqs.srgb {
-            if check_ext(extensions, "GLX_ARB_framebuffer_sRGB") {
-                out.push(
-                    ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB as raw::c_int,
-                );
-                out.push(1);
-            } else if check_ext(extensions, "GLX_EXT_framebuffer_sRGB") {
-                out.push(
-                    ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_EXT as raw::c_int,
-                );
-                out.push(1);
-            } else {
-                return Err(CreationError::NoAvailablePixelFormat);
-            }
-        }
+    out.push(0);
+    Ok(out)
+}
 
-        match pf_reqs.release_behavior {
-            ReleaseBehavior::Flush => (),
-            ReleaseBehavior::None => {
-                if check_ext(extensions, "GLX_ARB_context_flush_control") {
-                    out.push(
-                        ffi::glx_extra::CONTEXT_RELEASE_BEHAVIOR_ARB
-                            as raw::c_int,
-                    );
-                    out.push(
-                        ffi::glx_extra::CONTEXT_RELEASE_BEHAVIOR_NONE_ARB
-                            as raw::c_int,
-                    );
-                }
-            }
-        }
+/// Enumerates all available FBConfigs
+unsafe fn choose_fbconfig(
+    extensions: &Extensions,
+    xconn: &Arc<XConnection>,
+    screen_id: raw::c_int,
+    pf_reqs: &PixelFormatRequirements,
+    surface_type: SurfaceType,
+    transparent: Option<bool>,
+) -> Result<
+    (ffi::glx::types::GLXFBConfig, PixelFormat, ffi::XVisualInfo),
+    CreationError,
+> {
+    let glx = GLX.as_ref().unwrap();
 
-        out.push(ffi::glx::CONFIG_CAVEAT as raw::c_int);
-        out.push(ffi::glx::DONT_CARE as raw::c_int);
+    // without the extension, ordinary multisampling is used instead
+    let coverage_sampling = extensions.contains("GLX_NV_multisample_coverage");
 
-        out.push(0);
-        out
-    };
+    let descriptor =
+        fbconfig_attributes(extensions, xconn, pf_reqs, surface_type)?;
 
     // calling glXChooseFBConfig
     let (fb_config, visual_infos): (
@@ -771,11 +2346,77 @@ unsafe fn choose_fbconfig(
             return Err(CreationError::NoAvailablePixelFormat);
         }
 
+        // the visual of the config must have the depth of the pixmap, or
+        // `glXCreatePixmap` fails with `BadMatch`
+        let pixmap_depth = match pf_reqs.x11_pixmap {
+            Some(pixmap) => {
+                match crate::platform_impl::x11_utils::get_pixmap_screen_and_depth(
+                    xconn, pixmap,
+                ) {
+                    Some((_, depth)) => Some(depth as raw::c_int),
+                    None => {
+                        (xconn.xlib.XFree)(configs as *mut _);
+                        return Err(CreationError::OsError(
+                            "The pixmap is not a valid X11 drawable".to_string(),
+                        ));
+                    }
+                }
+            }
+            None => None,
+        };
+        let visual_depth = |config_id: raw::c_int| {
+            let visual_infos_raw = glx.GetVisualFromFBConfig(
+                xconn.display as *mut _,
+                *configs.offset(config_id as isize),
+            );
+            if visual_infos_raw.is_null() {
+                return None;
+            }
+            let depth = (*visual_infos_raw).depth;
+            (xconn.xlib.XFree)(visual_infos_raw as *mut _);
+            Some(depth)
+        };
+
+        // drivers treat `GLX_SAMPLES` as a minimum
+        let samples_attrib =
+            if pf_reqs.coverage_samples.is_some() && coverage_sampling {
+                ffi::glx_extra::COLOR_SAMPLES_NV
+            } else {
+                ffi::glx_extra::SAMPLES_ARB
+            };
+        let config_ids: Vec<_> = (0..num_configs)
+            .filter(|&config_id| match pf_reqs.multisampling {
+                Some(samples) if pf_reqs.multisampling_exact => {
+                    let mut value = 0;
+                    glx.GetFBConfigAttrib(
+                        xconn.display as *mut _,
+                        *configs.offset(config_id as isize),
+                        samples_attrib as raw::c_int,
+                        &mut value,
+                    );
+                    value == samples as raw::c_int
+                }
+                _ => true,
+            })
+            .filter(|&config_id| match pixmap_depth {
+                Some(depth) => visual_depth(config_id) == Some(depth),
+                None => true,
+            })
+            .collect();
+        if let (Some(depth), true) = (pixmap_depth, config_ids.is_empty()) {
+            (xconn.xlib.XFree)(configs as *mut _);
+            return Err(CreationError::NotSupported(format!(
+                "No GLX config with `GLX_PIXMAP_BIT` has a visual of the \
+                 depth of the pixmap, {}",
+                depth
+            )));
+        }
+
         match crate::platform_impl::x11_utils::select_config(
             xconn,
             transparent,
             pf_reqs,
-            (0..num_configs).collect(),
+            config_ids,
             |config_id| {
                 let visual_infos_raw = glx.GetVisualFromFBConfig(
                     xconn.display as *mut _,
@@ -818,44 +2459,206 @@ unsafe fn choose_fbconfig(
         value
     };
 
+    let (multisampling, coverage_samples) =
+        if get_attrib(ffi::glx::SAMPLE_BUFFERS as raw::c_int) != 0 {
+            let samples = get_attrib(ffi::glx::SAMPLES as raw::c_int) as u16;
+            let color_samples = if coverage_sampling {
+                get_attrib(ffi::glx_extra::COLOR_SAMPLES_NV as raw::c_int)
+                    as u16
+            } else {
+                samples
+            };
+            crate::split_coverage_samples(samples, color_samples)
+        } else {
+            (None, None)
+        };
+
     let pf_desc = PixelFormat {
         hardware_accelerated: get_attrib(ffi::glx::CONFIG_CAVEAT as raw::c_int)
             != ffi::glx::SLOW_CONFIG as raw::c_int,
         color_bits: get_attrib(ffi::glx::RED_SIZE as raw::c_int) as u8
             + get_attrib(ffi::glx::GREEN_SIZE as raw::c_int) as u8
             + get_attrib(ffi::glx::BLUE_SIZE as raw::c_int) as u8,
+        rgb_bits: Some((
+            get_attrib(ffi::glx::RED_SIZE as raw::c_int) as u8,
+            get_attrib(ffi::glx::GREEN_SIZE as raw::c_int) as u8,
+            get_attrib(ffi::glx::BLUE_SIZE as raw::c_int) as u8,
+        )),
         alpha_bits: get_attrib(ffi::glx::ALPHA_SIZE as raw::c_int) as u8,
         depth_bits: get_attrib(ffi::glx::DEPTH_SIZE as raw::c_int) as u8,
         stencil_bits: get_attrib(ffi::glx::STENCIL_SIZE as raw::c_int) as u8,
         stereoscopy: get_attrib(ffi::glx::STEREO as raw::c_int) != 0,
         double_buffer: get_attrib(ffi::glx::DOUBLEBUFFER as raw::c_int) != 0,
-        multisampling: if get_attrib(ffi::glx::SAMPLE_BUFFERS as raw::c_int)
-            != 0
-        {
-            Some(get_attrib(ffi::glx::SAMPLES as raw::c_int) as u16)
+        multisampling,
+        coverage_samples,
+        srgb: if extensions.contains("GLX_ARB_framebuffer_sRGB") {
+            get_attrib(
+                ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB as raw::c_int,
+            ) != 0
+        } else if extensions.contains("GLX_EXT_framebuffer_sRGB") {
+            get_attrib(
+                ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_EXT as raw::c_int,
+            ) != 0
         } else {
-            None
+            false
         },
-        srgb: get_attrib(
-            ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB as raw::c_int,
-        ) != 0
-            || get_attrib(
-                ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_EXT as raw::c_int,
-            ) != 0,
+        float_color_buffer: get_attrib(ffi::glx::RENDER_TYPE as raw::c_int)
+            & ffi::glx_extra::RGBA_FLOAT_BIT_ARB as raw::c_int
+            != 0,
+        swap_behavior: if extensions.contains("GLX_OML_swap_method") {
+            match get_attrib(ffi::glx_extra::SWAP_METHOD_OML as raw::c_int)
+                as u32
+            {
+                ffi::glx_extra::SWAP_COPY_OML => SwapBehavior::Preserved,
+                ffi::glx_extra::SWAP_EXCHANGE_OML => SwapBehavior::Exchanged,
+                _ => SwapBehavior::Undefined,
+            }
+        } else {
+            SwapBehavior::Undefined
+        },
+        swap_interval_range: None,
     };
 
     Ok((fb_config, pf_desc, visual_infos))
 }
 
-/// Checks if `ext` is available.
-fn check_ext(extensions: &str, ext: &str) -> bool {
-    extensions.split(' ').find(|&s| s == ext).is_some()
+/// Chooses a visual with `glXChooseVisual`, for servers without fbconfigs.
+unsafe fn choose_legacy_visual(
+    extensions: &Extensions,
+    xconn: &Arc<XConnection>,
+    screen_id: raw::c_int,
+    pf_reqs: &PixelFormatRequirements,
+) -> Result<(PixelFormat, ffi::XVisualInfo), CreationError> {
+    let glx = GLX.as_ref().unwrap();
+
+    match pf_reqs.multisampling {
+        Some(0) | None => (),
+        Some(_) => {
+            return Err(CreationError::NotSupported(
+                "Multisampling is not supported by legacy GLX visuals"
+                    .to_string(),
+            ));
+        }
+    }
+    if pf_reqs.float_color_buffer {
+        return Err(CreationError::NotSupported(
+            "Float color buffers are not supported by legacy GLX visuals"
+                .to_string(),
+        ));
+    }
+    if pf_reqs.preserved_swap {
+        return Err(CreationError::NotSupported(
+            "Preserved swaps are not supported by legacy GLX visuals"
+                .to_string(),
+        ));
+    }
+
+    let mut visual_infos = if let Some(xid) = pf_reqs.x11_visual_xid {
+        crate::platform_impl::x11_utils::get_visual_info_from_xid(xconn, xid)
+    } else {
+        // boolean attributes have no value
+        let mut out: Vec<raw::c_int> = Vec::with_capacity(16);
+        out.push(ffi::glx::RGBA as raw::c_int);
+        if pf_reqs.double_buffer.unwrap_or(true) {
+            out.push(ffi::glx::DOUBLEBUFFER as raw::c_int);
+        }
+        if pf_reqs.stereoscopy == Some(true) {
+            out.push(ffi::glx::STEREO as raw::c_int);
+        }
+        if let Some(color) = pf_reqs.color_bits {
+            out.push(ffi::glx::RED_SIZE as raw::c_int);
+            out.push((color / 3) as raw::c_int);
+            out.push(ffi::glx::GREEN_SIZE as raw::c_int);
+            out.push(
+                (color / 3 + if color % 3 != 0 { 1 } else { 0 }) as raw::c_int,
+            );
+            out.push(ffi::glx::BLUE_SIZE as raw::c_int);
+            out.push(
+                (color / 3 + if color % 3 == 2 { 1 } else { 0 }) as raw::c_int,
+            );
+        }
+        if let Some(alpha) = pf_reqs.alpha_bits {
+            out.push(ffi::glx::ALPHA_SIZE as raw::c_int);
+            out.push(alpha as raw::c_int);
+        }
+        if let Some(depth) = pf_reqs.depth_bits {
+            out.push(ffi::glx::DEPTH_SIZE as raw::c_int);
+            out.push(depth as raw::c_int);
+        }
+        if let Some(stencil) = pf_reqs.stencil_bits {
+            out.push(ffi::glx::STENCIL_SIZE as raw::c_int);
+            out.push(stencil as raw::c_int);
+        }
+        out.push(0);
+
+        let visual_infos_raw = glx.ChooseVisual(
+            xconn.display as *mut _,
+            screen_id,
+            out.as_mut_ptr(),
+        );
+        if visual_infos_raw.is_null() {
+            return Err(CreationError::NoAvailablePixelFormat);
+        }
+        let visual_infos: ffi::XVisualInfo =
+            std::ptr::read(visual_infos_raw as *const _);
+        (xconn.xlib.XFree)(visual_infos_raw as *mut _);
+        visual_infos
+    };
+
+    let mut get_config = |attrib: raw::c_int| -> i32 {
+        let mut value = 0;
+        glx.GetConfig(
+            xconn.display as *mut _,
+            &mut visual_infos as *mut ffi::XVisualInfo as *mut _,
+            attrib,
+            &mut value,
+        );
+        value
+    };
+
+    // the visual of a raw window may not support OpenGL at all
+    if get_config(ffi::glx::USE_GL as raw::c_int) == 0 {
+        return Err(CreationError::NoAvailablePixelFormat);
+    }
+    let stereoscopy = get_config(ffi::glx::STEREO as raw::c_int) != 0;
+    if pf_reqs.stereoscopy.map_or(false, |s| s != stereoscopy) {
+        return Err(CreationError::NoAvailablePixelFormat);
+    }
+
+    let pf_desc = PixelFormat {
+        hardware_accelerated: true,
+        color_bits: get_config(ffi::glx::RED_SIZE as raw::c_int) as u8
+            + get_config(ffi::glx::GREEN_SIZE as raw::c_int) as u8
+            + get_config(ffi::glx::BLUE_SIZE as raw::c_int) as u8,
+        rgb_bits: Some((
+            get_config(ffi::glx::RED_SIZE as raw::c_int) as u8,
+            get_config(ffi::glx::GREEN_SIZE as raw::c_int) as u8,
+            get_config(ffi::glx::BLUE_SIZE as raw::c_int) as u8,
+        )),
+        alpha_bits: get_config(ffi::glx::ALPHA_SIZE as raw::c_int) as u8,
+        depth_bits: get_config(ffi::glx::DEPTH_SIZE as raw::c_int) as u8,
+        stencil_bits: get_config(ffi::glx::STENCIL_SIZE as raw::c_int) as u8,
+        stereoscopy,
+        double_buffer: get_config(ffi::glx::DOUBLEBUFFER as raw::c_int) != 0,
+        multisampling: None,
+        coverage_samples: None,
+        srgb: (extensions.contains("GLX_ARB_framebuffer_sRGB")
+            || extensions.contains("GLX_EXT_framebuffer_sRGB"))
+            && get_config(
+                ffi::glx_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB as raw::c_int,
+            ) != 0,
+        float_color_buffer: false,
+        swap_behavior: SwapBehavior::Undefined,
+        swap_interval_range: None,
+    };
+
+    Ok((pf_desc, visual_infos))
 }
 
 fn load_extensions(
     xconn: &Arc<XConnection>,
     screen_id: raw::c_int,
-) -> Result<String, CreationError> {
+) -> Result<Extensions, CreationError> {
     unsafe {
         let glx = GLX.as_ref().unwrap();
         let extensions =
@@ -866,7 +2669,7 @@ fn load_extensions(
                     .to_string(),
             ));
         }
-        let extensions = CStr::from_ptr(extensions).to_bytes().to_vec();
-        Ok(String::from_utf8(extensions).unwrap())
+        let extensions = CStr::from_ptr(extensions).to_string_lossy();
+        Ok(Extensions::parse(&extensions))
     }
 }
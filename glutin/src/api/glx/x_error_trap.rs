@@ -0,0 +1,132 @@
+use crate::{ContextError, CreationError, ErrorCode};
+use glutin_glx_sys as ffi;
+
+use std::os::raw;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+lazy_static! {
+    // Xlib only has one error handler per process, so the traps set by
+    // different threads must not overlap.
+    static ref HANDLER_LOCK: Mutex<()> = Mutex::new(());
+}
+
+// The handler type taken and returned by `XSetErrorHandler`.
+type XErrorHandler = Option<
+    unsafe extern "C" fn(
+        *mut ffi::Display,
+        *mut ffi::XErrorEvent,
+    ) -> raw::c_int,
+>;
+
+// The first error raised while the trap is set. X error codes start at 1.
+static ERROR_CODE: AtomicU8 = AtomicU8::new(0);
+static REQUEST_CODE: AtomicU8 = AtomicU8::new(0);
+static MINOR_CODE: AtomicU8 = AtomicU8::new(0);
+
+unsafe extern "C" fn x_error_callback(
+    _dpy: *mut ffi::Display,
+    err: *mut ffi::XErrorEvent,
+) -> raw::c_int {
+    let err = &*err;
+    if ERROR_CODE
+        .compare_exchange(0, err.error_code, Ordering::SeqCst, Ordering::SeqCst)
+        .is_ok()
+    {
+        REQUEST_CODE.store(err.request_code, Ordering::SeqCst);
+        MINOR_CODE.store(err.minor_code, Ordering::SeqCst);
+    }
+    0
+}
+
+/// Catches the X errors raised by GLX requests, which would otherwise be
+/// reported asynchronously to the previous error handler, which usually
+/// aborts the process.
+///
+/// The previous handler is restored when the trap is dropped, even while
+/// unwinding.
+pub struct XErrorTrap<'a> {
+    xlib: &'a ffi::Xlib,
+    display: *mut ffi::Display,
+    old_handler: XErrorHandler,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl<'a> XErrorTrap<'a> {
+    pub fn new(xlib: &'a ffi::Xlib, display: *mut ffi::Display) -> Self {
+        // a panic while a trap was set doesn't leave the handler behind
+        let lock = HANDLER_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        unsafe {
+            // the errors of earlier requests belong to the previous handler
+            (xlib.XSync)(display, ffi::False);
+            ERROR_CODE.store(0, Ordering::SeqCst);
+            let old_handler = (xlib.XSetErrorHandler)(Some(x_error_callback));
+            XErrorTrap {
+                xlib,
+                display,
+                old_handler,
+                _lock: lock,
+            }
+        }
+    }
+
+    /// Waits for the server to process the requests made so far, and returns
+    /// the code and description of the first error they raised.
+    pub fn check(&self, cmd: &str) -> Option<(ErrorCode, String)> {
+        unsafe {
+            (self.xlib.XSync)(self.display, ffi::False);
+        }
+        match ERROR_CODE.swap(0, Ordering::SeqCst) {
+            0 => None,
+            code => Some((
+                ErrorCode::X11(code),
+                format!(
+                    "`{}` failed: {} (X error {}, request {}.{})",
+                    cmd,
+                    self.error_text(code),
+                    code,
+                    REQUEST_CODE.load(Ordering::SeqCst),
+                    MINOR_CODE.load(Ordering::SeqCst),
+                ),
+            )),
+        }
+    }
+
+    #[inline]
+    pub fn creation_error(&self, cmd: &str) -> Option<CreationError> {
+        self.check(cmd)
+            .map(|(code, msg)| CreationError::ApiError(code, msg))
+    }
+
+    #[inline]
+    pub fn context_error(&self, cmd: &str) -> Option<ContextError> {
+        self.check(cmd)
+            .map(|(code, msg)| ContextError::ApiError(code, msg))
+    }
+
+    fn error_text(&self, code: u8) -> String {
+        let mut buf = [0 as raw::c_char; 256];
+        unsafe {
+            (self.xlib.XGetErrorText)(
+                self.display,
+                code as raw::c_int,
+                buf.as_mut_ptr(),
+                buf.len() as raw::c_int,
+            );
+            std::ffi::CStr::from_ptr(buf.as_ptr())
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+}
+
+impl<'a> Drop for XErrorTrap<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            // the errors of the trapped requests must not reach the previous
+            // handler
+            (self.xlib.XSync)(self.display, ffi::False);
+            (self.xlib.XSetErrorHandler)(self.old_handler);
+        }
+    }
+}
@@ -62,8 +62,8 @@
 
 use crate::platform::ios::{WindowBuilderExtIOS, WindowExtIOS};
 use crate::{
-    Api, ContextError, CreationError, GlAttributes, GlRequest, PixelFormat,
-    PixelFormatRequirements, Rect,
+    Api, ContextError, CreationError, DirectRendering, GlAttributes, GlRequest,
+    PixelFormat, PixelFormatRequirements, Rect, StateMask,
 };
 
 use glutin_gles2_sys as ffi;
@@ -176,9 +176,61 @@ impl Context {
     pub fn new_windowed<T>(
         builder: WindowBuilder,
         el: &EventLoopWindowTarget<T>,
-        _: &PixelFormatRequirements,
+        pf_reqs: &PixelFormatRequirements,
         gl_attrs: &GlAttributes<&Context>,
     ) -> Result<(winit::window::Window, Self), CreationError> {
+        if gl_attrs.priority.is_some() {
+            return Err(CreationError::NotSupported(
+                "Context priorities are not supported by EAGL".to_string(),
+            ));
+        }
+
+        if gl_attrs.protected_content {
+            return Err(CreationError::NotSupported(
+                "Protected content is not supported by EAGL".to_string(),
+            ));
+        }
+
+        if gl_attrs.direct_rendering == DirectRendering::RequireIndirect {
+            return Err(CreationError::NotSupported(
+                "Indirect rendering is not supported by EAGL".to_string(),
+            ));
+        }
+
+        if gl_attrs.reset_isolation {
+            return Err(CreationError::NotSupported(
+                "Reset isolation is not supported by EAGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.color_space.is_some() {
+            return Err(CreationError::NotSupported(
+                "Color spaces are not supported by EAGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.textureable {
+            return Err(CreationError::NotSupported(
+                "Textureable pbuffers are not supported by EAGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.preserved_swap {
+            return Err(CreationError::NotSupported(
+                "Preserved swaps are not supported by EAGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.stereoscopy == Some(true) {
+            return Err(CreationError::NotSupported(
+                "Stereoscopy is not supported by EAGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.release_behavior != crate::ReleaseBehavior::Flush {
+            return Err(CreationError::ReleaseBehaviorNotSupported);
+        }
+
         create_view_class();
         let view_class =
             Class::get("MainGLView").expect("Failed to get class `MainGLView`");
@@ -327,6 +379,40 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub fn swap_buffers_with_frame_id(
+        &self,
+    ) -> Result<crate::FrameId, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn presentation_times(
+        &self,
+        _frame: crate::FrameId,
+    ) -> Result<crate::PresentationTimes, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to query the buffer age"
+                    .to_string(),
+            ));
+        }
+        Ok(0)
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        _rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub fn swap_buffers_with_damage(
         &self,
@@ -338,23 +424,49 @@ impl Context {
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        crate::DamageSupport::None
+    }
+
+    #[inline]
+    pub fn set_swap_interval(
+        &self,
+        _interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        _interval: crate::SwapInterval,
+    ) -> bool {
         false
     }
 
+    #[inline]
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
+        None
+    }
+
     #[inline]
     pub fn get_pixel_format(&self) -> PixelFormat {
         let color_format = ColorFormat::for_view(self.view);
         PixelFormat {
             hardware_accelerated: true,
             color_bits: color_format.color_bits(),
+            rgb_bits: None,
             alpha_bits: color_format.alpha_bits(),
             depth_bits: depth_for_view(self.view),
             stencil_bits: stencil_for_view(self.view),
             stereoscopy: false,
             double_buffer: true,
             multisampling: multisampling_for_view(self.view),
+            coverage_samples: None,
             srgb: color_format.srgb(),
+            float_color_buffer: false,
+            swap_behavior: crate::SwapBehavior::Undefined,
+            swap_interval_range: None,
         }
     }
 
@@ -379,6 +491,54 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        // EAGL can't read from another surface than the one drawn to
+        if std::ptr::eq(draw, self) && std::ptr::eq(read, self) {
+            self.make_current()
+        } else {
+            Err(ContextError::FunctionUnavailable)
+        }
+    }
+
+    #[inline]
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn copy_state_from(
+        &self,
+        _src: &Context,
+        _mask: StateMask,
+    ) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
         if !self.is_current() {
@@ -433,6 +593,26 @@ impl Context {
     pub fn get_api(&self) -> Api {
         Api::OpenGlEs
     }
+
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        None
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        crate::ReleaseBehavior::Flush
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        None
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> Result<&crate::Extensions, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
 }
 
 fn create_view_class() {
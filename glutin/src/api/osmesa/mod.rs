@@ -11,21 +11,450 @@ pub mod ffi {
 }
 
 use crate::{
-    Api, ContextError, CreationError, GlAttributes, GlProfile, GlRequest,
-    PixelFormatRequirements, Robustness,
+    Api, ContextError, CreationError, DirectRendering, GlAttributes, GlProfile,
+    GlRequest, PixelFormatRequirements, Robustness,
 };
 
 use winit::dpi;
 
-use std::ffi::CString;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
 use std::os::raw;
+use std::sync::Mutex;
+
+// `glext.h` values of the `type` argument of `OSMesaMakeCurrent`.
+const GL_UNSIGNED_BYTE: raw::c_uint = 0x1401;
+const GL_UNSIGNED_SHORT: raw::c_uint = 0x1403;
+const GL_FLOAT: raw::c_uint = 0x1406;
+const GL_UNSIGNED_SHORT_5_6_5: raw::c_uint = 0x8363;
+
+// `glGetString` names.
+const GL_RENDERER: raw::c_uint = 0x1F01;
+const GL_VERSION: raw::c_uint = 0x1F02;
+
+const GL_CONTEXT_PROFILE_MASK: raw::c_uint = 0x9126;
+const GL_CONTEXT_CORE_PROFILE_BIT: raw::c_int = 0x0000_0001;
+
+lazy_static! {
+    static ref DRIVER_INFO: Option<OsMesaDriverInfo> =
+        unsafe { probe_driver() };
+}
+
+/// The versions tried, in order, when the requested core profile version is not
+/// available.
+const CORE_VERSIONS: &[(u8, u8)] = &[
+    (4, 6),
+    (4, 5),
+    (4, 4),
+    (4, 3),
+    (4, 2),
+    (4, 1),
+    (4, 0),
+    (3, 3),
+];
+
+/// The versions tried, in order, when the requested compatibility profile
+/// version is not available.
+const COMPAT_VERSIONS: &[(u8, u8)] = &[(3, 0), (2, 1)];
+
+/// The layout of the pixels in the buffer an OsMesa context renders into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsMesaBufferFormat {
+    /// Four channels, in red, green, blue, alpha order.
+    Rgba,
+    /// Four channels, in blue, green, red, alpha order.
+    Bgra,
+    /// Four channels, in alpha, red, green, blue order.
+    Argb,
+    /// Three channels, in red, green, blue order.
+    Rgb,
+    /// Three channels, in blue, green, red order.
+    Bgr,
+    /// One packed 16-bit value per pixel, with 5 bits of red, 6 bits of
+    /// green and 5 bits of blue.
+    ///
+    /// Only valid with [`OsMesaChannelType::UnsignedByte`].
+    ///
+    /// [`OsMesaChannelType::UnsignedByte`]:
+    /// enum.OsMesaChannelType.html#variant.UnsignedByte
+    Rgb565,
+}
+
+impl OsMesaBufferFormat {
+    /// Returns the number of bytes a single pixel takes up in the buffer when
+    /// using the given channel type.
+    #[inline]
+    pub fn bytes_per_pixel(&self, channel_type: OsMesaChannelType) -> usize {
+        match *self {
+            OsMesaBufferFormat::Rgba
+            | OsMesaBufferFormat::Bgra
+            | OsMesaBufferFormat::Argb => 4 * channel_type.size(),
+            OsMesaBufferFormat::Rgb | OsMesaBufferFormat::Bgr => {
+                3 * channel_type.size()
+            }
+            OsMesaBufferFormat::Rgb565 => 2,
+        }
+    }
+
+    fn to_osmesa(&self) -> raw::c_int {
+        (match *self {
+            OsMesaBufferFormat::Rgba => osmesa_sys::OSMESA_RGBA,
+            OsMesaBufferFormat::Bgra => osmesa_sys::OSMESA_BGRA,
+            OsMesaBufferFormat::Argb => osmesa_sys::OSMESA_ARGB,
+            OsMesaBufferFormat::Rgb => osmesa_sys::OSMESA_RGB,
+            OsMesaBufferFormat::Bgr => osmesa_sys::OSMESA_BGR,
+            OsMesaBufferFormat::Rgb565 => osmesa_sys::OSMESA_RGB_565,
+        }) as raw::c_int
+    }
+
+    fn gl_type(&self, channel_type: OsMesaChannelType) -> raw::c_uint {
+        match *self {
+            OsMesaBufferFormat::Rgb565 => GL_UNSIGNED_SHORT_5_6_5,
+            _ => channel_type.gl_type(),
+        }
+    }
+
+    /// Converts a single pixel to 8-bit RGBA.
+    fn pixel_to_rgba8(
+        &self,
+        pixel: &[u8],
+        channel_type: OsMesaChannelType,
+    ) -> [u8; 4] {
+        // The position of the red, green, blue and alpha channels.
+        let (r, g, b, a) = match *self {
+            OsMesaBufferFormat::Rgba => (0, 1, 2, Some(3)),
+            OsMesaBufferFormat::Bgra => (2, 1, 0, Some(3)),
+            OsMesaBufferFormat::Argb => (1, 2, 3, Some(0)),
+            OsMesaBufferFormat::Rgb => (0, 1, 2, None),
+            OsMesaBufferFormat::Bgr => (2, 1, 0, None),
+            OsMesaBufferFormat::Rgb565 => {
+                let value = u16::from_ne_bytes([pixel[0], pixel[1]]);
+                let r = (value >> 11) as u8;
+                let g = (value >> 5) as u8 & 0x3f;
+                let b = value as u8 & 0x1f;
+                return [
+                    r << 3 | r >> 2,
+                    g << 2 | g >> 4,
+                    b << 3 | b >> 2,
+                    255,
+                ];
+            }
+        };
+
+        let size = channel_type.size();
+        let channel = |i: usize| channel_type.to_u8(&pixel[i * size..]);
+        [channel(r), channel(g), channel(b), a.map_or(255, channel)]
+    }
+}
+
+impl Default for OsMesaBufferFormat {
+    #[inline]
+    fn default() -> Self {
+        OsMesaBufferFormat::Rgba
+    }
+}
+
+/// The type of each channel in the buffer an OsMesa context renders into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsMesaChannelType {
+    /// 8-bit unsigned normalized channels.
+    UnsignedByte,
+    /// 16-bit unsigned normalized channels.
+    UnsignedShort,
+    /// 32-bit floating point channels, which allow values outside of the
+    /// `[0.0, 1.0]` range.
+    Float,
+}
+
+impl OsMesaChannelType {
+    /// Converts the channel at the start of `data` to 8 bits.
+    fn to_u8(&self, data: &[u8]) -> u8 {
+        match *self {
+            OsMesaChannelType::UnsignedByte => data[0],
+            OsMesaChannelType::UnsignedShort => {
+                (u16::from_ne_bytes([data[0], data[1]]) >> 8) as u8
+            }
+            OsMesaChannelType::Float => {
+                let value =
+                    f32::from_ne_bytes([data[0], data[1], data[2], data[3]]);
+                (value.max(0.0).min(1.0) * 255.0).round() as u8
+            }
+        }
+    }
+
+    /// Returns the size of a single channel, in bytes.
+    #[inline]
+    pub fn size(&self) -> usize {
+        match *self {
+            OsMesaChannelType::UnsignedByte => 1,
+            OsMesaChannelType::UnsignedShort => 2,
+            OsMesaChannelType::Float => 4,
+        }
+    }
+
+    fn gl_type(&self) -> raw::c_uint {
+        match *self {
+            OsMesaChannelType::UnsignedByte => GL_UNSIGNED_BYTE,
+            OsMesaChannelType::UnsignedShort => GL_UNSIGNED_SHORT,
+            OsMesaChannelType::Float => GL_FLOAT,
+        }
+    }
+}
+
+impl Default for OsMesaChannelType {
+    #[inline]
+    fn default() -> Self {
+        OsMesaChannelType::UnsignedByte
+    }
+}
+
+/// OsMesa-specific attributes to use when creating an OsMesa context.
+#[derive(Debug, Clone, Default)]
+pub struct OsMesaAttributes {
+    /// The layout of the buffer the context renders into.
+    ///
+    /// The default is [`Rgba`].
+    ///
+    /// [`Rgba`]: enum.OsMesaBufferFormat.html#variant.Rgba
+    pub format: OsMesaBufferFormat,
+
+    /// The type of each channel of the buffer the context renders into.
+    ///
+    /// The default is [`UnsignedByte`].
+    ///
+    /// [`UnsignedByte`]: enum.OsMesaChannelType.html#variant.UnsignedByte
+    pub channel_type: OsMesaChannelType,
+
+    /// Number of bits in the accumulation buffer. `None` means "don't care".
+    ///
+    /// The default is `None`.
+    pub accum_bits: Option<u8>,
+
+    /// If true, context creation fails if the exact OpenGL version requested
+    /// is not available. Otherwise, lower versions of the same profile are
+    /// tried one after the other.
+    ///
+    /// The default is `false`.
+    pub exact_version: bool,
+
+    /// If true, context creation fails with [`CreationError::NotSupported`]
+    /// when [`GlAttributes::debug`] or [`GlAttributes::forward_compatible`]
+    /// is set, as OsMesa has no attributes for them, and when a core profile
+    /// is requested but the driver creates a compatibility context.
+    /// Otherwise, the flags are ignored.
+    ///
+    /// The default is `false`, because `debug` is enabled by default in debug
+    /// builds.
+    ///
+    /// [`CreationError::NotSupported`]:
+    /// ../../enum.CreationError.html#variant.NotSupported
+    /// [`GlAttributes::debug`]:
+    /// ../../struct.GlAttributes.html#structfield.debug
+    /// [`GlAttributes::forward_compatible`]:
+    /// ../../struct.GlAttributes.html#structfield.forward_compatible
+    pub reject_unsupported_flags: bool,
+}
+
+impl<'a> OsMesaColorBuffer<'a> {
+    /// Returns the pixel data as 16-bit channels, or `None` if the channel
+    /// type is not [`UnsignedShort`].
+    ///
+    /// [`UnsignedShort`]: enum.OsMesaChannelType.html#variant.UnsignedShort
+    #[inline]
+    pub fn as_u16(&self) -> Option<&'a [u16]> {
+        match self.channel_type {
+            OsMesaChannelType::UnsignedShort => {
+                Some(unsafe { cast_slice(self.data) })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the pixel data as floating point channels, or `None` if the
+    /// channel type is not [`Float`].
+    ///
+    /// [`Float`]: enum.OsMesaChannelType.html#variant.Float
+    #[inline]
+    pub fn as_f32(&self) -> Option<&'a [f32]> {
+        match self.channel_type {
+            OsMesaChannelType::Float => Some(unsafe { cast_slice(self.data) }),
+            _ => None,
+        }
+    }
+
+    /// Converts the pixel data to tightly packed 8-bit RGBA, keeping the row
+    /// order of the buffer.
+    ///
+    /// Channels are scaled down to 8 bits, and floating point channels are
+    /// clamped to `[0.0, 1.0]`. Alpha is 255 for formats without an alpha
+    /// channel.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let width = self.width as usize;
+        let bytes_per_pixel = self.format.bytes_per_pixel(self.channel_type);
+        let mut pixels = Vec::with_capacity(width * self.height as usize * 4);
+        for row in self.data.chunks(self.stride).take(self.height as usize) {
+            for pixel in row[..width * bytes_per_pixel].chunks(bytes_per_pixel)
+            {
+                pixels.extend_from_slice(
+                    &self.format.pixel_to_rgba8(pixel, self.channel_type),
+                );
+            }
+        }
+        pixels
+    }
+}
+
+/// Reinterprets a byte slice as a slice of `T`.
+///
+/// The slice must be suitably aligned for `T`, which we make sure of for all
+/// buffers we hand to OsMesa.
+unsafe fn cast_slice<T>(data: &[u8]) -> &[T] {
+    let size = std::mem::size_of::<T>();
+    assert_eq!(data.as_ptr() as usize % std::mem::align_of::<T>(), 0);
+    std::slice::from_raw_parts(data.as_ptr() as *const T, data.len() / size)
+}
+
+/// Information about the OsMesa driver in use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsMesaDriverInfo {
+    /// The Mesa version as `(major, minor, patch)`, parsed from `GL_VERSION`.
+    /// `None` if it couldn't be parsed.
+    pub mesa_version: Option<(u32, u32, u32)>,
+    /// The `GL_RENDERER` string, for example `llvmpipe (LLVM 9.0, 256 bits)`.
+    pub renderer: String,
+    /// Whether the driver is gallium-based (llvmpipe, softpipe, swr), as
+    /// opposed to the classic swrast driver.
+    pub gallium: bool,
+    /// Whether the driver can make a context not current. Older gallium-based
+    /// drivers can't.
+    pub supports_unbind: bool,
+}
+
+/// An attribute of the current OsMesa context that can be queried with
+/// `OSMesaGetIntegerv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsMesaAttrib {
+    /// The width of the bound buffer, in pixels.
+    Width,
+    /// The height of the bound buffer, in pixels.
+    Height,
+    /// The `OSMESA_*` format of the bound buffer.
+    Format,
+    /// The GL type of the channels of the bound buffer.
+    Type,
+    /// The number of pixels between the start of two consecutive rows.
+    RowLength,
+    /// Whether the first row of the buffer is the bottom row of the image.
+    YUp,
+    /// The maximum width of a buffer, in pixels.
+    MaxWidth,
+    /// The maximum height of a buffer, in pixels.
+    MaxHeight,
+}
+
+impl OsMesaAttrib {
+    fn to_osmesa(&self) -> raw::c_int {
+        match *self {
+            OsMesaAttrib::Width => osmesa_sys::OSMESA_WIDTH,
+            OsMesaAttrib::Height => osmesa_sys::OSMESA_HEIGHT,
+            OsMesaAttrib::Format => osmesa_sys::OSMESA_FORMAT,
+            OsMesaAttrib::Type => osmesa_sys::OSMESA_TYPE,
+            OsMesaAttrib::RowLength => osmesa_sys::OSMESA_ROW_LENGTH,
+            OsMesaAttrib::YUp => osmesa_sys::OSMESA_Y_UP,
+            OsMesaAttrib::MaxWidth => osmesa_sys::OSMESA_MAX_WIDTH,
+            OsMesaAttrib::MaxHeight => osmesa_sys::OSMESA_MAX_HEIGHT,
+        }
+    }
+}
+
+/// A view of the color buffer an OsMesa context rendered into.
+#[derive(Debug, Clone, Copy)]
+pub struct OsMesaColorBuffer<'a> {
+    /// The width of the buffer, in pixels.
+    pub width: u32,
+    /// The height of the buffer, in pixels.
+    pub height: u32,
+    /// The number of bytes between the start of two consecutive rows.
+    pub stride: usize,
+    /// The layout of each pixel.
+    pub format: OsMesaBufferFormat,
+    /// The type of each channel.
+    pub channel_type: OsMesaChannelType,
+    /// The raw pixel data, `stride * height` bytes long.
+    ///
+    /// Unless the context was told otherwise with [`set_y_up`], the first row
+    /// is the bottom row of the image.
+    ///
+    /// [`set_y_up`]: trait.OsMesaContextExt.html#tymethod.set_y_up
+    pub data: &'a [u8],
+}
+
+/// The memory an OsMesa context renders into.
+#[derive(Debug)]
+enum Buffer {
+    /// Allocated by glutin. Made of `u32`s so that it is suitably aligned for
+    /// every format.
+    Owned(Vec<u32>),
+    /// Allocated by glutin for formats with 8-bit channels, which need no
+    /// alignment. Can be handed out as a `Vec<u8>` without copying.
+    OwnedBytes(Vec<u8>),
+    /// Provided by the caller, who is responsible for keeping it alive. We
+    /// never free it.
+    Raw(*mut u8, usize),
+}
+
+impl Buffer {
+    #[inline]
+    fn as_ptr(&self) -> *mut raw::c_void {
+        match *self {
+            Buffer::Owned(ref vec) => vec.as_ptr() as *mut _,
+            Buffer::OwnedBytes(ref vec) => vec.as_ptr() as *mut _,
+            Buffer::Raw(ptr, _) => ptr as *mut _,
+        }
+    }
+
+    /// Length of the buffer in bytes.
+    #[inline]
+    fn len(&self) -> usize {
+        match *self {
+            Buffer::Owned(ref vec) => vec.len() * 4,
+            Buffer::OwnedBytes(ref vec) => vec.len(),
+            Buffer::Raw(_, len) => len,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct OsMesaContext {
     context: osmesa_sys::OSMesaContext,
-    buffer: Vec<u32>,
+    buffer: Buffer,
+    format: OsMesaBufferFormat,
+    channel_type: OsMesaChannelType,
     width: u32,
     height: u32,
+    // Zero means "same as `width`".
+    row_length: u32,
+    y_up: bool,
+    version: Option<(u8, u8)>,
+    // `OSMesaGetProcAddress` walks Mesa's dispatch tables on every call, so
+    // cache what it returns.
+    proc_addresses: Mutex<HashMap<CString, *const core::ffi::c_void>>,
+    // Whether we destroy `context` when dropped.
+    owns_context: bool,
+}
+
+/// A view of the depth buffer of an OsMesa context.
+#[derive(Debug, Clone, Copy)]
+pub struct OsMesaDepthBuffer<'a> {
+    /// The width of the buffer, in pixels.
+    pub width: u32,
+    /// The height of the buffer, in pixels.
+    pub height: u32,
+    /// The number of bytes taken up by each depth value, usually 2 or 4.
+    pub bytes_per_value: usize,
+    /// The raw depth values, in native endianness,
+    /// `width * height * bytes_per_value` bytes long.
+    pub data: &'a [u8],
 }
 
 #[derive(Debug)]
@@ -67,31 +496,295 @@ impl std::error::Error for LoadingError {
     }
 }
 
+fn load() -> Result<(), CreationError> {
+    osmesa_sys::OsMesa::try_loading()
+        .map(|_| ())
+        .map_err(LoadingError::new)
+        .map_err(|e| CreationError::NoBackendAvailable(Box::new(e)))
+}
+
+/// Loads OsMesa if needed and returns information about its driver.
+///
+/// The driver is only probed once. Doing so briefly makes a scratch context
+/// current on the calling thread; whatever OsMesa context was current before
+/// is made current again afterwards.
+pub fn driver_info() -> Result<OsMesaDriverInfo, CreationError> {
+    load()?;
+    DRIVER_INFO.clone().ok_or_else(|| {
+        CreationError::OsError("Failed to probe the OsMesa driver".to_string())
+    })
+}
+
+/// The OsMesa context current on the calling thread, along with the buffer it
+/// renders into, so that it can be made current again later.
+#[derive(Debug)]
+struct Binding {
+    context: osmesa_sys::OSMesaContext,
+    buffer: *mut raw::c_void,
+    ty: raw::c_uint,
+    width: raw::c_int,
+    height: raw::c_int,
+    y_up: raw::c_int,
+    row_length: raw::c_int,
+}
+
+impl Binding {
+    /// Returns `None` if no OsMesa context is current.
+    unsafe fn current() -> Option<Binding> {
+        let context = osmesa_sys::OSMesaGetCurrentContext();
+        if context.is_null() {
+            return None;
+        }
+
+        let mut width = 0;
+        let mut height = 0;
+        let mut format = 0;
+        let mut buffer = std::ptr::null_mut();
+        if osmesa_sys::OSMesaGetColorBuffer(
+            context,
+            &mut width,
+            &mut height,
+            &mut format,
+            &mut buffer,
+        ) == 0
+        {
+            return None;
+        }
+        let get_integer = |pname| {
+            let mut value = 0;
+            osmesa_sys::OSMesaGetIntegerv(pname, &mut value);
+            value
+        };
+        Some(Binding {
+            context,
+            buffer,
+            ty: get_integer(osmesa_sys::OSMESA_TYPE) as raw::c_uint,
+            width,
+            height,
+            y_up: get_integer(osmesa_sys::OSMESA_Y_UP),
+            row_length: get_integer(osmesa_sys::OSMESA_ROW_LENGTH),
+        })
+    }
+
+    unsafe fn restore(&self) {
+        if osmesa_sys::OSMesaMakeCurrent(
+            self.context,
+            self.buffer,
+            self.ty,
+            self.width,
+            self.height,
+        ) != 0
+        {
+            osmesa_sys::OSMesaPixelStore(osmesa_sys::OSMESA_Y_UP, self.y_up);
+            osmesa_sys::OSMesaPixelStore(
+                osmesa_sys::OSMESA_ROW_LENGTH,
+                self.row_length,
+            );
+        }
+    }
+}
+
+/// Makes the OsMesa context that was current on the calling thread when this
+/// was created current again when dropped, even while unwinding. If there was
+/// none, whatever context is current is unbound, when the driver supports
+/// it.
+///
+/// This belongs to the thread it was created on, which the raw pointers of
+/// the binding ensure.
+#[derive(Debug)]
+pub(crate) struct RestoreBinding {
+    previous: Option<Binding>,
+}
+
+impl RestoreBinding {
+    pub(crate) fn new() -> Self {
+        RestoreBinding {
+            previous: unsafe { Binding::current() },
+        }
+    }
+}
+
+impl Drop for RestoreBinding {
+    fn drop(&mut self) {
+        unsafe {
+            match self.previous {
+                Some(ref binding) => binding.restore(),
+                None => {
+                    osmesa_sys::OSMesaMakeCurrent(
+                        std::ptr::null_mut(),
+                        std::ptr::null_mut(),
+                        0,
+                        0,
+                        0,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether `context` has the core profile, by binding it to a
+/// scratch pixel and reading `GL_CONTEXT_PROFILE_MASK`.
+unsafe fn is_core_profile(
+    context: osmesa_sys::OSMesaContext,
+    format: OsMesaBufferFormat,
+) -> bool {
+    let _restore = RestoreBinding::new();
+
+    let mut pixel = 0u32;
+    if osmesa_sys::OSMesaMakeCurrent(
+        context,
+        &mut pixel as *mut u32 as *mut _,
+        format.gl_type(OsMesaChannelType::UnsignedByte),
+        1,
+        1,
+    ) == 0
+    {
+        return false;
+    }
+
+    let get_integerv = osmesa_sys::OSMesaGetProcAddress(
+        b"glGetIntegerv\0".as_ptr() as *const _ as *mut _,
+    );
+    let get_integerv: extern "system" fn(raw::c_uint, *mut raw::c_int) =
+        match get_integerv {
+            Some(f) => std::mem::transmute(f),
+            None => return false,
+        };
+    let mut mask = 0;
+    get_integerv(GL_CONTEXT_PROFILE_MASK, &mut mask);
+    mask & GL_CONTEXT_CORE_PROFILE_BIT != 0
+}
+
+unsafe fn probe_driver() -> Option<OsMesaDriverInfo> {
+    // Remember the current binding so that we can restore it.
+    let prev_binding = Binding::current();
+    let restore = || {
+        if let Some(ref binding) = prev_binding {
+            binding.restore();
+        }
+    };
+
+    let attribs = [
+        osmesa_sys::OSMESA_FORMAT,
+        OsMesaBufferFormat::Rgba.to_osmesa(),
+        osmesa_sys::OSMESA_DEPTH_BITS,
+        0,
+        osmesa_sys::OSMESA_STENCIL_BITS,
+        0,
+        0,
+    ];
+    let context = osmesa_sys::OSMesaCreateContextAttribs(
+        attribs.as_ptr(),
+        std::ptr::null_mut(),
+    );
+    if context.is_null() {
+        return None;
+    }
+
+    let mut pixel = 0u32;
+    if osmesa_sys::OSMesaMakeCurrent(
+        context,
+        &mut pixel as *mut u32 as *mut _,
+        GL_UNSIGNED_BYTE,
+        1,
+        1,
+    ) == 0
+    {
+        osmesa_sys::OSMesaDestroyContext(context);
+        restore();
+        return None;
+    }
+
+    let get_string = osmesa_sys::OSMesaGetProcAddress(
+        b"glGetString\0".as_ptr() as *const _ as *mut _,
+    );
+    let (renderer, version) = if get_string.is_none() {
+        (String::new(), String::new())
+    } else {
+        let get_string: extern "system" fn(raw::c_uint) -> *const raw::c_char =
+            std::mem::transmute(get_string);
+        let to_string = |p: *const raw::c_char| {
+            if p.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(p).to_string_lossy().into_owned()
+            }
+        };
+        (
+            to_string(get_string(GL_RENDERER)),
+            to_string(get_string(GL_VERSION)),
+        )
+    };
+
+    let supports_unbind = osmesa_sys::OSMesaMakeCurrent(
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+        0,
+        0,
+        0,
+    ) != 0;
+
+    osmesa_sys::OSMesaDestroyContext(context);
+    restore();
+
+    Some(OsMesaDriverInfo {
+        mesa_version: parse_mesa_version(&version),
+        gallium: ["llvmpipe", "softpipe", "swr", "Gallium"]
+            .iter()
+            .any(|name| renderer.contains(name)),
+        renderer,
+        supports_unbind,
+    })
+}
+
+/// Parses the Mesa version out of a `GL_VERSION` string like
+/// `3.1 Mesa 20.0.4` or `4.5 (Core Profile) Mesa 20.1.0-devel`.
+fn parse_mesa_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mesa = version.split("Mesa ").nth(1)?;
+    let mut parts = mesa.split('.').map(|part| {
+        part.chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .parse::<u32>()
+            .ok()
+    });
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().and_then(|p| p).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
 impl OsMesaContext {
     pub fn new(
-        _pf_reqs: &PixelFormatRequirements,
+        pf_reqs: &PixelFormatRequirements,
         opengl: &GlAttributes<&OsMesaContext>,
+        osmesa: &OsMesaAttributes,
         size: dpi::PhysicalSize<u32>,
     ) -> Result<Self, CreationError> {
-        osmesa_sys::OsMesa::try_loading()
-            .map_err(LoadingError::new)
-            .map_err(|e| CreationError::NoBackendAvailable(Box::new(e)))?;
+        load()?;
 
-        if opengl.sharing.is_some() {
-            panic!("Context sharing not possible with OsMesa")
-        }
+        check_attributes(pf_reqs, opengl, osmesa)?;
 
-        match opengl.robustness {
-            Robustness::RobustNoResetNotification
-            | Robustness::RobustLoseContextOnReset => {
-                return Err(CreationError::RobustnessNotSupported.into());
-            }
-            _ => (),
+        let mut attribs = Vec::new();
+
+        attribs.push(osmesa_sys::OSMESA_FORMAT);
+        attribs.push(osmesa.format.to_osmesa());
+
+        if let Some(depth) = pf_reqs.depth_bits {
+            attribs.push(osmesa_sys::OSMESA_DEPTH_BITS);
+            attribs.push(depth as raw::c_int);
         }
 
-        // TODO: use `pf_reqs` for the format
+        if let Some(stencil) = pf_reqs.stencil_bits {
+            attribs.push(osmesa_sys::OSMESA_STENCIL_BITS);
+            attribs.push(stencil as raw::c_int);
+        }
 
-        let mut attribs = Vec::new();
+        if let Some(accum) = osmesa.accum_bits {
+            attribs.push(osmesa_sys::OSMESA_ACCUM_BITS);
+            attribs.push(accum as raw::c_int);
+        }
 
         if let Some(profile) = opengl.profile {
             attribs.push(osmesa_sys::OSMESA_PROFILE);
@@ -106,73 +799,216 @@ impl OsMesaContext {
             }
         }
 
-        match opengl.version {
-            GlRequest::Latest => {}
-            GlRequest::Specific(Api::OpenGl, (major, minor)) => {
-                attribs.push(osmesa_sys::OSMESA_CONTEXT_MAJOR_VERSION);
-                attribs.push(major as raw::c_int);
-                attribs.push(osmesa_sys::OSMESA_CONTEXT_MINOR_VERSION);
-                attribs.push(minor as raw::c_int);
-            }
+        let requested = match opengl.version {
+            GlRequest::Latest => None,
+            GlRequest::Specific(Api::OpenGl, version) => Some(version),
             GlRequest::Specific(Api::OpenGlEs, _)
             | GlRequest::Specific(Api::WebGl, _) => {
                 return Err(CreationError::NoBackendAvailable(Box::new(
                     NoEsOrWebGlSupported,
                 )));
             }
-            GlRequest::GlThenGles {
-                opengl_version: (major, minor),
-                ..
-            } => {
+            GlRequest::GlThenGles { opengl_version, .. } => {
+                Some(opengl_version)
+            }
+        };
+
+        // Try the requested version first, then, unless told otherwise, walk
+        // down the versions of the same profile.
+        let versions = match requested {
+            None => vec![None],
+            Some(version) if osmesa.exact_version => vec![Some(version)],
+            Some(version) => {
+                let fallbacks = match opengl.profile {
+                    Some(GlProfile::Core) => CORE_VERSIONS,
+                    Some(GlProfile::Compatibility) => COMPAT_VERSIONS,
+                    None if version >= (3, 2) => CORE_VERSIONS,
+                    None => COMPAT_VERSIONS,
+                };
+                std::iter::once(version)
+                    .chain(fallbacks.iter().cloned().filter(|&v| v < version))
+                    .map(Some)
+                    .collect()
+            }
+        };
+
+        let mut context = None;
+        for &version in &versions {
+            let mut attribs = attribs.clone();
+            if let Some((major, minor)) = version {
                 attribs.push(osmesa_sys::OSMESA_CONTEXT_MAJOR_VERSION);
                 attribs.push(major as raw::c_int);
                 attribs.push(osmesa_sys::OSMESA_CONTEXT_MINOR_VERSION);
                 attribs.push(minor as raw::c_int);
             }
+
+            // attribs array must be NULL terminated.
+            attribs.push(0);
+
+            let ctx = unsafe {
+                osmesa_sys::OSMesaCreateContextAttribs(
+                    attribs.as_ptr(),
+                    std::ptr::null_mut(),
+                )
+            };
+            if !ctx.is_null() {
+                context = Some((ctx, version));
+                break;
+            }
         }
 
-        // attribs array must be NULL terminated.
-        attribs.push(0);
+        let (context, version) = match context {
+            Some(context) => context,
+            None => {
+                return Err(CreationError::OsError(format!(
+                    "OSMesaCreateContextAttribs failed (version: {:?}, \
+                     format: {:?}, depth bits: {:?}, stencil bits: {:?}, \
+                     accum bits: {:?})",
+                    requested,
+                    osmesa.format,
+                    pf_reqs.depth_bits,
+                    pf_reqs.stencil_bits,
+                    osmesa.accum_bits,
+                )));
+            }
+        };
+
+        // Drivers without `OSMESA_PROFILE` support ignore it and create a
+        // compatibility context.
+        if opengl.profile == Some(GlProfile::Core)
+            && osmesa.reject_unsupported_flags
+            && !unsafe { is_core_profile(context, osmesa.format) }
+        {
+            unsafe { osmesa_sys::OSMesaDestroyContext(context) };
+            return Err(CreationError::NotSupported(
+                "OsMesa created a compatibility context instead of the \
+                 requested core profile"
+                    .to_string(),
+            ));
+        }
 
         let size: (u32, u32) = size.into();
 
         Ok(OsMesaContext {
             width: size.0,
             height: size.1,
-            buffer: std::iter::repeat(unsafe { std::mem::zeroed() })
-                .take((size.0 * size.1) as usize)
-                .collect(),
-            context: unsafe {
-                let ctx = osmesa_sys::OSMesaCreateContextAttribs(
-                    attribs.as_ptr(),
-                    std::ptr::null_mut(),
-                );
-                if ctx.is_null() {
-                    return Err(CreationError::OsError(
-                        "OSMesaCreateContextAttribs failed".to_string(),
-                    ));
-                }
-                ctx
-            },
+            buffer: alloc_buffer(
+                osmesa.format,
+                osmesa.channel_type,
+                size.0,
+                size.1,
+            ),
+            format: osmesa.format,
+            channel_type: osmesa.channel_type,
+            row_length: 0,
+            y_up: true,
+            version,
+            proc_addresses: Mutex::new(HashMap::new()),
+            owns_context: true,
+            context,
+        })
+    }
+
+    /// Wraps an OsMesa context created elsewhere, giving it a new buffer of
+    /// the given size using the format and channel type of `osmesa`.
+    ///
+    /// The attributes OsMesa can't honor are rejected like by [`new`]. The
+    /// version, profile and buffer bits are those the context was created
+    /// with, so [`version`] returns `None`.
+    ///
+    /// If `owned` is true, the context is destroyed when dropped. Otherwise
+    /// it is left to its owner, and only unbound if it is still bound to the
+    /// buffer of this context.
+    ///
+    /// [`new`]: #method.new
+    /// [`version`]: #method.version
+    pub unsafe fn from_raw(
+        context: osmesa_sys::OSMesaContext,
+        owned: bool,
+        pf_reqs: &PixelFormatRequirements,
+        opengl: &GlAttributes<&OsMesaContext>,
+        osmesa: &OsMesaAttributes,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<Self, CreationError> {
+        load()?;
+
+        if context.is_null() {
+            return Err(CreationError::OsError(
+                "The raw OsMesa context is null".to_string(),
+            ));
+        }
+
+        check_attributes(pf_reqs, opengl, osmesa)?;
+
+        if let GlRequest::Specific(Api::OpenGlEs, _)
+        | GlRequest::Specific(Api::WebGl, _) = opengl.version
+        {
+            return Err(CreationError::NoBackendAvailable(Box::new(
+                NoEsOrWebGlSupported,
+            )));
+        }
+
+        let (width, height) = check_size(size)
+            .map_err(|err| CreationError::OsError(format!("{}", err)))?;
+
+        Ok(OsMesaContext {
+            width,
+            height,
+            buffer: alloc_buffer(
+                osmesa.format,
+                osmesa.channel_type,
+                width,
+                height,
+            ),
+            format: osmesa.format,
+            channel_type: osmesa.channel_type,
+            row_length: 0,
+            y_up: true,
+            version: None,
+            proc_addresses: Mutex::new(HashMap::new()),
+            owns_context: owned,
+            context,
         })
     }
 
+    /// Returns the version that was passed to `OSMesaCreateContextAttribs`,
+    /// which may be lower than the requested one if fallbacks were allowed.
+    ///
+    /// Returns `None` if no specific version was requested.
+    #[inline]
+    pub fn version(&self) -> Option<(u8, u8)> {
+        self.version
+    }
+
     #[inline]
     pub unsafe fn make_current(&self) -> Result<(), ContextError> {
         let ret = osmesa_sys::OSMesaMakeCurrent(
             self.context,
-            self.buffer.as_ptr() as *mut _,
-            0x1401,
+            self.buffer.as_ptr(),
+            self.format.gl_type(self.channel_type),
             self.width as raw::c_int,
             self.height as raw::c_int,
         );
 
-        // an error can only happen in case of invalid parameter, which would
-        // indicate a bug in glutin
+        // Not every driver supports every format and channel type.
         if ret == 0 {
-            panic!("OSMesaMakeCurrent failed");
+            return Err(ContextError::OsError(format!(
+                "OSMesaMakeCurrent failed (format: {:?}, channel type: {:?})",
+                self.format, self.channel_type
+            )));
         }
 
+        // The pixel store parameters belong to the binding, so restore them
+        // every time.
+        osmesa_sys::OSMesaPixelStore(
+            osmesa_sys::OSMESA_Y_UP,
+            self.y_up as raw::c_int,
+        );
+        osmesa_sys::OSMesaPixelStore(
+            osmesa_sys::OSMESA_ROW_LENGTH,
+            self.row_length as raw::c_int,
+        );
+
         Ok(())
     }
 
@@ -184,10 +1020,15 @@ impl OsMesaContext {
             // however, older users (or anyone not running mesa-git, tbh)
             // probably won't support this.
             //
-            // There is no way to tell, ofc, without just calling the function
-            // and seeing if it work.
+            // The driver is probed once for this, so we don't have to try.
             //
             // https://gitlab.freedesktop.org/mesa/mesa/merge_requests/533
+            if let Some(ref info) = *DRIVER_INFO {
+                if !info.supports_unbind {
+                    return Err(ContextError::FunctionUnavailable);
+                }
+            }
+
             let ret = osmesa_sys::OSMesaMakeCurrent(
                 std::ptr::null_mut(),
                 std::ptr::null_mut(),
@@ -197,9 +1038,9 @@ impl OsMesaContext {
             );
 
             if ret == 0 {
-                unimplemented!(
-                    "OSMesaMakeCurrent failed to make the context not current. This most likely means that you're using an older gallium-based mesa driver."
-                )
+                // This most likely means that we're using an older
+                // gallium-based mesa driver.
+                return Err(ContextError::FunctionUnavailable);
             }
         }
 
@@ -223,11 +1064,629 @@ impl OsMesaContext {
 
     #[inline]
     pub fn get_proc_address(&self, addr: &str) -> *const core::ffi::c_void {
-        unsafe {
-            let c_str = CString::new(addr.as_bytes().to_vec()).unwrap();
+        let c_str = CString::new(addr.as_bytes().to_vec()).unwrap();
+        let mut proc_addresses = self.proc_addresses.lock().unwrap();
+        if let Some(&addr) = proc_addresses.get(&c_str) {
+            return addr;
+        }
+        let addr = unsafe {
             core::mem::transmute(osmesa_sys::OSMesaGetProcAddress(
                 c_str.as_ptr() as *mut _,
             ))
+        };
+        proc_addresses.insert(c_str, addr);
+        addr
+    }
+
+    /// Replaces the buffer the context renders into with a new zeroed one of
+    /// the given size. This also stops rendering into a buffer set with
+    /// [`set_raw_buffer`].
+    ///
+    /// If the context is current, the new buffer is bound before the old one
+    /// is freed. If that fails, the old buffer is kept.
+    ///
+    /// [`set_raw_buffer`]: #method.set_raw_buffer
+    pub fn resize(
+        &mut self,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<(), ContextError> {
+        let (width, height) = check_size(size)?;
+        self.check_max_size(width, height)?;
+        self.realloc(width, height, self.row_length)
+    }
+
+    /// Resizes the buffer to `size` if needed, makes the context current,
+    /// calls `f` and returns what it rendered as tightly packed 8-bit RGBA
+    /// rows, from top to bottom. The previous binding of the thread is
+    /// restored afterwards, even if `f` panics.
+    pub fn render_to_image<F: FnOnce()>(
+        &mut self,
+        size: dpi::PhysicalSize<u32>,
+        f: F,
+    ) -> Result<Vec<u8>, ContextError> {
+        let (width, height) = check_size(size)?;
+        if (width, height) != (self.width, self.height) {
+            self.resize(size)?;
+        }
+
+        // Saved after resizing, which rebinds the new buffer if this context
+        // was current.
+        let _restore = RestoreBinding::new();
+        unsafe { self.make_current()? };
+        f();
+
+        let mut pixels = self.color_buffer()?.to_rgba8();
+        if self.y_up {
+            // The first row in the buffer is the bottom one.
+            let stride = width as usize * 4;
+            let height = height as usize;
+            for y in 0..height / 2 {
+                let (top, bottom) =
+                    pixels.split_at_mut((height - y - 1) * stride);
+                top[y * stride..(y + 1) * stride]
+                    .swap_with_slice(&mut bottom[..stride]);
+            }
+        }
+        Ok(pixels)
+    }
+
+    /// Queries an attribute of the context, which must be current.
+    pub fn get_attrib(
+        &self,
+        attrib: OsMesaAttrib,
+    ) -> Result<raw::c_int, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The OsMesa context must be current to query its attributes"
+                    .to_string(),
+            ));
+        }
+
+        let mut value = 0;
+        unsafe {
+            osmesa_sys::OSMesaGetIntegerv(attrib.to_osmesa(), &mut value)
+        };
+        Ok(value)
+    }
+
+    /// Returns the largest buffer the context can render into. The context
+    /// must be current.
+    pub fn max_buffer_size(
+        &self,
+    ) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        Ok(dpi::PhysicalSize::new(
+            self.get_attrib(OsMesaAttrib::MaxWidth)? as u32,
+            self.get_attrib(OsMesaAttrib::MaxHeight)? as u32,
+        ))
+    }
+
+    /// Returns an error if the context is current and the given size is
+    /// larger than what it supports.
+    fn check_max_size(
+        &self,
+        width: u32,
+        height: u32,
+    ) -> Result<(), ContextError> {
+        if !self.is_current() {
+            return Ok(());
+        }
+
+        let max = self.max_buffer_size()?;
+        if width > max.width || height > max.height {
+            return Err(ContextError::OsError(format!(
+                "OsMesa buffer size {}x{} exceeds the maximum of {}x{}",
+                width, height, max.width, max.height
+            )));
+        }
+        Ok(())
+    }
+
+    /// Makes the context render into memory owned by the caller, which must
+    /// be at least `row_length * height * bytes_per_pixel` bytes long.
+    ///
+    /// If the context is current, it is rebound to the new buffer right away.
+    ///
+    /// Unsafe behaviour might happen if the memory is freed or accessed
+    /// elsewhere while the context may still render into it.
+    pub unsafe fn set_raw_buffer(
+        &mut self,
+        ptr: *mut u8,
+        len: usize,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<(), ContextError> {
+        let (width, height) = check_size(size)?;
+        self.check_max_size(width, height)?;
+        if ptr.is_null() {
+            return Err(ContextError::OsError(
+                "OsMesa buffer pointer is null".to_string(),
+            ));
+        }
+        if ptr as usize % self.channel_type.size() != 0 {
+            return Err(ContextError::OsError(format!(
+                "OsMesa buffer pointer is not aligned to {} bytes",
+                self.channel_type.size()
+            )));
+        }
+
+        let row_length = if self.row_length < width {
+            0
+        } else {
+            self.row_length
+        };
+        let needed = std::cmp::max(row_length, width) as usize
+            * height as usize
+            * self.bytes_per_pixel();
+        if len < needed {
+            return Err(ContextError::OsError(format!(
+                "OsMesa buffer too small: got {} bytes, need {}",
+                len, needed
+            )));
+        }
+
+        self.replace_buffer(Buffer::Raw(ptr, len), width, height, row_length)
+    }
+
+    /// Replaces the buffer and its dimensions, and rebinds the context if it
+    /// is current. If that fails, the previous buffer and dimensions are
+    /// restored, which Mesa still renders into.
+    unsafe fn replace_buffer(
+        &mut self,
+        buffer: Buffer,
+        width: u32,
+        height: u32,
+        row_length: u32,
+    ) -> Result<(), ContextError> {
+        let was_current = self.is_current();
+        let old_width = std::mem::replace(&mut self.width, width);
+        let old_height = std::mem::replace(&mut self.height, height);
+        let old_row_length =
+            std::mem::replace(&mut self.row_length, row_length);
+        let old_buffer = std::mem::replace(&mut self.buffer, buffer);
+
+        if was_current {
+            if let Err(err) = self.make_current() {
+                self.width = old_width;
+                self.height = old_height;
+                self.row_length = old_row_length;
+                self.buffer = old_buffer;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets whether the first row of the buffer is the bottom row of the
+    /// image, which is the default.
+    ///
+    /// Takes effect immediately if the context is current, otherwise the next
+    /// time it is made current.
+    pub fn set_y_up(&mut self, y_up: bool) {
+        self.y_up = y_up;
+        if self.is_current() {
+            unsafe {
+                osmesa_sys::OSMesaPixelStore(
+                    osmesa_sys::OSMESA_Y_UP,
+                    y_up as raw::c_int,
+                );
+            }
+        }
+    }
+
+    /// Sets the number of pixels between the start of two consecutive rows of
+    /// the buffer. Zero means "same as the width of the buffer", which is the
+    /// default.
+    ///
+    /// If the new row length is wider than the buffer, the buffer is
+    /// reallocated and its contents discarded.
+    pub fn set_row_length(
+        &mut self,
+        row_length: u32,
+    ) -> Result<(), ContextError> {
+        if row_length != 0 && row_length < self.width {
+            return Err(ContextError::OsError(format!(
+                "OsMesa row length ({}) is smaller than the buffer width ({})",
+                row_length, self.width
+            )));
+        }
+
+        let grow = row_length > self.row_length();
+        if let Buffer::Raw(_, len) = self.buffer {
+            let needed = std::cmp::max(row_length, self.width) as usize
+                * self.height as usize
+                * self.bytes_per_pixel();
+            if needed > len {
+                return Err(ContextError::OsError(format!(
+                    "OsMesa buffer too small for row length {}: got {} \
+                     bytes, need {}",
+                    row_length, len, needed
+                )));
+            }
+        }
+
+        if grow && self.is_owned() {
+            return self.realloc(self.width, self.height, row_length);
+        }
+
+        self.row_length = row_length;
+        if self.is_current() {
+            unsafe {
+                osmesa_sys::OSMesaPixelStore(
+                    osmesa_sys::OSMESA_ROW_LENGTH,
+                    row_length as raw::c_int,
+                );
+            }
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn is_owned(&self) -> bool {
+        match self.buffer {
+            Buffer::Owned(_) | Buffer::OwnedBytes(_) => true,
+            Buffer::Raw(..) => false,
+        }
+    }
+
+    #[inline]
+    fn bytes_per_pixel(&self) -> usize {
+        self.format.bytes_per_pixel(self.channel_type)
+    }
+
+    /// Returns whether the context is current and renders into our buffer.
+    fn is_bound_to_buffer(&self) -> bool {
+        if !self.is_current() {
+            return false;
+        }
+
+        let mut width = 0;
+        let mut height = 0;
+        let mut format = 0;
+        let mut buffer = std::ptr::null_mut();
+        let ret = unsafe {
+            osmesa_sys::OSMesaGetColorBuffer(
+                self.context,
+                &mut width,
+                &mut height,
+                &mut format,
+                &mut buffer,
+            )
+        };
+        ret != 0 && buffer == self.buffer.as_ptr()
+    }
+
+    /// Returns the number of pixels between the start of two consecutive rows.
+    #[inline]
+    fn row_length(&self) -> u32 {
+        std::cmp::max(self.row_length, self.width)
+    }
+
+    /// Returns the number of bytes between the start of two consecutive rows.
+    #[inline]
+    fn stride(&self) -> usize {
+        self.row_length() as usize * self.bytes_per_pixel()
+    }
+
+    /// Replaces the buffer with a new zeroed one of the given size, binding
+    /// it before the old one is freed if the context is current. Nothing
+    /// changes if binding fails.
+    fn realloc(
+        &mut self,
+        width: u32,
+        height: u32,
+        row_length: u32,
+    ) -> Result<(), ContextError> {
+        // A row length narrower than the buffer doesn't make sense anymore.
+        let row_length = if row_length < width { 0 } else { row_length };
+        let buffer = alloc_buffer(
+            self.format,
+            self.channel_type,
+            std::cmp::max(row_length, width),
+            height,
+        );
+        unsafe { self.replace_buffer(buffer, width, height, row_length) }
+    }
+
+    /// Waits for all rendering to complete, then returns the color buffer
+    /// that is currently bound to the context.
+    ///
+    /// The context must be current.
+    pub fn color_buffer(&self) -> Result<OsMesaColorBuffer<'_>, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The OsMesa context must be current to read its color buffer"
+                    .to_string(),
+            ));
+        }
+
+        unsafe {
+            self.finish();
+
+            let mut width = 0;
+            let mut height = 0;
+            let mut format = 0;
+            let mut buffer = std::ptr::null_mut();
+            let ret = osmesa_sys::OSMesaGetColorBuffer(
+                self.context,
+                &mut width,
+                &mut height,
+                &mut format,
+                &mut buffer,
+            );
+            if ret == 0 || buffer.is_null() {
+                return Err(ContextError::OsError(
+                    "OSMesaGetColorBuffer failed".to_string(),
+                ));
+            }
+
+            let stride = self.stride();
+            let len = stride * height as usize;
+            if buffer != self.buffer.as_ptr() || len > self.buffer.len() {
+                return Err(ContextError::OsError(
+                    "OsMesa color buffer doesn't match the bound buffer"
+                        .to_string(),
+                ));
+            }
+
+            Ok(OsMesaColorBuffer {
+                width: width as u32,
+                height: height as u32,
+                stride,
+                format: self.format,
+                channel_type: self.channel_type,
+                data: std::slice::from_raw_parts(buffer as *const u8, len),
+            })
+        }
+    }
+
+    /// Returns the color buffer for writing, waiting for all rendering to
+    /// complete first if the context is current.
+    pub fn color_buffer_mut(&mut self) -> &mut [u8] {
+        if self.is_current() {
+            unsafe { self.finish() };
+        }
+
+        let len = self.stride() * self.height as usize;
+        unsafe {
+            std::slice::from_raw_parts_mut(self.buffer.as_ptr() as *mut u8, len)
+        }
+    }
+
+    /// Waits for all rendering to complete if the context is current, then
+    /// moves the color buffer out of the context, replacing it with a new
+    /// zeroed one.
+    ///
+    /// This doesn't copy for formats with 8-bit channels, other than
+    /// `Rgb565`.
+    pub fn take_color_buffer(&mut self) -> Result<Vec<u8>, ContextError> {
+        if !self.is_owned() {
+            return Err(ContextError::OsError(
+                "Can't take a buffer that isn't owned by the OsMesa context"
+                    .to_string(),
+            ));
+        }
+
+        let was_current = self.is_current();
+        if was_current {
+            unsafe { self.finish() };
+        }
+
+        let new_buffer = alloc_buffer(
+            self.format,
+            self.channel_type,
+            self.row_length(),
+            self.height,
+        );
+        let old_buffer = std::mem::replace(&mut self.buffer, new_buffer);
+
+        // Mesa must stop pointing at the old buffer before we give it away.
+        if was_current {
+            if let Err(err) = unsafe { self.make_current() } {
+                self.buffer = old_buffer;
+                return Err(err);
+            }
+        }
+
+        let len = self.stride() * self.height as usize;
+        Ok(match old_buffer {
+            Buffer::OwnedBytes(mut vec) => {
+                vec.truncate(len);
+                vec
+            }
+            Buffer::Owned(vec) => unsafe {
+                std::slice::from_raw_parts(vec.as_ptr() as *const u8, len)
+                    .to_vec()
+            },
+            Buffer::Raw(..) => unreachable!(),
+        })
+    }
+
+    /// Waits for all rendering to complete, then returns the depth buffer of
+    /// the context.
+    ///
+    /// The context must be current and have been created with depth bits.
+    pub fn depth_buffer(&self) -> Result<OsMesaDepthBuffer<'_>, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The OsMesa context must be current to read its depth buffer"
+                    .to_string(),
+            ));
+        }
+
+        unsafe {
+            self.finish();
+
+            let mut width = 0;
+            let mut height = 0;
+            let mut bytes_per_value = 0;
+            let mut buffer = std::ptr::null_mut();
+            let ret = osmesa_sys::OSMesaGetDepthBuffer(
+                self.context,
+                &mut width,
+                &mut height,
+                &mut bytes_per_value,
+                &mut buffer,
+            );
+            if ret == 0 || buffer.is_null() {
+                return Err(ContextError::OsError(
+                    "The OsMesa context has no depth buffer".to_string(),
+                ));
+            }
+
+            let bytes_per_value = bytes_per_value as usize;
+            Ok(OsMesaDepthBuffer {
+                width: width as u32,
+                height: height as u32,
+                bytes_per_value,
+                data: std::slice::from_raw_parts(
+                    buffer as *const u8,
+                    width as usize * height as usize * bytes_per_value,
+                ),
+            })
+        }
+    }
+
+    /// Calls `glFinish` on the current context.
+    unsafe fn finish(&self) {
+        let gl_finish_fn = self.get_proc_address("glFinish");
+        assert!(gl_finish_fn != std::ptr::null());
+        let gl_finish_fn =
+            std::mem::transmute::<_, extern "system" fn()>(gl_finish_fn);
+        gl_finish_fn();
+    }
+}
+
+/// Rejects the attributes OsMesa can't honor, which are the same for new and
+/// wrapped contexts.
+fn check_attributes<S>(
+    pf_reqs: &PixelFormatRequirements,
+    opengl: &GlAttributes<S>,
+    osmesa: &OsMesaAttributes,
+) -> Result<(), CreationError> {
+    if opengl.sharing.is_some() {
+        return Err(CreationError::NotSupported(
+            "Context sharing not possible with OsMesa".to_string(),
+        ));
+    }
+
+    if opengl.priority.is_some() {
+        return Err(CreationError::NotSupported(
+            "Context priorities are not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if opengl.protected_content {
+        return Err(CreationError::NotSupported(
+            "Protected content is not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if opengl.direct_rendering == DirectRendering::RequireIndirect {
+        return Err(CreationError::NotSupported(
+            "Indirect rendering is not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if opengl.reset_isolation {
+        return Err(CreationError::NotSupported(
+            "Reset isolation is not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if pf_reqs.color_space.is_some() {
+        return Err(CreationError::NotSupported(
+            "Color spaces are not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if pf_reqs.textureable {
+        return Err(CreationError::NotSupported(
+            "Textureable pbuffers are not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if pf_reqs.preserved_swap {
+        return Err(CreationError::NotSupported(
+            "Preserved swaps are not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if pf_reqs.stereoscopy == Some(true) {
+        return Err(CreationError::NotSupported(
+            "Stereoscopy is not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if pf_reqs.release_behavior != crate::ReleaseBehavior::Flush {
+        return Err(CreationError::ReleaseBehaviorNotSupported);
+    }
+
+    match opengl.robustness {
+        Robustness::RobustNoResetNotification
+        | Robustness::RobustLoseContextOnReset => {
+            return Err(CreationError::RobustnessNotSupported.into());
+        }
+        _ => (),
+    }
+
+    if opengl.debug && osmesa.reject_unsupported_flags {
+        return Err(CreationError::NotSupported(
+            "Debug contexts are not supported by OsMesa".to_string(),
+        ));
+    }
+
+    if opengl.forward_compatible && osmesa.reject_unsupported_flags {
+        return Err(CreationError::NotSupported(
+            "Forward compatible contexts are not supported by OsMesa"
+                .to_string(),
+        ));
+    }
+
+    if osmesa.format == OsMesaBufferFormat::Rgb565
+        && osmesa.channel_type != OsMesaChannelType::UnsignedByte
+    {
+        return Err(CreationError::NotSupported(format!(
+            "The RGB 565 OsMesa format can't be used with {:?} channels",
+            osmesa.channel_type
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the size as a tuple, or an error if either dimension is zero.
+fn check_size(
+    size: dpi::PhysicalSize<u32>,
+) -> Result<(u32, u32), ContextError> {
+    let (width, height): (u32, u32) = size.into();
+    if width == 0 || height == 0 {
+        return Err(ContextError::OsError(format!(
+            "Invalid OsMesa buffer size: {}x{}",
+            width, height
+        )));
+    }
+    Ok((width, height))
+}
+
+/// Allocates a zeroed buffer large enough to hold `width * height` pixels of
+/// the given format.
+fn alloc_buffer(
+    format: OsMesaBufferFormat,
+    channel_type: OsMesaChannelType,
+    width: u32,
+    height: u32,
+) -> Buffer {
+    let bytes =
+        width as usize * height as usize * format.bytes_per_pixel(channel_type);
+    match (format, channel_type) {
+        (OsMesaBufferFormat::Rgb565, _)
+        | (_, OsMesaChannelType::UnsignedShort)
+        | (_, OsMesaChannelType::Float) => {
+            Buffer::Owned(vec![0; (bytes + 3) / 4])
+        }
+        (_, OsMesaChannelType::UnsignedByte) => {
+            Buffer::OwnedBytes(vec![0; bytes])
         }
     }
 }
@@ -235,7 +1694,18 @@ impl OsMesaContext {
 impl Drop for OsMesaContext {
     #[inline]
     fn drop(&mut self) {
-        unsafe { osmesa_sys::OSMesaDestroyContext(self.context) }
+        unsafe {
+            // Mesa must not keep pointing at our buffer once it's freed. If we
+            // can't unbind it, destroying the context still releases it. A
+            // context we don't own may have been bound to another buffer by
+            // its owner, which is left alone.
+            if self.owns_context || self.is_bound_to_buffer() {
+                let _ = self.make_not_current();
+            }
+            if self.owns_context {
+                osmesa_sys::OSMesaDestroyContext(self.context);
+            }
+        }
     }
 }
 
@@ -0,0 +1,295 @@
+//! Sharing Direct3D resources with OpenGL through `WGL_NV_DX_interop2`.
+
+use crate::{Context, ContextError, PossiblyCurrent};
+
+use glutin_wgl_sys as gl;
+
+use std::cell::Cell;
+use std::fmt;
+use std::os::raw;
+
+/// How OpenGL accesses a registered Direct3D resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DxInteropAccess {
+    /// OpenGL only reads the resource.
+    ReadOnly,
+    /// OpenGL reads and writes the resource. This is the default of the
+    /// extension.
+    ReadWrite,
+    /// OpenGL overwrites the whole resource without reading it.
+    WriteDiscard,
+}
+
+impl DxInteropAccess {
+    fn to_raw(self) -> u32 {
+        match self {
+            DxInteropAccess::ReadOnly => gl::wgl_extra::ACCESS_READ_ONLY_NV,
+            DxInteropAccess::ReadWrite => gl::wgl_extra::ACCESS_READ_WRITE_NV,
+            DxInteropAccess::WriteDiscard => {
+                gl::wgl_extra::ACCESS_WRITE_DISCARD_NV
+            }
+        }
+    }
+}
+
+/// A Direct3D device opened for sharing its resources with a WGL context,
+/// through `WGL_NV_DX_interop2`.
+///
+/// The device is closed when dropped. The context it was opened with must
+/// stay current on the thread until then.
+pub struct DxInteropDevice<'a> {
+    context: &'a Context<PossiblyCurrent>,
+    functions: gl::wgl_extra::Wgl,
+    handle: gl::wgl_extra::types::HANDLE,
+}
+
+impl<'a> DxInteropDevice<'a> {
+    /// Opens the Direct3D 9Ex, 10 or 11 device `d3d_device` for the WGL
+    /// context `context`, which must be current.
+    ///
+    /// This requires `WGL_NV_DX_interop2`. If it is missing, or `context`
+    /// doesn't use WGL, [`ContextError::FunctionUnavailable`] is returned.
+    ///
+    /// # Safety
+    ///
+    /// `d3d_device` must point to a valid Direct3D device, which must outlive
+    /// the returned `DxInteropDevice`.
+    ///
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    pub unsafe fn open(
+        context: &'a Context<PossiblyCurrent>,
+        d3d_device: *mut raw::c_void,
+    ) -> Result<Self, ContextError> {
+        let wgl = context
+            .context
+            .wgl()
+            .ok_or(ContextError::FunctionUnavailable)?;
+        if !wgl.extensions().contains("WGL_NV_DX_interop2") {
+            return Err(ContextError::FunctionUnavailable);
+        }
+        if !wgl.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to open a Direct3D device"
+                    .to_string(),
+            ));
+        }
+
+        let functions = gl::wgl_extra::Wgl::load_with(|addr| {
+            wgl.get_proc_address(addr) as *const _
+        });
+        if !functions.DXOpenDeviceNV.is_loaded() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let handle = functions.DXOpenDeviceNV(d3d_device as *mut _);
+        if handle.is_null() {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(DxInteropDevice {
+            context,
+            functions,
+            handle,
+        })
+    }
+
+    /// Returns the context the device was opened with.
+    #[inline]
+    pub fn context(&self) -> &'a Context<PossiblyCurrent> {
+        self.context
+    }
+
+    /// Registers the Direct3D resource `d3d_resource` as the OpenGL object
+    /// `gl_name`, which is a texture of target `gl_type` (for example
+    /// `GL_TEXTURE_2D`) or a renderbuffer if `gl_type` is `GL_RENDERBUFFER`.
+    ///
+    /// The object has to be locked with [`lock_objects`] before OpenGL uses
+    /// it, and is unregistered when dropped.
+    ///
+    /// # Safety
+    ///
+    /// `d3d_resource` must be a valid resource of the device, which must
+    /// outlive the returned `DxInteropObject`, and `gl_name` must be a
+    /// texture or renderbuffer of the context.
+    ///
+    /// [`lock_objects`]: #method.lock_objects
+    pub unsafe fn register_object(
+        &self,
+        d3d_resource: *mut raw::c_void,
+        gl_name: u32,
+        gl_type: u32,
+        access: DxInteropAccess,
+    ) -> Result<DxInteropObject<'_>, ContextError> {
+        let handle = self.functions.DXRegisterObjectNV(
+            self.handle,
+            d3d_resource as *mut _,
+            gl_name,
+            gl_type,
+            access.to_raw(),
+        );
+        if handle.is_null() {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(DxInteropObject {
+            device: self,
+            handle,
+            locked: Cell::new(false),
+        })
+    }
+
+    /// Locks `objects` for OpenGL, which can then use them until they are
+    /// unlocked with [`unlock_objects`]. Direct3D must not use them
+    /// meanwhile.
+    ///
+    /// Locking several objects at once is cheaper than locking them one by
+    /// one. Either all of them are locked or none of them are.
+    ///
+    /// [`unlock_objects`]: #method.unlock_objects
+    pub fn lock_objects(
+        &self,
+        objects: &[&DxInteropObject<'_>],
+    ) -> Result<(), ContextError> {
+        let mut handles = self.handles(objects)?;
+        if objects.iter().any(|object| object.locked.get()) {
+            return Err(ContextError::OsError(
+                "The Direct3D resource is already locked".to_string(),
+            ));
+        }
+
+        let res = unsafe {
+            self.functions.DXLockObjectsNV(
+                self.handle,
+                handles.len() as _,
+                handles.as_mut_ptr(),
+            )
+        };
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        for object in objects {
+            object.locked.set(true);
+        }
+        Ok(())
+    }
+
+    /// Unlocks `objects`, giving them back to Direct3D. OpenGL must not use
+    /// them anymore until they are locked again.
+    pub fn unlock_objects(
+        &self,
+        objects: &[&DxInteropObject<'_>],
+    ) -> Result<(), ContextError> {
+        let mut handles = self.handles(objects)?;
+        if objects.iter().any(|object| !object.locked.get()) {
+            return Err(ContextError::OsError(
+                "The Direct3D resource isn't locked".to_string(),
+            ));
+        }
+
+        let res = unsafe {
+            self.functions.DXUnlockObjectsNV(
+                self.handle,
+                handles.len() as _,
+                handles.as_mut_ptr(),
+            )
+        };
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        for object in objects {
+            object.locked.set(false);
+        }
+        Ok(())
+    }
+
+    fn handles(
+        &self,
+        objects: &[&DxInteropObject<'_>],
+    ) -> Result<Vec<gl::wgl_extra::types::HANDLE>, ContextError> {
+        objects
+            .iter()
+            .map(|object| {
+                if object.device.handle != self.handle {
+                    return Err(ContextError::OsError(
+                        "The Direct3D resource was registered with another \
+                         device"
+                            .to_string(),
+                    ));
+                }
+                Ok(object.handle)
+            })
+            .collect()
+    }
+}
+
+impl<'a> fmt::Debug for DxInteropDevice<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DxInteropDevice")
+            .field("handle", &self.handle)
+            .finish()
+    }
+}
+
+impl<'a> Drop for DxInteropDevice<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            self.functions.DXCloseDeviceNV(self.handle);
+        }
+    }
+}
+
+/// A Direct3D resource registered as an OpenGL texture or renderbuffer with
+/// [`DxInteropDevice::register_object`].
+///
+/// The resource is unlocked if needed, then unregistered when dropped.
+///
+/// [`DxInteropDevice::register_object`]:
+/// struct.DxInteropDevice.html#method.register_object
+#[derive(Debug)]
+pub struct DxInteropObject<'a> {
+    device: &'a DxInteropDevice<'a>,
+    handle: gl::wgl_extra::types::HANDLE,
+    locked: Cell<bool>,
+}
+
+impl<'a> DxInteropObject<'a> {
+    /// Returns whether the object is locked for OpenGL.
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.locked.get()
+    }
+
+    /// Changes how OpenGL accesses the object. The object must not be
+    /// locked.
+    pub fn set_access(
+        &self,
+        access: DxInteropAccess,
+    ) -> Result<(), ContextError> {
+        if self.locked.get() {
+            return Err(ContextError::OsError(
+                "The Direct3D resource is locked".to_string(),
+            ));
+        }
+        let res = unsafe {
+            self.device
+                .functions
+                .DXObjectAccessNV(self.handle, access.to_raw())
+        };
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Drop for DxInteropObject<'a> {
+    fn drop(&mut self) {
+        if self.locked.get() {
+            let _ = self.device.unlock_objects(&[&*self]);
+        }
+        unsafe {
+            self.device
+                .functions
+                .DXUnregisterObjectNV(self.device.handle, self.handle);
+        }
+    }
+}
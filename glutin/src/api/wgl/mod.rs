@@ -1,10 +1,15 @@
 #![cfg(any(target_os = "windows"))]
 
+mod dx_interop;
 mod make_current_guard;
 
+pub use self::dx_interop::{DxInteropAccess, DxInteropDevice, DxInteropObject};
+
 use crate::{
-    Api, ContextError, CreationError, GlAttributes, GlProfile, GlRequest,
-    PixelFormat, PixelFormatRequirements, ReleaseBehavior, Robustness,
+    Api, ContextError, CreationError, DirectRendering, ErrorCode, Extensions,
+    GlAttributes, GlProfile, GlRequest, GpuId, GpuInfo, PixelFormat,
+    PixelFormatRequirements, ReleaseBehavior, Robustness, StateMask,
+    SwapBehavior, SwapGroupLimits, SwapInterval,
 };
 
 use self::make_current_guard::CurrentContextGuard;
@@ -18,9 +23,12 @@ use winapi::um::libloaderapi::*;
 use winapi::um::wingdi::*;
 use winapi::um::winuser::*;
 
+use parking_lot::Mutex;
+
 use std::ffi::{CStr, CString, OsStr};
 use std::os::raw;
 use std::os::windows::ffi::OsStrExt;
+use std::sync::atomic::{AtomicI32, AtomicU32, Ordering};
 
 /// A WGL context.
 ///
@@ -31,6 +39,17 @@ pub struct Context {
 
     hdc: HDC,
 
+    /// Releases `hdc` after the context is destroyed, if it was handed over
+    /// by the caller.
+    owned_dc: Option<DcWrapper>,
+
+    /// The pbuffer `hdc` belongs to, destroyed after the context.
+    pbuffer: Option<PbufferWrapper>,
+
+    /// The affinity device context the pbuffer was created with, destroyed
+    /// after the pbuffer.
+    _affinity_dc: Option<AffinityDcWrapper>,
+
     /// Bound to `opengl32.dll`.
     ///
     /// `wglGetProcAddress` returns null for GL 1.1 functions because they are
@@ -39,8 +58,39 @@ pub struct Context {
 
     /// The pixel format that has been used to create this context.
     pixel_format: PixelFormat,
+
+    /// Whether DWM can composite the window with its pixel format.
+    composition: bool,
+
+    /// The WGL extensions supported by the driver.
+    extensions: Extensions,
+
+    /// The release behavior the context was created with.
+    release_behavior: ReleaseBehavior,
+
+    /// Whether the context was created with `WGL_CONTEXT_OPENGL_NO_ERROR_ARB`.
+    no_error: bool,
+
+    /// Whether the context was created with `WGL_LOSE_CONTEXT_ON_RESET_ARB`.
+    lose_context_on_reset: bool,
+
+    /// The swap group `hdc` joined, or 0.
+    swap_group: AtomicU32,
+
+    /// The WGL functions that are not guaranteed to be supported.
+    extra_functions: ExtraFunctions,
+
+    /// The swap interval of `hdc`, or `NO_SWAP_INTERVAL` if it wasn't set.
+    swap_interval: AtomicI32,
+
+    /// The child window whose device context is acquired again around every
+    /// swap, because its class lacks `CS_OWNDC`.
+    child_window: Option<HWND>,
 }
 
+// The swap interval of a window that was never set.
+const NO_SWAP_INTERVAL: i32 = i32::min_value();
+
 /// A simple wrapper that destroys the window when it is destroyed.
 #[derive(Debug)]
 struct WindowWrapper(HWND, HDC);
@@ -54,15 +104,103 @@ impl Drop for WindowWrapper {
     }
 }
 
+/// A simple wrapper that releases a device context when it is destroyed.
+#[derive(Debug)]
+struct DcWrapper(HWND, HDC);
+
+impl Drop for DcWrapper {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ReleaseDC(self.0, self.1);
+        }
+    }
+}
+
 /// Wraps around a context so that it is destroyed when necessary.
+///
+/// Contexts associated with an AMD GPU hold the functions of
+/// `WGL_AMD_gpu_association`, which manage them instead of the WGL ones.
 #[derive(Debug)]
-struct ContextWrapper(HGLRC);
+struct ContextWrapper(HGLRC, Option<ExtraFunctions>);
 
 impl Drop for ContextWrapper {
     #[inline]
     fn drop(&mut self) {
         unsafe {
-            gl::wgl::DeleteContext(self.0 as *const _);
+            match self.1 {
+                Some(ExtraFunctions(ref extra_functions)) => {
+                    extra_functions
+                        .DeleteAssociatedContextAMD(self.0 as *const _);
+                }
+                None => {
+                    gl::wgl::DeleteContext(self.0 as *const _);
+                }
+            }
+        }
+    }
+}
+
+/// A device context of `WGL_NV_gpu_affinity`, which only renders on the GPU
+/// it was created for.
+#[derive(Debug)]
+struct AffinityDcWrapper {
+    extra_functions: ExtraFunctions,
+    hdc: HDC,
+}
+
+impl Drop for AffinityDcWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            self.extra_functions.0.DeleteDCNV(self.hdc as *const _);
+        }
+    }
+}
+
+/// A pbuffer created with `WGL_ARB_pbuffer`, along with its device context.
+struct PbufferWrapper {
+    extra_functions: gl::wgl_extra::Wgl,
+    pbuffer: gl::wgl_extra::types::HPBUFFERARB,
+    hdc: HDC,
+    /// Whether the pbuffer can be bound with `WGL_ARB_render_texture`.
+    textureable: bool,
+}
+
+impl PbufferWrapper {
+    /// Returns the value of a `WGL_PBUFFER_*_ARB` attribute.
+    fn query(&self, attrib: u32) -> Result<raw::c_int, ContextError> {
+        let mut value = 0;
+        let res = unsafe {
+            self.extra_functions.QueryPbufferARB(
+                self.pbuffer,
+                attrib as raw::c_int,
+                &mut value,
+            )
+        };
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(value)
+    }
+}
+
+impl std::fmt::Debug for PbufferWrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PbufferWrapper")
+            .field("pbuffer", &self.pbuffer)
+            .field("hdc", &self.hdc)
+            .field("textureable", &self.textureable)
+            .finish()
+    }
+}
+
+impl Drop for PbufferWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            // the device context must be released first
+            self.extra_functions
+                .ReleasePbufferDCARB(self.pbuffer, self.hdc as *const _);
+            self.extra_functions.DestroyPbufferARB(self.pbuffer);
         }
     }
 }
@@ -77,10 +215,112 @@ impl Context {
     #[inline]
     pub unsafe fn new(
         pf_reqs: &PixelFormatRequirements,
-        opengl: &GlAttributes<HGLRC>,
+        opengl: &GlAttributes<&Context>,
         win: HWND,
     ) -> Result<Context, CreationError> {
-        let hdc = GetDC(win);
+        Self::new_impl(pf_reqs, opengl, win, None, None)
+    }
+
+    /// Attempt to build a new WGL context on a device context of a window,
+    /// setting its pixel format if it has none.
+    ///
+    /// If `owned` is true, the device context is released when the context
+    /// is dropped. It is left alone if building the context fails.
+    ///
+    /// # Unsafety
+    ///
+    /// The device context and its window must continue to exist as long as
+    /// the resulting `Context` exists.
+    #[inline]
+    pub unsafe fn new_with_hdc(
+        pf_reqs: &PixelFormatRequirements,
+        opengl: &GlAttributes<&Context>,
+        hdc: HDC,
+        owned: bool,
+    ) -> Result<Context, CreationError> {
+        // the window is needed to load the WGL functions
+        let win = WindowFromDC(hdc);
+        if win.is_null() {
+            return Err(CreationError::NotSupported(
+                "The device context doesn't belong to a window".to_string(),
+            ));
+        }
+        let mut context =
+            Self::new_impl(pf_reqs, opengl, win, Some(hdc), None)?;
+        if owned {
+            context.owned_dc = Some(DcWrapper(win, hdc));
+        }
+        Ok(context)
+    }
+
+    /// Attempt to build a new WGL context rendering to a pbuffer of `size`,
+    /// through `WGL_ARB_pbuffer`. The window is only used to find out the
+    /// pixel formats of the device.
+    ///
+    /// # Unsafety
+    ///
+    /// The `window` must continue to exist as long as the resulting `Context`
+    /// exists.
+    #[inline]
+    pub unsafe fn new_pbuffer(
+        pf_reqs: &PixelFormatRequirements,
+        opengl: &GlAttributes<&Context>,
+        win: HWND,
+        size: (u32, u32),
+    ) -> Result<Context, CreationError> {
+        Self::new_impl(pf_reqs, opengl, win, None, Some(size))
+    }
+
+    unsafe fn new_impl(
+        pf_reqs: &PixelFormatRequirements,
+        opengl: &GlAttributes<&Context>,
+        win: HWND,
+        hdc: Option<HDC>,
+        pbuffer_size: Option<(u32, u32)>,
+    ) -> Result<Context, CreationError> {
+        if opengl.priority.is_some() {
+            return Err(CreationError::NotSupported(
+                "Context priorities are not supported by WGL".to_string(),
+            ));
+        }
+
+        if opengl.protected_content {
+            return Err(CreationError::NotSupported(
+                "Protected content is not supported by WGL".to_string(),
+            ));
+        }
+
+        if opengl.direct_rendering == DirectRendering::RequireIndirect {
+            return Err(CreationError::NotSupported(
+                "Indirect rendering is not supported by WGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.color_space.is_some() {
+            return Err(CreationError::NotSupported(
+                "Color spaces are not supported by WGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.textureable && pbuffer_size.is_none() {
+            return Err(CreationError::NotSupported(
+                "Only headless WGL contexts can be textureable".to_string(),
+            ));
+        }
+
+        let child_window = if pf_reqs.wgl_child_window && pbuffer_size.is_none()
+        {
+            check_child_window_styles(win)?;
+            if GetClassLongW(win, GCL_STYLE) & CS_OWNDC == 0 {
+                Some(win)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let hdc = hdc.unwrap_or_else(|| GetDC(win));
         if hdc.is_null() {
             let err = Err(CreationError::OsError(format!(
                 "GetDC function failed: {}",
@@ -90,40 +330,134 @@ impl Context {
         }
 
         // loading the functions that are not guaranteed to be supported
-        let extra_functions = load_extra_functions(win)?;
+        let extra_functions = extra_functions(win)?;
 
         // getting the list of the supported extensions
-        let extensions = if extra_functions.GetExtensionsStringARB.is_loaded() {
-            let data = extra_functions.GetExtensionsStringARB(hdc as *const _);
-            let data = CStr::from_ptr(data).to_bytes().to_vec();
-            String::from_utf8(data).unwrap()
-        } else if extra_functions.GetExtensionsStringEXT.is_loaded() {
-            let data = extra_functions.GetExtensionsStringEXT();
-            let data = CStr::from_ptr(data).to_bytes().to_vec();
-            String::from_utf8(data).unwrap()
-        } else {
-            format!("")
+        let extensions = query_extensions(&extra_functions, hdc);
+
+        let use_arb_for_pixel_format =
+            extensions.contains("WGL_ARB_pixel_format");
+
+        // contexts associated with an AMD GPU have no default framebuffer,
+        // so they don't need a pbuffer
+        let associated = match pf_reqs.gpu {
+            None => false,
+            Some(_) if pbuffer_size.is_none() => {
+                return Err(CreationError::NotSupported(
+                    "Only headless contexts can be bound to a GPU by WGL"
+                        .to_string(),
+                ));
+            }
+            Some(GpuId::Amd(_)) => {
+                if !(extensions.contains("WGL_AMD_gpu_association")
+                    && extensions.contains("WGL_ARB_create_context"))
+                {
+                    return Err(CreationError::NotSupported(
+                        "AMD GPUs require WGL_AMD_gpu_association".to_string(),
+                    ));
+                }
+                true
+            }
+            Some(GpuId::Nvidia(_)) => {
+                if !extensions.contains("WGL_NV_gpu_affinity") {
+                    return Err(CreationError::NotSupported(
+                        "NVIDIA GPUs require WGL_NV_gpu_affinity".to_string(),
+                    ));
+                }
+                false
+            }
+        };
+
+        // the pbuffer is created on the GPU of the affinity device context
+        let affinity_dc = match pf_reqs.gpu {
+            Some(GpuId::Nvidia(index)) => {
+                Some(create_affinity_dc(&extra_functions, index)?)
+            }
+            _ => None,
         };
+        let hdc = affinity_dc.as_ref().map_or(hdc, |dc| dc.hdc);
+
+        if pbuffer_size.is_some()
+            && !associated
+            && !(use_arb_for_pixel_format
+                && extensions.contains("WGL_ARB_pbuffer"))
+        {
+            return Err(CreationError::NotSupported(
+                "Pbuffers require WGL_ARB_pbuffer".to_string(),
+            ));
+        }
 
-        let use_arb_for_pixel_format = extensions
-            .split(' ')
-            .find(|&i| i == "WGL_ARB_pixel_format")
-            .is_some();
+        // contexts associated with an AMD GPU have no pbuffer to bind
+        if pf_reqs.textureable
+            && (associated || !extensions.contains("WGL_ARB_render_texture"))
+        {
+            return Err(CreationError::NotSupported(
+                "Textureable pbuffers require WGL_ARB_render_texture"
+                    .to_string(),
+            ));
+        }
 
-        // calling SetPixelFormat, if not already done
-        let mut pixel_format_id = GetPixelFormat(hdc);
-        if pixel_format_id == 0 {
+        // the pixel format of a pbuffer is given when creating it, the
+        // window keeps its own
+        let existing_pixel_format_id = match pbuffer_size {
+            Some(_) => 0,
+            None => GetPixelFormat(hdc),
+        };
+        let mut pixel_format_id = existing_pixel_format_id;
+        let mut pbuffer = None;
+        if let Some(size) = pbuffer_size.filter(|_| !associated) {
+            let id = choose_arb_pixel_format_id(
+                &extra_functions,
+                &extensions,
+                hdc,
+                pf_reqs,
+                true,
+            )
+            .map_err(|_| {
+                no_format_error(
+                    Some(&extra_functions),
+                    &extensions,
+                    hdc,
+                    pf_reqs,
+                    true,
+                )
+            })?;
+            let texture_format = if pf_reqs.textureable {
+                Some(texture_format(pf_reqs).1)
+            } else {
+                None
+            };
+            pbuffer = Some(create_pbuffer(
+                &extra_functions,
+                hdc,
+                id,
+                size,
+                pf_reqs.largest_pbuffer,
+                texture_format,
+            )?);
+            pixel_format_id = id;
+        } else if pixel_format_id == 0 {
             let id = if use_arb_for_pixel_format {
                 choose_arb_pixel_format_id(
                     &extra_functions,
                     &extensions,
                     hdc,
                     pf_reqs,
+                    false,
                 )
-                .map_err(|_| CreationError::NoAvailablePixelFormat)?
+                .map_err(|_| {
+                    no_format_error(
+                        Some(&extra_functions),
+                        &extensions,
+                        hdc,
+                        pf_reqs,
+                        false,
+                    )
+                })?
             } else {
-                choose_native_pixel_format_id(hdc, pf_reqs)
-                    .map_err(|_| CreationError::NoAvailablePixelFormat)?
+                choose_native_pixel_format_id(hdc, pf_reqs).map_err(|_| {
+                    no_format_error(None, &extensions, hdc, pf_reqs, false)
+                })?
             };
 
             set_pixel_format(hdc, id)?;
@@ -137,12 +471,74 @@ impl Context {
                 hdc,
                 pixel_format_id,
             )
-            .map_err(|_| CreationError::NoAvailablePixelFormat)?
+            .map_err(|_| pf_reqs.no_config_error())?
         } else {
             choose_native_pixel_format(hdc, pf_reqs, pixel_format_id)
-                .map_err(|_| CreationError::NoAvailablePixelFormat)?
+                .map_err(|_| pf_reqs.no_config_error())?
         };
 
+        // `SetPixelFormat` can only be called once per window, so a window
+        // reused with other requirements keeps its pixel format
+        if existing_pixel_format_id != 0 {
+            if let Some(unmet) = pf_reqs.unmet_by(&pixel_format) {
+                return Err(CreationError::PixelFormatAlreadySet(format!(
+                    "The window already has pixel format {} ({:?}), which \
+                     doesn't meet the requested {}",
+                    existing_pixel_format_id, pixel_format, unmet
+                )));
+            }
+        }
+
+        // pbuffers aren't composited
+        let composition = pbuffer.is_none()
+            && describe_pixel_format(hdc, pixel_format_id)
+                .map_or(false, |output| {
+                    output.dwFlags & PFD_SUPPORT_COMPOSITION != 0
+                });
+
+        // `create_context` only honors `Robustness::NoError` with these
+        // extensions
+        let no_error = opengl.robustness == Robustness::NoError
+            && extensions.contains("WGL_ARB_create_context")
+            && extensions.contains("WGL_ARB_create_context_no_error");
+        if let Some(ctx) = opengl.sharing {
+            if ctx.no_error != no_error {
+                return Err(CreationError::NotSupported(
+                    "Shared contexts must agree on the no-error flag"
+                        .to_string(),
+                ));
+            }
+        }
+
+        // all the contexts of a share group must have the same reset
+        // notification strategy
+        let lose_context_on_reset = opengl.robustness.loses_context_on_reset()
+            && extensions.contains("WGL_ARB_create_context")
+            && extensions.contains("WGL_ARB_create_context_robustness");
+        if let Some(ctx) = opengl.sharing {
+            if ctx.lose_context_on_reset != lose_context_on_reset {
+                return Err(CreationError::NotSupported(
+                    "Shared contexts must have the same reset notification \
+                     strategy"
+                        .to_string(),
+                ));
+            }
+        }
+        if opengl.reset_isolation
+            && !(lose_context_on_reset
+                && (extensions
+                    .contains("WGL_ARB_robustness_application_isolation")
+                    || extensions
+                        .contains("WGL_ARB_robustness_share_group_isolation")))
+        {
+            return Err(CreationError::NotSupported(
+                "Reset isolation is not supported by WGL".to_string(),
+            ));
+        }
+
+        // the context renders to the device context of the pbuffer, if any
+        let hdc = pbuffer.as_ref().map_or(hdc, |pbuffer| pbuffer.hdc);
+
         // creating the OpenGL context
         let context = create_context(
             Some((&extra_functions, pf_reqs, opengl, &extensions)),
@@ -153,17 +549,14 @@ impl Context {
         // loading the opengl32 module
         let gl_library = load_opengl32_dll()?;
 
-        // handling vsync
-        if extensions
-            .split(' ')
-            .find(|&i| i == "WGL_EXT_swap_control")
-            .is_some()
+        // handling vsync, pbuffers aren't swapped
+        let mut swap_interval = NO_SWAP_INTERVAL;
+        if pbuffer_size.is_none() && extensions.contains("WGL_EXT_swap_control")
         {
             let _guard = CurrentContextGuard::make_current(hdc, context.0)?;
 
-            if extra_functions.SwapIntervalEXT(if opengl.vsync { 1 } else { 0 })
-                == 0
-            {
+            swap_interval = if opengl.vsync { 1 } else { 0 };
+            if extra_functions.SwapIntervalEXT(swap_interval) == 0 {
                 return Err(CreationError::OsError(
                     "wglSwapIntervalEXT failed".to_string(),
                 ));
@@ -173,8 +566,20 @@ impl Context {
         Ok(Context {
             context,
             hdc,
+            owned_dc: None,
+            pbuffer,
+            _affinity_dc: affinity_dc,
             gl_library,
             pixel_format,
+            composition,
+            extensions,
+            release_behavior: pf_reqs.release_behavior,
+            no_error,
+            lose_context_on_reset,
+            swap_group: AtomicU32::new(0),
+            extra_functions: ExtraFunctions(extra_functions),
+            swap_interval: AtomicI32::new(swap_interval),
+            child_window,
         })
     }
 
@@ -186,22 +591,108 @@ impl Context {
 
     #[inline]
     pub unsafe fn make_current(&self) -> Result<(), ContextError> {
-        if gl::wgl::MakeCurrent(
-            self.hdc as *const _,
+        self.make_current_draw_read(self, self)
+    }
+
+    /// Makes the context current, drawing to the device context of `draw`
+    /// and reading from the device context of `read`. Either can be the
+    /// context itself.
+    ///
+    /// Different draw and read device contexts require
+    /// `WGL_ARB_make_current_read`.
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        // associated contexts aren't bound to a device context
+        if let Some(ExtraFunctions(ref extra_functions)) = self.context.1 {
+            if draw.context.0 != self.context.0
+                || read.context.0 != self.context.0
+            {
+                return Err(ContextError::FunctionUnavailable);
+            }
+            if extra_functions
+                .MakeAssociatedContextCurrentAMD(self.context.0 as *const _)
+                == 0
+            {
+                return Err(ContextError::IoError(
+                    std::io::Error::last_os_error(),
+                ));
+            }
+            return Ok(());
+        }
+
+        if self.is_current_draw_read(draw, read) {
+            return Ok(());
+        }
+
+        if draw.hdc == read.hdc {
+            if gl::wgl::MakeCurrent(
+                draw.hdc as *const _,
+                self.context.0 as *const _,
+            ) == 0
+            {
+                return Err(ContextError::IoError(
+                    std::io::Error::last_os_error(),
+                ));
+            }
+            return Ok(());
+        }
+
+        let ExtraFunctions(ref extra_functions) = self.extra_functions;
+        if !self.extensions.contains("WGL_ARB_make_current_read")
+            || !extra_functions.MakeContextCurrentARB.is_loaded()
+        {
+            return Err(ContextError::OsError(
+                "Different draw and read device contexts require \
+                 WGL_ARB_make_current_read"
+                    .to_string(),
+            ));
+        }
+        if extra_functions.MakeContextCurrentARB(
+            draw.hdc as *const _,
+            read.hdc as *const _,
             self.context.0 as *const _,
-        ) != 0
+        ) == 0
         {
-            Ok(())
-        } else {
-            Err(ContextError::IoError(std::io::Error::last_os_error()))
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the context is current, drawing to the device context
+    /// of `draw` and reading from the one of `read`. Without
+    /// `WGL_ARB_make_current_read`, the context reads from the device context
+    /// it draws to.
+    pub fn is_current_draw_read(&self, draw: &Context, read: &Context) -> bool {
+        if !self.is_current() {
+            return false;
+        }
+        unsafe {
+            let current_draw = gl::wgl::GetCurrentDC() as HDC;
+            let ExtraFunctions(ref extra_functions) = self.extra_functions;
+            let current_read =
+                if extra_functions.GetCurrentReadDCARB.is_loaded() {
+                    extra_functions.GetCurrentReadDCARB() as HDC
+                } else {
+                    current_draw
+                };
+            current_draw == draw.hdc && current_read == read.hdc
         }
     }
 
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
-        if self.is_current()
-            && gl::wgl::MakeCurrent(self.hdc as *const _, std::ptr::null()) != 0
-        {
+        let release = || match self.context.1 {
+            Some(ExtraFunctions(ref extra_functions)) => extra_functions
+                .MakeAssociatedContextCurrentAMD(std::ptr::null()),
+            None => {
+                gl::wgl::MakeCurrent(self.hdc as *const _, std::ptr::null())
+            }
+        };
+        if self.is_current() && release() != 0 {
             Ok(())
         } else {
             Err(ContextError::IoError(std::io::Error::last_os_error()))
@@ -211,7 +702,13 @@ impl Context {
     #[inline]
     pub fn is_current(&self) -> bool {
         unsafe {
-            gl::wgl::GetCurrentContext() == self.context.0 as *const raw::c_void
+            let current = match self.context.1 {
+                Some(ExtraFunctions(ref extra_functions)) => {
+                    extra_functions.GetCurrentAssociatedContextAMD()
+                }
+                None => gl::wgl::GetCurrentContext(),
+            };
+            current == self.context.0 as *const raw::c_void
         }
     }
 
@@ -230,16 +727,127 @@ impl Context {
 
     #[inline]
     pub fn swap_buffers(&self) -> Result<(), ContextError> {
+        self.apply_swap_interval();
+
         // TODO: decide how to handle the error
         // if unsafe { SwapBuffers(self.hdc) } != 0 {
         // Ok(())
         // } else {
         // Err(ContextError::IoError(std::io::Error::last_os_error()))
         // }
-        unsafe { SwapBuffers(self.hdc) };
+        match self.child_window {
+            // without `CS_OWNDC`, the device context acquired when creating
+            // the context may no longer match the window
+            Some(hwnd) => unsafe {
+                let hdc = GetDC(hwnd);
+                if hdc.is_null() {
+                    return Err(ContextError::IoError(
+                        std::io::Error::last_os_error(),
+                    ));
+                }
+                SwapBuffers(hdc);
+                ReleaseDC(hwnd, hdc);
+            },
+            None => unsafe {
+                SwapBuffers(self.hdc);
+            },
+        }
         Ok(())
     }
 
+    pub fn set_swap_interval(
+        &self,
+        interval: SwapInterval,
+    ) -> Result<SwapInterval, ContextError> {
+        if !self.supports_swap_interval(interval) {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        // the context is current, so its extra functions can be loaded
+        // directly
+        let extra_functions = gl::wgl_extra::Wgl::load_with(|addr| {
+            let addr = CString::new(addr.as_bytes()).unwrap();
+            let addr = addr.as_ptr();
+            unsafe { gl::wgl::GetProcAddress(addr) as *const raw::c_void }
+        });
+        if !extra_functions.SwapIntervalEXT.is_loaded() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        // A negative interval requests adaptive vsync.
+        let interval = match interval {
+            SwapInterval::DontWait => 0,
+            SwapInterval::Wait(n) => n.get() as raw::c_int,
+            SwapInterval::AdaptiveWait(n) => -(n.get() as raw::c_int),
+        };
+        if unsafe { extra_functions.SwapIntervalEXT(interval) } == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        self.swap_interval.store(interval, Ordering::SeqCst);
+
+        Ok(SwapInterval::from_raw(interval))
+    }
+
+    /// The swap interval belongs to the current context rather than to the
+    /// window, so a context drawing to several windows has to apply the
+    /// interval of the window being swapped.
+    fn apply_swap_interval(&self) {
+        let interval = self.swap_interval.load(Ordering::SeqCst);
+        if interval == NO_SWAP_INTERVAL {
+            return;
+        }
+
+        let extra_functions = &self.extra_functions.0;
+        unsafe {
+            if gl::wgl::GetCurrentDC() != self.hdc as *const _
+                || !extra_functions.GetSwapIntervalEXT.is_loaded()
+            {
+                return;
+            }
+            if extra_functions.GetSwapIntervalEXT() != interval {
+                extra_functions.SwapIntervalEXT(interval);
+            }
+        }
+    }
+
+    pub fn get_swap_interval(&self) -> Option<SwapInterval> {
+        if !self.supports_swap_interval(SwapInterval::DontWait) {
+            return None;
+        }
+
+        // the interval of the window, which the current context may not
+        // have applied yet
+        let interval = self.swap_interval.load(Ordering::SeqCst);
+        if interval != NO_SWAP_INTERVAL {
+            return Some(SwapInterval::from_raw(interval));
+        }
+
+        let extra_functions = gl::wgl_extra::Wgl::load_with(|addr| {
+            let addr = CString::new(addr.as_bytes()).unwrap();
+            let addr = addr.as_ptr();
+            unsafe { gl::wgl::GetProcAddress(addr) as *const raw::c_void }
+        });
+        if !extra_functions.GetSwapIntervalEXT.is_loaded() {
+            return None;
+        }
+
+        let interval = unsafe { extra_functions.GetSwapIntervalEXT() };
+        Some(SwapInterval::from_raw(interval as i32))
+    }
+
+    pub fn supports_swap_interval(&self, interval: SwapInterval) -> bool {
+        let has_ext = |ext| self.extensions.contains(ext);
+        match interval {
+            SwapInterval::DontWait | SwapInterval::Wait(_) => {
+                has_ext("WGL_EXT_swap_control")
+            }
+            SwapInterval::AdaptiveWait(_) => {
+                has_ext("WGL_EXT_swap_control")
+                    && has_ext("WGL_EXT_swap_control_tear")
+            }
+        }
+    }
+
     #[inline]
     pub fn get_api(&self) -> Api {
         // FIXME: can be opengl es
@@ -250,43 +858,245 @@ impl Context {
     pub fn get_pixel_format(&self) -> PixelFormat {
         self.pixel_format.clone()
     }
-}
 
-unsafe impl Send for Context {}
-unsafe impl Sync for Context {}
+    /// Returns whether the pixel format has `PFD_SUPPORT_COMPOSITION`.
+    #[inline]
+    pub fn supports_composition(&self) -> bool {
+        self.composition
+    }
 
-/// Creates an OpenGL context.
-///
-/// If `extra` is `Some`, this function will attempt to use the latest WGL
-/// functions to create the context.
-///
-/// Otherwise, only the basic API will be used and the chances of
-/// `CreationError::NotSupported` being returned increase.
-unsafe fn create_context(
-    extra: Option<(
-        &gl::wgl_extra::Wgl,
-        &PixelFormatRequirements,
-        &GlAttributes<HGLRC>,
-        &str,
-    )>,
-    _: HWND,
-    hdc: HDC,
-) -> Result<ContextWrapper, CreationError> {
-    let share;
+    #[inline]
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
 
-    if let Some((extra_functions, _pf_reqs, opengl, extensions)) = extra {
-        share = opengl.sharing.unwrap_or(std::ptr::null_mut());
+    #[inline]
+    pub fn release_behavior(&self) -> ReleaseBehavior {
+        self.release_behavior
+    }
 
-        if extensions
-            .split(' ')
-            .find(|&i| i == "WGL_ARB_create_context")
-            .is_some()
-        {
-            let mut attributes = Vec::new();
+    /// Copies the state selected by `mask` from `src` with `wglCopyContext`.
+    /// The context must not be current.
+    pub fn copy_state_from(
+        &self,
+        src: &Context,
+        mask: StateMask,
+    ) -> Result<(), ContextError> {
+        if self.is_current() {
+            return Err(ContextError::OsError(
+                "The destination of `wglCopyContext` can't be current"
+                    .to_string(),
+            ));
+        }
 
-            match opengl.version {
-                GlRequest::Latest => {}
-                GlRequest::Specific(Api::OpenGl, (major, minor)) => {
+        if unsafe {
+            gl::wgl::CopyContext(
+                src.context.0 as *const _,
+                self.context.0 as *const _,
+                mask.0 as _,
+            )
+        } == 0
+        {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    /// Loads the `WGL_NV_swap_group` functions, which requires the context to
+    /// be current, and calls `f` with them.
+    fn with_swap_group_functions<T, F>(&self, f: F) -> Result<T, ContextError>
+    where
+        F: FnOnce(&gl::wgl_extra::Wgl) -> T,
+    {
+        if !self.extensions.contains("WGL_NV_swap_group") {
+            return Err(ContextError::FunctionUnavailable);
+        }
+
+        let _guard = unsafe {
+            CurrentContextGuard::make_current(self.hdc, self.context.0)
+        }
+        .map_err(|err| ContextError::OsError(format!("{}", err)))?;
+        let extra_functions = gl::wgl_extra::Wgl::load_with(|addr| {
+            let addr = CString::new(addr.as_bytes()).unwrap();
+            let addr = addr.as_ptr();
+            unsafe { gl::wgl::GetProcAddress(addr) as *const raw::c_void }
+        });
+        if !extra_functions.QueryMaxSwapGroupsNV.is_loaded() {
+            return Err(ContextError::FunctionUnavailable);
+        }
+        Ok(f(&extra_functions))
+    }
+
+    /// Returns the size of the pbuffer, which may be smaller than requested
+    /// with `WGL_PBUFFER_LARGEST_ARB`.
+    pub fn pbuffer_size(&self) -> Result<(u32, u32), ContextError> {
+        let pbuffer = self
+            .pbuffer
+            .as_ref()
+            .ok_or(ContextError::FunctionUnavailable)?;
+        Ok((
+            pbuffer.query(gl::wgl_extra::PBUFFER_WIDTH_ARB)? as u32,
+            pbuffer.query(gl::wgl_extra::PBUFFER_HEIGHT_ARB)? as u32,
+        ))
+    }
+
+    /// Returns whether the memory of the pbuffer was lost, which happens on
+    /// display mode changes. A lost pbuffer has to be recreated.
+    pub fn is_pbuffer_lost(&self) -> Result<bool, ContextError> {
+        let pbuffer = self
+            .pbuffer
+            .as_ref()
+            .ok_or(ContextError::FunctionUnavailable)?;
+        Ok(pbuffer.query(gl::wgl_extra::PBUFFER_LOST_ARB)? != 0)
+    }
+
+    /// Binds the color buffer of the pbuffer to the `GL_TEXTURE_2D` texture
+    /// of the current context, with `wglBindTexImageARB`.
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        let (pbuffer, buffer) = self.texture_pbuffer()?;
+        let res = unsafe {
+            pbuffer
+                .extra_functions
+                .BindTexImageARB(pbuffer.pbuffer, buffer)
+        };
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    /// Releases the color buffer bound with `bind_tex_image`.
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        let (pbuffer, buffer) = self.texture_pbuffer()?;
+        let res = unsafe {
+            pbuffer
+                .extra_functions
+                .ReleaseTexImageARB(pbuffer.pbuffer, buffer)
+        };
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    // The textureable pbuffer along with the buffer that is rendered to.
+    fn texture_pbuffer(
+        &self,
+    ) -> Result<(&PbufferWrapper, raw::c_int), ContextError> {
+        let pbuffer = match self.pbuffer {
+            Some(ref pbuffer) if pbuffer.textureable => pbuffer,
+            _ => return Err(ContextError::FunctionUnavailable),
+        };
+        let buffer = if self.pixel_format.double_buffer {
+            gl::wgl_extra::BACK_LEFT_ARB
+        } else {
+            gl::wgl_extra::FRONT_LEFT_ARB
+        };
+        Ok((pbuffer, buffer as raw::c_int))
+    }
+
+    pub fn query_max_swap_groups(
+        &self,
+    ) -> Result<SwapGroupLimits, ContextError> {
+        let (mut max_groups, mut max_barriers) = (0, 0);
+        let res = self.with_swap_group_functions(|extra_functions| unsafe {
+            extra_functions.QueryMaxSwapGroupsNV(
+                self.hdc as *const _,
+                &mut max_groups,
+                &mut max_barriers,
+            )
+        })?;
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(SwapGroupLimits {
+            max_groups,
+            max_barriers,
+        })
+    }
+
+    pub fn join_swap_group(&self, group: u32) -> Result<(), ContextError> {
+        self.query_max_swap_groups()?.check_group(group)?;
+
+        let res = self.with_swap_group_functions(|extra_functions| unsafe {
+            extra_functions.JoinSwapGroupNV(self.hdc as *const _, group)
+        })?;
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        self.swap_group.store(group, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn bind_swap_barrier(
+        &self,
+        group: u32,
+        barrier: u32,
+    ) -> Result<(), ContextError> {
+        let limits = self.query_max_swap_groups()?;
+        limits.check_group(group)?;
+        limits.check_barrier(barrier)?;
+
+        let res = self.with_swap_group_functions(|extra_functions| unsafe {
+            extra_functions.BindSwapBarrierNV(group, barrier)
+        })?;
+        if res == 0 {
+            return Err(ContextError::IoError(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+}
+
+unsafe impl Send for Context {}
+unsafe impl Sync for Context {}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        if *self.swap_group.get_mut() != 0 {
+            let hdc = self.hdc;
+            let _ = self.with_swap_group_functions(|extra_functions| unsafe {
+                extra_functions.JoinSwapGroupNV(hdc as *const _, 0)
+            });
+        }
+    }
+}
+
+/// Creates an OpenGL context.
+///
+/// If `extra` is `Some`, this function will attempt to use the latest WGL
+/// functions to create the context.
+///
+/// Otherwise, only the basic API will be used and the chances of
+/// `CreationError::NotSupported` being returned increase.
+unsafe fn create_context(
+    extra: Option<(
+        &gl::wgl_extra::Wgl,
+        &PixelFormatRequirements,
+        &GlAttributes<&Context>,
+        &Extensions,
+    )>,
+    _: HWND,
+    hdc: HDC,
+) -> Result<ContextWrapper, CreationError> {
+    let share;
+
+    if let Some((extra_functions, pf_reqs, opengl, extensions)) = extra {
+        share = opengl
+            .sharing
+            .map_or(std::ptr::null_mut(), |ctx| ctx.context.0);
+
+        if pf_reqs.release_behavior == ReleaseBehavior::None
+            && !extensions.contains("WGL_ARB_context_flush_control")
+        {
+            return Err(CreationError::ReleaseBehaviorNotSupported);
+        }
+
+        if extensions.contains("WGL_ARB_create_context") {
+            let mut attributes = Vec::new();
+
+            match opengl.version {
+                GlRequest::Latest => {}
+                GlRequest::Specific(Api::OpenGl, (major, minor)) => {
                     attributes.push(
                         gl::wgl_extra::CONTEXT_MAJOR_VERSION_ARB as raw::c_int,
                     );
@@ -297,10 +1107,7 @@ unsafe fn create_context(
                     attributes.push(minor as raw::c_int);
                 }
                 GlRequest::Specific(Api::OpenGlEs, (major, minor)) => {
-                    if extensions
-                        .split(' ')
-                        .find(|&i| i == "WGL_EXT_create_context_es2_profile")
-                        .is_some()
+                    if extensions.contains("WGL_EXT_create_context_es2_profile")
                     {
                         attributes.push(
                             gl::wgl_extra::CONTEXT_PROFILE_MASK_ARB
@@ -342,11 +1149,7 @@ unsafe fn create_context(
             }
 
             if let Some(profile) = opengl.profile {
-                if extensions
-                    .split(' ')
-                    .find(|&i| i == "WGL_ARB_create_context_profile")
-                    .is_some()
-                {
+                if extensions.contains("WGL_ARB_create_context_profile") {
                     let flag = match profile {
                         GlProfile::Compatibility => {
                             gl::wgl_extra::CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB
@@ -370,11 +1173,7 @@ unsafe fn create_context(
                 let mut flags = 0;
 
                 // robustness
-                if extensions
-                    .split(' ')
-                    .find(|&i| i == "WGL_ARB_create_context_robustness")
-                    .is_some()
-                {
+                if extensions.contains("WGL_ARB_create_context_robustness") {
                     match opengl.robustness {
                         Robustness::RobustNoResetNotification
                         | Robustness::TryRobustNoResetNotification => {
@@ -401,6 +1200,11 @@ unsafe fn create_context(
                             flags = flags
                                 | gl::wgl_extra::CONTEXT_ROBUST_ACCESS_BIT_ARB
                                     as raw::c_int;
+                            if opengl.reset_isolation {
+                                flags = flags
+                                    | gl::wgl_extra::CONTEXT_RESET_ISOLATION_BIT_ARB
+                                        as raw::c_int;
+                            }
                         }
                         Robustness::NotRobust => (),
                         Robustness::NoError => (),
@@ -420,14 +1224,61 @@ unsafe fn create_context(
                         | gl::wgl_extra::CONTEXT_DEBUG_BIT_ARB as raw::c_int;
                 }
 
+                if opengl.forward_compatible {
+                    flags = flags
+                        | gl::wgl_extra::CONTEXT_FORWARD_COMPATIBLE_BIT_ARB
+                            as raw::c_int;
+                }
+
                 flags
             };
 
+            if opengl.robustness == Robustness::NoError
+                && extensions.contains("WGL_ARB_create_context_no_error")
+            {
+                if opengl.debug {
+                    return Err(CreationError::NotSupported(
+                        "No-error contexts can't be debug contexts".to_string(),
+                    ));
+                }
+                attributes.push(
+                    gl::wgl_extra::CONTEXT_OPENGL_NO_ERROR_ARB as raw::c_int,
+                );
+                attributes.push(1);
+            }
+
+            if pf_reqs.release_behavior == ReleaseBehavior::None {
+                attributes.push(
+                    gl::wgl_extra::CONTEXT_RELEASE_BEHAVIOR_ARB as raw::c_int,
+                );
+                attributes.push(
+                    gl::wgl_extra::CONTEXT_RELEASE_BEHAVIOR_NONE_ARB
+                        as raw::c_int,
+                );
+            }
+
             attributes.push(gl::wgl_extra::CONTEXT_FLAGS_ARB as raw::c_int);
             attributes.push(flags);
 
             attributes.push(0);
 
+            if let Some(GpuId::Amd(id)) = pf_reqs.gpu {
+                let ctx = extra_functions.CreateAssociatedContextAttribsAMD(
+                    id,
+                    share as *const raw::c_void,
+                    attributes.as_ptr(),
+                );
+                if ctx.is_null() {
+                    return Err(last_error(
+                        "wglCreateAssociatedContextAttribsAMD failed",
+                    ));
+                }
+                return Ok(ContextWrapper(
+                    ctx as HGLRC,
+                    Some(ExtraFunctions(extra_functions.clone())),
+                ));
+            }
+
             let ctx = extra_functions.CreateContextAttribsARB(
                 hdc as *const raw::c_void,
                 share as *const raw::c_void,
@@ -435,36 +1286,41 @@ unsafe fn create_context(
             );
 
             if ctx.is_null() {
-                return Err(CreationError::OsError(format!(
-                    "wglCreateContextAttribsARB failed: {}",
-                    std::io::Error::last_os_error()
-                )));
+                return Err(last_error("wglCreateContextAttribsARB failed"));
             } else {
-                return Ok(ContextWrapper(ctx as HGLRC));
+                return Ok(ContextWrapper(ctx as HGLRC, None));
             }
         }
+
+        // the legacy path has no way of passing the release behavior
+        if pf_reqs.release_behavior == ReleaseBehavior::None {
+            return Err(CreationError::ReleaseBehaviorNotSupported);
+        }
+
+        // nor the robustness, which only the `Try*` variants can do without
+        match opengl.robustness {
+            Robustness::RobustNoResetNotification
+            | Robustness::RobustLoseContextOnReset => {
+                return Err(CreationError::RobustnessNotSupported);
+            }
+            _ => (),
+        }
     } else {
         share = std::ptr::null_mut();
     }
 
     let ctx = gl::wgl::CreateContext(hdc as *const raw::c_void);
     if ctx.is_null() {
-        return Err(CreationError::OsError(format!(
-            "wglCreateContext failed: {}",
-            std::io::Error::last_os_error()
-        )));
+        return Err(last_error("wglCreateContext failed"));
     }
 
     if !share.is_null() {
         if gl::wgl::ShareLists(share as *const raw::c_void, ctx) == 0 {
-            return Err(CreationError::OsError(format!(
-                "wglShareLists failed: {}",
-                std::io::Error::last_os_error()
-            )));
+            return Err(last_error("wglShareLists failed"));
         }
     };
 
-    Ok(ContextWrapper(ctx as HGLRC))
+    Ok(ContextWrapper(ctx as HGLRC, None))
 }
 
 /// Chooses a pixel formats without using WGL.
@@ -474,8 +1330,6 @@ unsafe fn choose_native_pixel_format_id(
     hdc: HDC,
     pf_reqs: &PixelFormatRequirements,
 ) -> Result<raw::c_int, ()> {
-    // TODO: hardware acceleration is not handled
-
     // handling non-supported stuff
     if pf_reqs.float_color_buffer {
         return Err(());
@@ -487,18 +1341,27 @@ unsafe fn choose_native_pixel_format_id(
         Some(_) => return Err(()),
     };
 
-    if pf_reqs.stereoscopy {
+    if pf_reqs.stereoscopy == Some(true) {
         return Err(());
     }
 
-    if pf_reqs.srgb {
+    if pf_reqs.srgb && pf_reqs.srgb_required {
         return Err(());
     }
 
-    if pf_reqs.release_behavior != ReleaseBehavior::Flush {
+    // `PFD_SWAP_COPY` is only a hint
+    if pf_reqs.preserved_swap {
         return Err(());
     }
 
+    // copying the back buffer doesn't expose the undefined contents of an
+    // exchanged one while a child window is resized
+    let swap_copy = if pf_reqs.wgl_child_window {
+        PFD_SWAP_COPY
+    } else {
+        0
+    };
+
     // building the descriptor to pass to ChoosePixelFormat
     let descriptor = PIXELFORMATDESCRIPTOR {
         nSize: std::mem::size_of::<PIXELFORMATDESCRIPTOR>() as u16,
@@ -510,9 +1373,18 @@ unsafe fn choose_native_pixel_format_id(
                 Some(false) => 0,
             };
 
-            let f2 = if pf_reqs.stereoscopy { PFD_STEREO } else { 0 };
+            let f2 = match pf_reqs.stereoscopy {
+                None => PFD_STEREO_DONTCARE,
+                Some(_) => 0,
+            };
 
-            PFD_DRAW_TO_WINDOW | PFD_SUPPORT_OPENGL | f1 | f2
+            let f3 = if pf_reqs.wgl_transparent {
+                PFD_SUPPORT_COMPOSITION
+            } else {
+                0
+            };
+
+            PFD_DRAW_TO_WINDOW | PFD_SUPPORT_OPENGL | f1 | f2 | f3 | swap_copy
         },
         iPixelType: PFD_TYPE_RGBA,
         cColorBits: pf_reqs.color_bits.unwrap_or(0),
@@ -522,7 +1394,7 @@ unsafe fn choose_native_pixel_format_id(
         cGreenShift: 0,
         cBlueBits: 0,
         cBlueShift: 0,
-        cAlphaBits: pf_reqs.alpha_bits.unwrap_or(0),
+        cAlphaBits: transparent_alpha_bits(pf_reqs).unwrap_or(0),
         cAlphaShift: 0,
         cAccumBits: 0,
         cAccumRedBits: 0,
@@ -545,6 +1417,19 @@ unsafe fn choose_native_pixel_format_id(
         return Err(());
     }
 
+    // `ChoosePixelFormat` returns the closest format, which may be opaque
+    if pf_reqs.wgl_transparent && !supports_composition(hdc, pf_id) {
+        return Err(());
+    }
+
+    // or the GDI Generic implementation, when the driver is broken
+    if let Some(hardware_accelerated) = pf_reqs.hardware_accelerated {
+        let output = describe_pixel_format(hdc, pf_id).ok_or(())?;
+        if is_accelerated(&output) != hardware_accelerated {
+            return Err(());
+        }
+    }
+
     Ok(pf_id)
 }
 
@@ -577,17 +1462,7 @@ unsafe fn choose_native_pixel_format(
         return Err(());
     }
 
-    let pf_desc = PixelFormat {
-        hardware_accelerated: (output.dwFlags & PFD_GENERIC_FORMAT) == 0,
-        color_bits: output.cRedBits + output.cGreenBits + output.cBlueBits,
-        alpha_bits: output.cAlphaBits,
-        depth_bits: output.cDepthBits,
-        stencil_bits: output.cStencilBits,
-        stereoscopy: (output.dwFlags & PFD_STEREO) != 0,
-        double_buffer: (output.dwFlags & PFD_DOUBLEBUFFER) != 0,
-        multisampling: None,
-        srgb: false,
-    };
+    let pf_desc = native_pixel_format(&output);
 
     if pf_desc.alpha_bits < pf_reqs.alpha_bits.unwrap_or(0) {
         return Err(());
@@ -615,160 +1490,285 @@ unsafe fn choose_native_pixel_format(
     Ok(pf_desc)
 }
 
+/// Returns whether a native pixel format is implemented by the driver,
+/// either fully or through the generic implementation, rather than rendered
+/// in software by the GDI Generic implementation.
+fn is_accelerated(output: &PIXELFORMATDESCRIPTOR) -> bool {
+    output.dwFlags & PFD_GENERIC_FORMAT == 0
+        || output.dwFlags & PFD_GENERIC_ACCELERATED != 0
+}
+
+/// Converts the description of a native pixel format.
+fn native_pixel_format(output: &PIXELFORMATDESCRIPTOR) -> PixelFormat {
+    PixelFormat {
+        hardware_accelerated: is_accelerated(output),
+        color_bits: output.cRedBits + output.cGreenBits + output.cBlueBits,
+        rgb_bits: Some((output.cRedBits, output.cGreenBits, output.cBlueBits)),
+        alpha_bits: output.cAlphaBits,
+        depth_bits: output.cDepthBits,
+        stencil_bits: output.cStencilBits,
+        stereoscopy: (output.dwFlags & PFD_STEREO) != 0,
+        double_buffer: (output.dwFlags & PFD_DOUBLEBUFFER) != 0,
+        multisampling: None,
+        coverage_samples: None,
+        srgb: false,
+        float_color_buffer: false,
+        swap_behavior: if (output.dwFlags & PFD_SWAP_COPY) != 0 {
+            SwapBehavior::Preserved
+        } else if (output.dwFlags & PFD_SWAP_EXCHANGE) != 0 {
+            SwapBehavior::Exchanged
+        } else {
+            SwapBehavior::Undefined
+        },
+        swap_interval_range: None,
+    }
+}
+
 /// Enumerates the list of pixel formats by using extra WGL functions.
 ///
 /// Gives more precise results than `enumerate_native_pixel_formats`.
 unsafe fn choose_arb_pixel_format_id(
     extra: &gl::wgl_extra::Wgl,
-    extensions: &str,
+    extensions: &Extensions,
     hdc: HDC,
     pf_reqs: &PixelFormatRequirements,
+    pbuffer: bool,
 ) -> Result<raw::c_int, ()> {
-    let descriptor = {
-        let mut out: Vec<raw::c_int> = Vec::with_capacity(37);
+    // when sRGB is only preferred, the other formats are the fallback
+    if pf_reqs.srgb && !pf_reqs.srgb_required {
+        let required = PixelFormatRequirements {
+            srgb_required: true,
+            ..pf_reqs.clone()
+        };
+        let fallback = PixelFormatRequirements {
+            srgb: false,
+            ..pf_reqs.clone()
+        };
+        return choose_arb_pixel_format_id(
+            extra, extensions, hdc, &required, pbuffer,
+        )
+        .or_else(|_| {
+            choose_arb_pixel_format_id(
+                extra, extensions, hdc, &fallback, pbuffer,
+            )
+        });
+    }
 
-        out.push(gl::wgl_extra::DRAW_TO_WINDOW_ARB as raw::c_int);
-        out.push(1);
+    // child windows prefer formats that copy the back buffer, which doesn't
+    // flicker while the window is resized
+    if pf_reqs.wgl_child_window && !pf_reqs.preserved_swap && !pbuffer {
+        let preferred = PixelFormatRequirements {
+            preserved_swap: true,
+            ..pf_reqs.clone()
+        };
+        let fallback = PixelFormatRequirements {
+            wgl_child_window: false,
+            ..pf_reqs.clone()
+        };
+        return choose_arb_pixel_format_id(
+            extra, extensions, hdc, &preferred, pbuffer,
+        )
+        .or_else(|_| {
+            choose_arb_pixel_format_id(
+                extra, extensions, hdc, &fallback, pbuffer,
+            )
+        });
+    }
 
-        out.push(gl::wgl_extra::SUPPORT_OPENGL_ARB as raw::c_int);
-        out.push(1);
+    // without the extension, ordinary multisampling is used instead
+    let coverage_sampling = extensions.contains("WGL_NV_multisample_coverage");
 
-        out.push(gl::wgl_extra::PIXEL_TYPE_ARB as raw::c_int);
-        if pf_reqs.float_color_buffer {
-            if extensions
-                .split(' ')
-                .find(|&i| i == "WGL_ARB_pixel_format_float")
-                .is_some()
-            {
-                out.push(gl::wgl_extra::TYPE_RGBA_FLOAT_ARB as raw::c_int);
-            } else {
-                return Err(());
-            }
+    let descriptor = arb_pixel_format_attributes(extensions, pf_reqs, pbuffer)?;
+
+    // drivers treat `WGL_SAMPLES_ARB` as a minimum, so when an exact count
+    // is requested, look through more formats for one that matches it
+    let samples_attrib =
+        if pf_reqs.coverage_samples.is_some() && coverage_sampling {
+            gl::wgl_extra::COLOR_SAMPLES_NV
         } else {
-            out.push(gl::wgl_extra::TYPE_RGBA_ARB as raw::c_int);
-        }
+            gl::wgl_extra::SAMPLES_ARB
+        };
+    let exact_samples = match pf_reqs.multisampling {
+        Some(samples) if pf_reqs.multisampling_exact => Some(samples),
+        _ => None,
+    };
 
-        if let Some(hardware_accelerated) = pf_reqs.hardware_accelerated {
-            out.push(gl::wgl_extra::ACCELERATION_ARB as raw::c_int);
-            out.push(if hardware_accelerated {
-                gl::wgl_extra::FULL_ACCELERATION_ARB as raw::c_int
-            } else {
-                gl::wgl_extra::NO_ACCELERATION_ARB as raw::c_int
-            });
-        }
+    let mut format_ids: Vec<raw::c_int> =
+        vec![0; if exact_samples.is_some() { 256 } else { 1 }];
+    let mut num_formats = std::mem::zeroed();
+    if extra.ChoosePixelFormatARB(
+        hdc as *const _,
+        descriptor.as_ptr(),
+        std::ptr::null(),
+        format_ids.len() as _,
+        format_ids.as_mut_ptr(),
+        &mut num_formats,
+    ) == 0
+    {
+        return Err(());
+    }
+    format_ids.truncate(num_formats as usize);
 
-        if let Some(color) = pf_reqs.color_bits {
-            out.push(gl::wgl_extra::COLOR_BITS_ARB as raw::c_int);
-            out.push(color as raw::c_int);
-        }
+    format_ids
+        .into_iter()
+        .filter(|&format_id| {
+            !pf_reqs.wgl_transparent || supports_composition(hdc, format_id)
+        })
+        .find(|&format_id| match exact_samples {
+            Some(samples) => {
+                let mut value = 0;
+                extra.GetPixelFormatAttribivARB(
+                    hdc as *const _,
+                    format_id,
+                    0,
+                    1,
+                    [samples_attrib as raw::c_int].as_ptr(),
+                    &mut value,
+                );
+                value == samples as raw::c_int
+            }
+            None => true,
+        })
+        .ok_or(())
+}
 
-        if let Some(alpha) = pf_reqs.alpha_bits {
-            out.push(gl::wgl_extra::ALPHA_BITS_ARB as raw::c_int);
-            out.push(alpha as raw::c_int);
-        }
+/// The `wglChoosePixelFormatARB` attributes for the requirements.
+fn arb_pixel_format_attributes(
+    extensions: &Extensions,
+    pf_reqs: &PixelFormatRequirements,
+    pbuffer: bool,
+) -> Result<Vec<raw::c_int>, ()> {
+    // without the extension, ordinary multisampling is used instead
+    let coverage_sampling = extensions.contains("WGL_NV_multisample_coverage");
 
-        if let Some(depth) = pf_reqs.depth_bits {
-            out.push(gl::wgl_extra::DEPTH_BITS_ARB as raw::c_int);
-            out.push(depth as raw::c_int);
-        }
+    let mut out: Vec<raw::c_int> = Vec::with_capacity(37);
 
-        if let Some(stencil) = pf_reqs.stencil_bits {
-            out.push(gl::wgl_extra::STENCIL_BITS_ARB as raw::c_int);
-            out.push(stencil as raw::c_int);
-        }
+    if pbuffer {
+        out.push(gl::wgl_extra::DRAW_TO_PBUFFER_ARB as raw::c_int);
+    } else {
+        out.push(gl::wgl_extra::DRAW_TO_WINDOW_ARB as raw::c_int);
+    }
+    out.push(1);
 
-        // Prefer double buffering if unspecified (probably shouldn't once you
-        // can choose)
-        let double_buffer = pf_reqs.double_buffer.unwrap_or(true);
-        out.push(gl::wgl_extra::DOUBLE_BUFFER_ARB as raw::c_int);
-        out.push(if double_buffer { 1 } else { 0 });
+    if pbuffer && pf_reqs.textureable {
+        out.push(texture_format(pf_reqs).0 as raw::c_int);
+        out.push(1);
+    }
 
-        if let Some(multisampling) = pf_reqs.multisampling {
-            if extensions
-                .split(' ')
-                .find(|&i| i == "WGL_ARB_multisample")
-                .is_some()
-            {
-                out.push(gl::wgl_extra::SAMPLE_BUFFERS_ARB as raw::c_int);
-                out.push(if multisampling == 0 { 0 } else { 1 });
-                out.push(gl::wgl_extra::SAMPLES_ARB as raw::c_int);
-                out.push(multisampling as raw::c_int);
-            } else {
-                return Err(());
-            }
-        }
+    out.push(gl::wgl_extra::SUPPORT_OPENGL_ARB as raw::c_int);
+    out.push(1);
 
-        out.push(gl::wgl_extra::STEREO_ARB as raw::c_int);
-        out.push(if pf_reqs.stereoscopy { 1 } else { 0 });
-
-        // WGL_*_FRAMEBUFFER_SRGB might be assumed to be true if not listed;
-        // so it's best to list it out and set its value as necessary.
-        if extensions
-            .split(' ')
-            .find(|&i| i == "WGL_ARB_framebuffer_sRGB")
-            .is_some()
-        {
-            out.push(gl::wgl_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB as raw::c_int);
-            out.push(pf_reqs.srgb as raw::c_int);
-        } else if extensions
-            .split(' ')
-            .find(|&i| i == "WGL_EXT_framebuffer_sRGB")
-            .is_some()
-        {
-            out.push(gl::wgl_extra::FRAMEBUFFER_SRGB_CAPABLE_EXT as raw::c_int);
-            out.push(pf_reqs.srgb as raw::c_int);
-        } else if pf_reqs.srgb {
+    out.push(gl::wgl_extra::PIXEL_TYPE_ARB as raw::c_int);
+    if pf_reqs.float_color_buffer {
+        if extensions.contains("WGL_ARB_pixel_format_float") {
+            out.push(gl::wgl_extra::TYPE_RGBA_FLOAT_ARB as raw::c_int);
+        } else {
             return Err(());
         }
+    } else {
+        out.push(gl::wgl_extra::TYPE_RGBA_ARB as raw::c_int);
+    }
+
+    if let Some(hardware_accelerated) = pf_reqs.hardware_accelerated {
+        out.push(gl::wgl_extra::ACCELERATION_ARB as raw::c_int);
+        out.push(if hardware_accelerated {
+            gl::wgl_extra::FULL_ACCELERATION_ARB as raw::c_int
+        } else {
+            gl::wgl_extra::NO_ACCELERATION_ARB as raw::c_int
+        });
+    }
+
+    if let Some(color) = pf_reqs.color_bits {
+        out.push(gl::wgl_extra::COLOR_BITS_ARB as raw::c_int);
+        out.push(color as raw::c_int);
+
+        // a minimum sum is also met by uneven formats such as 11-11-10,
+        // so deep color asks for the bits of every channel
+        if color > 24 {
+            let channel = (color / 3) as raw::c_int;
+            out.push(gl::wgl_extra::RED_BITS_ARB as raw::c_int);
+            out.push(channel);
+            out.push(gl::wgl_extra::GREEN_BITS_ARB as raw::c_int);
+            out.push(channel);
+            out.push(gl::wgl_extra::BLUE_BITS_ARB as raw::c_int);
+            out.push(channel);
+        }
+    }
+
+    if let Some(alpha) = transparent_alpha_bits(pf_reqs) {
+        out.push(gl::wgl_extra::ALPHA_BITS_ARB as raw::c_int);
+        out.push(alpha as raw::c_int);
+    }
 
-        match pf_reqs.release_behavior {
-            ReleaseBehavior::Flush => (),
-            ReleaseBehavior::None => {
-                if extensions
-                    .split(' ')
-                    .find(|&i| i == "WGL_ARB_context_flush_control")
-                    .is_some()
+    if let Some(depth) = pf_reqs.depth_bits {
+        out.push(gl::wgl_extra::DEPTH_BITS_ARB as raw::c_int);
+        out.push(depth as raw::c_int);
+    }
+
+    if let Some(stencil) = pf_reqs.stencil_bits {
+        out.push(gl::wgl_extra::STENCIL_BITS_ARB as raw::c_int);
+        out.push(stencil as raw::c_int);
+    }
+
+    // Prefer double buffering if unspecified (probably shouldn't once you
+    // can choose)
+    let double_buffer = pf_reqs.double_buffer.unwrap_or(true);
+    out.push(gl::wgl_extra::DOUBLE_BUFFER_ARB as raw::c_int);
+    out.push(if double_buffer { 1 } else { 0 });
+
+    if let Some(multisampling) = pf_reqs.multisampling {
+        if extensions.contains("WGL_ARB_multisample") {
+            out.push(gl::wgl_extra::SAMPLE_BUFFERS_ARB as raw::c_int);
+            out.push(if multisampling == 0 { 0 } else { 1 });
+            match pf_reqs.coverage_samples {
+                // `WGL_COVERAGE_SAMPLES_NV` is `WGL_SAMPLES_ARB`
+                Some(coverage_samples)
+                    if multisampling != 0 && coverage_sampling =>
                 {
-                    out.push(
-                        gl::wgl_extra::CONTEXT_RELEASE_BEHAVIOR_ARB
-                            as raw::c_int,
-                    );
-                    out.push(
-                        gl::wgl_extra::CONTEXT_RELEASE_BEHAVIOR_NONE_ARB
-                            as raw::c_int,
-                    );
+                    out.push(gl::wgl_extra::COVERAGE_SAMPLES_NV as raw::c_int);
+                    out.push(coverage_samples as raw::c_int);
+                    out.push(gl::wgl_extra::COLOR_SAMPLES_NV as raw::c_int);
+                    out.push(multisampling as raw::c_int);
+                }
+                _ => {
+                    out.push(gl::wgl_extra::SAMPLES_ARB as raw::c_int);
+                    out.push(multisampling as raw::c_int);
                 }
             }
+        } else {
+            return Err(());
         }
+    }
 
-        out.push(0);
-        out
-    };
+    if let Some(stereoscopy) = pf_reqs.stereoscopy {
+        out.push(gl::wgl_extra::STEREO_ARB as raw::c_int);
+        out.push(if stereoscopy { 1 } else { 0 });
+    }
 
-    let mut format_id = std::mem::zeroed();
-    let mut num_formats = std::mem::zeroed();
-    if extra.ChoosePixelFormatARB(
-        hdc as *const _,
-        descriptor.as_ptr(),
-        std::ptr::null(),
-        1,
-        &mut format_id,
-        &mut num_formats,
-    ) == 0
-    {
-        return Err(());
+    if pf_reqs.preserved_swap {
+        out.push(gl::wgl_extra::SWAP_METHOD_ARB as raw::c_int);
+        out.push(gl::wgl_extra::SWAP_COPY_ARB as raw::c_int);
     }
 
-    if num_formats == 0 {
+    // WGL_*_FRAMEBUFFER_SRGB might be assumed to be true if not listed;
+    // so it's best to list it out and set its value as necessary.
+    if extensions.contains("WGL_ARB_framebuffer_sRGB") {
+        out.push(gl::wgl_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB as raw::c_int);
+        out.push(pf_reqs.srgb as raw::c_int);
+    } else if extensions.contains("WGL_EXT_framebuffer_sRGB") {
+        out.push(gl::wgl_extra::FRAMEBUFFER_SRGB_CAPABLE_EXT as raw::c_int);
+        out.push(pf_reqs.srgb as raw::c_int);
+    } else if pf_reqs.srgb {
         return Err(());
     }
 
-    Ok(format_id)
+    out.push(0);
+    Ok(out)
 }
 
 unsafe fn choose_arb_pixel_format(
     extra: &gl::wgl_extra::Wgl,
-    extensions: &str,
+    extensions: &Extensions,
     hdc: HDC,
     format_id: raw::c_int,
 ) -> Result<PixelFormat, ()> {
@@ -785,51 +1785,184 @@ unsafe fn choose_arb_pixel_format(
         value as u32
     };
 
+    let (multisampling, coverage_samples) =
+        if extensions.contains("WGL_ARB_multisample") {
+            let samples = get_info(gl::wgl_extra::SAMPLES_ARB) as u16;
+            // with coverage sampling, `WGL_SAMPLES_ARB` counts the coverage
+            // samples
+            let color_samples =
+                if extensions.contains("WGL_NV_multisample_coverage") {
+                    get_info(gl::wgl_extra::COLOR_SAMPLES_NV) as u16
+                } else {
+                    samples
+                };
+            crate::split_coverage_samples(samples, color_samples)
+        } else {
+            (None, None)
+        };
+
     let pf_desc = PixelFormat {
         hardware_accelerated: get_info(gl::wgl_extra::ACCELERATION_ARB)
             != gl::wgl_extra::NO_ACCELERATION_ARB,
         color_bits: get_info(gl::wgl_extra::RED_BITS_ARB) as u8
             + get_info(gl::wgl_extra::GREEN_BITS_ARB) as u8
             + get_info(gl::wgl_extra::BLUE_BITS_ARB) as u8,
+        rgb_bits: Some((
+            get_info(gl::wgl_extra::RED_BITS_ARB) as u8,
+            get_info(gl::wgl_extra::GREEN_BITS_ARB) as u8,
+            get_info(gl::wgl_extra::BLUE_BITS_ARB) as u8,
+        )),
         alpha_bits: get_info(gl::wgl_extra::ALPHA_BITS_ARB) as u8,
         depth_bits: get_info(gl::wgl_extra::DEPTH_BITS_ARB) as u8,
         stencil_bits: get_info(gl::wgl_extra::STENCIL_BITS_ARB) as u8,
         stereoscopy: get_info(gl::wgl_extra::STEREO_ARB) != 0,
         double_buffer: get_info(gl::wgl_extra::DOUBLE_BUFFER_ARB) != 0,
-        multisampling: {
-            if extensions
-                .split(' ')
-                .find(|&i| i == "WGL_ARB_multisample")
-                .is_some()
-            {
-                match get_info(gl::wgl_extra::SAMPLES_ARB) {
-                    0 => None,
-                    a => Some(a as u16),
-                }
-            } else {
-                None
-            }
-        },
-        srgb: if extensions
-            .split(' ')
-            .find(|&i| i == "WGL_ARB_framebuffer_sRGB")
-            .is_some()
-        {
+        multisampling,
+        coverage_samples,
+        srgb: if extensions.contains("WGL_ARB_framebuffer_sRGB") {
             get_info(gl::wgl_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB) != 0
-        } else if extensions
-            .split(' ')
-            .find(|&i| i == "WGL_EXT_framebuffer_sRGB")
-            .is_some()
-        {
+        } else if extensions.contains("WGL_EXT_framebuffer_sRGB") {
             get_info(gl::wgl_extra::FRAMEBUFFER_SRGB_CAPABLE_EXT) != 0
         } else {
             false
         },
+        float_color_buffer: get_info(gl::wgl_extra::PIXEL_TYPE_ARB)
+            == gl::wgl_extra::TYPE_RGBA_FLOAT_ARB,
+        swap_behavior: match get_info(gl::wgl_extra::SWAP_METHOD_ARB) {
+            gl::wgl_extra::SWAP_COPY_ARB => SwapBehavior::Preserved,
+            gl::wgl_extra::SWAP_EXCHANGE_ARB => SwapBehavior::Exchanged,
+            _ => SwapBehavior::Undefined,
+        },
+        swap_interval_range: None,
     };
 
     Ok(pf_desc)
 }
 
+/// Returns the error for requirements no pixel format meets. Deep color and
+/// float formats are often missing, so the error names the closest format
+/// without them.
+///
+/// Without `WGL_ARB_pixel_format`, `extra` is `None`.
+unsafe fn no_format_error(
+    extra: Option<&gl::wgl_extra::Wgl>,
+    extensions: &Extensions,
+    hdc: HDC,
+    pf_reqs: &PixelFormatRequirements,
+    pbuffer: bool,
+) -> CreationError {
+    // a broken driver leaves only the GDI Generic implementation
+    if pf_reqs.hardware_accelerated == Some(true) {
+        let software = PixelFormatRequirements {
+            hardware_accelerated: None,
+            ..pf_reqs.clone()
+        };
+        let found = match extra {
+            Some(extra) => choose_arb_pixel_format_id(
+                extra, extensions, hdc, &software, pbuffer,
+            ),
+            None => choose_native_pixel_format_id(hdc, &software),
+        };
+        if found.is_ok() {
+            return CreationError::NoHardwareAcceleration;
+        }
+    }
+
+    let extra = match extra {
+        Some(extra) => extra,
+        None => return pf_reqs.no_config_error(),
+    };
+    let deep_color = pf_reqs.color_bits.map_or(false, |bits| bits > 24);
+    if !deep_color && !pf_reqs.float_color_buffer {
+        return pf_reqs.no_config_error();
+    }
+
+    let relaxed = PixelFormatRequirements {
+        color_bits: Some(24),
+        float_color_buffer: false,
+        ..pf_reqs.clone()
+    };
+    let closest =
+        choose_arb_pixel_format_id(extra, extensions, hdc, &relaxed, pbuffer)
+            .and_then(|id| choose_arb_pixel_format(extra, extensions, hdc, id));
+    match closest {
+        Ok(pf) => {
+            CreationError::NotSupported(format!(
+            "No {}pixel format with {} color bits is available, the closest \
+             has {} color bits {:?}{}",
+            if pf_reqs.float_color_buffer { "float " } else { "" },
+            pf_reqs.color_bits.unwrap_or(0),
+            pf.color_bits,
+            pf.rgb_bits.unwrap_or_default(),
+            if pf.float_color_buffer { " (float)" } else { "" },
+        ))
+        }
+        Err(()) => pf_reqs.no_config_error(),
+    }
+}
+
+/// Checks that a child window and its parent have the styles that keep them
+/// from drawing over each other.
+unsafe fn check_child_window_styles(win: HWND) -> Result<(), CreationError> {
+    let style = GetWindowLongW(win, GWL_STYLE) as DWORD;
+    if style & WS_CHILD == 0 || style & WS_CLIPSIBLINGS == 0 {
+        return Err(CreationError::NotSupported(
+            "Child windows require the WS_CHILD and WS_CLIPSIBLINGS styles"
+                .to_string(),
+        ));
+    }
+
+    let parent = GetParent(win);
+    if parent.is_null()
+        || GetWindowLongW(parent, GWL_STYLE) as DWORD & WS_CLIPCHILDREN == 0
+    {
+        return Err(CreationError::NotSupported(
+            "The parent of a child window requires the WS_CLIPCHILDREN style"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the alpha bits to require, which are at least 8 for transparent
+/// windows.
+fn transparent_alpha_bits(pf_reqs: &PixelFormatRequirements) -> Option<u8> {
+    if pf_reqs.wgl_transparent {
+        Some(pf_reqs.alpha_bits.unwrap_or(8).max(8))
+    } else {
+        pf_reqs.alpha_bits
+    }
+}
+
+/// Returns whether DWM can composite a window of the pixel format `id` with
+/// its alpha channel. GDI-compatible formats are rendered by the generic
+/// implementation, which ignores the alpha channel.
+unsafe fn supports_composition(hdc: HDC, id: raw::c_int) -> bool {
+    describe_pixel_format(hdc, id).map_or(false, |output| {
+        output.dwFlags & PFD_SUPPORT_COMPOSITION != 0
+            && output.dwFlags & PFD_SUPPORT_GDI == 0
+            && output.cAlphaBits >= 8
+    })
+}
+
+/// Returns the descriptor of the pixel format `id` of `hdc`.
+unsafe fn describe_pixel_format(
+    hdc: HDC,
+    id: raw::c_int,
+) -> Option<PIXELFORMATDESCRIPTOR> {
+    let mut output: PIXELFORMATDESCRIPTOR = std::mem::zeroed();
+    if DescribePixelFormat(
+        hdc,
+        id,
+        std::mem::size_of::<PIXELFORMATDESCRIPTOR>() as UINT,
+        &mut output,
+    ) == 0
+    {
+        return None;
+    }
+    Some(output)
+}
+
 /// Calls `SetPixelFormat` on a window.
 unsafe fn set_pixel_format(
     hdc: HDC,
@@ -844,20 +1977,579 @@ unsafe fn set_pixel_format(
         &mut output,
     ) == 0
     {
+        return Err(last_error("DescribePixelFormat function failed"));
+    }
+
+    if SetPixelFormat(hdc, id, &output) == 0 {
+        return Err(last_error("SetPixelFormat function failed"));
+    }
+
+    Ok(())
+}
+
+/// Creates an affinity device context for the GPU `index` of
+/// `WGL_NV_gpu_affinity`.
+unsafe fn create_affinity_dc(
+    extra_functions: &gl::wgl_extra::Wgl,
+    index: u32,
+) -> Result<AffinityDcWrapper, CreationError> {
+    let mut gpu = std::mem::zeroed();
+    if extra_functions.EnumGpusNV(index, &mut gpu) == 0 {
+        return Err(CreationError::NotSupported(format!(
+            "There is no GPU {} for WGL_NV_gpu_affinity",
+            index
+        )));
+    }
+
+    // the list of GPUs is null-terminated
+    let gpus = [gpu, std::mem::zeroed()];
+    let hdc = extra_functions.CreateAffinityDCNV(gpus.as_ptr());
+    if hdc.is_null() {
+        return Err(last_error("wglCreateAffinityDCNV function failed"));
+    }
+
+    Ok(AffinityDcWrapper {
+        extra_functions: ExtraFunctions(extra_functions.clone()),
+        hdc: hdc as HDC,
+    })
+}
+
+/// Enumerates the GPUs of `WGL_AMD_gpu_association` and
+/// `WGL_NV_gpu_affinity`.
+///
+/// The `window` is used to load the WGL functions.
+pub unsafe fn enumerate_gpus(win: HWND) -> Result<Vec<GpuInfo>, CreationError> {
+    // `wglGetGPUInfoAMD` data types
+    const UNSIGNED_BYTE: u32 = 0x1401;
+    const UNSIGNED_INT: u32 = 0x1405;
+
+    let hdc = GetDC(win);
+    if hdc.is_null() {
         return Err(CreationError::OsError(format!(
-            "DescribePixelFormat function failed: {}",
+            "GetDC function failed: {}",
             std::io::Error::last_os_error()
         )));
     }
+    let extra_functions = extra_functions(win)?;
+    let extensions = query_extensions(&extra_functions, hdc);
+    ReleaseDC(win, hdc);
+
+    let mut gpus = Vec::new();
+
+    if extensions.contains("WGL_AMD_gpu_association") {
+        let mut ids =
+            vec![0; extra_functions.GetGPUIDsAMD(0, std::ptr::null_mut()) as _];
+        let count =
+            extra_functions.GetGPUIDsAMD(ids.len() as _, ids.as_mut_ptr());
+        ids.truncate(count as usize);
+
+        for id in ids {
+            let mut name = [0u8; 256];
+            let len = extra_functions.GetGPUInfoAMD(
+                id,
+                gl::wgl_extra::GPU_RENDERER_STRING_AMD as _,
+                UNSIGNED_BYTE,
+                name.len() as _,
+                name.as_mut_ptr() as *mut _,
+            );
+            let name = if len > 0 {
+                let name = &name[..(len as usize).min(name.len())];
+                let end =
+                    name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                Some(String::from_utf8_lossy(&name[..end]).into_owned())
+            } else {
+                None
+            };
 
-    if SetPixelFormat(hdc, id, &output) == 0 {
+            let mut memory_mb = 0u32;
+            let len = extra_functions.GetGPUInfoAMD(
+                id,
+                gl::wgl_extra::GPU_RAM_AMD as _,
+                UNSIGNED_INT,
+                1,
+                &mut memory_mb as *mut u32 as *mut _,
+            );
+
+            gpus.push(GpuInfo {
+                id: GpuId::Amd(id),
+                name,
+                memory_mb: if len > 0 { Some(memory_mb) } else { None },
+            });
+        }
+    }
+
+    if extensions.contains("WGL_NV_gpu_affinity") {
+        let mut index = 0;
+        let mut gpu = std::mem::zeroed();
+        while extra_functions.EnumGpusNV(index, &mut gpu) != 0 {
+            gpus.push(GpuInfo {
+                id: GpuId::Nvidia(index),
+                name: None,
+                memory_mb: None,
+            });
+            index += 1;
+        }
+    }
+
+    Ok(gpus)
+}
+
+/// A pixel format of the device, as enumerated by [`wgl_pixel_formats`].
+///
+/// [`wgl_pixel_formats`]: fn.wgl_pixel_formats.html
+#[derive(Debug, Clone)]
+pub struct WglPixelFormat {
+    /// The index of the pixel format, starting at 1.
+    pub id: i32,
+    /// The pixel format, as glutin describes the one it chooses.
+    pub pixel_format: PixelFormat,
+    /// The names and values of the `WGL_*_ARB` attributes of the pixel
+    /// format. Empty without `WGL_ARB_pixel_format`, in which case
+    /// `pixel_format` comes from `DescribePixelFormat`.
+    pub attributes: Vec<(&'static str, i32)>,
+}
+
+// The attributes queried by `enumerate_pixel_formats`, with the extension
+// they require besides `WGL_ARB_pixel_format`.
+const ARB_ATTRIBUTES: &[(&str, u32, Option<&str>)] = &[
+    (
+        "WGL_DRAW_TO_WINDOW_ARB",
+        gl::wgl_extra::DRAW_TO_WINDOW_ARB,
+        None,
+    ),
+    (
+        "WGL_DRAW_TO_BITMAP_ARB",
+        gl::wgl_extra::DRAW_TO_BITMAP_ARB,
+        None,
+    ),
+    (
+        "WGL_DRAW_TO_PBUFFER_ARB",
+        gl::wgl_extra::DRAW_TO_PBUFFER_ARB,
+        Some("WGL_ARB_pbuffer"),
+    ),
+    (
+        "WGL_ACCELERATION_ARB",
+        gl::wgl_extra::ACCELERATION_ARB,
+        None,
+    ),
+    (
+        "WGL_NEED_PALETTE_ARB",
+        gl::wgl_extra::NEED_PALETTE_ARB,
+        None,
+    ),
+    (
+        "WGL_NEED_SYSTEM_PALETTE_ARB",
+        gl::wgl_extra::NEED_SYSTEM_PALETTE_ARB,
+        None,
+    ),
+    (
+        "WGL_SWAP_LAYER_BUFFERS_ARB",
+        gl::wgl_extra::SWAP_LAYER_BUFFERS_ARB,
+        None,
+    ),
+    ("WGL_SWAP_METHOD_ARB", gl::wgl_extra::SWAP_METHOD_ARB, None),
+    (
+        "WGL_NUMBER_OVERLAYS_ARB",
+        gl::wgl_extra::NUMBER_OVERLAYS_ARB,
+        None,
+    ),
+    (
+        "WGL_NUMBER_UNDERLAYS_ARB",
+        gl::wgl_extra::NUMBER_UNDERLAYS_ARB,
+        None,
+    ),
+    ("WGL_TRANSPARENT_ARB", gl::wgl_extra::TRANSPARENT_ARB, None),
+    ("WGL_SUPPORT_GDI_ARB", gl::wgl_extra::SUPPORT_GDI_ARB, None),
+    (
+        "WGL_SUPPORT_OPENGL_ARB",
+        gl::wgl_extra::SUPPORT_OPENGL_ARB,
+        None,
+    ),
+    (
+        "WGL_DOUBLE_BUFFER_ARB",
+        gl::wgl_extra::DOUBLE_BUFFER_ARB,
+        None,
+    ),
+    ("WGL_STEREO_ARB", gl::wgl_extra::STEREO_ARB, None),
+    ("WGL_PIXEL_TYPE_ARB", gl::wgl_extra::PIXEL_TYPE_ARB, None),
+    ("WGL_COLOR_BITS_ARB", gl::wgl_extra::COLOR_BITS_ARB, None),
+    ("WGL_RED_BITS_ARB", gl::wgl_extra::RED_BITS_ARB, None),
+    ("WGL_RED_SHIFT_ARB", gl::wgl_extra::RED_SHIFT_ARB, None),
+    ("WGL_GREEN_BITS_ARB", gl::wgl_extra::GREEN_BITS_ARB, None),
+    ("WGL_GREEN_SHIFT_ARB", gl::wgl_extra::GREEN_SHIFT_ARB, None),
+    ("WGL_BLUE_BITS_ARB", gl::wgl_extra::BLUE_BITS_ARB, None),
+    ("WGL_BLUE_SHIFT_ARB", gl::wgl_extra::BLUE_SHIFT_ARB, None),
+    ("WGL_ALPHA_BITS_ARB", gl::wgl_extra::ALPHA_BITS_ARB, None),
+    ("WGL_ALPHA_SHIFT_ARB", gl::wgl_extra::ALPHA_SHIFT_ARB, None),
+    ("WGL_ACCUM_BITS_ARB", gl::wgl_extra::ACCUM_BITS_ARB, None),
+    (
+        "WGL_ACCUM_RED_BITS_ARB",
+        gl::wgl_extra::ACCUM_RED_BITS_ARB,
+        None,
+    ),
+    (
+        "WGL_ACCUM_GREEN_BITS_ARB",
+        gl::wgl_extra::ACCUM_GREEN_BITS_ARB,
+        None,
+    ),
+    (
+        "WGL_ACCUM_BLUE_BITS_ARB",
+        gl::wgl_extra::ACCUM_BLUE_BITS_ARB,
+        None,
+    ),
+    (
+        "WGL_ACCUM_ALPHA_BITS_ARB",
+        gl::wgl_extra::ACCUM_ALPHA_BITS_ARB,
+        None,
+    ),
+    ("WGL_DEPTH_BITS_ARB", gl::wgl_extra::DEPTH_BITS_ARB, None),
+    (
+        "WGL_STENCIL_BITS_ARB",
+        gl::wgl_extra::STENCIL_BITS_ARB,
+        None,
+    ),
+    ("WGL_AUX_BUFFERS_ARB", gl::wgl_extra::AUX_BUFFERS_ARB, None),
+    (
+        "WGL_SAMPLE_BUFFERS_ARB",
+        gl::wgl_extra::SAMPLE_BUFFERS_ARB,
+        Some("WGL_ARB_multisample"),
+    ),
+    (
+        "WGL_SAMPLES_ARB",
+        gl::wgl_extra::SAMPLES_ARB,
+        Some("WGL_ARB_multisample"),
+    ),
+    (
+        "WGL_FRAMEBUFFER_SRGB_CAPABLE_ARB",
+        gl::wgl_extra::FRAMEBUFFER_SRGB_CAPABLE_ARB,
+        Some("WGL_ARB_framebuffer_sRGB"),
+    ),
+];
+
+/// Returns the WGL extensions supported by the driver of `window`.
+pub unsafe fn enumerate_extensions(
+    win: HWND,
+) -> Result<Extensions, CreationError> {
+    let hdc = GetDC(win);
+    if hdc.is_null() {
         return Err(CreationError::OsError(format!(
-            "SetPixelFormat function failed: {}",
+            "GetDC function failed: {}",
             std::io::Error::last_os_error()
         )));
     }
+    let extra_functions = extra_functions(win);
+    let extensions = extra_functions
+        .map(|extra_functions| query_extensions(&extra_functions, hdc));
+    ReleaseDC(win, hdc);
+    extensions
+}
 
-    Ok(())
+/// Returns the distinct `WGL_SAMPLES_ARB` of the window pixel formats of the
+/// device context of `window` that match the requirements, apart from
+/// multisampling. `0` stands for formats without multisampling.
+pub unsafe fn supported_sample_counts(
+    win: HWND,
+    pf_reqs: &PixelFormatRequirements,
+) -> Result<Vec<u16>, CreationError> {
+    let hdc = GetDC(win);
+    if hdc.is_null() {
+        return Err(CreationError::OsError(format!(
+            "GetDC function failed: {}",
+            std::io::Error::last_os_error()
+        )));
+    }
+    let counts = extra_functions(win).and_then(|extra_functions| {
+        let extensions = query_extensions(&extra_functions, hdc);
+        arb_sample_counts(&extra_functions, &extensions, hdc, pf_reqs)
+    });
+    ReleaseDC(win, hdc);
+    counts
+}
+
+unsafe fn arb_sample_counts(
+    extra: &gl::wgl_extra::Wgl,
+    extensions: &Extensions,
+    hdc: HDC,
+    pf_reqs: &PixelFormatRequirements,
+) -> Result<Vec<u16>, CreationError> {
+    if !extensions.contains("WGL_ARB_pixel_format") {
+        return Err(CreationError::NotSupported(
+            "Enumerating sample counts requires WGL_ARB_pixel_format"
+                .to_string(),
+        ));
+    }
+
+    let mut pf_reqs = PixelFormatRequirements {
+        multisampling: None,
+        multisampling_exact: false,
+        // the preference falls back to any swap method
+        wgl_child_window: false,
+        ..pf_reqs.clone()
+    };
+    // when sRGB is only preferred, both kinds of formats can be chosen
+    let variants = if pf_reqs.srgb && !pf_reqs.srgb_required {
+        pf_reqs.srgb_required = true;
+        let fallback = PixelFormatRequirements {
+            srgb: false,
+            ..pf_reqs.clone()
+        };
+        vec![pf_reqs, fallback]
+    } else {
+        vec![pf_reqs]
+    };
+
+    let mut counts = Vec::new();
+    for pf_reqs in variants {
+        let descriptor =
+            match arb_pixel_format_attributes(extensions, &pf_reqs, false) {
+                Ok(descriptor) => descriptor,
+                Err(()) => continue,
+            };
+        let mut format_ids: Vec<raw::c_int> = vec![0; 256];
+        let mut num_formats = 0;
+        if extra.ChoosePixelFormatARB(
+            hdc as *const _,
+            descriptor.as_ptr(),
+            std::ptr::null(),
+            format_ids.len() as _,
+            format_ids.as_mut_ptr(),
+            &mut num_formats,
+        ) == 0
+        {
+            continue;
+        }
+        format_ids.truncate(num_formats as usize);
+
+        counts.extend(format_ids.into_iter().map(|format_id| {
+            // formats without `WGL_ARB_multisample` have no samples
+            let mut value = 0;
+            extra.GetPixelFormatAttribivARB(
+                hdc as *const _,
+                format_id,
+                0,
+                1,
+                [gl::wgl_extra::SAMPLES_ARB as raw::c_int].as_ptr(),
+                &mut value,
+            );
+            value as u16
+        }));
+    }
+    counts.sort();
+    counts.dedup();
+    Ok(counts)
+}
+
+/// Enumerates the pixel formats of the device context of `window`, with
+/// every attribute of `WGL_ARB_pixel_format`. The pixel formats are described
+/// the same way as the one chosen when creating a context.
+pub unsafe fn enumerate_pixel_formats(
+    win: HWND,
+) -> Result<Vec<WglPixelFormat>, CreationError> {
+    let hdc = GetDC(win);
+    if hdc.is_null() {
+        return Err(CreationError::OsError(format!(
+            "GetDC function failed: {}",
+            std::io::Error::last_os_error()
+        )));
+    }
+    let pixel_formats = extra_functions(win).and_then(|extra_functions| {
+        let extensions = query_extensions(&extra_functions, hdc);
+        if extensions.contains("WGL_ARB_pixel_format") {
+            enumerate_arb_pixel_formats(&extra_functions, &extensions, hdc)
+        } else {
+            Ok(enumerate_native_pixel_formats(hdc))
+        }
+    });
+    ReleaseDC(win, hdc);
+    pixel_formats
+}
+
+unsafe fn enumerate_arb_pixel_formats(
+    extra: &gl::wgl_extra::Wgl,
+    extensions: &Extensions,
+    hdc: HDC,
+) -> Result<Vec<WglPixelFormat>, CreationError> {
+    let mut count = 0;
+    if extra.GetPixelFormatAttribivARB(
+        hdc as *const _,
+        1,
+        0,
+        1,
+        [gl::wgl_extra::NUMBER_PIXEL_FORMATS_ARB as raw::c_int].as_ptr(),
+        &mut count,
+    ) == 0
+    {
+        return Err(last_error("wglGetPixelFormatAttribivARB failed"));
+    }
+
+    let attributes: Vec<_> = ARB_ATTRIBUTES
+        .iter()
+        .filter(|&&(_, _, extension)| {
+            extension.map_or(true, |extension| extensions.contains(extension))
+        })
+        .collect();
+    let names: Vec<_> = attributes
+        .iter()
+        .map(|&&(_, attrib, _)| attrib as raw::c_int)
+        .collect();
+
+    let mut pixel_formats = Vec::with_capacity(count as usize);
+    for id in 1..=count {
+        // all the attributes are queried at once
+        let mut values = vec![0; names.len()];
+        if extra.GetPixelFormatAttribivARB(
+            hdc as *const _,
+            id,
+            0,
+            names.len() as UINT,
+            names.as_ptr(),
+            values.as_mut_ptr(),
+        ) == 0
+        {
+            return Err(last_error("wglGetPixelFormatAttribivARB failed"));
+        }
+
+        let pixel_format = choose_arb_pixel_format(extra, extensions, hdc, id)
+            .map_err(|_| CreationError::NoAvailablePixelFormat)?;
+        pixel_formats.push(WglPixelFormat {
+            id,
+            pixel_format,
+            attributes: attributes
+                .iter()
+                .map(|&&(name, _, _)| name)
+                .zip(values)
+                .collect(),
+        });
+    }
+    Ok(pixel_formats)
+}
+
+unsafe fn enumerate_native_pixel_formats(hdc: HDC) -> Vec<WglPixelFormat> {
+    // `DescribePixelFormat` returns the largest index
+    let count = DescribePixelFormat(hdc, 1, 0, std::ptr::null_mut());
+    (1..=count)
+        .filter_map(|id| {
+            describe_pixel_format(hdc, id).map(|output| (id, output))
+        })
+        .map(|(id, output)| WglPixelFormat {
+            id,
+            pixel_format: native_pixel_format(&output),
+            attributes: Vec::new(),
+        })
+        .collect()
+}
+
+/// Returns the WGL extensions supported by the driver of `hdc`.
+unsafe fn query_extensions(
+    extra_functions: &gl::wgl_extra::Wgl,
+    hdc: HDC,
+) -> Extensions {
+    if extra_functions.GetExtensionsStringARB.is_loaded() {
+        let data = extra_functions.GetExtensionsStringARB(hdc as *const _);
+        Extensions::parse(&CStr::from_ptr(data).to_string_lossy())
+    } else if extra_functions.GetExtensionsStringEXT.is_loaded() {
+        let data = extra_functions.GetExtensionsStringEXT();
+        Extensions::parse(&CStr::from_ptr(data).to_string_lossy())
+    } else {
+        Extensions::default()
+    }
+}
+
+/// Creates a pbuffer of `size` with the pixel format `id` of `hdc`, which can
+/// be bound as a texture of `texture_format` if given.
+unsafe fn create_pbuffer(
+    extra_functions: &gl::wgl_extra::Wgl,
+    hdc: HDC,
+    id: raw::c_int,
+    size: (u32, u32),
+    largest: bool,
+    texture_format: Option<u32>,
+) -> Result<PbufferWrapper, CreationError> {
+    if !largest {
+        // fails with a readable error instead of the allocation error of the
+        // driver
+        let get_info = |attrib: u32| {
+            let mut value = 0;
+            extra_functions.GetPixelFormatAttribivARB(
+                hdc as *const _,
+                id,
+                0,
+                1,
+                [attrib as raw::c_int].as_ptr(),
+                &mut value,
+            );
+            value as u64
+        };
+        crate::check_pbuffer_size(
+            size,
+            get_info(gl::wgl_extra::MAX_PBUFFER_WIDTH_ARB),
+            get_info(gl::wgl_extra::MAX_PBUFFER_HEIGHT_ARB),
+            get_info(gl::wgl_extra::MAX_PBUFFER_PIXELS_ARB),
+        )?;
+    }
+
+    let mut attributes = vec![
+        gl::wgl_extra::PBUFFER_LARGEST_ARB as raw::c_int,
+        largest as raw::c_int,
+    ];
+    if let Some(texture_format) = texture_format {
+        attributes.push(gl::wgl_extra::TEXTURE_FORMAT_ARB as raw::c_int);
+        attributes.push(texture_format as raw::c_int);
+        attributes.push(gl::wgl_extra::TEXTURE_TARGET_ARB as raw::c_int);
+        attributes.push(gl::wgl_extra::TEXTURE_2D_ARB as raw::c_int);
+    }
+    attributes.push(0);
+    let pbuffer = extra_functions.CreatePbufferARB(
+        hdc as *const _,
+        id,
+        size.0 as raw::c_int,
+        size.1 as raw::c_int,
+        attributes.as_ptr(),
+    );
+    if pbuffer.is_null() {
+        return Err(last_error("wglCreatePbufferARB function failed"));
+    }
+
+    let pbuffer_hdc = extra_functions.GetPbufferDCARB(pbuffer) as HDC;
+    if pbuffer_hdc.is_null() {
+        let err = last_error("wglGetPbufferDCARB function failed");
+        extra_functions.DestroyPbufferARB(pbuffer);
+        return Err(err);
+    }
+
+    Ok(PbufferWrapper {
+        extra_functions: extra_functions.clone(),
+        pbuffer,
+        hdc: pbuffer_hdc,
+        textureable: texture_format.is_some(),
+    })
+}
+
+// The format a textureable pbuffer is bound as, which has an alpha channel
+// if one was requested, along with the pixel format attribute it requires.
+fn texture_format(pf_reqs: &PixelFormatRequirements) -> (u32, u32) {
+    if pf_reqs.alpha_bits.unwrap_or(0) > 0 {
+        (
+            gl::wgl_extra::BIND_TO_TEXTURE_RGBA_ARB,
+            gl::wgl_extra::TEXTURE_RGBA_ARB,
+        )
+    } else {
+        (
+            gl::wgl_extra::BIND_TO_TEXTURE_RGB_ARB,
+            gl::wgl_extra::TEXTURE_RGB_ARB,
+        )
+    }
+}
+
+// A `CreationError` for the last failed call on this thread, which keeps the
+// code returned by `GetLastError`.
+fn last_error(message: &str) -> CreationError {
+    let err = std::io::Error::last_os_error();
+    let code = err.raw_os_error().unwrap_or(0) as u32;
+    CreationError::ApiError(
+        ErrorCode::Win32(code),
+        format!("{}: {}", message, err),
+    )
 }
 
 /// Loads the `opengl32.dll` library.
@@ -879,6 +2571,38 @@ unsafe fn load_opengl32_dll() -> Result<HMODULE, CreationError> {
     Ok(lib)
 }
 
+// `wglGetProcAddress` requires a current context, so loading the WGL
+// functions means creating a dummy window and context. They are only loaded
+// once per process.
+struct ExtraFunctions(gl::wgl_extra::Wgl);
+
+unsafe impl Send for ExtraFunctions {}
+
+impl std::fmt::Debug for ExtraFunctions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExtraFunctions").finish()
+    }
+}
+
+lazy_static! {
+    static ref EXTRA_FUNCTIONS: Mutex<Option<ExtraFunctions>> =
+        Mutex::new(None);
+}
+
+/// Returns the WGL functions that are not guaranteed to be supported,
+/// loading them with `window` the first time.
+unsafe fn extra_functions(
+    win: HWND,
+) -> Result<gl::wgl_extra::Wgl, CreationError> {
+    let mut cached = EXTRA_FUNCTIONS.lock();
+    if let Some(ExtraFunctions(ref extra_functions)) = *cached {
+        return Ok(extra_functions.clone());
+    }
+    let extra_functions = load_extra_functions(win)?;
+    *cached = Some(ExtraFunctions(extra_functions.clone()));
+    Ok(extra_functions)
+}
+
 /// Loads the WGL functions that are not guaranteed to be supported.
 ///
 /// The `window` must be passed because the driver can vary depending on the
@@ -1,6 +1,8 @@
 use super::*;
 
+use std::ffi::CStr;
 use std::marker::PhantomData;
+use std::os::raw;
 use winit::event_loop::EventLoopWindowTarget;
 
 /// Represents an OpenGL [`Context`].
@@ -59,6 +61,29 @@ impl<T: ContextCurrentState> Context<T> {
         }
     }
 
+    /// See [`ContextWrapper::make_current_draw_read`].
+    ///
+    /// [`ContextWrapper::make_current_draw_read`]:
+    /// struct.ContextWrapper.html#method.make_current_draw_read
+    pub unsafe fn make_current_draw_read<T2: ContextCurrentState>(
+        self,
+        draw: Option<&Context<T2>>,
+        read: Option<&Context<T2>>,
+    ) -> Result<Context<PossiblyCurrent>, (Self, ContextError)> {
+        let res = {
+            let draw = draw.map(|c| &c.context).unwrap_or(&self.context);
+            let read = read.map(|c| &c.context).unwrap_or(&self.context);
+            self.context.make_current_draw_read(draw, read)
+        };
+        match res {
+            Ok(()) => Ok(Context {
+                context: self.context,
+                phantom: PhantomData,
+            }),
+            Err(err) => Err((self, err)),
+        }
+    }
+
     /// See [`ContextWrapper::make_not_current`].
     ///
     /// [`ContextWrapper::make_not_current`]:
@@ -117,6 +142,83 @@ impl<T: ContextCurrentState> Context<T> {
     pub fn get_api(&self) -> Api {
         self.context.get_api()
     }
+
+    /// See [`ContextWrapper::priority`].
+    ///
+    /// [`ContextWrapper::priority`]:
+    /// struct.ContextWrapper.html#method.priority
+    pub fn priority(&self) -> Option<Priority> {
+        self.context.priority()
+    }
+
+    /// See [`ContextWrapper::release_behavior`].
+    ///
+    /// [`ContextWrapper::release_behavior`]:
+    /// struct.ContextWrapper.html#method.release_behavior
+    pub fn release_behavior(&self) -> ReleaseBehavior {
+        self.context.release_behavior()
+    }
+
+    /// See [`ContextWrapper::color_space`].
+    ///
+    /// [`ContextWrapper::color_space`]:
+    /// struct.ContextWrapper.html#method.color_space
+    pub fn color_space(&self) -> Option<ColorSpace> {
+        self.context.color_space()
+    }
+
+    /// See [`ContextWrapper::extensions`].
+    ///
+    /// [`ContextWrapper::extensions`]:
+    /// struct.ContextWrapper.html#method.extensions
+    pub fn extensions(&self) -> Result<&Extensions, ContextError> {
+        self.context.extensions()
+    }
+
+    /// Binds the color buffer of this context's pbuffer to the `GL_TEXTURE_2D`
+    /// texture bound in the current context, which is usually another one.
+    /// Call [`release_tex_image`] before rendering to the pbuffer again.
+    ///
+    /// The context must have been built with [`build_headless`] and
+    /// [`with_textureable_pbuffer`], otherwise this returns
+    /// [`ContextError::FunctionUnavailable`]. This is only supported with
+    /// EGL, through `eglBindTexImage`, and WGL, through `wglBindTexImageARB`.
+    ///
+    /// [`release_tex_image`]: struct.Context.html#method.release_tex_image
+    /// [`build_headless`]: struct.ContextBuilder.html#method.build_headless
+    /// [`with_textureable_pbuffer`]:
+    /// struct.ContextBuilder.html#method.with_textureable_pbuffer
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        self.context.bind_tex_image()
+    }
+
+    /// Releases the color buffer bound with [`bind_tex_image`], through
+    /// `eglReleaseTexImage` or `wglReleaseTexImageARB`.
+    ///
+    /// [`bind_tex_image`]: struct.Context.html#method.bind_tex_image
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        self.context.release_tex_image()
+    }
+
+    /// Returns the size of this context's pbuffer, which may be smaller than
+    /// requested if it was built with [`with_largest_pbuffer`].
+    ///
+    /// The context must have been built with [`build_headless`] on a backend
+    /// that uses pbuffers, which are GLX, EGL, WGL with `WGL_ARB_pbuffer` and
+    /// CGL, otherwise this returns [`ContextError::FunctionUnavailable`]. CGL
+    /// falls back to a context without a default framebuffer, which renders
+    /// to framebuffer objects, if `NSOpenGLPixelBuffer` fails.
+    ///
+    /// [`with_largest_pbuffer`]:
+    /// struct.ContextBuilder.html#method.with_largest_pbuffer
+    /// [`build_headless`]: struct.ContextBuilder.html#method.build_headless
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        self.context.pbuffer_size()
+    }
 }
 
 impl Context<PossiblyCurrent> {
@@ -127,6 +229,190 @@ impl Context<PossiblyCurrent> {
     pub fn get_proc_address(&self, addr: &str) -> *const core::ffi::c_void {
         self.context.get_proc_address(addr)
     }
+
+    /// See [`ContextWrapper::wait_gl`].
+    ///
+    /// [`ContextWrapper::wait_gl`]:
+    /// struct.ContextWrapper.html#method.wait_gl
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        self.context.wait_gl()
+    }
+
+    /// See [`ContextWrapper::wait_native`].
+    ///
+    /// [`ContextWrapper::wait_native`]:
+    /// struct.ContextWrapper.html#method.wait_native
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        self.context.wait_native()
+    }
+
+    /// See [`ContextWrapper::reset_status`].
+    ///
+    /// [`ContextWrapper::reset_status`]:
+    /// struct.ContextWrapper.html#method.reset_status
+    pub fn reset_status(&self) -> ResetStatus {
+        type GetGraphicsResetStatus = unsafe extern "system" fn() -> u32;
+
+        let addr = match self.reset_status_fn() {
+            Some(name) => self.get_proc_address(name),
+            None => return ResetStatus::NoError,
+        };
+        if addr.is_null() {
+            return ResetStatus::NoError;
+        }
+        unsafe {
+            let get_status: GetGraphicsResetStatus = std::mem::transmute(addr);
+            ResetStatus::from_raw(get_status())
+        }
+    }
+
+    // Returns the variant of `glGetGraphicsResetStatus` the context supports.
+    // Some loaders, such as `glXGetProcAddress`, return an address for any
+    // name, so this goes by the GL version and extensions instead.
+    fn reset_status_fn(&self) -> Option<&'static str> {
+        type GetString = unsafe extern "system" fn(u32) -> *const raw::c_char;
+        type GetStringi =
+            unsafe extern "system" fn(u32, u32) -> *const raw::c_char;
+        type GetIntegerv = unsafe extern "system" fn(u32, *mut i32);
+        const VERSION: u32 = 0x1F02;
+        const EXTENSIONS: u32 = 0x1F03;
+        const NUM_EXTENSIONS: u32 = 0x821D;
+
+        let addr = self.get_proc_address("glGetString");
+        if addr.is_null() {
+            return None;
+        }
+        let get_string: GetString = unsafe { std::mem::transmute(addr) };
+        let string = |ptr: *const raw::c_char| {
+            if ptr.is_null() {
+                None
+            } else {
+                Some(unsafe { CStr::from_ptr(ptr) }.to_string_lossy())
+            }
+        };
+
+        let version = string(unsafe { get_string(VERSION) })?;
+        let (es, version) = parse_gl_version(&version)?;
+        if (es && version >= (3, 2)) || (!es && version >= (4, 5)) {
+            return Some("glGetGraphicsResetStatus");
+        }
+
+        // `GL_EXTENSIONS` isn't a valid `glGetString` name in core profiles
+        let get_stringi = self.get_proc_address("glGetStringi");
+        let get_integerv = self.get_proc_address("glGetIntegerv");
+        let extensions = if version.0 >= 3
+            && !get_stringi.is_null()
+            && !get_integerv.is_null()
+        {
+            let get_stringi: GetStringi =
+                unsafe { std::mem::transmute(get_stringi) };
+            let get_integerv: GetIntegerv =
+                unsafe { std::mem::transmute(get_integerv) };
+            let mut count = 0;
+            unsafe { get_integerv(NUM_EXTENSIONS, &mut count) };
+            (0..count.max(0) as u32)
+                .filter_map(|i| string(unsafe { get_stringi(EXTENSIONS, i) }))
+                .map(|name| name.into_owned())
+                .collect::<Vec<_>>()
+        } else {
+            string(unsafe { get_string(EXTENSIONS) })
+                .map(|names| {
+                    names.split_whitespace().map(str::to_owned).collect()
+                })
+                .unwrap_or_default()
+        };
+        let has = |name: &str| extensions.iter().any(|ext| ext == name);
+
+        // `GL_KHR_robustness` only suffixes its functions in OpenGL ES
+        if has("GL_KHR_robustness") {
+            Some(if es {
+                "glGetGraphicsResetStatusKHR"
+            } else {
+                "glGetGraphicsResetStatus"
+            })
+        } else if has("GL_ARB_robustness") {
+            Some("glGetGraphicsResetStatusARB")
+        } else if has("GL_EXT_robustness") {
+            Some("glGetGraphicsResetStatusEXT")
+        } else {
+            None
+        }
+    }
+
+    /// See [`ContextWrapper::is_lost`].
+    ///
+    /// [`ContextWrapper::is_lost`]: struct.ContextWrapper.html#method.is_lost
+    pub fn is_lost(&self) -> bool {
+        self.reset_status() != ResetStatus::NoError
+    }
+
+    /// See [`ContextWrapper::is_no_error`].
+    ///
+    /// [`ContextWrapper::is_no_error`]:
+    /// struct.ContextWrapper.html#method.is_no_error
+    pub fn is_no_error(&self) -> bool {
+        type GetIntegerv = unsafe extern "system" fn(u32, *mut i32);
+        const CONTEXT_FLAGS: u32 = 0x821E;
+        const CONTEXT_FLAG_NO_ERROR_BIT: i32 = 0x0008;
+
+        let addr = self.get_proc_address("glGetIntegerv");
+        if addr.is_null() {
+            return false;
+        }
+        // older contexts raise `GL_INVALID_ENUM` and leave the value alone
+        let mut flags = 0;
+        unsafe {
+            let get_integerv: GetIntegerv = std::mem::transmute(addr);
+            get_integerv(CONTEXT_FLAGS, &mut flags);
+        }
+        flags & CONTEXT_FLAG_NO_ERROR_BIT != 0
+    }
+}
+
+// Parses a `GL_VERSION` string, such as `4.6 (Core Profile) Mesa 20.0` or
+// `OpenGL ES 3.2 Mesa 20.0`, into whether it is OpenGL ES and its version.
+fn parse_gl_version(version: &str) -> Option<(bool, (u32, u32))> {
+    let (es, version) = if version.starts_with("OpenGL ES") {
+        // `OpenGL ES-CM 1.1` and `OpenGL ES-CL 1.1` for OpenGL ES 1
+        (true, version.split(' ').nth(2)?)
+    } else {
+        (false, version)
+    };
+    let mut numbers = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|n| n.parse::<u32>());
+    match (numbers.next(), numbers.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) => Some((es, (major, minor))),
+        _ => None,
+    }
+}
+
+impl Context<NotCurrent> {
+    /// Copies the fixed-function state selected by `mask` from `src` into
+    /// this context, like `glPushAttrib` and `glPopAttrib` would. Objects
+    /// such as textures and buffers aren't copied, share them with
+    /// [`with_shared_lists`] instead.
+    ///
+    /// This context must not be current on any thread, which is why it has to
+    /// be [`NotCurrent`]. If `src` is current, it must be on this thread,
+    /// and its pending commands are flushed first.
+    ///
+    /// This uses `glXCopyContext` or `wglCopyContext`, and requires both
+    /// contexts to use the same one, on the same X display with GLX.
+    /// Otherwise, this returns [`ContextError::FunctionUnavailable`].
+    ///
+    /// [`with_shared_lists`]:
+    /// struct.ContextBuilder.html#method.with_shared_lists
+    /// [`NotCurrent`]: enum.NotCurrent.html
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub fn copy_state_from<T: ContextCurrentState>(
+        &self,
+        src: &Context<T>,
+        mask: StateMask,
+    ) -> Result<(), ContextError> {
+        self.context.copy_state_from(&src.context, mask)
+    }
 }
 
 impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
@@ -107,6 +107,7 @@ pub use winit::*;
 
 use winit::error::OsError;
 
+use std::collections::HashSet;
 use std::io;
 
 /// An object that allows you to build [`Context`]s, [`RawContext<T>`]s and
@@ -153,6 +154,28 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
         self
     }
 
+    /// Sets whether the version requested with [`with_gl`] must be honored
+    /// exactly, rather than clamped to the latest version the platform
+    /// supports.
+    ///
+    /// The default value is `false`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// This is only honored by CGL, as macOS supports at most OpenGL 4.1 with
+    /// the core profile and 2.1 with the compatibility profile. Without it,
+    /// a higher version is clamped, and [`CglContextExt::version`] reports
+    /// the version obtained.
+    ///
+    /// [`with_gl`]: #method.with_gl
+    /// [`CglContextExt::version`]:
+    /// platform/macos/trait.CglContextExt.html#tymethod.version
+    #[inline]
+    pub fn with_exact_gl_version(mut self, exact_version: bool) -> Self {
+        self.gl_attr.exact_version = exact_version;
+        self
+    }
+
     /// Sets the *debug* flag for the OpenGL [`Context`].
     ///
     /// The default value for this flag is `cfg!(debug_assertions)`, which means
@@ -166,6 +189,17 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
         self
     }
 
+    /// Sets the *forward compatible* flag for the OpenGL [`Context`].
+    ///
+    /// The default value for this flag is `false`.
+    ///
+    /// [`Context`]: struct.Context.html
+    #[inline]
+    pub fn with_gl_forward_compatible_flag(mut self, flag: bool) -> Self {
+        self.gl_attr.forward_compatible = flag;
+        self
+    }
+
     /// Sets the robustness of the OpenGL [`Context`]. See the docs of
     /// [`Robustness`].
     ///
@@ -177,15 +211,131 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
         self
     }
 
+    /// Requests that a reset caused by the OpenGL [`Context`] doesn't affect
+    /// the other contexts of the system, and that resets caused by them don't
+    /// affect it. See the docs of [`GlAttributes::reset_isolation`].
+    ///
+    /// [`Context`]: struct.Context.html
+    /// [`GlAttributes::reset_isolation`]:
+    /// struct.GlAttributes.html#structfield.reset_isolation
+    #[inline]
+    pub fn with_reset_isolation(mut self, reset_isolation: bool) -> Self {
+        self.gl_attr.reset_isolation = reset_isolation;
+        self
+    }
+
+    /// Requests a `GL_KHR_no_error` context, in which the driver doesn't
+    /// check for errors. This is a shortcut for setting the robustness to
+    /// [`NoError`], see its docs. Passing `false` reverts it to
+    /// [`NotRobust`].
+    ///
+    /// The *debug* flag is enabled by default in debug builds, so you will
+    /// likely want to disable it with [`with_gl_debug_flag`] as well.
+    ///
+    /// [`NoError`]: enum.Robustness.html#variant.NoError
+    /// [`NotRobust`]: enum.Robustness.html#variant.NotRobust
+    /// [`with_gl_debug_flag`]: #method.with_gl_debug_flag
+    #[inline]
+    pub fn with_no_error(mut self, no_error: bool) -> Self {
+        if no_error {
+            self.gl_attr.robustness = Robustness::NoError;
+        } else if self.gl_attr.robustness == Robustness::NoError {
+            self.gl_attr.robustness = Robustness::NotRobust;
+        }
+        self
+    }
+
+    /// Sets what happens to the OpenGL [`Context`] when it stops being
+    /// current. See the docs of [`ReleaseBehavior`].
+    ///
+    /// The default value is [`Flush`].
+    ///
+    /// [`Context`]: struct.Context.html
+    /// [`ReleaseBehavior`]: enum.ReleaseBehavior.html
+    /// [`Flush`]: enum.ReleaseBehavior.html#variant.Flush
+    #[inline]
+    pub fn with_release_behavior(mut self, behavior: ReleaseBehavior) -> Self {
+        self.pf_reqs.release_behavior = behavior;
+        self
+    }
+
     /// Requests that the window has vsync enabled.
     ///
-    /// By default, vsync is not enabled.
+    /// By default, vsync is not enabled. It can be changed after creation
+    /// with [`set_swap_interval`].
+    ///
+    /// [`set_swap_interval`]:
+    /// struct.ContextWrapper.html#method.set_swap_interval
     #[inline]
     pub fn with_vsync(mut self, vsync: bool) -> Self {
         self.gl_attr.vsync = vsync;
         self
     }
 
+    /// Sets whether the OpenGL [`Context`] must render directly to the
+    /// hardware, or through the X server. See the docs of
+    /// [`DirectRendering`].
+    ///
+    /// The default is [`DirectRendering::PreferDirect`].
+    ///
+    /// [`Context`]: struct.Context.html
+    /// [`DirectRendering`]: enum.DirectRendering.html
+    /// [`DirectRendering::PreferDirect`]:
+    /// enum.DirectRendering.html#variant.PreferDirect
+    #[inline]
+    pub fn with_direct_rendering(
+        mut self,
+        direct_rendering: DirectRendering,
+    ) -> Self {
+        self.gl_attr.direct_rendering = direct_rendering;
+        self
+    }
+
+    /// Requests a scheduling priority for the OpenGL [`Context`]. See the
+    /// docs of [`Priority`].
+    ///
+    /// [`Context`]: struct.Context.html
+    /// [`Priority`]: enum.Priority.html
+    #[inline]
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.gl_attr.priority = Some(priority);
+        self
+    }
+
+    /// Requests that the OpenGL [`Context`] is created without a config, so
+    /// that it can be made current with surfaces of other configs, for
+    /// example an RGBA8 window and an RGBA16F pbuffer.
+    ///
+    /// This requires EGL and `EGL_KHR_no_config_context`. Since it only lifts
+    /// a restriction, it is ignored if unsupported.
+    ///
+    /// By default, the context is created with the config of its surface.
+    ///
+    /// [`Context`]: struct.Context.html
+    #[inline]
+    pub fn with_no_config(mut self, no_config: bool) -> Self {
+        self.gl_attr.no_config = no_config;
+        self
+    }
+
+    /// Requests that the OpenGL [`Context`] and its surface are protected,
+    /// so that they can hold protected content such as DRM-protected video.
+    /// Protected content can't be read back by unprotected contexts.
+    ///
+    /// This requires EGL and `EGL_EXT_protected_content`. Context creation
+    /// fails with [`CreationError::NotSupported`] otherwise.
+    ///
+    /// By default, the context isn't protected.
+    ///
+    /// [`Context`]: struct.Context.html
+    /// [`CreationError::NotSupported`]:
+    /// enum.CreationError.html#variant.NotSupported
+    #[inline]
+    pub fn with_protected_content(mut self, protected_content: bool) -> Self {
+        self.gl_attr.protected_content = protected_content;
+        self
+    }
+
     /// Share the display lists with the given [`Context`].
     ///
     /// [`Context`]: struct.Context.html
@@ -215,6 +365,61 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
                 Some(samples)
             }
         };
+        self.pf_reqs.multisampling_exact = false;
+        self
+    }
+
+    /// Sets the exact multisampling level to request. Unlike
+    /// [`with_multisampling`], which some drivers treat as a minimum, only
+    /// formats with exactly `samples` samples per pixel will be considered. A
+    /// value of `0` only considers formats without multisampling.
+    ///
+    /// # Panic
+    ///
+    /// Will panic if `samples` is not a power of two.
+    ///
+    /// ## Platform-specific
+    ///
+    /// This is only honored by EGL, GLX and WGL. Other backends behave like
+    /// [`with_multisampling`].
+    ///
+    /// [`with_multisampling`]: #method.with_multisampling
+    #[inline]
+    pub fn with_multisampling_exact(mut self, samples: u16) -> Self {
+        assert!(samples == 0 || samples.is_power_of_two());
+        self.pf_reqs.multisampling = Some(samples);
+        self.pf_reqs.multisampling_exact = true;
+        self
+    }
+
+    /// Requests coverage sampling (CSAA) with `samples` coverage samples per
+    /// pixel, in addition to the color samples requested with
+    /// [`with_multisampling`]. Passing `0` disables it.
+    ///
+    /// If coverage sampling isn't supported, ordinary multisampling is used
+    /// instead. The `coverage_samples` field of the [`PixelFormat`] tells
+    /// which was obtained.
+    ///
+    /// # Panic
+    ///
+    /// Will panic if `samples` is not a power of two.
+    ///
+    /// ## Platform-specific
+    ///
+    /// This is only honored by GLX and WGL, with their
+    /// `NV_multisample_coverage` extensions. Other backends ignore it.
+    ///
+    /// [`with_multisampling`]: #method.with_multisampling
+    /// [`PixelFormat`]: struct.PixelFormat.html
+    #[inline]
+    pub fn with_coverage_samples(mut self, samples: u16) -> Self {
+        self.pf_reqs.coverage_samples = match samples {
+            0 => None,
+            _ => {
+                assert!(samples.is_power_of_two());
+                Some(samples)
+            }
+        };
         self
     }
 
@@ -233,6 +438,11 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
     }
 
     /// Sets the number of bits in the color buffer.
+    ///
+    /// ## Platform-specific
+    ///
+    /// With WGL, more than 24 color bits, such as 30 for 10 bits per channel,
+    /// are split evenly between the red, green and blue channels.
     #[inline]
     pub fn with_pixel_format(mut self, color_bits: u8, alpha_bits: u8) -> Self {
         self.pf_reqs.color_bits = Some(color_bits);
@@ -240,22 +450,252 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
         self
     }
 
-    /// Request the backend to be stereoscopic.
+    /// Sets whether the pixel format must be stereoscopic, or quad-buffered,
+    /// with a left and a right back buffer. `None` means "don't care".
+    ///
+    /// The default value is `Some(false)`. Requiring stereoscopy fails
+    /// creation with [`CreationError::NotSupported`] if no stereo-capable
+    /// config matches the other requirements.
+    ///
+    /// ## Platform-specific
+    ///
+    /// This option will be taken into account on the following platforms:
+    ///
+    ///   * MacOS
+    ///   * Unix operating systems using GLX
+    ///   * Windows using WGL
+    ///
+    /// EGL, iOS, WebGL and OsMesa have no stereo configs.
+    ///
+    /// [`CreationError::NotSupported`]:
+    /// enum.CreationError.html#variant.NotSupported
     #[inline]
-    pub fn with_stereoscopy(mut self) -> Self {
-        self.pf_reqs.stereoscopy = true;
+    pub fn with_stereoscopy(mut self, stereoscopy: Option<bool>) -> Self {
+        self.pf_reqs.stereoscopy = stereoscopy;
         self
     }
 
     /// Sets whether sRGB should be enabled on the window.
     ///
+    /// Whether the chosen format is sRGB-capable is reported by the `srgb`
+    /// field of the [`PixelFormat`]. If it is, enable `GL_FRAMEBUFFER_SRGB`
+    /// to have writes gamma encoded.
+    ///
     /// The default value is `true`.
+    ///
+    /// [`PixelFormat`]: struct.PixelFormat.html
     #[inline]
     pub fn with_srgb(mut self, srgb_enabled: bool) -> Self {
         self.pf_reqs.srgb = srgb_enabled;
         self
     }
 
+    /// Sets whether requesting sRGB with [`with_srgb`] fails when no
+    /// sRGB-capable format matches. If false, sRGB is only preferred, and a
+    /// format that isn't sRGB-capable is chosen instead.
+    ///
+    /// The default value is `true`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// This is only honored by GLX, where remote and virtual X servers often
+    /// have no sRGB-capable formats, and WGL, where drivers without
+    /// `WGL_ARB_framebuffer_sRGB` or `WGL_EXT_framebuffer_sRGB` have none.
+    /// Other backends ignore it.
+    ///
+    /// [`with_srgb`]: #method.with_srgb
+    #[inline]
+    pub fn with_srgb_required(mut self, srgb_required: bool) -> Self {
+        self.pf_reqs.srgb_required = srgb_required;
+        self
+    }
+
+    /// Sets whether the back buffer must keep its contents when swapping
+    /// buffers, so that only what changed needs to be redrawn. The
+    /// resulting behavior is reported by the [`SwapBehavior`] of
+    /// [`PixelFormat`].
+    ///
+    /// The default value is `false`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// EGL requires version 1.4, GLX requires `GLX_OML_swap_method` and WGL
+    /// requires `WGL_ARB_pixel_format`. This is not supported by EAGL, WebGL
+    /// and OsMesa, which fail with [`CreationError::NotSupported`].
+    ///
+    /// [`SwapBehavior`]: enum.SwapBehavior.html
+    /// [`PixelFormat`]: struct.PixelFormat.html
+    /// [`CreationError::NotSupported`]: enum.CreationError.html#variant.NotSupported
+    #[inline]
+    pub fn with_preserved_swap(mut self, preserved_swap: bool) -> Self {
+        self.pf_reqs.preserved_swap = preserved_swap;
+        self
+    }
+
+    /// Requires the format to support swap intervals up to at least
+    /// `interval`, so that for example an interval of `2` halves the frame
+    /// rate instead of being clamped to `1`. The supported range is reported
+    /// by the `swap_interval_range` of [`PixelFormat`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// This is only honored by EGL, where the range depends on the config.
+    /// Other backends ignore it.
+    ///
+    /// [`PixelFormat`]: struct.PixelFormat.html
+    #[inline]
+    pub fn with_max_swap_interval_at_least(mut self, interval: u32) -> Self {
+        self.pf_reqs.max_swap_interval = Some(interval);
+        self
+    }
+
+    /// Sets the APIs to try, in order, when creating a windowed context. If
+    /// one fails to find a pixel format or to create the context, the next
+    /// one is tried. See [`Backend`].
+    ///
+    /// The default is an empty list, for which WGL is used for OpenGL and
+    /// EGL is preferred for OpenGL ES.
+    ///
+    /// ## Platform-specific
+    ///
+    /// This is only honored on Windows, and only by contexts that don't share
+    /// lists with another context, which use the API of that context.
+    ///
+    /// EGL is loaded as configured by `EglDisplayBuilder`, which by default
+    /// loads the `libEGL.dll` next to the executable or in the search path.
+    /// If every API fails, [`CreationError::CreationErrors`] holds the
+    /// error of each one. The API that was used is returned by
+    /// `WglContextExt::backend`.
+    ///
+    /// [`Backend`]: enum.Backend.html
+    /// [`CreationError::CreationErrors`]:
+    /// enum.CreationError.html#variant.CreationErrors
+    #[inline]
+    pub fn with_windows_backends(mut self, backends: &[Backend]) -> Self {
+        self.pf_reqs.windows_backends = backends.to_vec();
+        self
+    }
+
+    /// Sets the GPU a headless context renders on, for systems with several
+    /// GPUs such as laptops with hybrid graphics. See [`GpuId`].
+    ///
+    /// A context associated with an AMD GPU has no default framebuffer, so it
+    /// has to render to framebuffer objects.
+    ///
+    /// ## Platform-specific
+    ///
+    /// This is only honored by WGL, through `WGL_NV_gpu_affinity` or
+    /// `WGL_AMD_gpu_association`, for [`build_headless`]. Building a windowed
+    /// context with a GPU, or a headless one when the extension of the GPU is
+    /// missing, fails with [`CreationError::NotSupported`]. Other backends
+    /// ignore it.
+    ///
+    /// Without either extension, the drivers of hybrid graphics can be asked
+    /// to run the whole program on the discrete GPU by exporting the
+    /// `NvOptimusEnablement` and `AmdPowerXpressRequestHighPerformance`
+    /// symbols from the executable. glutin has no crate feature defining
+    /// them: the drivers only read the export table of the executable, and
+    /// rustc doesn't export the statics of an executable or of its
+    /// dependencies there. The application has to define them itself:
+    ///
+    /// ```no_run
+    /// #![allow(non_upper_case_globals)]
+    /// #[no_mangle]
+    /// pub static NvOptimusEnablement: u32 = 1;
+    /// #[no_mangle]
+    /// pub static AmdPowerXpressRequestHighPerformance: u32 = 1;
+    /// ```
+    ///
+    /// and have the linker export them, for instance with
+    /// `/EXPORT:NvOptimusEnablement` and
+    /// `/EXPORT:AmdPowerXpressRequestHighPerformance` when linking with MSVC.
+    ///
+    /// [`GpuId`]: enum.GpuId.html
+    /// [`build_headless`]: struct.ContextBuilder.html#method.build_headless
+    /// [`CreationError::NotSupported`]:
+    /// enum.CreationError.html#variant.NotSupported
+    #[inline]
+    pub fn with_gpu(mut self, gpu: GpuId) -> Self {
+        self.pf_reqs.gpu = Some(gpu);
+        self
+    }
+
+    /// Sets the color space of the surface. See the docs of [`ColorSpace`].
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`ColorSpace`]: enum.ColorSpace.html
+    #[inline]
+    pub fn with_color_space(mut self, color_space: ColorSpace) -> Self {
+        self.pf_reqs.color_space = Some(color_space);
+        self
+    }
+
+    /// Sets whether the color buffer must store floating point components,
+    /// which is needed to write values outside of the `[0.0, 1.0]` range, for
+    /// example for HDR output.
+    ///
+    /// The default value is `false`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// EGL requires `EGL_EXT_pixel_format_float` and fails with
+    /// [`CreationError::NotSupported`] without it. WGL requires
+    /// `WGL_ARB_pixel_format_float`, and its error names the closest
+    /// non-float format.
+    ///
+    /// [`CreationError::NotSupported`]: enum.CreationError.html#variant.NotSupported
+    #[inline]
+    pub fn with_float_color_buffer(mut self, float_color_buffer: bool) -> Self {
+        self.pf_reqs.float_color_buffer = float_color_buffer;
+        self
+    }
+
+    /// Requests that the pbuffer of a headless [`Context`] can be bound as a
+    /// texture with [`Context::bind_tex_image`], instead of copying its
+    /// contents, for example on drivers without framebuffer objects.
+    ///
+    /// This only applies to [`build_headless`], and requires EGL or
+    /// `WGL_ARB_render_texture`. Other backends fail with
+    /// [`CreationError::NotSupported`].
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`Context`]: struct.Context.html
+    /// [`Context::bind_tex_image`]: struct.Context.html#method.bind_tex_image
+    /// [`build_headless`]: struct.ContextBuilder.html#method.build_headless
+    /// [`CreationError::NotSupported`]:
+    /// enum.CreationError.html#variant.NotSupported
+    #[inline]
+    pub fn with_textureable_pbuffer(mut self, textureable: bool) -> Self {
+        self.pf_reqs.textureable = textureable;
+        self
+    }
+
+    /// Requests that the pbuffer of a headless [`Context`] is created as large
+    /// as possible, up to the requested size, when the requested size can't
+    /// be allocated. Use [`Context::pbuffer_size`] to find out the size it
+    /// got.
+    ///
+    /// Otherwise, building a pbuffer larger than the maximum of its config
+    /// fails with [`CreationError::NotSupported`], naming the maximum.
+    ///
+    /// This only applies to [`build_headless`] with GLX, EGL or WGL, and is
+    /// ignored otherwise.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`Context`]: struct.Context.html
+    /// [`Context::pbuffer_size`]: struct.Context.html#method.pbuffer_size
+    /// [`build_headless`]: struct.ContextBuilder.html#method.build_headless
+    /// [`CreationError::NotSupported`]:
+    /// enum.CreationError.html#variant.NotSupported
+    #[inline]
+    pub fn with_largest_pbuffer(mut self, largest_pbuffer: bool) -> Self {
+        self.pf_reqs.largest_pbuffer = largest_pbuffer;
+        self
+    }
+
     /// Sets whether double buffering should be enabled.
     ///
     /// The default value is `None`.
@@ -283,14 +723,29 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
     ///
     ///   * MacOS
     ///   * Unix operating systems using EGL with either X or Wayland
+    ///   * Unix operating systems using GLX with `GLX_MESA_query_renderer`,
+    ///     which rejects software renderers like llvmpipe only if this was
+    ///     called, so that the default doesn't rule out Xvfb or VMs
     ///   * Windows using EGL or WGL
     ///   * Android using EGL
+    ///
+    /// When WGL only finds software pixel formats, usually because the
+    /// graphics driver is broken, or CGL only finds the Apple software
+    /// renderer, requiring hardware acceleration fails with
+    /// [`CreationError::NoHardwareAcceleration`]. Otherwise check
+    /// [`PixelFormat::hardware_accelerated`] to warn about slow rendering.
+    ///
+    /// [`CreationError::NoHardwareAcceleration`]:
+    /// enum.CreationError.html#variant.NoHardwareAcceleration
+    /// [`PixelFormat::hardware_accelerated`]:
+    /// struct.PixelFormat.html#structfield.hardware_accelerated
     #[inline]
     pub fn with_hardware_acceleration(
         mut self,
         acceleration: Option<bool>,
     ) -> Self {
         self.pf_reqs.hardware_accelerated = acceleration;
+        self.pf_reqs.hardware_acceleration_explicit = true;
         self
     }
 }
@@ -302,16 +757,43 @@ pub enum CreationError {
     NotSupported(String),
     NoBackendAvailable(Box<dyn std::error::Error + Send + Sync>),
     RobustnessNotSupported,
+    ReleaseBehaviorNotSupported,
+    /// [`DirectRendering::RequireDirect`] was requested, but the context
+    /// renders indirectly.
+    ///
+    /// [`DirectRendering::RequireDirect`]:
+    /// enum.DirectRendering.html#variant.RequireDirect
+    DirectRenderingNotSupported,
     OpenGlVersionNotSupported,
     NoAvailablePixelFormat,
+    /// The window already has a pixel format which doesn't meet the
+    /// requirements, and a window's pixel format can only be set once. The
+    /// message describes the existing pixel format and what it lacks.
+    ///
+    /// This happens when a window is reused after building a context for it
+    /// with other requirements, and only on WGL.
+    PixelFormatAlreadySet(String),
+    /// Hardware acceleration was required, but only software pixel formats,
+    /// such as the ones of Microsoft's GDI Generic implementation, meet the
+    /// other requirements. This usually means the graphics driver is missing
+    /// or broken.
+    ///
+    /// This is only returned by WGL.
+    NoHardwareAcceleration,
     PlatformSpecific(String),
     Window(OsError),
     /// We received multiple errors, instead of one.
     CreationErrors(Vec<Box<CreationError>>),
+    /// A call to the windowing system API failed with the given error code.
+    /// See [`ErrorCode`].
+    ///
+    /// [`ErrorCode`]: enum.ErrorCode.html
+    ApiError(ErrorCode, String),
 }
 
 impl CreationError {
     #[cfg(any(
+        target_os = "windows",
         target_os = "linux",
         target_os = "dragonfly",
         target_os = "freebsd",
@@ -331,20 +813,49 @@ impl CreationError {
         }
     }
 
+    /// Returns what kind of failure this is, so that for example creation can
+    /// be retried with lower requirements. See [`ErrorKind`].
+    ///
+    /// [`ErrorKind`]: enum.ErrorKind.html
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            CreationError::ApiError(code, _) => code.kind(),
+            CreationError::NoAvailablePixelFormat
+            | CreationError::PixelFormatAlreadySet(_)
+            | CreationError::NoHardwareAcceleration => {
+                ErrorKind::BadConfigMatch
+            }
+            _ => ErrorKind::Other,
+        }
+    }
+
     fn to_string(&self) -> &str {
         match *self {
             CreationError::OsError(ref text)
-            | CreationError::NotSupported(ref text) => &text,
+            | CreationError::NotSupported(ref text)
+            | CreationError::PixelFormatAlreadySet(ref text)
+            | CreationError::ApiError(_, ref text) => &text,
             CreationError::NoBackendAvailable(_) => "No backend is available",
             CreationError::RobustnessNotSupported => {
                 "You requested robustness, but it is not supported."
             }
+            CreationError::ReleaseBehaviorNotSupported => {
+                "You requested a release behavior, but it is not supported."
+            }
+            CreationError::DirectRenderingNotSupported => {
+                "You required direct rendering, but the context renders \
+                 indirectly."
+            }
             CreationError::OpenGlVersionNotSupported => {
                 "The requested OpenGL version is not supported."
             }
             CreationError::NoAvailablePixelFormat => {
                 "Couldn't find any pixel format that matches the criteria."
             }
+            CreationError::NoHardwareAcceleration => {
+                "You required hardware acceleration, but only software pixel \
+                 formats are available."
+            }
             CreationError::PlatformSpecific(ref text) => &text,
             CreationError::Window(ref err) => {
                 std::error::Error::description(err)
@@ -403,17 +914,53 @@ pub enum ContextError {
     /// General platform error.
     OsError(String),
     IoError(io::Error),
+    /// The context was lost, for example to a graphics reset, and must be
+    /// recreated along with all of its resources. See
+    /// [`ContextWrapper::is_lost`].
+    ///
+    /// [`ContextWrapper::is_lost`]:
+    /// struct.ContextWrapper.html#method.is_lost
     ContextLost,
+    /// The display connection was terminated, for example by a driver update
+    /// or a restart of the display server. The context and every other
+    /// context on the same display must be recreated; building a new context
+    /// reinitializes the display.
+    DisplayLost,
     FunctionUnavailable,
+    /// The context can't be made current on the drawable because the
+    /// drawable wasn't created with the config of the context, for example
+    /// an X window with a 32-bit visual and a config with a 24-bit one. The
+    /// message compares both.
+    ConfigMismatch(String),
+    /// A call to the windowing system API failed with the given error code.
+    /// See [`ErrorCode`].
+    ///
+    /// [`ErrorCode`]: enum.ErrorCode.html
+    ApiError(ErrorCode, String),
 }
 
 impl ContextError {
+    /// Returns what kind of failure this is. See [`ErrorKind`].
+    ///
+    /// [`ErrorKind`]: enum.ErrorKind.html
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            ContextError::ApiError(code, _) => code.kind(),
+            ContextError::ContextLost => ErrorKind::ContextLost,
+            ContextError::ConfigMismatch(_) => ErrorKind::BadConfigMatch,
+            _ => ErrorKind::Other,
+        }
+    }
+
     fn to_string(&self) -> &str {
         use std::error::Error;
         match *self {
-            ContextError::OsError(ref string) => string,
+            ContextError::OsError(ref string)
+            | ContextError::ApiError(_, ref string)
+            | ContextError::ConfigMismatch(ref string) => string,
             ContextError::IoError(ref err) => err.description(),
             ContextError::ContextLost => "Context lost",
+            ContextError::DisplayLost => "Display lost",
             ContextError::FunctionUnavailable => "Function unavailable",
         }
     }
@@ -434,6 +981,109 @@ impl std::error::Error for ContextError {
     }
 }
 
+/// What kind of failure an error is, for the failures applications can
+/// react to the same way on every backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The context was lost and must be recreated.
+    ContextLost,
+    /// The driver ran out of memory. Requesting fewer or smaller buffers,
+    /// for example less multisampling, may succeed.
+    OutOfMemory,
+    /// The config doesn't match what was requested of it, for example the
+    /// context version or the surface. Requesting less may succeed.
+    BadConfigMatch,
+    /// Any other failure.
+    Other,
+}
+
+/// The error code a call to the windowing system API failed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// The error returned by `eglGetError`.
+    Egl(EglErrorCode),
+    /// The X11 protocol error code of a failed GLX request, like `BadMatch`
+    /// or `BadAlloc`.
+    X11(u8),
+    /// The error returned by `GetLastError` after a failed WGL call.
+    Win32(u32),
+}
+
+impl ErrorCode {
+    /// Returns what kind of failure the code stands for.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            ErrorCode::Egl(EglErrorCode::ContextLost) => ErrorKind::ContextLost,
+            ErrorCode::Egl(EglErrorCode::BadAlloc) => ErrorKind::OutOfMemory,
+            ErrorCode::Egl(EglErrorCode::BadMatch)
+            | ErrorCode::Egl(EglErrorCode::BadConfig) => {
+                ErrorKind::BadConfigMatch
+            }
+            // `BadMatch` and `BadAlloc`
+            ErrorCode::X11(8) => ErrorKind::BadConfigMatch,
+            ErrorCode::X11(11) => ErrorKind::OutOfMemory,
+            // `ERROR_NOT_ENOUGH_MEMORY` and `ERROR_OUTOFMEMORY`
+            ErrorCode::Win32(8) | ErrorCode::Win32(14) => {
+                ErrorKind::OutOfMemory
+            }
+            // `ERROR_INVALID_PIXEL_FORMAT`, `ERROR_INVALID_VERSION_ARB`,
+            // `ERROR_INVALID_PROFILE_ARB`, `ERROR_INVALID_PIXEL_TYPE_ARB` and
+            // `ERROR_INCOMPATIBLE_DEVICE_CONTEXTS_ARB`
+            ErrorCode::Win32(2000)
+            | ErrorCode::Win32(0x2095)
+            | ErrorCode::Win32(0x2096)
+            | ErrorCode::Win32(0x2043)
+            | ErrorCode::Win32(0x2054) => ErrorKind::BadConfigMatch,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// The error codes of EGL, as returned by `eglGetError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EglErrorCode {
+    NotInitialized,
+    BadAccess,
+    BadAlloc,
+    BadAttribute,
+    BadConfig,
+    BadContext,
+    BadCurrentSurface,
+    BadDisplay,
+    BadMatch,
+    BadNativePixmap,
+    BadNativeWindow,
+    BadParameter,
+    BadSurface,
+    ContextLost,
+    /// A code the EGL specification doesn't define, usually from an
+    /// extension.
+    Other(u32),
+}
+
+impl EglErrorCode {
+    /// Converts the value returned by `eglGetError`.
+    pub fn from_raw(code: u32) -> Self {
+        match code {
+            0x3001 => EglErrorCode::NotInitialized,
+            0x3002 => EglErrorCode::BadAccess,
+            0x3003 => EglErrorCode::BadAlloc,
+            0x3004 => EglErrorCode::BadAttribute,
+            0x3005 => EglErrorCode::BadConfig,
+            0x3006 => EglErrorCode::BadContext,
+            0x3007 => EglErrorCode::BadCurrentSurface,
+            0x3008 => EglErrorCode::BadDisplay,
+            0x3009 => EglErrorCode::BadMatch,
+            0x300A => EglErrorCode::BadNativePixmap,
+            0x300B => EglErrorCode::BadNativeWindow,
+            0x300C => EglErrorCode::BadParameter,
+            0x300D => EglErrorCode::BadSurface,
+            0x300E => EglErrorCode::ContextLost,
+            code => EglErrorCode::Other(code),
+        }
+    }
+}
+
 /// All APIs related to OpenGL that you can possibly get while using glutin.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Api {
@@ -521,7 +1171,12 @@ pub enum Robustness {
     ///
     /// Since this option is purely an optimization, no error will be returned
     /// if the backend doesn't support it. Instead it will automatically
-    /// fall back to [`NotRobust`].
+    /// fall back to [`NotRobust`]. It is supported by EGL, GLX and WGL with
+    /// their `create_context_no_error` extensions.
+    ///
+    /// No-error contexts can't be debug contexts, so context creation fails
+    /// if the *debug* flag is also set. A no-error context can't share lists
+    /// with a context that isn't one, and vice versa.
     ///
     /// [`NotRobust`]: enum.Robustness.html#variant.NotRobust
     NoError,
@@ -542,6 +1197,11 @@ pub enum Robustness {
     /// context will enter a "context lost" state. It must then be
     /// recreated. For the moment, glutin doesn't provide a way to recreate
     /// a context with the same window :-/
+    ///
+    /// All the contexts of a share group must have the same reset
+    /// notification strategy, so context creation fails if the shared
+    /// context loses its state on reset and the new one wouldn't, or vice
+    /// versa.
     RobustLoseContextOnReset,
 
     /// Same as [`RobustLoseContextOnReset`] but the context creation doesn't
@@ -552,10 +1212,372 @@ pub enum Robustness {
     TryRobustLoseContextOnReset,
 }
 
+impl Robustness {
+    /// Whether the context is asked to lose its state on reset. Whether it
+    /// does depends on the backend.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    #[inline]
+    pub(crate) fn loses_context_on_reset(self) -> bool {
+        match self {
+            Robustness::RobustLoseContextOnReset
+            | Robustness::TryRobustLoseContextOnReset => true,
+            _ => false,
+        }
+    }
+}
+
+/// The graphics reset status of a [`Context`], as reported by
+/// `glGetGraphicsResetStatus`.
+///
+/// A reset is only ever reported for contexts created with
+/// [`Robustness::RobustLoseContextOnReset`] or
+/// [`Robustness::TryRobustLoseContextOnReset`]. Once a reset has happened the
+/// context is lost for good: it and all of its resources must be recreated.
+///
+/// [`Context`]: struct.Context.html
+/// [`Robustness::RobustLoseContextOnReset`]:
+/// enum.Robustness.html#variant.RobustLoseContextOnReset
+/// [`Robustness::TryRobustLoseContextOnReset`]:
+/// enum.Robustness.html#variant.TryRobustLoseContextOnReset
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResetStatus {
+    /// No reset happened, or the driver can't report resets.
+    NoError,
+
+    /// A reset was caused by this context.
+    GuiltyContextReset,
+
+    /// A reset was caused by another context.
+    InnocentContextReset,
+
+    /// A reset happened, but its cause is unknown.
+    UnknownContextReset,
+}
+
+impl ResetStatus {
+    /// Converts a value returned by `glGetGraphicsResetStatus`.
+    pub(crate) fn from_raw(status: u32) -> ResetStatus {
+        const NO_ERROR: u32 = 0;
+        const GUILTY_CONTEXT_RESET: u32 = 0x8253;
+        const INNOCENT_CONTEXT_RESET: u32 = 0x8254;
+
+        match status {
+            NO_ERROR => ResetStatus::NoError,
+            GUILTY_CONTEXT_RESET => ResetStatus::GuiltyContextReset,
+            INNOCENT_CONTEXT_RESET => ResetStatus::InnocentContextReset,
+            _ => ResetStatus::UnknownContextReset,
+        }
+    }
+}
+
+/// Identifies a frame swapped with
+/// [`ContextWrapper::swap_buffers_with_frame_id`], to query its
+/// [`PresentationTimes`] later on.
+///
+/// [`ContextWrapper::swap_buffers_with_frame_id`]:
+/// struct.ContextWrapper.html#method.swap_buffers_with_frame_id
+/// [`PresentationTimes`]: struct.PresentationTimes.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrameId(pub(crate) u64);
+
+/// When a frame went through the stages of its presentation, as returned by
+/// [`ContextWrapper::presentation_times`].
+///
+/// The times are offsets from the epoch of `CLOCK_MONOTONIC`, which is the
+/// clock [`Instant`] uses on Linux and Android, and usually the time since
+/// boot. A time is `None` if the driver doesn't report it, or hasn't
+/// recorded it yet, in which case it may be available later.
+///
+/// [`ContextWrapper::presentation_times`]:
+/// struct.ContextWrapper.html#method.presentation_times
+/// [`Instant`]: https://doc.rust-lang.org/std/time/struct.Instant.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PresentationTimes {
+    /// The time the frame was requested to be presented at.
+    pub requested_present: Option<std::time::Duration>,
+
+    /// The time the GPU finished rendering the frame.
+    pub rendering_complete: Option<std::time::Duration>,
+
+    /// The time the frame started being scanned out to the display.
+    pub display_present: Option<std::time::Duration>,
+
+    /// The interval at which the compositor presents frames, which is
+    /// usually the refresh period of the display.
+    pub composite_interval: Option<std::time::Duration>,
+}
+
+/// The scheduling priority of an OpenGL [`Context`] relative to the other
+/// contexts on the system.
+///
+/// Only honored by EGL implementations advertising
+/// `EGL_IMG_context_priority`; on those without it the hint is silently
+/// dropped, and the driver may also grant a lower priority than requested.
+/// Other backends fail context creation with [`NotSupported`] when a priority
+/// is requested.
+///
+/// [`Context`]: struct.Context.html
+/// [`NotSupported`]: enum.CreationError.html#variant.NotSupported
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Priority {
+    /// Scheduled ahead of medium and low priority contexts. Typically
+    /// reserved for compositors and may require special privileges.
+    High,
+    /// The default priority of most contexts.
+    Medium,
+    /// Scheduled after medium and high priority contexts.
+    Low,
+}
+
+/// Whether an OpenGL [`Context`] renders directly to the hardware, or sends
+/// its commands through the X server, which is much slower but works over
+/// remote X connections.
+///
+/// Only GLX can render indirectly. Other backends always render directly,
+/// and fail context creation with [`NotSupported`] for
+/// [`RequireIndirect`].
+///
+/// [`Context`]: struct.Context.html
+/// [`NotSupported`]: enum.CreationError.html#variant.NotSupported
+/// [`RequireIndirect`]: enum.DirectRendering.html#variant.RequireIndirect
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DirectRendering {
+    /// Context creation fails with [`DirectRenderingNotSupported`] if the
+    /// context would render indirectly.
+    ///
+    /// [`DirectRenderingNotSupported`]:
+    /// enum.CreationError.html#variant.DirectRenderingNotSupported
+    RequireDirect,
+    /// Renders directly if possible, and indirectly otherwise.
+    PreferDirect,
+    /// Always renders indirectly.
+    RequireIndirect,
+}
+
+/// The color space in which the contents of a surface are interpreted.
+///
+/// Only supported by EGL; other backends fail context creation with
+/// [`NotSupported`] when a color space is requested. Use [`with_srgb`] there
+/// instead.
+///
+/// [`NotSupported`]: enum.CreationError.html#variant.NotSupported
+/// [`with_srgb`]: struct.ContextBuilder.html#method.with_srgb
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// Linear RGB. Requires EGL 1.5 or `EGL_KHR_gl_colorspace`.
+    Linear,
+    /// sRGB, so that writes to the framebuffer are gamma encoded when
+    /// `GL_FRAMEBUFFER_SRGB` is enabled. Requires EGL 1.5 or
+    /// `EGL_KHR_gl_colorspace`, and a config capable of sRGB rendering.
+    Srgb,
+    /// Linear extended sRGB, typically used with a floating point color
+    /// buffer for HDR. Requires `EGL_EXT_gl_colorspace_scrgb_linear`.
+    ScRgbLinear,
+    /// Display-P3 with the sRGB transfer function. Requires
+    /// `EGL_EXT_gl_colorspace_display_p3`.
+    DisplayP3,
+}
+
+/// How [`swap_buffers`] synchronizes with the vertical blank of the display.
+///
+/// [`swap_buffers`]: struct.ContextWrapper.html#method.swap_buffers
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SwapInterval {
+    /// Swap immediately, which may cause tearing.
+    DontWait,
+    /// Wait for the given number of vertical blanks before swapping.
+    Wait(std::num::NonZeroU32),
+    /// Like [`Wait`], but swap immediately if a vertical blank was already
+    /// missed, tearing instead of stalling for a whole frame.
+    ///
+    /// Requires `GLX_EXT_swap_control_tear` or `WGL_EXT_swap_control_tear`.
+    ///
+    /// [`Wait`]: enum.SwapInterval.html#variant.Wait
+    AdaptiveWait(std::num::NonZeroU32),
+}
+
+impl SwapInterval {
+    /// Converts a swap interval as used by `glXSwapIntervalEXT` and
+    /// `wglSwapIntervalEXT`, where negative values request adaptive vsync.
+    pub(crate) fn from_raw(interval: i32) -> SwapInterval {
+        match std::num::NonZeroU32::new(interval.abs() as u32) {
+            None => SwapInterval::DontWait,
+            Some(n) if interval < 0 => SwapInterval::AdaptiveWait(n),
+            Some(n) => SwapInterval::Wait(n),
+        }
+    }
+}
+
+/// How many swap groups and swap barriers `GLX_NV_swap_group` or
+/// `WGL_NV_swap_group` provide. Windows in the same swap group swap their
+/// buffers together, and swap groups bound to the same swap barrier swap
+/// together across systems.
+///
+/// Groups and barriers are numbered from `1` to the maximum, `0` standing for
+/// none.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SwapGroupLimits {
+    /// The highest swap group number.
+    pub max_groups: u32,
+    /// The highest swap barrier number.
+    pub max_barriers: u32,
+}
+
+impl SwapGroupLimits {
+    /// Returns an error if `group` isn't `0` or one of the swap groups.
+    #[allow(dead_code)]
+    pub(crate) fn check_group(&self, group: u32) -> Result<(), ContextError> {
+        if group > self.max_groups {
+            return Err(ContextError::OsError(format!(
+                "Swap group {} is out of range, the maximum is {}",
+                group, self.max_groups
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns an error if `barrier` isn't `0` or one of the swap barriers.
+    #[allow(dead_code)]
+    pub(crate) fn check_barrier(
+        &self,
+        barrier: u32,
+    ) -> Result<(), ContextError> {
+        if barrier > self.max_barriers {
+            return Err(ContextError::OsError(format!(
+                "Swap barrier {} is out of range, the maximum is {}",
+                barrier, self.max_barriers
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// An API used to create contexts on Windows. See
+/// [`ContextBuilder::with_windows_backends`].
+///
+/// [`ContextBuilder::with_windows_backends`]:
+/// struct.ContextBuilder.html#method.with_windows_backends
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// The OpenGL driver, through WGL.
+    Wgl,
+    /// The EGL library, usually ANGLE, which translates OpenGL ES to
+    /// Direct3D.
+    Egl,
+}
+
+/// Identifies a GPU of the system. Use one of the GPUs enumerated by the
+/// platform, for example with `glutin::platform::windows::enumerate_gpus`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GpuId {
+    /// A GPU id of `WGL_AMD_gpu_association`.
+    Amd(u32),
+    /// The index of a GPU of `WGL_NV_gpu_affinity`.
+    Nvidia(u32),
+}
+
+/// Describes a GPU of the system. See [`GpuId`].
+///
+/// [`GpuId`]: enum.GpuId.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GpuInfo {
+    /// The id to pass to [`ContextBuilder::with_gpu`].
+    ///
+    /// [`ContextBuilder::with_gpu`]:
+    /// struct.ContextBuilder.html#method.with_gpu
+    pub id: GpuId,
+    /// The name of the GPU, if the driver reports it.
+    pub name: Option<String>,
+    /// The memory of the GPU in megabytes, if the driver reports it.
+    pub memory_mb: Option<u32>,
+}
+
+/// The groups of fixed-function state copied by
+/// [`Context::copy_state_from`], which are the `GL_*_BIT` flags of
+/// `glPushAttrib`. Combine them with `|`.
+///
+/// The default is [`ALL`].
+///
+/// [`Context::copy_state_from`]: struct.Context.html#method.copy_state_from
+/// [`ALL`]: struct.StateMask.html#associatedconstant.ALL
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StateMask(pub u32);
+
+impl StateMask {
+    pub const CURRENT: StateMask = StateMask(0x0000_0001);
+    pub const POINT: StateMask = StateMask(0x0000_0002);
+    pub const LINE: StateMask = StateMask(0x0000_0004);
+    pub const POLYGON: StateMask = StateMask(0x0000_0008);
+    pub const POLYGON_STIPPLE: StateMask = StateMask(0x0000_0010);
+    pub const PIXEL_MODE: StateMask = StateMask(0x0000_0020);
+    pub const LIGHTING: StateMask = StateMask(0x0000_0040);
+    pub const FOG: StateMask = StateMask(0x0000_0080);
+    pub const DEPTH_BUFFER: StateMask = StateMask(0x0000_0100);
+    pub const ACCUM_BUFFER: StateMask = StateMask(0x0000_0200);
+    pub const STENCIL_BUFFER: StateMask = StateMask(0x0000_0400);
+    pub const VIEWPORT: StateMask = StateMask(0x0000_0800);
+    pub const TRANSFORM: StateMask = StateMask(0x0000_1000);
+    pub const ENABLE: StateMask = StateMask(0x0000_2000);
+    pub const COLOR_BUFFER: StateMask = StateMask(0x0000_4000);
+    pub const HINT: StateMask = StateMask(0x0000_8000);
+    pub const EVAL: StateMask = StateMask(0x0001_0000);
+    pub const LIST: StateMask = StateMask(0x0002_0000);
+    pub const TEXTURE: StateMask = StateMask(0x0004_0000);
+    pub const SCISSOR: StateMask = StateMask(0x0008_0000);
+    /// `GL_ALL_ATTRIB_BITS`.
+    pub const ALL: StateMask = StateMask(0xFFFF_FFFF);
+
+    /// Returns whether all the flags of `other` are set.
+    #[inline]
+    pub fn contains(self, other: StateMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for StateMask {
+    #[inline]
+    fn default() -> Self {
+        StateMask::ALL
+    }
+}
+
+impl std::ops::BitOr for StateMask {
+    type Output = StateMask;
+
+    #[inline]
+    fn bitor(self, other: StateMask) -> StateMask {
+        StateMask(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitOrAssign for StateMask {
+    #[inline]
+    fn bitor_assign(&mut self, other: StateMask) {
+        self.0 |= other.0;
+    }
+}
+
 /// The behavior of the driver when you change the current context.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ReleaseBehavior {
-    /// Doesn't do anything. Most notably doesn't flush.
+    /// Doesn't do anything. Most notably doesn't flush, so you must call
+    /// `glFlush` yourself before switching contexts if another context or
+    /// thread depends on the rendering.
+    ///
+    /// Requires `EGL_KHR_context_flush_control`,
+    /// `GLX_ARB_context_flush_control` or `WGL_ARB_context_flush_control`.
+    /// Context creation fails with [`ReleaseBehaviorNotSupported`] otherwise.
+    ///
+    /// [`ReleaseBehaviorNotSupported`]:
+    /// enum.CreationError.html#variant.ReleaseBehaviorNotSupported
     None,
 
     /// Flushes the context that was previously current as if `glFlush` was
@@ -563,6 +1585,69 @@ pub enum ReleaseBehavior {
     Flush,
 }
 
+/// What the back buffer contains after swapping buffers. See
+/// [`ContextBuilder::with_preserved_swap`].
+///
+/// [`ContextBuilder::with_preserved_swap`]:
+/// struct.ContextBuilder.html#method.with_preserved_swap
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SwapBehavior {
+    /// The back buffer keeps its contents, so you can draw over the previous
+    /// frame.
+    Preserved,
+    /// The back and front buffers are exchanged, so the back buffer contains
+    /// the frame before the previous one.
+    Exchanged,
+    /// The contents of the back buffer are undefined.
+    Undefined,
+}
+
+/// The extensions supported by the windowing system API of a context, as
+/// returned by [`ContextWrapper::extensions`].
+///
+/// These are the EGL, GLX or WGL extensions, not the OpenGL ones, which are
+/// queried with `glGetString` once the context is current.
+///
+/// [`ContextWrapper::extensions`]:
+/// struct.ContextWrapper.html#method.extensions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extensions {
+    names: HashSet<String>,
+}
+
+impl Extensions {
+    /// Parses a space-separated list of extensions, as returned by
+    /// `eglQueryString`, `glXQueryExtensionsString` and
+    /// `wglGetExtensionsStringARB`.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub(crate) fn parse(list: &str) -> Self {
+        Extensions {
+            names: list.split_whitespace().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    /// Returns true if the extension is supported.
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Returns an iterator over the names of the extensions, in no particular
+    /// order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|e| e.as_str())
+    }
+}
+
 /// Describes a possible format.
 #[allow(missing_docs)]
 #[derive(Debug, Clone)]
@@ -570,6 +1655,9 @@ pub struct PixelFormat {
     pub hardware_accelerated: bool,
     /// The number of color bits. Does not include alpha bits.
     pub color_bits: u8,
+    /// The number of red, green and blue bits, or `None` if the backend only
+    /// reports their sum.
+    pub rgb_bits: Option<(u8, u8, u8)>,
     pub alpha_bits: u8,
     pub depth_bits: u8,
     pub stencil_bits: u8,
@@ -578,11 +1666,24 @@ pub struct PixelFormat {
     /// `None` if multisampling is disabled, otherwise `Some(N)` where `N` is
     /// the multisampling level.
     pub multisampling: Option<u16>,
+    /// `Some(N)` if the format uses coverage sampling, where `N` is the
+    /// number of coverage samples and `multisampling` the number of color
+    /// samples, `None` otherwise.
+    pub coverage_samples: Option<u16>,
+    /// True if the format is sRGB-capable, in which case enabling
+    /// `GL_FRAMEBUFFER_SRGB` gamma encodes writes to the framebuffer.
     pub srgb: bool,
+    /// True if the color buffer stores floating point components.
+    pub float_color_buffer: bool,
+    /// What the back buffer contains after swapping buffers. With EGL this is
+    /// queried from the surface each time the pixel format is retrieved.
+    pub swap_behavior: SwapBehavior,
+    /// The smallest and largest swap intervals the format supports, or `None`
+    /// if the backend doesn't report them.
+    pub swap_interval_range: Option<(u32, u32)>,
 }
 
 /// Describes how the backend should choose a pixel format.
-// TODO: swap method? (swap, copy)
 #[derive(Clone, Debug)]
 pub struct PixelFormatRequirements {
     /// If true, only hardware-accelerated formats will be considered. If
@@ -623,20 +1724,235 @@ pub struct PixelFormatRequirements {
     /// A value of `Some(0)` indicates that multisampling must not be enabled.
     pub multisampling: Option<u16>,
 
+    /// If true, only formats with exactly `multisampling` samples will be
+    /// considered, instead of at least that many. The default is `false`.
+    pub multisampling_exact: bool,
+
+    /// The number of coverage samples per pixel, which is more than the
+    /// number of color samples in `multisampling`. Only honored with
+    /// `NV_multisample_coverage`. The default is `None`.
+    pub coverage_samples: Option<u16>,
+
     /// If true, only stereoscopic formats will be considered. If false, only
-    /// non-stereoscopic formats. The default is `false`.
-    pub stereoscopy: bool,
+    /// non-stereoscopic formats. `None` means "don't care". The default is
+    /// `Some(false)`.
+    pub stereoscopy: Option<bool>,
 
     /// If true, only sRGB-capable formats will be considered. If false, don't
     /// care. The default is `true`.
     pub srgb: bool,
 
+    /// If false, formats which aren't sRGB-capable are considered when none
+    /// is and `srgb` is true. The default is `true`.
+    pub srgb_required: bool,
+
+    /// The color space the surface is created with. `None` leaves it to the
+    /// driver. The default is `None`.
+    pub color_space: Option<ColorSpace>,
+
     /// The behavior when changing the current context. Default is `Flush`.
     pub release_behavior: ReleaseBehavior,
 
+    /// If true, only formats whose pbuffers can be bound as textures will be
+    /// considered. The default is `false`.
+    pub textureable: bool,
+
+    /// If true, pbuffers are created as large as possible when the requested
+    /// size can't be allocated. The default is `false`.
+    pub largest_pbuffer: bool,
+
+    /// If true, only formats which preserve the back buffer when swapping
+    /// buffers will be considered. The default is `false`.
+    pub preserved_swap: bool,
+
+    /// Only formats whose largest supported swap interval is at least this
+    /// will be considered. The default is `None`.
+    pub max_swap_interval: Option<u32>,
+
+    /// If true, GLX falls back to a legacy visual when no fbconfig matches.
+    /// The default is `false`.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub(crate) legacy_glx_visuals: bool,
+
+    /// X11 only: the screen to choose the config on. `None` means the
+    /// default screen. The default is `None`.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub(crate) x11_screen: Option<std::os::raw::c_int>,
+
+    /// The GPU a headless context renders on. `None` means the GPU chosen by
+    /// the driver. The default is `None`.
+    pub gpu: Option<GpuId>,
+
+    /// Windows only: whether the context renders to a child window embedded
+    /// in another window. The default is `false`.
+    #[cfg(target_os = "windows")]
+    pub(crate) wgl_child_window: bool,
+
+    /// macOS only: whether the context may move between GPUs. The default
+    /// is `true`.
+    #[cfg(target_os = "macos")]
+    pub(crate) automatic_graphics_switching: bool,
+
+    /// macOS only: whether the context should render on the integrated GPU.
+    /// The default is `false`.
+    #[cfg(target_os = "macos")]
+    pub(crate) prefer_integrated_gpu: bool,
+
+    /// macOS only: whether the surface is composited with its alpha channel.
+    /// The default is `false`.
+    #[cfg(target_os = "macos")]
+    pub(crate) transparent_surface: bool,
+
+    /// macOS only: whether the surface is above, with a positive order, or
+    /// below the window's content. The default is `None`.
+    #[cfg(target_os = "macos")]
+    pub(crate) surface_order: Option<i32>,
+
+    /// Windows only: the APIs to try, in order. An empty list means WGL for
+    /// OpenGL and EGL first for OpenGL ES. The default is empty.
+    pub windows_backends: Vec<Backend>,
+
     /// X11 only: set internally to insure a certain visual xid is used when
     /// choosing the fbconfig.
     pub(crate) x11_visual_xid: Option<std::os::raw::c_ulong>,
+
+    /// Set internally when `hardware_accelerated` was set explicitly, as GLX
+    /// only rejects software renderers then.
+    pub(crate) hardware_acceleration_explicit: bool,
+
+    /// Windows only: set internally for transparent windows, to choose a
+    /// pixel format with an alpha channel that DWM can composite.
+    pub(crate) wgl_transparent: bool,
+
+    /// X11 only: set internally to only choose EGL configs compatible with a
+    /// certain pixmap.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd",
+    ))]
+    pub(crate) x11_pixmap: Option<std::os::raw::c_ulong>,
+}
+
+impl PixelFormatRequirements {
+    // The error returned when no config matches the requirements, which
+    // names stereoscopy since it is rarely available.
+    #[allow(dead_code)]
+    pub(crate) fn no_config_error(&self) -> CreationError {
+        if self.stereoscopy == Some(true) {
+            CreationError::NotSupported(
+                "No stereo-capable config matches the requirements".to_string(),
+            )
+        } else {
+            CreationError::NoAvailablePixelFormat
+        }
+    }
+
+    // Returns the first requirement `pixel_format` doesn't meet, if any.
+    #[allow(dead_code)]
+    pub(crate) fn unmet_by(
+        &self,
+        pixel_format: &PixelFormat,
+    ) -> Option<&'static str> {
+        let at_least =
+            |req: Option<u8>, bits: u8| req.map_or(true, |r| bits >= r);
+        if self.hardware_accelerated == Some(true)
+            && !pixel_format.hardware_accelerated
+        {
+            Some("hardware acceleration")
+        } else if !at_least(self.color_bits, pixel_format.color_bits) {
+            Some("color bits")
+        } else if !at_least(self.alpha_bits, pixel_format.alpha_bits) {
+            Some("alpha bits")
+        } else if self.wgl_transparent && pixel_format.alpha_bits < 8 {
+            Some("alpha channel of a transparent window")
+        } else if !at_least(self.depth_bits, pixel_format.depth_bits) {
+            Some("depth bits")
+        } else if !at_least(self.stencil_bits, pixel_format.stencil_bits) {
+            Some("stencil bits")
+        } else if self.float_color_buffer && !pixel_format.float_color_buffer {
+            Some("float color buffer")
+        } else if self
+            .double_buffer
+            .map_or(false, |d| d != pixel_format.double_buffer)
+        {
+            Some("double buffering")
+        } else if self
+            .stereoscopy
+            .map_or(false, |s| s != pixel_format.stereoscopy)
+        {
+            Some("stereoscopy")
+        } else if self.srgb && self.srgb_required && !pixel_format.srgb {
+            Some("sRGB")
+        } else if self.preserved_swap
+            && pixel_format.swap_behavior != SwapBehavior::Preserved
+        {
+            Some("preserved swaps")
+        } else {
+            let samples = pixel_format.multisampling.unwrap_or(0);
+            match self.multisampling {
+                Some(req) if self.multisampling_exact && samples != req => {
+                    Some("multisampling")
+                }
+                Some(req) if samples < req => Some("multisampling"),
+                _ => None,
+            }
+        }
+    }
+}
+
+// Fails with an error naming the limits of the config if a pbuffer of `size`
+// exceeds them, instead of the unhelpful allocation error of the driver. Some
+// drivers, like Mesa, report 0 for the maximum number of pixels, which is then
+// ignored.
+#[allow(dead_code)]
+pub(crate) fn check_pbuffer_size(
+    size: (u32, u32),
+    max_width: u64,
+    max_height: u64,
+    max_pixels: u64,
+) -> Result<(), CreationError> {
+    let (width, height) = (size.0 as u64, size.1 as u64);
+    let too_many_pixels = max_pixels != 0 && width * height > max_pixels;
+    if width > max_width || height > max_height || too_many_pixels {
+        return Err(CreationError::NotSupported(format!(
+            "The pbuffer size {}x{} exceeds the maximum of the config, which \
+             is {}x{} and {} pixels",
+            width, height, max_width, max_height, max_pixels
+        )));
+    }
+    Ok(())
+}
+
+// Splits the samples of a format into its color and coverage samples, given
+// the color samples reported by `NV_multisample_coverage`, whose coverage
+// samples are counted by the regular samples attribute.
+#[allow(dead_code)]
+pub(crate) fn split_coverage_samples(
+    samples: u16,
+    color_samples: u16,
+) -> (Option<u16>, Option<u16>) {
+    match (samples, color_samples) {
+        (0, _) => (None, None),
+        (samples, color) if color != 0 && color < samples => {
+            (Some(color), Some(samples))
+        }
+        (samples, _) => (Some(samples), None),
+    }
 }
 
 impl Default for PixelFormatRequirements {
@@ -651,10 +1967,56 @@ impl Default for PixelFormatRequirements {
             stencil_bits: Some(8),
             double_buffer: None,
             multisampling: None,
-            stereoscopy: false,
+            multisampling_exact: false,
+            coverage_samples: None,
+            stereoscopy: Some(false),
             srgb: true,
+            srgb_required: true,
+            color_space: None,
             release_behavior: ReleaseBehavior::Flush,
+            textureable: false,
+            largest_pbuffer: false,
+            preserved_swap: false,
+            max_swap_interval: None,
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+            ))]
+            legacy_glx_visuals: false,
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+            ))]
+            x11_screen: None,
+            gpu: None,
+            #[cfg(target_os = "windows")]
+            wgl_child_window: false,
+            #[cfg(target_os = "macos")]
+            automatic_graphics_switching: true,
+            #[cfg(target_os = "macos")]
+            prefer_integrated_gpu: false,
+            #[cfg(target_os = "macos")]
+            transparent_surface: false,
+            #[cfg(target_os = "macos")]
+            surface_order: None,
+            windows_backends: Vec::new(),
             x11_visual_xid: None,
+            hardware_acceleration_explicit: false,
+            wgl_transparent: false,
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+            ))]
+            x11_pixmap: None,
         }
     }
 }
@@ -677,6 +2039,12 @@ pub struct GlAttributes<S> {
     /// [`GlRequest`]: enum.GlRequest.html
     pub version: GlRequest,
 
+    /// Whether context creation fails, rather than clamping the version, if
+    /// `version` is higher than the platform supports. Only honored by CGL.
+    ///
+    /// The default is `false`.
+    pub exact_version: bool,
+
     /// OpenGL profile to use.
     ///
     /// The default is `None`.
@@ -689,6 +2057,13 @@ pub struct GlAttributes<S> {
     /// The default is `true` in debug mode and `false` in release mode.
     pub debug: bool,
 
+    /// Whether to enable the `forward compatible` flag of the context, which
+    /// removes the functionality deprecated by OpenGL 3.0. Only desktop
+    /// OpenGL 3.0 and later honor it.
+    ///
+    /// The default is `false`.
+    pub forward_compatible: bool,
+
     /// How the OpenGL [`Context`] should detect errors.
     ///
     /// The default is `NotRobust` because this is what is typically expected
@@ -700,12 +2075,52 @@ pub struct GlAttributes<S> {
     /// enum.Robustness.html#variant.TryRobustLoseContextOnReset
     pub robustness: Robustness,
 
+    /// Whether resets are isolated to the context that caused them. Requires
+    /// a [`Robustness`] that loses the context on reset, and the
+    /// `robustness_application_isolation` or
+    /// `robustness_share_group_isolation` extension of GLX or WGL. Context
+    /// creation fails if it isn't supported.
+    ///
+    /// The default is `false`.
+    ///
+    /// [`Robustness`]: enum.Robustness.html
+    pub reset_isolation: bool,
+
     /// Whether to use vsync. If vsync is enabled, calling `swap_buffers` will
     /// block until the screen refreshes. This is typically used to prevent
     /// screen tearing.
     ///
     /// The default is `false`.
     pub vsync: bool,
+
+    /// Whether the context renders directly. See [`DirectRendering`].
+    ///
+    /// The default is [`DirectRendering::PreferDirect`].
+    ///
+    /// [`DirectRendering`]: enum.DirectRendering.html
+    /// [`DirectRendering::PreferDirect`]:
+    /// enum.DirectRendering.html#variant.PreferDirect
+    pub direct_rendering: DirectRendering,
+
+    /// Scheduling priority to request for the context. See [`Priority`].
+    ///
+    /// The default is `None`, which leaves the choice to the driver.
+    ///
+    /// [`Priority`]: enum.Priority.html
+    pub priority: Option<Priority>,
+
+    /// Whether to create the context without a config, so that it can be
+    /// bound to surfaces of any config. Only honored by EGL implementations
+    /// advertising `EGL_KHR_no_config_context`, and ignored otherwise.
+    ///
+    /// The default is `false`.
+    pub no_config: bool,
+
+    /// Whether the context and its surface should be protected. Requires
+    /// `EGL_EXT_protected_content`.
+    ///
+    /// The default is `false`.
+    pub protected_content: bool,
 }
 
 impl<S> GlAttributes<S> {
@@ -718,10 +2133,17 @@ impl<S> GlAttributes<S> {
         GlAttributes {
             sharing: self.sharing.map(f),
             version: self.version,
+            exact_version: self.exact_version,
             profile: self.profile,
             debug: self.debug,
+            forward_compatible: self.forward_compatible,
             robustness: self.robustness,
+            reset_isolation: self.reset_isolation,
             vsync: self.vsync,
+            direct_rendering: self.direct_rendering,
+            priority: self.priority,
+            no_config: self.no_config,
+            protected_content: self.protected_content,
         }
     }
 
@@ -731,10 +2153,17 @@ impl<S> GlAttributes<S> {
         GlAttributes {
             sharing,
             version: self.version,
+            exact_version: self.exact_version,
             profile: self.profile,
             debug: self.debug,
+            forward_compatible: self.forward_compatible,
             robustness: self.robustness,
+            reset_isolation: self.reset_isolation,
             vsync: self.vsync,
+            direct_rendering: self.direct_rendering,
+            priority: self.priority,
+            no_config: self.no_config,
+            protected_content: self.protected_content,
         }
     }
 }
@@ -745,15 +2174,45 @@ impl<S> Default for GlAttributes<S> {
         GlAttributes {
             sharing: None,
             version: GlRequest::Latest,
+            exact_version: false,
             profile: None,
             debug: cfg!(debug_assertions),
+            forward_compatible: false,
             robustness: Robustness::NotRobust,
+            reset_isolation: false,
             vsync: false,
+            direct_rendering: DirectRendering::PreferDirect,
+            priority: None,
+            no_config: false,
+            protected_content: false,
         }
     }
 }
 
-// Rectangles to submit as buffer damage.
+/// How [`ContextWrapper::swap_buffers_with_damage`] honors the damage rects.
+///
+/// [`ContextWrapper::swap_buffers_with_damage`]:
+/// struct.ContextWrapper.html#method.swap_buffers_with_damage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageSupport {
+    /// Only the damaged areas are presented.
+    Native,
+    /// The damage rects are accepted but the whole buffer is swapped, so
+    /// there is no need to compute them. This is the case with WGL.
+    Emulated,
+    /// Swapping with damage fails, so [`ContextWrapper::swap_buffers`] has to
+    /// be called instead.
+    ///
+    /// [`ContextWrapper::swap_buffers`]:
+    /// struct.ContextWrapper.html#method.swap_buffers
+    None,
+}
+
+/// A rectangle to submit as buffer damage, in pixels.
+///
+/// As in OpenGL, the origin is the bottom-left corner of the surface, with `y`
+/// going up. Winit uses a top-left origin, so a rectangle at `y` in window
+/// coordinates is at `surface_height - y - height` here.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rect {
     pub x: u32,
@@ -1,13 +1,59 @@
 #![cfg(any(target_os = "android"))]
 
+pub use crate::api::egl::{
+    EglDebugMessage, EglDebugMessageType, EglDisplayInfo,
+};
+use crate::api::egl::{NativeDisplay, SurfaceType};
 use crate::platform::ContextTraitExt;
-use crate::{Context, ContextCurrentState};
+use crate::{Context, ContextBuilder, ContextCurrentState, CreationError};
 pub use glutin_egl_sys::EGLContext;
 
 pub use winit::platform::android::*;
 
 use std::os::raw;
 
+/// Sets a callback that receives the messages reported by the EGL driver
+/// through `EGL_KHR_debug`. Errors returned by glutin's EGL calls then include
+/// the driver's message as well.
+///
+/// Must be called before building the first context.
+#[inline]
+pub fn set_egl_debug_callback<F>(callback: F)
+where
+    F: Fn(EglDebugMessage) + Send + Sync + 'static,
+{
+    crate::api::egl::set_debug_callback(callback)
+}
+
+/// Returns information about the EGL display, without creating a context.
+/// See [`EglDisplayInfo`].
+///
+/// [`EglDisplayInfo`]: struct.EglDisplayInfo.html
+#[inline]
+pub fn egl_display_info() -> Result<EglDisplayInfo, CreationError> {
+    crate::api::egl::default_display_info()
+}
+
+/// Returns the distinct sample counts, `0` standing for no multisampling, of
+/// the EGL window configs of the default display that match the requirements of
+/// `builder` apart from multisampling, without creating a context. Use one of
+/// them with [`ContextBuilder::with_multisampling_exact`].
+///
+/// [`ContextBuilder::with_multisampling_exact`]:
+/// ../../struct.ContextBuilder.html#method.with_multisampling_exact
+#[inline]
+pub fn egl_supported_sample_counts<T: ContextCurrentState>(
+    builder: &ContextBuilder<'_, T>,
+) -> Result<Vec<u16>, CreationError> {
+    crate::api::egl::supported_sample_counts(
+        &NativeDisplay::Android,
+        SurfaceType::Window,
+        &builder.pf_reqs,
+        &builder.gl_attr.clone().set_sharing(None),
+        |_, _| true,
+    )
+}
+
 impl<T: ContextCurrentState> ContextTraitExt for Context<T> {
     type Handle = EGLContext;
 
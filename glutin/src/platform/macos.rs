@@ -1,12 +1,97 @@
 #![cfg(target_os = "macos")]
 
 use crate::platform::ContextTraitExt;
-use crate::{Context, ContextCurrentState};
+pub use crate::platform_impl::{CglContextExt, DisplayLink, Renderer};
+use crate::{Context, ContextBuilder, ContextCurrentState};
 
 pub use winit::platform::macos::*;
 
 use std::os::raw;
 
+/// A macOS-specific extension to the [`ContextBuilder`] which allows setting
+/// options that only apply to CGL.
+///
+/// [`ContextBuilder`]: ../../struct.ContextBuilder.html
+pub trait ContextBuilderExtMacOS {
+    /// Sets whether the system may move the context between GPUs, which
+    /// lets dual-GPU Macs keep the integrated GPU running. Otherwise the
+    /// discrete GPU is woken up.
+    ///
+    /// The default value is `true`. It adds
+    /// `NSOpenGLPFAAllowOfflineRenderers` and
+    /// `kCGLPFASupportsAutomaticGraphicsSwitching` to the pixel format. The
+    /// application bundle also needs the `NSSupportsAutomaticGraphicsSwitching`
+    /// key in its `Info.plist`.
+    fn with_automatic_graphics_switching(
+        self,
+        automatic_graphics_switching: bool,
+    ) -> Self;
+
+    /// Sets whether the context should render on the integrated GPU when
+    /// the system has one, to save power.
+    ///
+    /// The default value is `false`. It makes the context use the virtual
+    /// screen of an Intel renderer, if any, and implies
+    /// [`with_automatic_graphics_switching`]. The virtual screen and renderer
+    /// that were used are returned by [`CglContextExt`].
+    ///
+    /// [`with_automatic_graphics_switching`]:
+    /// #tymethod.with_automatic_graphics_switching
+    /// [`CglContextExt`]: trait.CglContextExt.html
+    fn with_prefer_integrated_gpu(self, prefer_integrated_gpu: bool) -> Self;
+
+    /// Sets whether the surface of a windowed context is composited with its
+    /// alpha channel, so that what is below shows through.
+    ///
+    /// The default value is `false`. It sets `kCGLCPSurfaceOpacity` to 0
+    /// and requires at least 8 alpha bits. It is implied by a transparent
+    /// window.
+    fn with_transparent_surface(self, transparent_surface: bool) -> Self;
+
+    /// Sets whether the surface of a windowed context is drawn above the
+    /// window's content, with a positive order, or below it, with a negative
+    /// one, which lets views of the window show on top of it.
+    ///
+    /// By default the surface is kept above. It sets
+    /// `NSOpenGLCPSurfaceOrder`.
+    fn with_surface_order(self, surface_order: i32) -> Self;
+}
+
+impl<'a, T: ContextCurrentState> ContextBuilderExtMacOS
+    for ContextBuilder<'a, T>
+{
+    #[inline]
+    fn with_automatic_graphics_switching(
+        mut self,
+        automatic_graphics_switching: bool,
+    ) -> Self {
+        self.pf_reqs.automatic_graphics_switching =
+            automatic_graphics_switching;
+        self
+    }
+
+    #[inline]
+    fn with_prefer_integrated_gpu(
+        mut self,
+        prefer_integrated_gpu: bool,
+    ) -> Self {
+        self.pf_reqs.prefer_integrated_gpu = prefer_integrated_gpu;
+        self
+    }
+
+    #[inline]
+    fn with_transparent_surface(mut self, transparent_surface: bool) -> Self {
+        self.pf_reqs.transparent_surface = transparent_surface;
+        self
+    }
+
+    #[inline]
+    fn with_surface_order(mut self, surface_order: i32) -> Self {
+        self.pf_reqs.surface_order = Some(surface_order);
+        self
+    }
+}
+
 impl<T: ContextCurrentState> ContextTraitExt for Context<T> {
     type Handle = *mut raw::c_void;
 
@@ -6,16 +6,213 @@
     target_os = "openbsd",
 ))]
 
+pub use crate::api::egl::{
+    Device, DmaBuf, DmaBufPlane, EglDebugMessage, EglDebugMessageType,
+    EglDisplayInfo, EglImage, Fence,
+};
+use crate::api::egl::{NativeDisplay, SurfaceType};
+pub use crate::api::glx::{GlxRenderer, GlxSwapControl, GlxSyncValues};
+pub use crate::api::osmesa::{
+    OsMesaAttrib, OsMesaAttributes, OsMesaBufferFormat, OsMesaChannelType,
+    OsMesaColorBuffer, OsMesaDepthBuffer, OsMesaDriverInfo,
+};
 use crate::platform::ContextTraitExt;
-pub use crate::platform_impl::{HeadlessContextExt, RawContextExt, RawHandle};
-use crate::{Context, ContextCurrentState};
+pub use crate::platform_impl::{
+    CurrentOsMesaContext, EglContextExt, GlxContextExt, HeadlessContextExt,
+    OsMesaContextExt, RawContextExt, RawHandle,
+};
+use crate::{Context, ContextBuilder, ContextCurrentState, CreationError};
 pub use glutin_egl_sys::EGLContext;
 pub use glutin_glx_sys::GLXContext;
 
+use winit::event_loop::EventLoopWindowTarget;
 pub use winit::platform::unix::*;
 
 use std::os::raw;
 
+/// Loads OsMesa if needed and returns information about its driver, so that
+/// you can decide whether to use it before building a context.
+///
+/// The driver is only probed once. Doing so briefly makes a scratch OsMesa
+/// context current on the calling thread; whatever OsMesa context was current
+/// before is made current again afterwards.
+#[inline]
+pub fn osmesa_driver_info() -> Result<OsMesaDriverInfo, CreationError> {
+    crate::api::osmesa::driver_info()
+}
+
+/// Sets a callback that receives the messages the EGL driver reports through
+/// `EGL_KHR_debug`, which are often more precise than the bare error codes.
+///
+/// The callback is registered with the driver whenever a display is
+/// initialized, so this must be called before building the first context.
+/// It is never called if the client extension isn't available. Once it is
+/// registered, the errors glutin returns for failed EGL calls also include
+/// the driver's message for that call.
+#[inline]
+pub fn set_egl_debug_callback<F>(callback: F)
+where
+    F: Fn(EglDebugMessage) + Send + Sync + 'static,
+{
+    crate::api::egl::set_debug_callback(callback)
+}
+
+/// Returns information about the default EGL display, without creating a
+/// context. See [`EglDisplayInfo`].
+///
+/// To query the display of a specific device, use [`Device::display_info`]
+/// instead.
+///
+/// [`EglDisplayInfo`]: struct.EglDisplayInfo.html
+/// [`Device::display_info`]: struct.Device.html#method.display_info
+#[inline]
+pub fn egl_display_info() -> Result<EglDisplayInfo, CreationError> {
+    crate::api::egl::default_display_info()
+}
+
+/// Returns the distinct sample counts, `0` standing for no multisampling, of
+/// the EGL window configs of the display of `el` that match the requirements
+/// of `builder` apart from multisampling, without creating a context. Use one
+/// of them with [`ContextBuilder::with_multisampling_exact`].
+///
+/// On X11, only the configs of the screen chosen with
+/// [`ContextBuilderExtUnix::with_x11_screen`] are considered.
+///
+/// [`ContextBuilder::with_multisampling_exact`]:
+/// ../../struct.ContextBuilder.html#method.with_multisampling_exact
+/// [`ContextBuilderExtUnix::with_x11_screen`]:
+/// trait.ContextBuilderExtUnix.html#tymethod.with_x11_screen
+#[inline]
+pub fn egl_supported_sample_counts<T: ContextCurrentState, TE>(
+    builder: &ContextBuilder<'_, T>,
+    el: &EventLoopWindowTarget<TE>,
+) -> Result<Vec<u16>, CreationError> {
+    crate::platform_impl::egl_supported_sample_counts(
+        el,
+        &builder.pf_reqs,
+        &builder.gl_attr.clone().set_sharing(None),
+    )
+}
+
+/// Like [`egl_supported_sample_counts`], but for the pbuffer configs of the
+/// display of `device`, which contexts built with
+/// [`HeadlessContextExt::build_headless_with_device`] choose from.
+///
+/// [`egl_supported_sample_counts`]: fn.egl_supported_sample_counts.html
+/// [`HeadlessContextExt::build_headless_with_device`]:
+/// trait.HeadlessContextExt.html#tymethod.build_headless_with_device
+#[inline]
+pub fn egl_device_supported_sample_counts<T: ContextCurrentState>(
+    builder: &ContextBuilder<'_, T>,
+    device: &Device,
+) -> Result<Vec<u16>, CreationError> {
+    crate::api::egl::supported_sample_counts(
+        &NativeDisplay::Device(device.raw_device()),
+        SurfaceType::PBuffer,
+        &builder.pf_reqs,
+        &builder.gl_attr.clone().set_sharing(None),
+        |_, _| true,
+    )
+}
+
+/// Like [`egl_supported_sample_counts`], but for the window configs of the
+/// display of `gbm_device` whose format is `format`, which contexts built
+/// with [`RawContextExt::build_raw_gbm_context`] choose from.
+///
+/// Unsafe behaviour might happen if `gbm_device` isn't a valid `gbm_device`.
+///
+/// [`egl_supported_sample_counts`]: fn.egl_supported_sample_counts.html
+/// [`RawContextExt::build_raw_gbm_context`]:
+/// trait.RawContextExt.html#tymethod.build_raw_gbm_context
+#[inline]
+pub unsafe fn egl_gbm_supported_sample_counts<T: ContextCurrentState>(
+    builder: &ContextBuilder<'_, T>,
+    gbm_device: *mut raw::c_void,
+    format: u32,
+) -> Result<Vec<u16>, CreationError> {
+    crate::api::egl::supported_sample_counts(
+        &NativeDisplay::Gbm(Some(gbm_device as *const _)),
+        SurfaceType::Window,
+        &builder.pf_reqs,
+        &builder.gl_attr.clone().set_sharing(None),
+        // the visual id of a GBM config is its format
+        |display, config| {
+            crate::api::egl::get_native_visual_id(display, config) as u32
+                == format
+        },
+    )
+}
+
+/// Returns the distinct sample counts, `0` standing for no multisampling, of
+/// the GLX configs of the X11 screen that match the requirements of
+/// `builder` apart from multisampling, without creating a context. Use one of
+/// them with [`ContextBuilder::with_multisampling_exact`].
+///
+/// [`ContextBuilder::with_multisampling_exact`]:
+/// ../../struct.ContextBuilder.html#method.with_multisampling_exact
+#[inline]
+pub fn glx_supported_sample_counts<T: ContextCurrentState, TE>(
+    builder: &ContextBuilder<'_, T>,
+    el: &EventLoopWindowTarget<TE>,
+) -> Result<Vec<u16>, CreationError> {
+    crate::platform_impl::glx_supported_sample_counts(el, &builder.pf_reqs)
+}
+
+/// A unix-specific extension to the [`ContextBuilder`] which allows setting
+/// options that only apply to X11 and GLX.
+///
+/// [`ContextBuilder`]: ../../struct.ContextBuilder.html
+pub trait ContextBuilderExtUnix {
+    /// Chooses the config on the X screen `screen_id` instead of the default
+    /// screen, for X servers with a screen per monitor.
+    ///
+    /// Windows built with the context are created on that screen. Building a
+    /// context for an existing window or pixmap fails with
+    /// [`CreationError::OsError`] if the drawable is on another screen.
+    ///
+    /// This is only honored by GLX and EGL on X11. Wayland ignores it.
+    ///
+    /// [`CreationError::OsError`]:
+    /// ../../enum.CreationError.html#variant.OsError
+    fn with_x11_screen(self, screen_id: raw::c_int) -> Self;
+
+    /// Allows falling back to a legacy visual chosen with `glXChooseVisual`
+    /// when no GLX fbconfig matches the requirements, which helps with old X
+    /// servers and remote X connections. Legacy visuals are always used if
+    /// the server only supports GLX 1.2 or older, which has no fbconfigs.
+    ///
+    /// Legacy visuals only support windows, and contexts are created with
+    /// `glXCreateContext`, so pbuffers, pixmaps, multisampling, float color
+    /// buffers, preserved swaps, core profiles, robustness and release
+    /// behaviors fail with [`CreationError::NotSupported`] or the matching
+    /// error. sRGB can't be requested, so [`with_srgb`] is ignored. The
+    /// contexts are made current with `glXMakeCurrent`, so they can't draw to
+    /// and read from different windows.
+    ///
+    /// This is only honored by GLX. EGL ignores it.
+    ///
+    /// [`CreationError::NotSupported`]:
+    /// ../../enum.CreationError.html#variant.NotSupported
+    /// [`with_srgb`]: ../../struct.ContextBuilder.html#method.with_srgb
+    fn with_legacy_glx_visuals(self, legacy_glx_visuals: bool) -> Self;
+}
+
+impl<'a, T: ContextCurrentState> ContextBuilderExtUnix
+    for ContextBuilder<'a, T>
+{
+    #[inline]
+    fn with_x11_screen(mut self, screen_id: raw::c_int) -> Self {
+        self.pf_reqs.x11_screen = Some(screen_id);
+        self
+    }
+
+    #[inline]
+    fn with_legacy_glx_visuals(mut self, legacy_glx_visuals: bool) -> Self {
+        self.pf_reqs.legacy_glx_visuals = legacy_glx_visuals;
+        self
+    }
+}
+
 impl<T: ContextCurrentState> ContextTraitExt for Context<T> {
     type Handle = RawHandle;
 
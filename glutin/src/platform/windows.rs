@@ -1,15 +1,173 @@
 #![cfg(target_os = "windows")]
 
+pub use crate::api::egl::{
+    AnglePlatform, EglDebugMessage, EglDebugMessageType, EglDisplayBuilder,
+    EglDisplayInfo,
+};
+use crate::api::egl::{NativeDisplay, SurfaceType};
+pub use crate::api::wgl::{
+    DxInteropAccess, DxInteropDevice, DxInteropObject, WglPixelFormat,
+};
 use crate::platform::ContextTraitExt;
-pub use crate::platform_impl::{RawContextExt, RawHandle};
-use crate::{Context, ContextCurrentState};
+pub use crate::platform_impl::{RawContextExt, RawHandle, WglContextExt};
+use crate::{
+    Context, ContextBuilder, ContextCurrentState, CreationError, Extensions,
+    GpuInfo,
+};
 pub use glutin_egl_sys::EGLContext;
 
 pub use winapi::shared::windef::HGLRC;
 pub use winit::platform::windows::*;
 
+use winit::event_loop::EventLoopWindowTarget;
+use winit::window::WindowBuilder;
+
 use std::os::raw;
 
+/// Sets a callback that receives the messages reported by the EGL
+/// implementation (usually ANGLE) through `EGL_KHR_debug`. Errors returned by
+/// glutin's EGL calls then include its message as well.
+///
+/// Must be called before building the first EGL context.
+#[inline]
+pub fn set_egl_debug_callback<F>(callback: F)
+where
+    F: Fn(EglDebugMessage) + Send + Sync + 'static,
+{
+    crate::api::egl::set_debug_callback(callback)
+}
+
+/// Returns information about the default EGL display, without creating a
+/// context. The display is created according to the installed
+/// [`EglDisplayBuilder`], if any. See [`EglDisplayInfo`].
+///
+/// [`EglDisplayBuilder`]: struct.EglDisplayBuilder.html
+/// [`EglDisplayInfo`]: struct.EglDisplayInfo.html
+#[inline]
+pub fn egl_display_info() -> Result<EglDisplayInfo, CreationError> {
+    crate::api::egl::default_display_info()
+}
+
+/// Returns the distinct sample counts, `0` standing for no multisampling, of
+/// the EGL window configs of the default display that match the requirements
+/// of `builder` apart from multisampling, without creating a context. Use one
+/// of them with [`ContextBuilder::with_multisampling_exact`].
+///
+/// The display is created according to the installed [`EglDisplayBuilder`],
+/// if any, like for windowed contexts.
+///
+/// [`ContextBuilder::with_multisampling_exact`]:
+/// ../../struct.ContextBuilder.html#method.with_multisampling_exact
+/// [`EglDisplayBuilder`]: struct.EglDisplayBuilder.html
+#[inline]
+pub fn egl_supported_sample_counts<T: ContextCurrentState>(
+    builder: &ContextBuilder<'_, T>,
+) -> Result<Vec<u16>, CreationError> {
+    crate::api::egl::supported_sample_counts(
+        &NativeDisplay::Other(Some(std::ptr::null())),
+        SurfaceType::Window,
+        &builder.pf_reqs,
+        &builder.gl_attr.clone().set_sharing(None),
+        |_, _| true,
+    )
+}
+
+/// Returns the distinct sample counts, `0` standing for no multisampling, of
+/// the WGL pixel formats that match the requirements of `builder` apart from
+/// multisampling, without creating a context. Use one of them with
+/// [`ContextBuilder::with_multisampling_exact`].
+///
+/// This requires `WGL_ARB_pixel_format`. An invisible window is created to
+/// load the WGL functions.
+///
+/// [`ContextBuilder::with_multisampling_exact`]:
+/// ../../struct.ContextBuilder.html#method.with_multisampling_exact
+pub fn wgl_supported_sample_counts<T: ContextCurrentState, TE>(
+    builder: &ContextBuilder<'_, T>,
+    el: &EventLoopWindowTarget<TE>,
+) -> Result<Vec<u16>, CreationError> {
+    let win = WindowBuilder::new().with_visible(false).build(el)?;
+    unsafe {
+        crate::api::wgl::supported_sample_counts(
+            win.hwnd() as _,
+            &builder.pf_reqs,
+        )
+    }
+}
+
+/// Enumerates the GPUs that [`ContextBuilder::with_gpu`] can choose, through
+/// `WGL_AMD_gpu_association` and `WGL_NV_gpu_affinity`. The list is empty if
+/// the drivers support neither. See [`GpuInfo`].
+///
+/// An invisible window is created to load the WGL functions.
+///
+/// [`ContextBuilder::with_gpu`]:
+/// ../../struct.ContextBuilder.html#method.with_gpu
+/// [`GpuInfo`]: ../../struct.GpuInfo.html
+pub fn enumerate_gpus<T>(
+    el: &EventLoopWindowTarget<T>,
+) -> Result<Vec<GpuInfo>, CreationError> {
+    let win = WindowBuilder::new().with_visible(false).build(el)?;
+    unsafe { crate::api::wgl::enumerate_gpus(win.hwnd() as _) }
+}
+
+/// Returns the WGL extensions supported by the driver, from
+/// `wglGetExtensionsStringARB` or `wglGetExtensionsStringEXT`.
+///
+/// An invisible window is created to load the WGL functions.
+pub fn wgl_extensions<T>(
+    el: &EventLoopWindowTarget<T>,
+) -> Result<Extensions, CreationError> {
+    let win = WindowBuilder::new().with_visible(false).build(el)?;
+    unsafe { crate::api::wgl::enumerate_extensions(win.hwnd() as _) }
+}
+
+/// Enumerates the pixel formats of the driver, with all their
+/// `WGL_ARB_pixel_format` attributes, for instance to include them in bug
+/// reports. See [`WglPixelFormat`].
+///
+/// An invisible window is created to load the WGL functions.
+///
+/// [`WglPixelFormat`]: struct.WglPixelFormat.html
+pub fn wgl_pixel_formats<T>(
+    el: &EventLoopWindowTarget<T>,
+) -> Result<Vec<WglPixelFormat>, CreationError> {
+    let win = WindowBuilder::new().with_visible(false).build(el)?;
+    unsafe { crate::api::wgl::enumerate_pixel_formats(win.hwnd() as _) }
+}
+
+/// A Windows-specific extension to the [`ContextBuilder`] which allows
+/// setting options that only apply to WGL.
+///
+/// [`ContextBuilder`]: ../../struct.ContextBuilder.html
+pub trait ContextBuilderExtWindows {
+    /// Sets whether the context renders to a child window embedded in
+    /// another application's window, such as a WinForms or Qt host.
+    ///
+    /// The default value is `false`. EGL ignores it.
+    ///
+    /// To avoid flickering, the window must have the `WS_CHILD` and
+    /// `WS_CLIPSIBLINGS` styles and its parent the `WS_CLIPCHILDREN` style.
+    /// Creation fails with [`CreationError::NotSupported`] otherwise.
+    /// Pixel formats that copy the back buffer on swaps are preferred, and
+    /// if the window class lacks `CS_OWNDC`, the device context is acquired
+    /// again around every swap.
+    ///
+    /// [`CreationError::NotSupported`]:
+    /// ../../enum.CreationError.html#variant.NotSupported
+    fn with_wgl_child_window(self, child_window: bool) -> Self;
+}
+
+impl<'a, T: ContextCurrentState> ContextBuilderExtWindows
+    for ContextBuilder<'a, T>
+{
+    #[inline]
+    fn with_wgl_child_window(mut self, child_window: bool) -> Self {
+        self.pf_reqs.wgl_child_window = child_window;
+        self
+    }
+}
+
 impl<T: ContextCurrentState> ContextTraitExt for Context<T> {
     type Handle = RawHandle;
 
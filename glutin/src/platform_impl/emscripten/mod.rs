@@ -1,8 +1,8 @@
 #![cfg(target_os = "emscripten")]
 
 use crate::{
-    Api, ContextError, CreationError, GlAttributes, GlRequest, PixelFormat,
-    PixelFormatRequirements,
+    Api, ContextError, CreationError, DirectRendering, GlAttributes, GlRequest,
+    PixelFormat, PixelFormatRequirements, StateMask,
 };
 
 use glutin_emscripten_sys as ffi;
@@ -27,9 +27,61 @@ impl Context {
     pub fn new_windowed<T>(
         wb: WindowBuilder,
         el: &EventLoopWindowTarget<T>,
-        _pf_reqs: &PixelFormatRequirements,
+        pf_reqs: &PixelFormatRequirements,
         gl_attr: &GlAttributes<&Context>,
     ) -> Result<(winit::window::Window, Self), CreationError> {
+        if gl_attr.priority.is_some() {
+            return Err(CreationError::NotSupported(
+                "Context priorities are not supported by WebGL".to_string(),
+            ));
+        }
+
+        if gl_attr.protected_content {
+            return Err(CreationError::NotSupported(
+                "Protected content is not supported by WebGL".to_string(),
+            ));
+        }
+
+        if gl_attr.direct_rendering == DirectRendering::RequireIndirect {
+            return Err(CreationError::NotSupported(
+                "Indirect rendering is not supported by WebGL".to_string(),
+            ));
+        }
+
+        if gl_attr.reset_isolation {
+            return Err(CreationError::NotSupported(
+                "Reset isolation is not supported by WebGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.color_space.is_some() {
+            return Err(CreationError::NotSupported(
+                "Color spaces are not supported by WebGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.textureable {
+            return Err(CreationError::NotSupported(
+                "Textureable pbuffers are not supported by WebGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.preserved_swap {
+            return Err(CreationError::NotSupported(
+                "Preserved swaps are not supported by WebGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.stereoscopy == Some(true) {
+            return Err(CreationError::NotSupported(
+                "Stereoscopy is not supported by WebGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.release_behavior != crate::ReleaseBehavior::Flush {
+            return Err(CreationError::ReleaseBehaviorNotSupported);
+        }
+
         let win = wb.build(el)?;
 
         let gl_attr = gl_attr.clone().map_sharing(|_| {
@@ -101,6 +153,26 @@ impl Context {
         Api::WebGl
     }
 
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        None
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        crate::ReleaseBehavior::Flush
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        None
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> Result<&crate::Extensions, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub unsafe fn raw_handle(&self) -> ffi::EMSCRIPTEN_WEBGL_CONTEXT_HANDLE {
         match self {
@@ -120,6 +192,54 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        // WebGL can't read from another surface than the one drawn to
+        if std::ptr::eq(draw, self) && std::ptr::eq(read, self) {
+            self.make_current()
+        } else {
+            Err(ContextError::FunctionUnavailable)
+        }
+    }
+
+    #[inline]
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn copy_state_from(
+        &self,
+        _src: &Context,
+        _mask: StateMask,
+    ) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
         if self.is_current() {
@@ -160,6 +280,40 @@ impl Context {
         Ok(())
     }
 
+    #[inline]
+    pub fn swap_buffers_with_frame_id(
+        &self,
+    ) -> Result<crate::FrameId, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn presentation_times(
+        &self,
+        _frame: crate::FrameId,
+    ) -> Result<crate::PresentationTimes, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to query the buffer age"
+                    .to_string(),
+            ));
+        }
+        Ok(0)
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        _rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub fn swap_buffers_with_damage(
         &self,
@@ -171,23 +325,49 @@ impl Context {
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        crate::DamageSupport::None
+    }
+
+    #[inline]
+    pub fn set_swap_interval(
+        &self,
+        _interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        _interval: crate::SwapInterval,
+    ) -> bool {
         false
     }
 
+    #[inline]
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
+        None
+    }
+
     #[inline]
     pub fn get_pixel_format(&self) -> PixelFormat {
         // FIXME: this is a dummy pixel format
         PixelFormat {
             hardware_accelerated: true,
             color_bits: 24,
+            rgb_bits: Some((8, 8, 8)),
             alpha_bits: 8,
             depth_bits: 24,
             stencil_bits: 8,
             stereoscopy: false,
             double_buffer: true,
             multisampling: None,
+            coverage_samples: None,
             srgb: true,
+            float_color_buffer: false,
+            swap_behavior: crate::SwapBehavior::Undefined,
+            swap_interval_range: None,
         }
     }
 }
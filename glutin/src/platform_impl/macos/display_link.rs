@@ -0,0 +1,259 @@
+use crate::{
+    ContextCurrentState, ContextError, CreationError, WindowedContext,
+};
+
+use crate::platform::macos::MonitorHandleExtMacOS;
+use winit::window::Window;
+
+use std::fmt;
+use std::os::raw;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+type CVDisplayLinkRef = *mut raw::c_void;
+type CVReturn = i32;
+type CGDirectDisplayID = u32;
+
+#[allow(dead_code)]
+#[repr(C)]
+struct CVSMPTETime {
+    subframes: i16,
+    subframe_divisor: i16,
+    counter: u32,
+    type_: u32,
+    flags: u32,
+    hours: i16,
+    minutes: i16,
+    seconds: i16,
+    frames: i16,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct CVTimeStamp {
+    version: u32,
+    video_time_scale: i32,
+    video_time: i64,
+    host_time: u64,
+    rate_scalar: f64,
+    video_refresh_period: i64,
+    smpte_time: CVSMPTETime,
+    flags: u64,
+    reserved: u64,
+}
+
+#[repr(C)]
+struct MachTimebaseInfo {
+    numer: u32,
+    denom: u32,
+}
+
+type CVDisplayLinkOutputCallback = unsafe extern "C" fn(
+    CVDisplayLinkRef,
+    *const CVTimeStamp,
+    *const CVTimeStamp,
+    u64,
+    *mut u64,
+    *mut raw::c_void,
+) -> CVReturn;
+
+#[link(name = "CoreVideo", kind = "framework")]
+extern "C" {
+    fn CVDisplayLinkCreateWithCGDisplay(
+        display: CGDirectDisplayID,
+        link: *mut CVDisplayLinkRef,
+    ) -> CVReturn;
+    fn CVDisplayLinkSetOutputCallback(
+        link: CVDisplayLinkRef,
+        callback: CVDisplayLinkOutputCallback,
+        user_info: *mut raw::c_void,
+    ) -> CVReturn;
+    fn CVDisplayLinkStart(link: CVDisplayLinkRef) -> CVReturn;
+    fn CVDisplayLinkStop(link: CVDisplayLinkRef) -> CVReturn;
+    fn CVDisplayLinkRelease(link: CVDisplayLinkRef);
+}
+
+extern "C" {
+    fn mach_absolute_time() -> u64;
+    fn mach_timebase_info(info: *mut MachTimebaseInfo) -> raw::c_int;
+}
+
+// Lives in a box, so that its address stays the same for the callbacks of
+// every link created for it.
+struct State {
+    callback: Mutex<Box<dyn FnMut(Instant) + Send>>,
+    // host time and `Instant` taken together, to convert the one to the
+    // other
+    epoch: (u64, Instant),
+    timebase: MachTimebaseInfo,
+}
+
+impl State {
+    fn to_instant(&self, host_time: u64) -> Instant {
+        let to_duration = |ticks: u64| {
+            let nanos = ticks as u128 * self.timebase.numer as u128
+                / self.timebase.denom as u128;
+            Duration::from_nanos(nanos as u64)
+        };
+        let (epoch_host_time, epoch) = self.epoch;
+        if host_time >= epoch_host_time {
+            epoch + to_duration(host_time - epoch_host_time)
+        } else {
+            epoch
+                .checked_sub(to_duration(epoch_host_time - host_time))
+                .unwrap_or(epoch)
+        }
+    }
+}
+
+unsafe extern "C" fn output_callback(
+    _link: CVDisplayLinkRef,
+    _now: *const CVTimeStamp,
+    output_time: *const CVTimeStamp,
+    _flags_in: u64,
+    _flags_out: *mut u64,
+    user_info: *mut raw::c_void,
+) -> CVReturn {
+    let state = &*(user_info as *const State);
+    let instant = state.to_instant((*output_time).host_time);
+    // unwinding into CoreVideo is undefined behavior
+    if let Ok(mut callback) = state.callback.lock() {
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            (*callback)(instant)
+        }));
+    }
+    0
+}
+
+/// Delivers the vertical blanks of the display a window is on, through a
+/// `CVDisplayLink`.
+///
+/// The callback gets the time at which the next frame will be shown, and is
+/// called on a thread of CoreVideo. Wrap a channel in it to receive the
+/// timestamps somewhere else.
+///
+/// A display link follows one display, so [`update`] must be called when
+/// the window may have moved to another one, for example on
+/// `WindowEvent::Moved` and `WindowEvent::ScaleFactorChanged`.
+///
+/// [`update`]: #method.update
+pub struct DisplayLink {
+    link: CVDisplayLinkRef,
+    display: CGDirectDisplayID,
+    state: Box<State>,
+}
+
+unsafe impl Send for DisplayLink {}
+
+impl fmt::Debug for DisplayLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DisplayLink")
+            .field("display", &self.display)
+            .finish()
+    }
+}
+
+impl DisplayLink {
+    /// Starts a display link for the display the window of the context is
+    /// currently on.
+    pub fn new<T: ContextCurrentState, F>(
+        windowed_context: &WindowedContext<T>,
+        callback: F,
+    ) -> Result<Self, CreationError>
+    where
+        F: FnMut(Instant) + Send + 'static,
+    {
+        let mut timebase = MachTimebaseInfo { numer: 0, denom: 0 };
+        let epoch = unsafe {
+            mach_timebase_info(&mut timebase);
+            (mach_absolute_time(), Instant::now())
+        };
+        let state = Box::new(State {
+            callback: Mutex::new(Box::new(callback)),
+            epoch,
+            timebase,
+        });
+
+        let display = current_display(windowed_context.window());
+        let link = unsafe { start_link(display, &state) }
+            .map_err(CreationError::OsError)?;
+        Ok(DisplayLink {
+            link,
+            display,
+            state,
+        })
+    }
+
+    /// Re-creates the display link if the window is now on another display.
+    pub fn update(&mut self, window: &Window) -> Result<(), ContextError> {
+        let display = current_display(window);
+        if display == self.display {
+            return Ok(());
+        }
+
+        let link = unsafe { start_link(display, &self.state) }
+            .map_err(ContextError::OsError)?;
+        unsafe {
+            stop_link(self.link);
+        }
+        self.link = link;
+        self.display = display;
+        Ok(())
+    }
+
+    /// Returns the `CGDirectDisplayID` of the display being followed.
+    #[inline]
+    pub fn display(&self) -> u32 {
+        self.display
+    }
+}
+
+impl Drop for DisplayLink {
+    fn drop(&mut self) {
+        // stopping waits for a callback in progress, so the state can be
+        // dropped afterwards
+        unsafe {
+            stop_link(self.link);
+        }
+    }
+}
+
+fn current_display(window: &Window) -> CGDirectDisplayID {
+    window.current_monitor().native_id()
+}
+
+unsafe fn start_link(
+    display: CGDirectDisplayID,
+    state: &State,
+) -> Result<CVDisplayLinkRef, String> {
+    let mut link = std::ptr::null_mut();
+    let err = CVDisplayLinkCreateWithCGDisplay(display, &mut link);
+    if err != 0 {
+        return Err(format!(
+            "CVDisplayLinkCreateWithCGDisplay failed: {}",
+            err
+        ));
+    }
+
+    let err = CVDisplayLinkSetOutputCallback(
+        link,
+        output_callback,
+        state as *const State as *mut raw::c_void,
+    );
+    if err != 0 {
+        CVDisplayLinkRelease(link);
+        return Err(format!("CVDisplayLinkSetOutputCallback failed: {}", err));
+    }
+
+    let err = CVDisplayLinkStart(link);
+    if err != 0 {
+        CVDisplayLinkRelease(link);
+        return Err(format!("CVDisplayLinkStart failed: {}", err));
+    }
+    Ok(link)
+}
+
+unsafe fn stop_link(link: CVDisplayLinkRef) {
+    CVDisplayLinkStop(link);
+    CVDisplayLinkRelease(link);
+}
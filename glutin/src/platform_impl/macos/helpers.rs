@@ -4,21 +4,27 @@ use crate::{
 };
 
 use cocoa::appkit::*;
-use cocoa::base::nil;
+use cocoa::base::{id, nil};
+
+// `NSOpenGLPFASupportsAutomaticGraphicsSwitching` is missing from `cocoa`.
+const SUPPORTS_AUTOMATIC_GRAPHICS_SWITCHING: u32 = 101;
+
+// The vendor bits of Intel renderer ids, such as `kCGLRendererIntelHDID`.
+const RENDERER_VENDOR_MASK: u32 = 0x000F_F000;
+const RENDERER_VENDOR_INTEL: u32 = 0x0002_4000;
 
 pub fn get_gl_profile<T>(
     opengl: &GlAttributes<&T>,
     pf_reqs: &PixelFormatRequirements,
 ) -> Result<NSOpenGLPFAOpenGLProfiles, CreationError> {
     let version = opengl.version.to_gl_version();
-    // first, compatibility profile support is strict
+    // first, the compatibility profile stops at 2.1
     if opengl.profile == Some(GlProfile::Compatibility) {
-        // Note: we are not using ranges because of a rust bug that should be
-        // fixed here: https://github.com/rust-lang/rust/pull/27050
-        if version.unwrap_or((2, 1)) < (3, 2) {
-            Ok(NSOpenGLProfileVersionLegacy)
-        } else {
-            Err(CreationError::OpenGlVersionNotSupported)
+        match version {
+            Some(v) if v > (2, 1) && opengl.exact_version => {
+                Err(version_not_supported(v, (2, 1)))
+            }
+            _ => Ok(NSOpenGLProfileVersionLegacy),
         }
     } else if let Some(v) = version {
         // second, process exact requested version, if any
@@ -28,40 +34,22 @@ pub fn get_gl_profile<T>(
             } else {
                 Err(CreationError::OpenGlVersionNotSupported)
             }
-        } else if v == (3, 2) {
-            Ok(NSOpenGLProfileVersion3_2Core)
-        } else {
+        } else if v > (4, 1) && opengl.exact_version {
+            Err(version_not_supported(v, (4, 1)))
+        } else if is_profile_available(NSOpenGLProfileVersion4_1Core, pf_reqs) {
+            // 4.1 is backwards compatible with the lower core versions
             Ok(NSOpenGLProfileVersion4_1Core)
+        } else if v > (3, 2) && opengl.exact_version {
+            Err(version_not_supported(v, (3, 2)))
+        } else {
+            Ok(NSOpenGLProfileVersion3_2Core)
         }
     } else if let GlRequest::Latest = opengl.version {
         // now, find the latest supported version automatically;
-        let mut attributes: [u32; 6] = [0; 6];
-        let mut current_idx = 0;
-        attributes[current_idx] = NSOpenGLPFAAllowOfflineRenderers as u32;
-        current_idx += 1;
-
-        if let Some(true) = pf_reqs.hardware_accelerated {
-            attributes[current_idx] = NSOpenGLPFAAccelerated as u32;
-            current_idx += 1;
-        }
-
-        if pf_reqs.double_buffer != Some(false) {
-            attributes[current_idx] = NSOpenGLPFADoubleBuffer as u32;
-            current_idx += 1
-        }
-
-        attributes[current_idx] = NSOpenGLPFAOpenGLProfile as u32;
-        current_idx += 1;
-
         for &profile in
             &[NSOpenGLProfileVersion4_1Core, NSOpenGLProfileVersion3_2Core]
         {
-            attributes[current_idx] = profile as u32;
-            let id = unsafe {
-                NSOpenGLPixelFormat::alloc(nil).initWithAttributes_(&attributes)
-            };
-            if id != nil {
-                unsafe { msg_send![id, release] }
+            if is_profile_available(profile, pf_reqs) {
                 return Ok(profile);
             }
         }
@@ -72,6 +60,51 @@ pub fn get_gl_profile<T>(
     }
 }
 
+/// Returns the OpenGL version of contexts created with `profile`.
+pub fn profile_version(profile: NSOpenGLPFAOpenGLProfiles) -> (u8, u8) {
+    match profile {
+        NSOpenGLProfileVersionLegacy => (2, 1),
+        NSOpenGLProfileVersion3_2Core => (3, 2),
+        NSOpenGLProfileVersion4_1Core => (4, 1),
+    }
+}
+
+fn version_not_supported(
+    requested: (u8, u8),
+    supported: (u8, u8),
+) -> CreationError {
+    CreationError::NotSupported(format!(
+        "macOS supports at most OpenGL {}.{} here, but {}.{} was requested",
+        supported.0, supported.1, requested.0, requested.1,
+    ))
+}
+
+// Whether some renderer supports `profile` with the basic requirements.
+fn is_profile_available(
+    profile: NSOpenGLPFAOpenGLProfiles,
+    pf_reqs: &PixelFormatRequirements,
+) -> bool {
+    let mut attributes = vec![NSOpenGLPFAAllowOfflineRenderers as u32];
+    if let Some(true) = pf_reqs.hardware_accelerated {
+        attributes.push(NSOpenGLPFAAccelerated as u32);
+    }
+    if pf_reqs.double_buffer != Some(false) {
+        attributes.push(NSOpenGLPFADoubleBuffer as u32);
+    }
+    attributes.push(NSOpenGLPFAOpenGLProfile as u32);
+    attributes.push(profile as u32);
+    attributes.push(0);
+
+    let id = unsafe {
+        NSOpenGLPixelFormat::alloc(nil).initWithAttributes_(&attributes)
+    };
+    if id == nil {
+        return false;
+    }
+    unsafe { msg_send![id, release] }
+    true
+}
+
 pub fn build_nsattributes(
     pf_reqs: &PixelFormatRequirements,
     profile: NSOpenGLPFAOpenGLProfiles,
@@ -81,7 +114,12 @@ pub fn build_nsattributes(
     // full color size and hope for the best. Another hiccup is that
     // `NSOpenGLPFAColorSize` also includes `NSOpenGLPFAAlphaSize`,
     // so we have to account for that as well.
-    let alpha_depth = pf_reqs.alpha_bits.unwrap_or(8);
+    let alpha_depth = match pf_reqs.alpha_bits {
+        // a transparent surface needs an alpha channel to be composited with
+        Some(alpha) if pf_reqs.transparent_surface => alpha.max(8),
+        Some(alpha) => alpha,
+        None => 8,
+    };
     let color_depth = pf_reqs.color_bits.unwrap_or(24) + alpha_depth;
 
     let mut attributes = vec![
@@ -96,9 +134,13 @@ pub fn build_nsattributes(
         pf_reqs.depth_bits.unwrap_or(24) as u32,
         NSOpenGLPFAStencilSize as u32,
         pf_reqs.stencil_bits.unwrap_or(8) as u32,
-        NSOpenGLPFAAllowOfflineRenderers as u32,
     ];
 
+    if pf_reqs.automatic_graphics_switching || pf_reqs.prefer_integrated_gpu {
+        attributes.push(NSOpenGLPFAAllowOfflineRenderers as u32);
+        attributes.push(SUPPORTS_AUTOMATIC_GRAPHICS_SWITCHING);
+    }
+
     if let Some(true) = pf_reqs.hardware_accelerated {
         attributes.push(NSOpenGLPFAAccelerated as u32);
     }
@@ -111,17 +153,21 @@ pub fn build_nsattributes(
     }
 
     if pf_reqs.release_behavior != ReleaseBehavior::Flush {
-        return Err(CreationError::NoAvailablePixelFormat);
+        return Err(CreationError::ReleaseBehaviorNotSupported);
     }
 
-    if pf_reqs.stereoscopy {
-        unimplemented!(); // TODO:
+    if pf_reqs.stereoscopy == Some(true) {
+        attributes.push(NSOpenGLPFAStereo as u32);
     }
 
     if pf_reqs.float_color_buffer {
         attributes.push(NSOpenGLPFAColorFloat as u32);
     }
 
+    if pf_reqs.preserved_swap {
+        attributes.push(NSOpenGLPFABackingStore as u32);
+    }
+
     if let Some(samples) = pf_reqs.multisampling {
         attributes.push(NSOpenGLPFAMultisample as u32);
         attributes.push(NSOpenGLPFASampleBuffers as u32);
@@ -135,3 +181,53 @@ pub fn build_nsattributes(
 
     Ok(attributes)
 }
+
+/// Fails if hardware acceleration is required but every virtual screen is
+/// driven by the Apple software renderer.
+pub fn check_hardware_acceleration(
+    renderer_ids: &[u32],
+    pf_reqs: &PixelFormatRequirements,
+) -> Result<(), CreationError> {
+    if pf_reqs.hardware_accelerated == Some(true)
+        && renderer_ids
+            .iter()
+            .all(|&id| super::renderer::is_software_renderer(id))
+    {
+        return Err(CreationError::NoHardwareAcceleration);
+    }
+    Ok(())
+}
+
+/// Returns the renderer id of each virtual screen of `pixel_format`, and
+/// makes `context` use the virtual screen of the integrated GPU if it is
+/// preferred.
+pub unsafe fn choose_virtual_screen(
+    pixel_format: id,
+    context: id,
+    pf_reqs: &PixelFormatRequirements,
+) -> Vec<u32> {
+    let count: i32 = msg_send![pixel_format, numberOfVirtualScreens];
+    let renderer_ids: Vec<u32> = (0..count)
+        .map(|screen| {
+            let mut value = 0;
+            NSOpenGLPixelFormat::getValues_forAttribute_forVirtualScreen_(
+                pixel_format,
+                &mut value,
+                NSOpenGLPFARendererID,
+                screen,
+            );
+            value as u32
+        })
+        .collect();
+
+    if pf_reqs.prefer_integrated_gpu {
+        let integrated = renderer_ids
+            .iter()
+            .position(|&id| id & RENDERER_VENDOR_MASK == RENDERER_VENDOR_INTEL);
+        if let Some(screen) = integrated {
+            let () = msg_send![context, setCurrentVirtualScreen: screen as i32];
+        }
+    }
+
+    renderer_ids
+}
@@ -1,12 +1,12 @@
 #![cfg(target_os = "macos")]
 use crate::{
-    ContextError, CreationError, GlAttributes, PixelFormat,
-    PixelFormatRequirements, Rect, Robustness,
+    ContextError, CreationError, DirectRendering, GlAttributes, PixelFormat,
+    PixelFormatRequirements, Rect, Robustness, StateMask, SwapBehavior,
 };
 
 use cgl::{
-    kCGLCECrashOnRemovedFunctions, kCGLCPSurfaceOpacity, CGLEnable,
-    CGLSetParameter,
+    kCGLCECrashOnRemovedFunctions, kCGLCPSurfaceOpacity, kCGLCPSwapInterval,
+    CGLEnable, CGLGetParameter, CGLSetParameter,
 };
 use cocoa::appkit::{self, NSOpenGLContext, NSOpenGLPixelFormat};
 use cocoa::base::{id, nil};
@@ -28,7 +28,12 @@ use std::ops::Deref;
 use std::os::raw;
 use std::str::FromStr;
 
+mod display_link;
 mod helpers;
+mod renderer;
+
+pub use self::display_link::DisplayLink;
+pub use self::renderer::Renderer;
 
 #[derive(Debug)]
 pub enum Context {
@@ -41,13 +46,29 @@ pub struct WindowedContext {
     // NSOpenGLContext
     context: IdRef,
     pixel_format: PixelFormat,
+    // the renderer of each virtual screen
+    renderer_ids: Vec<u32>,
+    // the OpenGL version of the profile, which may be lower than requested
+    version: (u8, u8),
+    // NSView, which the context loses when the window is recreated
+    view: IdRef,
 }
 
 #[derive(Debug)]
 pub struct HeadlessContext {
     context: IdRef,
+    renderer_ids: Vec<u32>,
+    version: (u8, u8),
+    // NSOpenGLPixelBuffer, or `None` if the context has no drawable and
+    // only renders to framebuffer objects
+    pbuffer: Option<IdRef>,
+    size: dpi::PhysicalSize<u32>,
 }
 
+// `NSOpenGLPixelBuffer` textures, which are never bound.
+const GL_TEXTURE_RECTANGLE: u32 = 0x84F5;
+const GL_RGBA: u32 = 0x1908;
+
 impl Context {
     #[inline]
     pub fn new_windowed<T>(
@@ -56,7 +77,45 @@ impl Context {
         pf_reqs: &PixelFormatRequirements,
         gl_attr: &GlAttributes<&Context>,
     ) -> Result<(Window, Self), CreationError> {
-        let transparent = wb.window.transparent;
+        if gl_attr.priority.is_some() {
+            return Err(CreationError::NotSupported(
+                "Context priorities are not supported by CGL".to_string(),
+            ));
+        }
+
+        if gl_attr.protected_content {
+            return Err(CreationError::NotSupported(
+                "Protected content is not supported by CGL".to_string(),
+            ));
+        }
+
+        if gl_attr.direct_rendering == DirectRendering::RequireIndirect {
+            return Err(CreationError::NotSupported(
+                "Indirect rendering is not supported by CGL".to_string(),
+            ));
+        }
+
+        if gl_attr.reset_isolation {
+            return Err(CreationError::NotSupported(
+                "Reset isolation is not supported by CGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.color_space.is_some() {
+            return Err(CreationError::NotSupported(
+                "Color spaces are not supported by CGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.textureable {
+            return Err(CreationError::NotSupported(
+                "Textureable pbuffers are not supported by CGL".to_string(),
+            ));
+        }
+
+        let mut pf_reqs = pf_reqs.clone();
+        pf_reqs.transparent_surface |= wb.window.transparent;
+        let pf_reqs = &pf_reqs;
         let win = wb.build(el)?;
 
         let share_ctx = gl_attr.sharing.map_or(nil, |c| *c.get_id());
@@ -79,7 +138,7 @@ impl Context {
                     .initWithAttributes_(&attributes),
             );
             let pixel_format = match pixel_format.non_nil() {
-                None => return Err(CreationError::NoAvailablePixelFormat),
+                None => return Err(pf_reqs.no_config_error()),
                 Some(pf) => pf,
             };
 
@@ -95,6 +154,12 @@ impl Context {
                     ));
                 }
             };
+            let renderer_ids = helpers::choose_virtual_screen(
+                *pixel_format,
+                *gl_context,
+                pf_reqs,
+            );
+            helpers::check_hardware_acceleration(&renderer_ids, pf_reqs)?;
 
             let pixel_format = {
                 let get_attr =
@@ -109,13 +174,20 @@ impl Context {
                         value
                     };
 
+                let software = renderer::is_software_renderer(get_attr(
+                    appkit::NSOpenGLPFARendererID,
+                )
+                    as u32);
+
                 PixelFormat {
                     hardware_accelerated: get_attr(
                         appkit::NSOpenGLPFAAccelerated,
-                    ) != 0,
+                    ) != 0
+                        && !software,
                     color_bits: (get_attr(appkit::NSOpenGLPFAColorSize)
                         - get_attr(appkit::NSOpenGLPFAAlphaSize))
                         as u8,
+                    rgb_bits: None,
                     alpha_bits: get_attr(appkit::NSOpenGLPFAAlphaSize) as u8,
                     depth_bits: get_attr(appkit::NSOpenGLPFADepthSize) as u8,
                     stencil_bits: get_attr(appkit::NSOpenGLPFAStencilSize)
@@ -130,7 +202,18 @@ impl Context {
                     } else {
                         None
                     },
+                    coverage_samples: None,
                     srgb: true,
+                    float_color_buffer: get_attr(appkit::NSOpenGLPFAColorFloat)
+                        != 0,
+                    swap_behavior: if get_attr(appkit::NSOpenGLPFABackingStore)
+                        != 0
+                    {
+                        SwapBehavior::Preserved
+                    } else {
+                        SwapBehavior::Undefined
+                    },
+                    swap_interval_range: None,
                 }
             };
 
@@ -141,7 +224,7 @@ impl Context {
                 appkit::NSOpenGLContextParameter::NSOpenGLCPSwapInterval,
             );
 
-            if transparent {
+            if pf_reqs.transparent_surface {
                 let mut opacity = 0;
                 CGLSetParameter(
                     gl_context.CGLContextObj() as *mut _,
@@ -150,6 +233,13 @@ impl Context {
                 );
             }
 
+            if let Some(order) = pf_reqs.surface_order {
+                gl_context.setValues_forParameter_(
+                    &order,
+                    appkit::NSOpenGLContextParameter::NSOpenGLCPSurfaceOrder,
+                );
+            }
+
             CGLEnable(
                 gl_context.CGLContextObj() as *mut _,
                 kCGLCECrashOnRemovedFunctions,
@@ -158,6 +248,9 @@ impl Context {
             let context = WindowedContext {
                 context: gl_context,
                 pixel_format: pixel_format,
+                renderer_ids,
+                version: helpers::profile_version(gl_profile),
+                view: IdRef::retain(view),
             };
             Ok((win, Context::WindowedContext(context)))
         }
@@ -168,11 +261,47 @@ impl Context {
         _el: &EventLoopWindowTarget<T>,
         pf_reqs: &PixelFormatRequirements,
         gl_attr: &GlAttributes<&Context>,
-        _size: dpi::PhysicalSize<u32>,
+        size: dpi::PhysicalSize<u32>,
     ) -> Result<Self, CreationError> {
+        if gl_attr.priority.is_some() {
+            return Err(CreationError::NotSupported(
+                "Context priorities are not supported by CGL".to_string(),
+            ));
+        }
+
+        if gl_attr.protected_content {
+            return Err(CreationError::NotSupported(
+                "Protected content is not supported by CGL".to_string(),
+            ));
+        }
+
+        if gl_attr.direct_rendering == DirectRendering::RequireIndirect {
+            return Err(CreationError::NotSupported(
+                "Indirect rendering is not supported by CGL".to_string(),
+            ));
+        }
+
+        if gl_attr.reset_isolation {
+            return Err(CreationError::NotSupported(
+                "Reset isolation is not supported by CGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.color_space.is_some() {
+            return Err(CreationError::NotSupported(
+                "Color spaces are not supported by CGL".to_string(),
+            ));
+        }
+
+        if pf_reqs.textureable {
+            return Err(CreationError::NotSupported(
+                "Textureable pbuffers are not supported by CGL".to_string(),
+            ));
+        }
+
         let gl_profile = helpers::get_gl_profile(gl_attr, pf_reqs)?;
         let attributes = helpers::build_nsattributes(pf_reqs, gl_profile)?;
-        let context = unsafe {
+        let (context, renderer_ids) = unsafe {
             let pixelformat = NSOpenGLPixelFormat::alloc(nil)
                 .initWithAttributes_(&attributes);
             if pixelformat == nil {
@@ -188,18 +317,78 @@ impl Context {
                 ));
             }
 
-            IdRef::new(context)
+            let renderer_ids =
+                helpers::choose_virtual_screen(pixelformat, context, pf_reqs);
+            let context = IdRef::new(context);
+            helpers::check_hardware_acceleration(&renderer_ids, pf_reqs)?;
+            (context, renderer_ids)
+        };
+
+        // the pbuffer gives the context a default framebuffer, without it
+        // the context can still render to framebuffer objects
+        let pbuffer = unsafe {
+            let pbuffer: id = msg_send![class!(NSOpenGLPixelBuffer), alloc];
+            let pbuffer: id = msg_send![pbuffer,
+                initWithTextureTarget: GL_TEXTURE_RECTANGLE
+                textureInternalFormat: GL_RGBA
+                textureMaxMipMapLevel: 0 as i32
+                pixelsWide: size.width as i32
+                pixelsHigh: size.height as i32];
+            IdRef::new(pbuffer).non_nil().map(|pbuffer| {
+                let screen = NSOpenGLContext::currentVirtualScreen(*context);
+                let () = msg_send![*context,
+                    setPixelBuffer: *pbuffer
+                    cubeMapFace: 0u32
+                    mipMapLevel: 0 as i32
+                    currentVirtualScreen: screen];
+                pbuffer
+            })
         };
 
-        let headless = HeadlessContext { context };
+        let headless = HeadlessContext {
+            context,
+            renderer_ids,
+            version: helpers::profile_version(gl_profile),
+            pbuffer,
+            size,
+        };
 
         Ok(Context::HeadlessContext(headless))
     }
 
-    pub fn resize(&self, _width: u32, _height: u32) {
-        match *self {
-            Context::WindowedContext(ref c) => unsafe { c.context.update() },
-            _ => unreachable!(),
+    pub fn resize(&self, width: u32, height: u32) {
+        // minimized windows have no size to update to
+        if width == 0 || height == 0 {
+            return;
+        }
+        let c = match *self {
+            Context::WindowedContext(ref c) => c,
+            Context::HeadlessContext(_) => return,
+        };
+        unsafe {
+            let view: id = msg_send![*c.context, view];
+            let is_main_thread: BOOL =
+                msg_send![class!(NSThread), isMainThread];
+            if is_main_thread != NO {
+                if view != *c.view {
+                    c.context.setView_(*c.view);
+                }
+                c.context.update();
+            } else {
+                // AppKit requires the main thread. Waiting for it could
+                // deadlock if it waits for this thread, so the update happens
+                // the next time its run loop runs.
+                if view != *c.view {
+                    let () = msg_send![*c.context,
+                        performSelectorOnMainThread: sel!(setView:)
+                        withObject: *c.view
+                        waitUntilDone: NO];
+                }
+                let () = msg_send![*c.context,
+                    performSelectorOnMainThread: sel!(update)
+                    withObject: nil
+                    waitUntilDone: NO];
+            }
         }
     }
 
@@ -218,6 +407,61 @@ impl Context {
         Ok(())
     }
 
+    #[inline]
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        // CGL can't read from another surface than the one drawn to
+        if std::ptr::eq(draw, self) && std::ptr::eq(read, self) {
+            self.make_current()
+        } else {
+            Err(ContextError::FunctionUnavailable)
+        }
+    }
+
+    #[inline]
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn copy_state_from(
+        &self,
+        _src: &Context,
+        _mask: StateMask,
+    ) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        match *self {
+            Context::HeadlessContext(HeadlessContext {
+                pbuffer: Some(_),
+                size,
+                ..
+            }) => Ok(size),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
         if self.is_current() {
@@ -283,12 +527,47 @@ impl Context {
                     c.context.flushBuffer();
                     let _: () = msg_send![pool, release];
                 }
-                Context::HeadlessContext(_) => unreachable!(),
+                // there is nothing to present, so the commands are flushed
+                Context::HeadlessContext(ref c) => c.context.flushBuffer(),
             }
         }
         Ok(())
     }
 
+    #[inline]
+    pub fn swap_buffers_with_frame_id(
+        &self,
+    ) -> Result<crate::FrameId, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn presentation_times(
+        &self,
+        _frame: crate::FrameId,
+    ) -> Result<crate::PresentationTimes, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        if !self.is_current() {
+            return Err(ContextError::OsError(
+                "The context must be current to query the buffer age"
+                    .to_string(),
+            ));
+        }
+        Ok(0)
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        _rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub fn swap_buffers_with_damage(
         &self,
@@ -300,8 +579,78 @@ impl Context {
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
-        false
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        crate::DamageSupport::None
+    }
+
+    // Only intervals of 0 and 1 are meaningful for `kCGLCPSwapInterval`, so
+    // longer intervals are clamped to 1, which is what gets returned. The
+    // interval belongs to the context rather than to its view.
+    #[inline]
+    pub fn set_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        let value = match interval {
+            crate::SwapInterval::DontWait => 0,
+            crate::SwapInterval::Wait(_) => 1,
+            crate::SwapInterval::AdaptiveWait(_) => {
+                return Err(ContextError::FunctionUnavailable)
+            }
+        };
+        match *self {
+            Context::WindowedContext(ref c) => unsafe {
+                let err = CGLSetParameter(
+                    c.context.CGLContextObj() as *mut _,
+                    kCGLCPSwapInterval,
+                    &value,
+                );
+                if err != 0 {
+                    return Err(ContextError::OsError(format!(
+                        "CGLSetParameter(kCGLCPSwapInterval) failed: {}",
+                        err
+                    )));
+                }
+            },
+            Context::HeadlessContext(_) => {
+                return Err(ContextError::FunctionUnavailable)
+            }
+        }
+        Ok(crate::SwapInterval::from_raw(value))
+    }
+
+    #[inline]
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
+        match *self {
+            Context::WindowedContext(ref c) => {
+                let mut value = 0;
+                let err = unsafe {
+                    CGLGetParameter(
+                        c.context.CGLContextObj() as *mut _,
+                        kCGLCPSwapInterval,
+                        &mut value,
+                    )
+                };
+                if err != 0 {
+                    return None;
+                }
+                Some(crate::SwapInterval::from_raw(value))
+            }
+            Context::HeadlessContext(_) => None,
+        }
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> bool {
+        match (self, interval) {
+            (Context::HeadlessContext(_), _) => false,
+            (_, crate::SwapInterval::DontWait) => true,
+            (_, crate::SwapInterval::Wait(n)) => n.get() == 1,
+            (_, crate::SwapInterval::AdaptiveWait(_)) => false,
+        }
     }
 
     #[inline]
@@ -309,6 +658,26 @@ impl Context {
         crate::Api::OpenGl
     }
 
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        None
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        crate::ReleaseBehavior::Flush
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        None
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> Result<&crate::Extensions, ContextError> {
+        Err(ContextError::FunctionUnavailable)
+    }
+
     #[inline]
     pub fn get_pixel_format(&self) -> PixelFormat {
         match *self {
@@ -329,6 +698,29 @@ impl Context {
         }
     }
 
+    /// Returns the virtual screen of the context, and the id of its
+    /// renderer.
+    pub fn virtual_screen(&self) -> (i32, u32) {
+        let (context, renderer_ids) = match *self {
+            Context::WindowedContext(ref c) => (&c.context, &c.renderer_ids),
+            Context::HeadlessContext(ref c) => (&c.context, &c.renderer_ids),
+        };
+        let screen: i32 =
+            unsafe { NSOpenGLContext::currentVirtualScreen(**context) };
+        let renderer_id =
+            renderer_ids.get(screen as usize).cloned().unwrap_or(0);
+        (screen, renderer_id)
+    }
+
+    /// Returns the OpenGL version of the context.
+    #[inline]
+    pub fn version(&self) -> (u8, u8) {
+        match *self {
+            Context::WindowedContext(ref c) => c.version,
+            Context::HeadlessContext(ref c) => c.version,
+        }
+    }
+
     #[inline]
     fn get_id(&self) -> IdRef {
         match self {
@@ -389,3 +781,58 @@ impl Clone for IdRef {
 
 unsafe impl Send for Context {}
 unsafe impl Sync for Context {}
+
+/// A macOS-specific extension to CGL [`Context`]s.
+///
+/// [`Context`]: ../../struct.Context.html
+pub trait CglContextExt {
+    /// Returns the virtual screen the context renders on. Each virtual
+    /// screen of a pixel format is driven by one renderer, so on dual-GPU
+    /// Macs it tells which GPU the context landed on.
+    fn virtual_screen(&self) -> i32;
+
+    /// Returns the `kCGLPFARendererID` of the renderer of the current
+    /// virtual screen, such as `kCGLRendererIntelHDID`, or `0` if it is
+    /// unknown.
+    fn renderer_id(&self) -> u32;
+
+    /// Returns the renderer of the current virtual screen, with its
+    /// capabilities, or `None` if it isn't among the renderers of
+    /// [`Renderer::enumerate`].
+    ///
+    /// [`Renderer::enumerate`]: struct.Renderer.html#method.enumerate
+    fn renderer(&self) -> Option<Renderer>;
+
+    /// Returns the OpenGL version of the context: 4.1 or 3.2 with the core
+    /// profile, or 2.1 with the legacy one. Requests for a higher version are
+    /// clamped to it unless [`with_exact_gl_version`] was set.
+    ///
+    /// [`with_exact_gl_version`]:
+    /// ../../struct.ContextBuilder.html#method.with_exact_gl_version
+    fn version(&self) -> (u8, u8);
+}
+
+impl<T: crate::ContextCurrentState> CglContextExt for crate::Context<T> {
+    #[inline]
+    fn virtual_screen(&self) -> i32 {
+        self.context.virtual_screen().0
+    }
+
+    #[inline]
+    fn renderer_id(&self) -> u32 {
+        self.context.virtual_screen().1
+    }
+
+    fn renderer(&self) -> Option<Renderer> {
+        let renderer_id = self.renderer_id();
+        Renderer::enumerate()
+            .ok()?
+            .into_iter()
+            .find(|renderer| renderer.matches(renderer_id))
+    }
+
+    #[inline]
+    fn version(&self) -> (u8, u8) {
+        self.context.version()
+    }
+}
@@ -0,0 +1,150 @@
+use crate::CreationError;
+
+use std::os::raw;
+
+type CGLRendererInfoObj = *mut raw::c_void;
+type CGLError = i32;
+type GLint = i32;
+
+const ALL_DISPLAYS: u32 = 0xFFFF_FFFF;
+
+// `CGLRendererProperty` values
+const RP_RENDERER_ID: i32 = 70;
+const RP_ACCELERATED: i32 = 73;
+const RP_DISPLAY_MASK: i32 = 84;
+const RP_MAX_SAMPLE_BUFFERS: i32 = 108;
+const RP_MAX_SAMPLES: i32 = 109;
+const RP_ONLINE: i32 = 129;
+const RP_VIDEO_MEMORY_MEGABYTES: i32 = 131;
+const RP_TEXTURE_MEMORY_MEGABYTES: i32 = 132;
+const RP_MAJOR_GL_VERSION: i32 = 133;
+
+// `kCGLRendererIDMatchingMask`, as renderer ids carry revision bits which
+// differ between the pixel format and the renderer info.
+const RENDERER_ID_MATCHING_MASK: u32 = 0x00FE_7F00;
+
+// `kCGLRendererGenericID` and `kCGLRendererGenericFloatID`, the Apple
+// software renderers.
+const SOFTWARE_RENDERER_IDS: &[u32] = &[0x0002_0200, 0x0002_0400];
+
+#[link(name = "OpenGL", kind = "framework")]
+extern "C" {
+    fn CGLQueryRendererInfo(
+        display_mask: u32,
+        info: *mut CGLRendererInfoObj,
+        count: *mut GLint,
+    ) -> CGLError;
+    fn CGLDescribeRenderer(
+        info: CGLRendererInfoObj,
+        index: GLint,
+        property: i32,
+        value: *mut GLint,
+    ) -> CGLError;
+    fn CGLDestroyRendererInfo(info: CGLRendererInfoObj) -> CGLError;
+}
+
+/// A renderer of the system, as enumerated by [`Renderer::enumerate`].
+///
+/// [`Renderer::enumerate`]: #method.enumerate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Renderer {
+    /// The `kCGLRPRendererID` of the renderer, such as
+    /// `kCGLRendererIntelHDID`.
+    pub id: u32,
+    /// Whether the renderer is a GPU rather than a software renderer.
+    pub accelerated: bool,
+    /// Whether the renderer drives a display. An offline GPU can only be
+    /// used with [`with_automatic_graphics_switching`].
+    ///
+    /// [`with_automatic_graphics_switching`]:
+    /// trait.ContextBuilderExtMacOS.html#tymethod.with_automatic_graphics_switching
+    pub online: bool,
+    /// The mask of the displays the renderer drives.
+    pub display_mask: u32,
+    /// The video memory in megabytes.
+    pub video_memory: u32,
+    /// The texture memory in megabytes.
+    pub texture_memory: u32,
+    /// The maximum number of multisample buffers.
+    pub max_sample_buffers: u32,
+    /// The maximum number of samples per pixel.
+    pub max_samples: u32,
+    /// The latest core profile the renderer supports, either `(3, 2)` or
+    /// `(4, 1)`, or `None` if it only supports the legacy profile, which
+    /// every renderer supports.
+    pub max_core_profile: Option<(u8, u8)>,
+}
+
+impl Renderer {
+    /// Enumerates the renderers of all the displays, with
+    /// `CGLQueryRendererInfo`, for instance to include them in bug reports.
+    pub fn enumerate() -> Result<Vec<Renderer>, CreationError> {
+        let mut info = std::ptr::null_mut();
+        let mut count = 0;
+        let err = unsafe {
+            CGLQueryRendererInfo(ALL_DISPLAYS, &mut info, &mut count)
+        };
+        if err != 0 {
+            return Err(CreationError::OsError(format!(
+                "CGLQueryRendererInfo failed: {}",
+                err
+            )));
+        }
+
+        let describe = |index, property| {
+            let mut value = 0;
+            let err = unsafe {
+                CGLDescribeRenderer(info, index, property, &mut value)
+            };
+            // properties unknown to older systems are reported as 0
+            if err != 0 {
+                0
+            } else {
+                value
+            }
+        };
+        let renderers = (0..count)
+            .map(|index| Renderer {
+                id: describe(index, RP_RENDERER_ID) as u32,
+                accelerated: describe(index, RP_ACCELERATED) != 0,
+                online: describe(index, RP_ONLINE) != 0,
+                display_mask: describe(index, RP_DISPLAY_MASK) as u32,
+                video_memory: describe(index, RP_VIDEO_MEMORY_MEGABYTES) as u32,
+                texture_memory: describe(index, RP_TEXTURE_MEMORY_MEGABYTES)
+                    as u32,
+                max_sample_buffers: describe(index, RP_MAX_SAMPLE_BUFFERS)
+                    as u32,
+                max_samples: describe(index, RP_MAX_SAMPLES) as u32,
+                max_core_profile: match describe(index, RP_MAJOR_GL_VERSION) {
+                    v if v >= 4 => Some((4, 1)),
+                    3 => Some((3, 2)),
+                    _ => None,
+                },
+            })
+            .collect();
+
+        unsafe {
+            CGLDestroyRendererInfo(info);
+        }
+        Ok(renderers)
+    }
+
+    /// Returns whether the renderer has the given `kCGLRPRendererID`,
+    /// ignoring its revision bits.
+    #[inline]
+    pub fn matches(&self, renderer_id: u32) -> bool {
+        same_renderer(self.id, renderer_id)
+    }
+}
+
+#[inline]
+fn same_renderer(a: u32, b: u32) -> bool {
+    a & RENDERER_ID_MATCHING_MASK == b & RENDERER_ID_MATCHING_MASK
+}
+
+/// Returns whether `renderer_id` is an Apple software renderer.
+pub fn is_software_renderer(renderer_id: u32) -> bool {
+    SOFTWARE_RENDERER_IDS
+        .iter()
+        .any(|&id| same_renderer(id, renderer_id))
+}
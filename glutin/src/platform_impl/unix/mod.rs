@@ -10,10 +10,16 @@ mod wayland;
 mod x11;
 
 use self::x11::X11Context;
+use crate::api::egl::{
+    self, Context as EglContext, NativeDisplay, SurfaceType as EglSurfaceType,
+    EGL,
+};
+use crate::api::glx::{Context as GlxContext, GlxSwapControl, GlxSyncValues};
 use crate::api::osmesa;
 use crate::{
     Api, ContextCurrentState, ContextError, CreationError, GlAttributes,
-    NotCurrent, PixelFormat, PixelFormatRequirements, Rect,
+    NotCurrent, PixelFormat, PixelFormatRequirements, PossiblyCurrent, Rect,
+    StateMask, SwapGroupLimits,
 };
 pub use x11::utils as x11_utils;
 
@@ -41,6 +47,8 @@ pub enum ContextType {
     X11,
     Wayland,
     OsMesa,
+    EglDevice,
+    Gbm,
 }
 
 #[derive(Debug)]
@@ -48,6 +56,8 @@ pub enum Context {
     X11(x11::Context),
     Wayland(wayland::Context),
     OsMesa(osmesa::OsMesaContext),
+    EglDevice(EglContext),
+    Gbm(EglContext),
 }
 
 impl Context {
@@ -84,6 +94,25 @@ impl Context {
                         ));
                     }
                 },
+                ContextType::EglDevice => match *c {
+                    Context::EglDevice(_) => Ok(()),
+                    _ => {
+                        let msg = "Cannot share an EGL device context with a non-EGL device context";
+                        return Err(CreationError::PlatformSpecific(
+                            msg.into(),
+                        ));
+                    }
+                },
+                ContextType::Gbm => match *c {
+                    Context::Gbm(_) => Ok(()),
+                    _ => {
+                        let msg =
+                            "Cannot share a GBM context with a non-GBM context";
+                        return Err(CreationError::PlatformSpecific(
+                            msg.into(),
+                        ));
+                    }
+                },
             }
         } else {
             Ok(())
@@ -152,12 +181,229 @@ impl Context {
         }
     }
 
+    /// Creates a pbuffer context, or a surfaceless one if `size` is `None`, on
+    /// the display of the given EGL device.
+    pub fn new_egl_device(
+        device: &egl::Device,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&Context>,
+        size: Option<dpi::PhysicalSize<u32>>,
+    ) -> Result<Self, CreationError> {
+        if EGL.is_none() {
+            return Err(CreationError::NotSupported(
+                "libEGL not present".to_string(),
+            ));
+        }
+
+        Context::is_compatible(&gl_attr.sharing, ContextType::EglDevice)?;
+        let gl_attr = gl_attr.clone().map_sharing(|ctx| match *ctx {
+            Context::EglDevice(ref ctx) => ctx,
+            _ => unreachable!(),
+        });
+        let native_display = NativeDisplay::Device(device.raw_device());
+        let context = if let Some(size) = size {
+            EglContext::new(
+                pf_reqs,
+                &gl_attr,
+                native_display,
+                EglSurfaceType::PBuffer,
+                |c, _| Ok(c[0]),
+            )
+            .and_then(|p| p.finish_pbuffer(size))?
+        } else {
+            EglContext::new(
+                pf_reqs,
+                &gl_attr,
+                native_display,
+                EglSurfaceType::Surfaceless,
+                |c, _| Ok(c[0]),
+            )
+            .and_then(|p| p.finish_surfaceless())?
+        };
+        Ok(Context::EglDevice(context))
+    }
+
+    /// Creates a context presenting to an output layer of the given EGL
+    /// device through an EGLStream.
+    pub fn new_egl_stream(
+        device: &egl::Device,
+        plane: Option<u32>,
+        size: dpi::PhysicalSize<u32>,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&Context>,
+    ) -> Result<Self, CreationError> {
+        if EGL.is_none() {
+            return Err(CreationError::NotSupported(
+                "libEGL not present".to_string(),
+            ));
+        }
+
+        Context::is_compatible(&gl_attr.sharing, ContextType::EglDevice)?;
+        let gl_attr = gl_attr.clone().map_sharing(|ctx| match *ctx {
+            Context::EglDevice(ref ctx) => ctx,
+            _ => unreachable!(),
+        });
+        let native_display = NativeDisplay::Device(device.raw_device());
+        let context = EglContext::new(
+            pf_reqs,
+            &gl_attr,
+            native_display,
+            EglSurfaceType::Stream,
+            |c, _| Ok(c[0]),
+        )
+        .and_then(|p| p.finish_stream(plane, size))?;
+        Ok(Context::EglDevice(context))
+    }
+
+    /// Creates a context rendering to a GBM surface, on the display of the
+    /// given GBM device. The config must match the `format` of the surface.
+    pub unsafe fn new_gbm(
+        gbm_device: *mut raw::c_void,
+        gbm_surface: *mut raw::c_void,
+        format: u32,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&Context>,
+    ) -> Result<Self, CreationError> {
+        if EGL.is_none() {
+            return Err(CreationError::NotSupported(
+                "libEGL not present".to_string(),
+            ));
+        }
+
+        Context::is_compatible(&gl_attr.sharing, ContextType::Gbm)?;
+        let gl_attr = gl_attr.clone().map_sharing(|ctx| match *ctx {
+            Context::Gbm(ref ctx) => ctx,
+            _ => unreachable!(),
+        });
+        let native_display = NativeDisplay::Gbm(Some(gbm_device as *const _));
+        let context = EglContext::new(
+            pf_reqs,
+            &gl_attr,
+            native_display,
+            EglSurfaceType::Window,
+            |c, display| {
+                // the visual id of a GBM config is its format
+                c.into_iter()
+                    .find(|&config| {
+                        egl::get_native_visual_id(display, config) as u32
+                            == format
+                    })
+                    .ok_or(())
+            },
+        )
+        .and_then(|p| p.finish(gbm_surface as _))?;
+        Ok(Context::Gbm(context))
+    }
+
     #[inline]
     pub unsafe fn make_current(&self) -> Result<(), ContextError> {
         match *self {
             Context::X11(ref ctx) => ctx.make_current(),
             Context::Wayland(ref ctx) => ctx.make_current(),
             Context::OsMesa(ref ctx) => ctx.make_current(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.make_current()
+            }
+        }
+    }
+
+    #[inline]
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        match (self, draw, read) {
+            (
+                Context::X11(ref ctx),
+                Context::X11(ref draw),
+                Context::X11(ref read),
+            ) => ctx.make_current_draw_read(draw, read),
+            // OsMesa only has the buffer of the context itself
+            (Context::OsMesa(ref ctx), _, _) => {
+                if std::ptr::eq(draw, self) && std::ptr::eq(read, self) {
+                    ctx.make_current()
+                } else {
+                    Err(ContextError::FunctionUnavailable)
+                }
+            }
+            _ => match (self.egl(), draw.egl(), read.egl()) {
+                (Some(ctx), Some(draw), Some(read)) => {
+                    ctx.make_current_draw_read(draw, read)
+                }
+                _ => Err(ContextError::FunctionUnavailable),
+            },
+        }
+    }
+
+    #[inline]
+    pub unsafe fn make_current_surfaceless(&self) -> Result<(), ContextError> {
+        match *self {
+            Context::X11(ref ctx) => ctx.make_current_surfaceless(),
+            Context::Wayland(ref ctx) => ctx.make_current_surfaceless(),
+            Context::OsMesa(_) => Err(ContextError::FunctionUnavailable),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.make_current_surfaceless()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .bind_tex_image()
+    }
+
+    #[inline]
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .release_tex_image()
+    }
+
+    #[inline]
+    pub fn copy_state_from(
+        &self,
+        src: &Context,
+        mask: StateMask,
+    ) -> Result<(), ContextError> {
+        match (self.glx(), src.glx()) {
+            (Some(dst), Some(src)) => dst.copy_state_from(src, mask),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    #[inline]
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        match *self {
+            Context::X11(ref ctx) => ctx.pbuffer_size(),
+            _ => self
+                .egl()
+                .ok_or(ContextError::FunctionUnavailable)?
+                .pbuffer_size(),
+        }
+    }
+
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        match *self {
+            Context::X11(ref ctx) => ctx.wait_gl(),
+            _ => self
+                .egl()
+                .ok_or(ContextError::FunctionUnavailable)?
+                .wait_gl(),
+        }
+    }
+
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        match *self {
+            Context::X11(ref ctx) => ctx.wait_native(),
+            _ => self
+                .egl()
+                .ok_or(ContextError::FunctionUnavailable)?
+                .wait_native(),
         }
     }
 
@@ -167,6 +413,9 @@ impl Context {
             Context::X11(ref ctx) => ctx.make_not_current(),
             Context::Wayland(ref ctx) => ctx.make_not_current(),
             Context::OsMesa(ref ctx) => ctx.make_not_current(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.make_not_current()
+            }
         }
     }
 
@@ -176,6 +425,9 @@ impl Context {
             Context::X11(ref ctx) => ctx.is_current(),
             Context::Wayland(ref ctx) => ctx.is_current(),
             Context::OsMesa(ref ctx) => ctx.is_current(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.is_current()
+            }
         }
     }
 
@@ -185,6 +437,57 @@ impl Context {
             Context::X11(ref ctx) => ctx.get_api(),
             Context::Wayland(ref ctx) => ctx.get_api(),
             Context::OsMesa(ref ctx) => ctx.get_api(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.get_api()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        match *self {
+            Context::X11(ref ctx) => ctx.priority(),
+            Context::Wayland(ref ctx) => ctx.priority(),
+            Context::OsMesa(_) => None,
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.priority()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        match *self {
+            Context::X11(ref ctx) => ctx.release_behavior(),
+            Context::Wayland(ref ctx) => ctx.release_behavior(),
+            Context::OsMesa(_) => crate::ReleaseBehavior::Flush,
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.release_behavior()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        match *self {
+            Context::X11(ref ctx) => ctx.color_space(),
+            Context::Wayland(ref ctx) => ctx.color_space(),
+            Context::OsMesa(_) => None,
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.color_space()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> Result<&crate::Extensions, ContextError> {
+        match *self {
+            Context::X11(ref ctx) => Ok(ctx.extensions()),
+            Context::Wayland(ref ctx) => Ok(ctx.extensions()),
+            Context::OsMesa(_) => Err(ContextError::FunctionUnavailable),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                Ok(ctx.extensions())
+            }
         }
     }
 
@@ -197,6 +500,9 @@ impl Context {
             },
             Context::Wayland(ref ctx) => RawHandle::Egl(ctx.raw_handle()),
             Context::OsMesa(ref ctx) => RawHandle::Egl(ctx.raw_handle()),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                RawHandle::Egl(ctx.raw_handle())
+            }
         }
     }
 
@@ -205,6 +511,9 @@ impl Context {
         match *self {
             Context::X11(ref ctx) => ctx.get_egl_display(),
             Context::Wayland(ref ctx) => ctx.get_egl_display(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                Some(ctx.get_egl_display())
+            }
             _ => None,
         }
     }
@@ -214,6 +523,8 @@ impl Context {
         match *self {
             Context::X11(_) => (),
             Context::Wayland(ref ctx) => ctx.resize(width, height),
+            // the size of a GBM surface is fixed when it is created
+            Context::Gbm(_) => (),
             _ => unreachable!(),
         }
     }
@@ -224,6 +535,9 @@ impl Context {
             Context::X11(ref ctx) => ctx.get_proc_address(addr),
             Context::Wayland(ref ctx) => ctx.get_proc_address(addr),
             Context::OsMesa(ref ctx) => ctx.get_proc_address(addr),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.get_proc_address(addr)
+            }
         }
     }
 
@@ -232,6 +546,61 @@ impl Context {
         match *self {
             Context::X11(ref ctx) => ctx.swap_buffers(),
             Context::Wayland(ref ctx) => ctx.swap_buffers(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.swap_buffers()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub fn swap_buffers_with_frame_id(
+        &self,
+    ) -> Result<crate::FrameId, ContextError> {
+        if let Some(ctx) = self.glx() {
+            return ctx.swap_buffers_with_frame_id();
+        }
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .swap_buffers_with_frame_id()
+    }
+
+    #[inline]
+    pub fn presentation_times(
+        &self,
+        frame: crate::FrameId,
+    ) -> Result<crate::PresentationTimes, ContextError> {
+        if let Some(ctx) = self.glx() {
+            return ctx.presentation_times(frame);
+        }
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .presentation_times(frame)
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        match *self {
+            Context::X11(ref ctx) => ctx.buffer_age(),
+            Context::Wayland(ref ctx) => ctx.buffer_age(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.buffer_age()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        match *self {
+            Context::X11(ref ctx) => ctx.set_damage_region(rects),
+            Context::Wayland(ref ctx) => ctx.set_damage_region(rects),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.set_damage_region(rects)
+            }
             _ => unreachable!(),
         }
     }
@@ -244,31 +613,141 @@ impl Context {
         match *self {
             Context::X11(ref ctx) => ctx.swap_buffers_with_damage(rects),
             Context::Wayland(ref ctx) => ctx.swap_buffers_with_damage(rects),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.swap_buffers_with_damage(rects)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        match *self {
+            Context::X11(ref ctx) => ctx.swap_damage_support(),
+            Context::Wayland(ref ctx) => ctx.swap_damage_support(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.swap_damage_support()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub fn set_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        match *self {
+            Context::X11(ref ctx) => ctx.set_swap_interval(interval),
+            Context::Wayland(ref ctx) => ctx.set_swap_interval(interval),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.set_swap_interval(interval)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> bool {
+        match *self {
+            Context::X11(ref ctx) => ctx.supports_swap_interval(interval),
+            Context::Wayland(ref ctx) => ctx.supports_swap_interval(interval),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.supports_swap_interval(interval)
+            }
             _ => unreachable!(),
         }
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
         match *self {
-            Context::X11(ref ctx) => ctx.swap_buffers_with_damage_supported(),
-            Context::Wayland(ref ctx) => {
-                ctx.swap_buffers_with_damage_supported()
+            Context::X11(ref ctx) => ctx.get_swap_interval(),
+            Context::Wayland(ref ctx) => ctx.get_swap_interval(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.get_swap_interval()
             }
             _ => unreachable!(),
         }
     }
 
+    #[inline]
+    pub fn egl(&self) -> Option<&EglContext> {
+        match *self {
+            Context::X11(ref ctx) => match *unsafe { ctx.raw_handle() } {
+                X11Context::Egl(ref ctx) => Some(ctx),
+                X11Context::Glx(_) => None,
+            },
+            Context::Wayland(ref ctx) => Some(&**ctx),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => Some(ctx),
+            Context::OsMesa(_) => None,
+        }
+    }
+
+    #[inline]
+    pub fn glx(&self) -> Option<&GlxContext> {
+        match *self {
+            Context::X11(ref ctx) => match *unsafe { ctx.raw_handle() } {
+                X11Context::Glx(ref ctx) => Some(ctx),
+                X11Context::Egl(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn osmesa(&self) -> Option<&osmesa::OsMesaContext> {
+        match *self {
+            Context::OsMesa(ref ctx) => Some(ctx),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn osmesa_mut(&mut self) -> Option<&mut osmesa::OsMesaContext> {
+        match *self {
+            Context::OsMesa(ref mut ctx) => Some(ctx),
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn get_pixel_format(&self) -> PixelFormat {
         match *self {
             Context::X11(ref ctx) => ctx.get_pixel_format(),
             Context::Wayland(ref ctx) => ctx.get_pixel_format(),
+            Context::EglDevice(ref ctx) | Context::Gbm(ref ctx) => {
+                ctx.get_pixel_format()
+            }
             _ => unreachable!(),
         }
     }
 }
 
+#[inline]
+pub fn glx_supported_sample_counts<T>(
+    el: &EventLoopWindowTarget<T>,
+    pf_reqs: &PixelFormatRequirements,
+) -> Result<Vec<u16>, CreationError> {
+    x11::Context::glx_supported_sample_counts(el, pf_reqs)
+}
+
+#[inline]
+pub fn egl_supported_sample_counts<T>(
+    el: &EventLoopWindowTarget<T>,
+    pf_reqs: &PixelFormatRequirements,
+    gl_attr: &GlAttributes<&EglContext>,
+) -> Result<Vec<u16>, CreationError> {
+    if el.is_wayland() {
+        wayland::Context::egl_supported_sample_counts(el, pf_reqs, gl_attr)
+    } else {
+        x11::Context::egl_supported_sample_counts(el, pf_reqs, gl_attr)
+    }
+}
+
 /// A unix-specific extension to the [`ContextBuilder`] which allows building
 /// unix-specific headless contexts.
 ///
@@ -278,9 +757,12 @@ pub trait HeadlessContextExt {
     ///
     /// Errors can occur if the OpenGL [`Context`] could not be created. This
     /// generally happens because the underlying platform doesn't support a
-    /// requested feature.
+    /// requested feature. OsMesa contexts can't share lists, so requesting
+    /// [`with_shared_lists`] results in a [`CreationError::NotSupported`].
     ///
     /// [`Context`]: struct.Context.html
+    /// [`with_shared_lists`]: ../../struct.ContextBuilder.html#method.with_shared_lists
+    /// [`CreationError::NotSupported`]: ../../enum.CreationError.html#variant.NotSupported
     fn build_osmesa(
         self,
         size: dpi::PhysicalSize<u32>,
@@ -288,6 +770,49 @@ pub trait HeadlessContextExt {
     where
         Self: Sized;
 
+    /// Builds an OsMesa context using the given OsMesa-specific attributes.
+    ///
+    /// See [`build_osmesa`] for more details.
+    ///
+    /// [`build_osmesa`]: trait.HeadlessContextExt.html#tymethod.build_osmesa
+    fn build_osmesa_with_attributes(
+        self,
+        size: dpi::PhysicalSize<u32>,
+        osmesa_attr: osmesa::OsMesaAttributes,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized;
+
+    /// Wraps an OsMesa context that was created elsewhere, for example by C
+    /// code, such as one returned by [`raw_handle`].
+    ///
+    /// The context gets a new buffer of the given size, using the format and
+    /// channel type of `osmesa_attr`, which must be supported by the context.
+    /// The attributes of the builder that OsMesa can't honor are rejected
+    /// like by [`build_osmesa`], but the version, profile and buffer bits are
+    /// those the context was created with.
+    ///
+    /// If `owned` is true, the returned [`Context`] destroys the OsMesa
+    /// context when dropped. Otherwise, it is left alone, and only unbound if
+    /// it is still bound to the buffer of the returned [`Context`].
+    ///
+    /// Unsafe behaviour might happen if you:
+    ///   - Provide us with an invalid context.
+    ///   - Destroy the context while the returned [`Context`] is alive.
+    ///   - Pass `true` for `owned` and destroy the context yourself.
+    ///
+    /// [`Context`]: ../../struct.Context.html
+    /// [`raw_handle`]: ../trait.ContextTraitExt.html#tymethod.raw_handle
+    unsafe fn build_raw_osmesa_context(
+        self,
+        context: *mut raw::c_void,
+        owned: bool,
+        size: dpi::PhysicalSize<u32>,
+        osmesa_attr: osmesa::OsMesaAttributes,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized;
+
     /// Builds an EGL-surfaceless context.
     ///
     /// Errors can occur if the OpenGL [`Context`] could not be created. This
@@ -301,6 +826,35 @@ pub trait HeadlessContextExt {
     ) -> Result<crate::Context<NotCurrent>, CreationError>
     where
         Self: Sized;
+
+    /// Builds a headless context backed by a pbuffer on the given EGL
+    /// [`Device`], without needing a display server.
+    ///
+    /// Returns a [`CreationError::NotSupported`] if libEGL lacks
+    /// `EGL_EXT_platform_device`, instead of falling back to another device.
+    ///
+    /// [`Device`]: struct.Device.html
+    /// [`CreationError::NotSupported`]: ../../enum.CreationError.html#variant.NotSupported
+    fn build_headless_with_device(
+        self,
+        device: &egl::Device,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized;
+
+    /// Builds an EGL-surfaceless context on the given EGL [`Device`].
+    ///
+    /// See [`build_headless_with_device`] for more details.
+    ///
+    /// [`Device`]: struct.Device.html
+    /// [`build_headless_with_device`]: trait.HeadlessContextExt.html#tymethod.build_headless_with_device
+    fn build_surfaceless_with_device(
+        self,
+        device: &egl::Device,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized;
 }
 
 impl<'a, T: ContextCurrentState> HeadlessContextExt
@@ -311,6 +865,18 @@ impl<'a, T: ContextCurrentState> HeadlessContextExt
         self,
         size: dpi::PhysicalSize<u32>,
     ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        self.build_osmesa_with_attributes(size, Default::default())
+    }
+
+    #[inline]
+    fn build_osmesa_with_attributes(
+        self,
+        size: dpi::PhysicalSize<u32>,
+        osmesa_attr: osmesa::OsMesaAttributes,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
     where
         Self: Sized,
     {
@@ -321,7 +887,7 @@ impl<'a, T: ContextCurrentState> HeadlessContextExt
             Context::OsMesa(ref ctx) => ctx,
             _ => unreachable!(),
         });
-        osmesa::OsMesaContext::new(&pf_reqs, &gl_attr, size)
+        osmesa::OsMesaContext::new(&pf_reqs, &gl_attr, &osmesa_attr, size)
             .map(|context| Context::OsMesa(context))
             .map(|context| crate::Context {
                 context,
@@ -330,24 +896,1083 @@ impl<'a, T: ContextCurrentState> HeadlessContextExt
     }
 
     #[inline]
-    fn build_surfaceless<TE>(
+    unsafe fn build_raw_osmesa_context(
         self,
-        el: &EventLoopWindowTarget<TE>,
+        context: *mut raw::c_void,
+        owned: bool,
+        size: dpi::PhysicalSize<u32>,
+        osmesa_attr: osmesa::OsMesaAttributes,
     ) -> Result<crate::Context<NotCurrent>, CreationError>
     where
         Self: Sized,
     {
         let crate::ContextBuilder { pf_reqs, gl_attr } = self;
         let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
-        Context::new_headless_impl(el, &pf_reqs, &gl_attr, None).map(
-            |context| crate::Context {
-                context,
+        Context::is_compatible(&gl_attr.sharing, ContextType::OsMesa)?;
+        let gl_attr = gl_attr.clone().map_sharing(|ctx| match *ctx {
+            Context::OsMesa(ref ctx) => ctx,
+            _ => unreachable!(),
+        });
+        osmesa::OsMesaContext::from_raw(
+            context as osmesa_sys::OSMesaContext,
+            owned,
+            &pf_reqs,
+            &gl_attr,
+            &osmesa_attr,
+            size,
+        )
+        .map(|context| Context::OsMesa(context))
+        .map(|context| crate::Context {
+            context,
+            phantom: PhantomData,
+        })
+    }
+
+    #[inline]
+    fn build_surfaceless<TE>(
+        self,
+        el: &EventLoopWindowTarget<TE>,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        let crate::ContextBuilder { pf_reqs, gl_attr } = self;
+        let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
+        Context::new_headless_impl(el, &pf_reqs, &gl_attr, None).map(
+            |context| crate::Context {
+                context,
+                phantom: PhantomData,
+            },
+        )
+    }
+
+    #[inline]
+    fn build_headless_with_device(
+        self,
+        device: &egl::Device,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        let crate::ContextBuilder { pf_reqs, gl_attr } = self;
+        let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
+        Context::new_egl_device(device, &pf_reqs, &gl_attr, Some(size)).map(
+            |context| crate::Context {
+                context,
+                phantom: PhantomData,
+            },
+        )
+    }
+
+    #[inline]
+    fn build_surfaceless_with_device(
+        self,
+        device: &egl::Device,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        let crate::ContextBuilder { pf_reqs, gl_attr } = self;
+        let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
+        Context::new_egl_device(device, &pf_reqs, &gl_attr, None).map(
+            |context| crate::Context {
+                context,
                 phantom: PhantomData,
             },
         )
     }
 }
 
+/// An OsMesa [`Context`] made current on this thread by
+/// [`make_current_scoped`], until this guard is dropped.
+///
+/// Dropping the guard makes the OsMesa context that was current before
+/// current again, even while unwinding. If there was none, the context is
+/// unbound, which older gallium drivers don't support, in which case it
+/// stays current.
+///
+/// The guard borrows the context, which therefore can't be dropped while it
+/// is current through the guard:
+///
+/// ```compile_fail,E0505
+/// # use glutin::dpi::PhysicalSize;
+/// # use glutin::platform::unix::{HeadlessContextExt, OsMesaContextExt};
+/// # let context = glutin::ContextBuilder::new()
+/// #     .build_osmesa(PhysicalSize::new(64, 64))
+/// #     .unwrap();
+/// # let mut context = unsafe { context.make_current() }.unwrap();
+/// let current = context.make_current_scoped().unwrap();
+/// drop(context);
+/// drop(current);
+/// ```
+///
+/// Nor can its buffer be replaced:
+///
+/// ```compile_fail,E0499
+/// # use glutin::dpi::PhysicalSize;
+/// # use glutin::platform::unix::{HeadlessContextExt, OsMesaContextExt};
+/// # let context = glutin::ContextBuilder::new()
+/// #     .build_osmesa(PhysicalSize::new(64, 64))
+/// #     .unwrap();
+/// # let mut context = unsafe { context.make_current() }.unwrap();
+/// let current = context.make_current_scoped().unwrap();
+/// context.resize_buffer(PhysicalSize::new(128, 128)).unwrap();
+/// drop(current);
+/// ```
+///
+/// As the context is only current on this thread, the guard is `!Send`:
+///
+/// ```compile_fail,E0277
+/// # use glutin::dpi::PhysicalSize;
+/// # use glutin::platform::unix::{HeadlessContextExt, OsMesaContextExt};
+/// # let context = glutin::ContextBuilder::new()
+/// #     .build_osmesa(PhysicalSize::new(64, 64))
+/// #     .unwrap();
+/// # let mut context = unsafe { context.make_current() }.unwrap();
+/// fn assert_send<T: Send>(_: &T) {}
+/// let current = context.make_current_scoped().unwrap();
+/// assert_send(&current);
+/// ```
+///
+/// The context can be used through the guard, which dereferences to it:
+///
+/// ```no_run
+/// # use glutin::dpi::PhysicalSize;
+/// # use glutin::platform::unix::{HeadlessContextExt, OsMesaContextExt};
+/// # let context = glutin::ContextBuilder::new()
+/// #     .build_osmesa(PhysicalSize::new(64, 64))
+/// #     .unwrap();
+/// # let mut context = unsafe { context.make_current() }.unwrap();
+/// let current = context.make_current_scoped().unwrap();
+/// let gl_clear = current.get_proc_address("glClear");
+/// // render...
+/// let pixels = current.color_buffer().unwrap().to_rgba8();
+/// ```
+///
+/// [`Context`]: ../../struct.Context.html
+/// [`make_current_scoped`]:
+/// trait.OsMesaContextExt.html#tymethod.make_current_scoped
+#[derive(Debug)]
+pub struct CurrentOsMesaContext<'a> {
+    context: &'a mut crate::Context<PossiblyCurrent>,
+    _restore: osmesa::RestoreBinding,
+}
+
+impl<'a> std::ops::Deref for CurrentOsMesaContext<'a> {
+    type Target = crate::Context<PossiblyCurrent>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.context
+    }
+}
+
+/// A unix-specific extension to [`Context`]s built with [`build_osmesa`].
+///
+/// The buffer an OsMesa context renders into is owned by the context, so it
+/// can't be freed while the context may still render into it. Methods that
+/// replace the buffer take `&mut self`, which means no view returned by
+/// [`color_buffer`] or [`depth_buffer`] can outlive it either:
+///
+/// ```compile_fail,E0502
+/// # use glutin::dpi::PhysicalSize;
+/// # use glutin::platform::unix::{HeadlessContextExt, OsMesaContextExt};
+/// # let context = glutin::ContextBuilder::new()
+/// #     .build_osmesa(PhysicalSize::new(64, 64))
+/// #     .unwrap();
+/// # let mut context = unsafe { context.make_current() }.unwrap();
+/// let pixels = context.color_buffer().unwrap();
+/// context.resize_buffer(PhysicalSize::new(128, 128)).unwrap();
+/// println!("{}", pixels.data.len());
+/// ```
+///
+/// Use [`make_current_scoped`] to tie the currency of the context to a
+/// borrow of it.
+///
+/// To render from several threads at once, give each thread its own context.
+/// A `Context<PossiblyCurrent>` is `!Send`, so a context that is current on one
+/// thread can't be handed to another without first calling
+/// [`make_not_current`] on it. Function pointers returned by
+/// [`get_proc_address`] are cached per context.
+///
+/// All methods return [`ContextError::FunctionUnavailable`] if the context is
+/// not an OsMesa context.
+///
+/// [`Context`]: ../../struct.Context.html
+/// [`make_not_current`]: ../../struct.Context.html#method.make_not_current
+/// [`get_proc_address`]: ../../struct.Context.html#method.get_proc_address
+/// [`build_osmesa`]: trait.HeadlessContextExt.html#tymethod.build_osmesa
+/// [`color_buffer`]: #tymethod.color_buffer
+/// [`depth_buffer`]: #tymethod.depth_buffer
+/// [`make_current_scoped`]: #tymethod.make_current_scoped
+/// [`ContextError::FunctionUnavailable`]:
+/// ../../enum.ContextError.html#variant.FunctionUnavailable
+pub trait OsMesaContextExt {
+    /// Makes the context current on this thread until the returned guard is
+    /// dropped, which makes the OsMesa context that was current before
+    /// current again. See [`CurrentOsMesaContext`].
+    ///
+    /// [`CurrentOsMesaContext`]: struct.CurrentOsMesaContext.html
+    fn make_current_scoped(
+        &mut self,
+    ) -> Result<CurrentOsMesaContext<'_>, ContextError>;
+
+    /// Waits for all rendering to complete, then returns a view of the color
+    /// buffer the context renders into.
+    fn color_buffer(
+        &self,
+    ) -> Result<osmesa::OsMesaColorBuffer<'_>, ContextError>;
+
+    /// Waits for all rendering to complete, then copies the color buffer the
+    /// context renders into to `out`.
+    ///
+    /// Returns the number of bytes written. `out` must be at least
+    /// `stride * height` bytes long.
+    fn read_pixels_into(&self, out: &mut [u8]) -> Result<usize, ContextError>;
+
+    /// Waits for all rendering to complete if the context is current, then
+    /// returns the color buffer the context renders into for writing.
+    ///
+    /// The buffer is laid out like the one returned by [`color_buffer`].
+    ///
+    /// [`color_buffer`]: #tymethod.color_buffer
+    fn color_buffer_mut(&mut self) -> Result<&mut [u8], ContextError>;
+
+    /// Waits for all rendering to complete if the context is current, then
+    /// moves the color buffer out of the context and gives the context a new
+    /// zeroed buffer of the same size.
+    ///
+    /// The returned buffer holds `height` rows of `stride` bytes each, where
+    /// `stride` is the row length (see [`set_row_length`]) times the size of
+    /// a pixel. Unless [`set_y_up`] was called with `false`, the first row is
+    /// the bottom one.
+    ///
+    /// With 8-bit channels this doesn't copy, except with the
+    /// [`Rgb565`] format. Returns an error if the context renders into a
+    /// buffer set with [`set_raw_buffer`].
+    ///
+    /// [`set_row_length`]: #tymethod.set_row_length
+    /// [`set_y_up`]: #tymethod.set_y_up
+    /// [`set_raw_buffer`]: #tymethod.set_raw_buffer
+    /// [`Rgb565`]: enum.OsMesaBufferFormat.html#variant.Rgb565
+    fn take_color_buffer(&mut self) -> Result<Vec<u8>, ContextError>;
+
+    /// Replaces the buffer the context renders into with a new one of the
+    /// given size, discarding its contents.
+    ///
+    /// If the context is current, it is rebound to the new buffer right away,
+    /// so there is no need to call `make_current` again. Returns an error if
+    /// either dimension is zero.
+    fn resize_buffer(
+        &mut self,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<(), ContextError>;
+
+    /// Sets whether the first row of the buffer is the bottom row of the
+    /// image (`OSMESA_Y_UP`). Pass `false` to render images top-to-bottom.
+    ///
+    /// The default is `true`.
+    fn set_y_up(&mut self, y_up: bool) -> Result<(), ContextError>;
+
+    /// Sets the number of pixels between the start of two consecutive rows of
+    /// the buffer (`OSMESA_ROW_LENGTH`). Zero means "same as the width of the
+    /// buffer", which is the default.
+    ///
+    /// Row lengths larger than the width leave padding at the end of each
+    /// row, in which case the buffer is reallocated and its contents
+    /// discarded. Row lengths smaller than the width are rejected.
+    fn set_row_length(&mut self, row_length: u32) -> Result<(), ContextError>;
+
+    /// Makes the context render into `len` bytes of memory owned by the
+    /// caller, such as shared memory, instead of its own buffer.
+    ///
+    /// `len` must be at least `row_length * height * bytes_per_pixel`, where
+    /// `row_length` is the width unless changed with [`set_row_length`], and
+    /// `ptr` must be aligned to the size of a channel. glutin
+    /// never frees this memory; calling [`resize_buffer`] switches back to a
+    /// buffer owned by the context.
+    ///
+    /// If the context is current, it is rebound to the new buffer right away.
+    ///
+    /// Unsafe behaviour might happen if you:
+    ///   - Free the memory while the context may still render into it.
+    ///   - Access the memory while the context renders into it.
+    ///
+    /// [`set_row_length`]: #tymethod.set_row_length
+    /// [`resize_buffer`]: #tymethod.resize_buffer
+    unsafe fn set_raw_buffer(
+        &mut self,
+        ptr: *mut u8,
+        len: usize,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<(), ContextError>;
+
+    /// Queries an attribute of the context with `OSMesaGetIntegerv`.
+    ///
+    /// The context must be current.
+    fn get_attrib(
+        &self,
+        attrib: osmesa::OsMesaAttrib,
+    ) -> Result<raw::c_int, ContextError>;
+
+    /// Returns the largest buffer size the context supports.
+    ///
+    /// The context must be current. [`resize_buffer`] and [`set_raw_buffer`]
+    /// already reject sizes larger than this.
+    ///
+    /// [`resize_buffer`]: #tymethod.resize_buffer
+    /// [`set_raw_buffer`]: #tymethod.set_raw_buffer
+    fn max_buffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError>;
+
+    /// Waits for all rendering to complete, then returns a view of the depth
+    /// buffer of the context.
+    ///
+    /// Returns an error if the context is not current, or if it was created
+    /// without a depth buffer, for example with `with_depth_buffer(0)`.
+    fn depth_buffer(
+        &self,
+    ) -> Result<osmesa::OsMesaDepthBuffer<'_>, ContextError>;
+
+    /// Returns the OpenGL version the context was created with, which may be
+    /// lower than the requested one unless [`OsMesaAttributes::exact_version`]
+    /// was set.
+    ///
+    /// Returns `Ok(None)` if no specific version was requested.
+    ///
+    /// [`OsMesaAttributes::exact_version`]:
+    /// struct.OsMesaAttributes.html#structfield.exact_version
+    fn context_version(&self) -> Result<Option<(u8, u8)>, ContextError>;
+
+    /// Returns information about the OsMesa driver. See [`osmesa_driver_info`].
+    ///
+    /// [`osmesa_driver_info`]: fn.osmesa_driver_info.html
+    fn driver_info(&self) -> Result<osmesa::OsMesaDriverInfo, ContextError>;
+
+    /// Renders a single frame and returns it as an image.
+    ///
+    /// Resizes the buffer to `size` if it has a different size, makes the
+    /// context current, calls `f` to render, waits for rendering to complete
+    /// and returns the color buffer as tightly packed 8-bit RGBA rows, from top
+    /// to bottom, regardless of [`set_y_up`] and the buffer format.
+    ///
+    /// Afterwards, the OsMesa context that was current on this thread before
+    /// is made current again, or this one is unbound if there was none, even
+    /// if `f` panics. Unbinding isn't supported by older gallium drivers, in
+    /// which case this context stays current.
+    ///
+    /// [`set_y_up`]: #tymethod.set_y_up
+    fn render_to_image<F: FnOnce()>(
+        &mut self,
+        size: dpi::PhysicalSize<u32>,
+        f: F,
+    ) -> Result<Vec<u8>, ContextError>;
+}
+
+impl OsMesaContextExt for crate::Context<PossiblyCurrent> {
+    #[inline]
+    fn make_current_scoped(
+        &mut self,
+    ) -> Result<CurrentOsMesaContext<'_>, ContextError> {
+        let ctx = self
+            .context
+            .osmesa()
+            .ok_or(ContextError::FunctionUnavailable)?;
+        let restore = osmesa::RestoreBinding::new();
+        unsafe { ctx.make_current()? };
+        Ok(CurrentOsMesaContext {
+            context: self,
+            _restore: restore,
+        })
+    }
+
+    #[inline]
+    fn color_buffer(
+        &self,
+    ) -> Result<osmesa::OsMesaColorBuffer<'_>, ContextError> {
+        self.context
+            .osmesa()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .color_buffer()
+    }
+
+    #[inline]
+    fn read_pixels_into(&self, out: &mut [u8]) -> Result<usize, ContextError> {
+        let buffer = self.color_buffer()?;
+        if out.len() < buffer.data.len() {
+            return Err(ContextError::OsError(format!(
+                "Output buffer too small: got {} bytes, need {}",
+                out.len(),
+                buffer.data.len()
+            )));
+        }
+        out[..buffer.data.len()].copy_from_slice(buffer.data);
+        Ok(buffer.data.len())
+    }
+
+    #[inline]
+    fn color_buffer_mut(&mut self) -> Result<&mut [u8], ContextError> {
+        self.context
+            .osmesa_mut()
+            .map(|ctx| ctx.color_buffer_mut())
+            .ok_or(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    fn take_color_buffer(&mut self) -> Result<Vec<u8>, ContextError> {
+        self.context
+            .osmesa_mut()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .take_color_buffer()
+    }
+
+    #[inline]
+    fn resize_buffer(
+        &mut self,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<(), ContextError> {
+        self.context
+            .osmesa_mut()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .resize(size)
+    }
+
+    #[inline]
+    fn set_y_up(&mut self, y_up: bool) -> Result<(), ContextError> {
+        self.context
+            .osmesa_mut()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .set_y_up(y_up);
+        Ok(())
+    }
+
+    #[inline]
+    fn set_row_length(&mut self, row_length: u32) -> Result<(), ContextError> {
+        self.context
+            .osmesa_mut()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .set_row_length(row_length)
+    }
+
+    #[inline]
+    unsafe fn set_raw_buffer(
+        &mut self,
+        ptr: *mut u8,
+        len: usize,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<(), ContextError> {
+        self.context
+            .osmesa_mut()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .set_raw_buffer(ptr, len, size)
+    }
+
+    #[inline]
+    fn get_attrib(
+        &self,
+        attrib: osmesa::OsMesaAttrib,
+    ) -> Result<raw::c_int, ContextError> {
+        self.context
+            .osmesa()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .get_attrib(attrib)
+    }
+
+    #[inline]
+    fn max_buffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        self.context
+            .osmesa()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .max_buffer_size()
+    }
+
+    #[inline]
+    fn depth_buffer(
+        &self,
+    ) -> Result<osmesa::OsMesaDepthBuffer<'_>, ContextError> {
+        self.context
+            .osmesa()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .depth_buffer()
+    }
+
+    #[inline]
+    fn context_version(&self) -> Result<Option<(u8, u8)>, ContextError> {
+        self.context
+            .osmesa()
+            .map(|ctx| ctx.version())
+            .ok_or(ContextError::FunctionUnavailable)
+    }
+
+    #[inline]
+    fn driver_info(&self) -> Result<osmesa::OsMesaDriverInfo, ContextError> {
+        self.context
+            .osmesa()
+            .ok_or(ContextError::FunctionUnavailable)?;
+        osmesa::driver_info()
+            .map_err(|err| ContextError::OsError(format!("{}", err)))
+    }
+
+    #[inline]
+    fn render_to_image<F: FnOnce()>(
+        &mut self,
+        size: dpi::PhysicalSize<u32>,
+        f: F,
+    ) -> Result<Vec<u8>, ContextError> {
+        self.context
+            .osmesa_mut()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .render_to_image(size, f)
+    }
+}
+
+/// A unix-specific extension to EGL [`Context`]s.
+///
+/// [`Context`]: ../../struct.Context.html
+pub trait EglContextExt {
+    /// Makes the context current without binding any surface, even if it was
+    /// built with one, so that you can render into framebuffer objects
+    /// without a pbuffer or window.
+    ///
+    /// This requires the `EGL_KHR_surfaceless_context` extension. If it is
+    /// missing, or the context doesn't use EGL, this returns
+    /// [`ContextError::FunctionUnavailable`] and the context is left alone.
+    ///
+    /// Call [`make_current`] to bind the surface of the context again.
+    ///
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    /// [`make_current`]: ../../struct.Context.html#method.make_current
+    unsafe fn make_current_surfaceless(
+        self,
+    ) -> Result<crate::Context<PossiblyCurrent>, (Self, ContextError)>
+    where
+        Self: Sized;
+
+    /// Makes the context current with the surface of `other` instead of its
+    /// own, so that one context can render to several surfaces.
+    ///
+    /// Both contexts must use EGL on the same display. Unless the context was
+    /// built [`with_no_config`] and `EGL_KHR_no_config_context` is supported,
+    /// the surface of `other` must also have the same config.
+    ///
+    /// This is unsafe because `other` must outlive the time the context is
+    /// current with its surface. Call [`make_current`] to bind the surface of
+    /// the context again.
+    ///
+    /// [`with_no_config`]:
+    /// ../../struct.ContextBuilder.html#method.with_no_config
+    /// [`make_current`]: ../../struct.Context.html#method.make_current
+    unsafe fn make_current_with_surface_of<T2: ContextCurrentState>(
+        self,
+        other: &crate::Context<T2>,
+    ) -> Result<crate::Context<PossiblyCurrent>, (Self, ContextError)>
+    where
+        Self: Sized;
+
+    /// Switches the window surface between single buffered (front buffer)
+    /// and double buffered rendering, for example for low-latency drawing.
+    ///
+    /// This requires `EGL_KHR_mutable_render_buffer` and a config whose
+    /// `EGL_SURFACE_TYPE` includes `EGL_MUTABLE_RENDER_BUFFER_BIT_KHR`,
+    /// otherwise [`ContextError::FunctionUnavailable`] is returned.
+    ///
+    /// The switch takes effect on the next [`swap_buffers`]. While single
+    /// buffered, [`swap_buffers`] remains valid and only flushes rendering to
+    /// the front buffer.
+    ///
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    /// [`swap_buffers`]:
+    /// ../../struct.ContextWrapper.html#method.swap_buffers
+    fn set_single_buffered(
+        &self,
+        single_buffered: bool,
+    ) -> Result<(), ContextError>;
+
+    /// Returns whether the context is currently rendering to the front
+    /// buffer, as reported by `eglQueryContext`.
+    fn is_single_buffered(&self) -> bool;
+
+    /// Returns whether [`create_fence`] is supported, which requires
+    /// `EGL_KHR_fence_sync`.
+    ///
+    /// [`create_fence`]: #tymethod.create_fence
+    fn fence_sync_supported(&self) -> bool;
+
+    /// Returns whether fences can be exported to and imported from file
+    /// descriptors, which requires `EGL_ANDROID_native_fence_sync`.
+    fn native_fence_fd_supported(&self) -> bool;
+
+    /// Inserts a [`Fence`] into the command stream of the context, which must
+    /// be current.
+    ///
+    /// If [`native_fence_fd_supported`] returns true, the fence is a native
+    /// fence and the context is flushed so that the fence can be exported
+    /// with [`Fence::export_fd`] right away.
+    ///
+    /// [`Fence`]: struct.Fence.html
+    /// [`native_fence_fd_supported`]: #tymethod.native_fence_fd_supported
+    /// [`Fence::export_fd`]: struct.Fence.html#method.export_fd
+    fn create_fence(&self) -> Result<egl::Fence<'_>, ContextError>;
+
+    /// Creates a [`Fence`] from a native fence file descriptor, such as one
+    /// returned by [`Fence::export_fd`] in another process. EGL takes
+    /// ownership of `fd`.
+    ///
+    /// [`Fence`]: struct.Fence.html
+    /// [`Fence::export_fd`]: struct.Fence.html#method.export_fd
+    fn import_fence_fd(
+        &self,
+        fd: std::os::unix::io::RawFd,
+    ) -> Result<egl::Fence<'_>, ContextError>;
+
+    /// Creates an [`EglImage`] from a level 0 `GL_TEXTURE_2D` texture of the
+    /// context, which must be current. Requires `EGL_KHR_image_base` and
+    /// `EGL_KHR_gl_texture_2D_image`.
+    ///
+    /// [`EglImage`]: struct.EglImage.html
+    fn create_image_from_texture(
+        &self,
+        texture: u32,
+    ) -> Result<egl::EglImage<'_>, ContextError>;
+
+    /// Creates an [`EglImage`] from a renderbuffer of the context, which must
+    /// be current. Requires `EGL_KHR_image_base` and
+    /// `EGL_KHR_gl_renderbuffer_image`.
+    ///
+    /// [`EglImage`]: struct.EglImage.html
+    fn create_image_from_renderbuffer(
+        &self,
+        renderbuffer: u32,
+    ) -> Result<egl::EglImage<'_>, ContextError>;
+
+    /// Imports a dma-buf as an [`EglImage`], which can then be bound to a
+    /// texture with `glEGLImageTargetTexture2DOES` without copying. Requires
+    /// `EGL_KHR_image_base` and `EGL_EXT_image_dma_buf_import`.
+    ///
+    /// Multi-planar formats such as NV12 take one [`DmaBufPlane`] per plane,
+    /// up to 3, or 4 with `EGL_EXT_image_dma_buf_import_modifiers`, which is
+    /// also needed to pass a `modifier`. When that extension is available,
+    /// the error says whether the fourcc or the modifier is unsupported.
+    ///
+    /// EGL doesn't take ownership of the file descriptors.
+    ///
+    /// [`EglImage`]: struct.EglImage.html
+    /// [`DmaBufPlane`]: struct.DmaBufPlane.html
+    fn create_image_from_dma_buf(
+        &self,
+        planes: &[egl::DmaBufPlane],
+        fourcc: u32,
+        width: u32,
+        height: u32,
+        modifier: Option<u64>,
+    ) -> Result<egl::EglImage<'_>, ContextError>;
+}
+
+impl<T: ContextCurrentState> EglContextExt for crate::Context<T> {
+    #[inline]
+    unsafe fn make_current_surfaceless(
+        self,
+    ) -> Result<crate::Context<PossiblyCurrent>, (Self, ContextError)> {
+        match self.context.make_current_surfaceless() {
+            Ok(()) => Ok(crate::Context {
+                context: self.context,
+                phantom: PhantomData,
+            }),
+            Err(err) => Err((self, err)),
+        }
+    }
+
+    #[inline]
+    unsafe fn make_current_with_surface_of<T2: ContextCurrentState>(
+        self,
+        other: &crate::Context<T2>,
+    ) -> Result<crate::Context<PossiblyCurrent>, (Self, ContextError)> {
+        let res = match (self.context.egl(), other.context.egl()) {
+            (Some(ctx), Some(other)) => ctx.make_current_with_surface_of(other),
+            _ => Err(ContextError::FunctionUnavailable),
+        };
+        match res {
+            Ok(()) => Ok(crate::Context {
+                context: self.context,
+                phantom: PhantomData,
+            }),
+            Err(err) => Err((self, err)),
+        }
+    }
+
+    #[inline]
+    fn set_single_buffered(
+        &self,
+        single_buffered: bool,
+    ) -> Result<(), ContextError> {
+        self.context
+            .egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .set_single_buffered(single_buffered)
+    }
+
+    #[inline]
+    fn is_single_buffered(&self) -> bool {
+        self.context
+            .egl()
+            .map(|ctx| ctx.is_single_buffered())
+            .unwrap_or(false)
+    }
+
+    #[inline]
+    fn fence_sync_supported(&self) -> bool {
+        self.context
+            .egl()
+            .map(|ctx| ctx.fence_sync_supported())
+            .unwrap_or(false)
+    }
+
+    #[inline]
+    fn native_fence_fd_supported(&self) -> bool {
+        self.context
+            .egl()
+            .map(|ctx| ctx.native_fence_fd_supported())
+            .unwrap_or(false)
+    }
+
+    #[inline]
+    fn create_fence(&self) -> Result<egl::Fence<'_>, ContextError> {
+        self.context
+            .egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .create_fence()
+    }
+
+    #[inline]
+    fn import_fence_fd(
+        &self,
+        fd: std::os::unix::io::RawFd,
+    ) -> Result<egl::Fence<'_>, ContextError> {
+        self.context
+            .egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .import_fence_fd(fd)
+    }
+
+    #[inline]
+    fn create_image_from_texture(
+        &self,
+        texture: u32,
+    ) -> Result<egl::EglImage<'_>, ContextError> {
+        self.context
+            .egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .create_image_from_texture(texture)
+    }
+
+    #[inline]
+    fn create_image_from_renderbuffer(
+        &self,
+        renderbuffer: u32,
+    ) -> Result<egl::EglImage<'_>, ContextError> {
+        self.context
+            .egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .create_image_from_renderbuffer(renderbuffer)
+    }
+
+    #[inline]
+    fn create_image_from_dma_buf(
+        &self,
+        planes: &[egl::DmaBufPlane],
+        fourcc: u32,
+        width: u32,
+        height: u32,
+        modifier: Option<u64>,
+    ) -> Result<egl::EglImage<'_>, ContextError> {
+        self.context
+            .egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .create_image_from_dma_buf(planes, fourcc, width, height, modifier)
+    }
+}
+
+/// A unix-specific extension to GLX [`Context`]s.
+///
+/// [`Context`]: ../../struct.Context.html
+pub trait GlxContextExt {
+    /// Returns the GLX extension used to change the swap interval of the
+    /// context, which tells apart what [`set_swap_interval`] and
+    /// [`get_swap_interval`] can do. See [`GlxSwapControl`].
+    ///
+    /// Returns `None` if the context doesn't use GLX or no swap control
+    /// extension is supported.
+    ///
+    /// [`GlxSwapControl`]: enum.GlxSwapControl.html
+    /// [`set_swap_interval`]:
+    /// ../../struct.ContextWrapper.html#method.set_swap_interval
+    /// [`get_swap_interval`]:
+    /// ../../struct.ContextWrapper.html#method.get_swap_interval
+    fn glx_swap_control(&self) -> Option<GlxSwapControl>;
+
+    /// Returns the GLX drawable the context renders to, which is the
+    /// GLXWindow created for its window, its pbuffer or the GLXPixmap created
+    /// for its pixmap, or `None` if the context doesn't use GLX.
+    ///
+    /// Contexts using a legacy visual, or built for a window that another
+    /// context already created a GLXWindow for, render to the X window
+    /// itself.
+    fn glx_drawable(&self) -> Option<glutin_glx_sys::glx::types::GLXDrawable>;
+
+    /// Returns the X window the context renders to, or `None` if the context
+    /// doesn't use GLX or doesn't render to a window. The GLXWindow returned
+    /// by [`glx_drawable`] is destroyed along with the context, before the X
+    /// window.
+    ///
+    /// [`glx_drawable`]: trait.GlxContextExt.html#tymethod.glx_drawable
+    fn x11_window(&self) -> Option<glutin_glx_sys::Window>;
+
+    /// Returns the X visual of the config of the context, or `None` if the
+    /// context doesn't use GLX. Windows the context is made current on must
+    /// have this visual, or making it current fails with
+    /// [`ContextError::ConfigMismatch`].
+    ///
+    /// To render to an existing window, build the context with
+    /// [`RawContextExt::build_raw_x11_context`], which chooses the config
+    /// matching the visual of the window.
+    ///
+    /// [`ContextError::ConfigMismatch`]:
+    /// ../../enum.ContextError.html#variant.ConfigMismatch
+    /// [`RawContextExt::build_raw_x11_context`]:
+    /// trait.RawContextExt.html#tymethod.build_raw_x11_context
+    fn glx_visual_id(&self) -> Option<glutin_glx_sys::VisualID>;
+
+    /// Returns whether the context uses a legacy visual chosen with
+    /// `glXChooseVisual` instead of an fbconfig. See
+    /// [`ContextBuilderExtUnix::with_legacy_glx_visuals`].
+    ///
+    /// [`ContextBuilderExtUnix::with_legacy_glx_visuals`]:
+    /// trait.ContextBuilderExtUnix.html#tymethod.with_legacy_glx_visuals
+    fn uses_legacy_glx_visual(&self) -> bool;
+
+    /// Returns whether the context renders directly to the hardware rather
+    /// than through the X server. See [`DirectRendering`].
+    ///
+    /// Contexts that don't use GLX always render directly.
+    ///
+    /// [`DirectRendering`]: ../../enum.DirectRendering.html
+    fn is_direct(&self) -> bool;
+
+    /// Returns the current counters of the window of the context. See
+    /// [`GlxSyncValues`].
+    ///
+    /// This requires `GLX_OML_sync_control`. If it is missing, or the
+    /// context doesn't use GLX, this and the other `GLX_OML_sync_control`
+    /// functions return [`ContextError::FunctionUnavailable`].
+    ///
+    /// [`GlxSyncValues`]: struct.GlxSyncValues.html
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    fn sync_values(&self) -> Result<GlxSyncValues, ContextError>;
+
+    /// Schedules a swap of the buffers for the first vertical retrace at
+    /// which the media stream counter is at least `target_msc`, or, if it
+    /// already passed, for the next one at which `msc % divisor ==
+    /// remainder`. Returns the swap buffer counter the swap will have.
+    ///
+    /// Like [`swap_buffers`], this doesn't wait for the swap to happen.
+    ///
+    /// [`swap_buffers`]: ../../struct.ContextWrapper.html#method.swap_buffers
+    fn swap_buffers_msc(
+        &self,
+        target_msc: u64,
+        divisor: u64,
+        remainder: u64,
+    ) -> Result<u64, ContextError>;
+
+    /// Blocks until the media stream counter reaches `target_msc`, or, if
+    /// it already passed, until the next vertical retrace at which
+    /// `msc % divisor == remainder`, and returns the counters at that time.
+    fn wait_for_msc(
+        &self,
+        target_msc: u64,
+        divisor: u64,
+        remainder: u64,
+    ) -> Result<GlxSyncValues, ContextError>;
+
+    /// Returns the vertical retrace counter of the screen of the context.
+    ///
+    /// This requires `GLX_SGI_video_sync`, which is more widely available
+    /// than `GLX_OML_sync_control`. If it is missing, or the context doesn't
+    /// use GLX, this and [`wait_video_sync`] return
+    /// [`ContextError::FunctionUnavailable`]. The context must be current on
+    /// the calling thread and render directly, otherwise they return
+    /// [`ContextError::OsError`].
+    ///
+    /// [`wait_video_sync`]: trait.GlxContextExt.html#tymethod.wait_video_sync
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    /// [`ContextError::OsError`]:
+    /// ../../enum.ContextError.html#variant.OsError
+    fn video_sync_counter(&self) -> Result<u32, ContextError>;
+
+    /// Blocks until the next vertical retrace at which the retrace counter
+    /// satisfies `counter % divisor == remainder`, and returns the counter
+    /// at that time. This allows staying locked to the retrace without
+    /// swapping buffers.
+    ///
+    /// `divisor` must be positive, and `remainder` less than `divisor`.
+    fn wait_video_sync(
+        &self,
+        divisor: u32,
+        remainder: u32,
+    ) -> Result<u32, ContextError>;
+
+    /// Returns how many swap groups and swap barriers `GLX_NV_swap_group`
+    /// provides for the screen of the context. See [`SwapGroupLimits`].
+    ///
+    /// This requires `GLX_NV_swap_group`. If it is missing, or the context
+    /// doesn't use GLX, this and the other swap group functions return
+    /// [`ContextError::FunctionUnavailable`], while a group or barrier out of
+    /// range is reported as [`ContextError::OsError`].
+    ///
+    /// [`SwapGroupLimits`]: ../../struct.SwapGroupLimits.html
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    /// [`ContextError::OsError`]:
+    /// ../../enum.ContextError.html#variant.OsError
+    fn query_max_swap_groups(&self) -> Result<SwapGroupLimits, ContextError>;
+
+    /// Makes the drawable of the context join the swap group `group`, or
+    /// leave its swap group if `group` is `0`. The drawables of a swap group
+    /// swap their buffers together.
+    ///
+    /// The drawable leaves its swap group when the context is dropped.
+    fn join_swap_group(&self, group: u32) -> Result<(), ContextError>;
+
+    /// Binds the swap group `group` to the swap barrier `barrier`, or unbinds
+    /// it if `barrier` is `0`. The swap groups bound to a barrier swap their
+    /// buffers together, across systems linked by a G-Sync board.
+    fn bind_swap_barrier(
+        &self,
+        group: u32,
+        barrier: u32,
+    ) -> Result<(), ContextError>;
+}
+
+impl<T: ContextCurrentState> GlxContextExt for crate::Context<T> {
+    #[inline]
+    fn glx_swap_control(&self) -> Option<GlxSwapControl> {
+        self.context.glx().and_then(|ctx| ctx.swap_control())
+    }
+
+    #[inline]
+    fn glx_drawable(&self) -> Option<glutin_glx_sys::glx::types::GLXDrawable> {
+        self.context.glx().map(|ctx| ctx.drawable())
+    }
+
+    #[inline]
+    fn x11_window(&self) -> Option<glutin_glx_sys::Window> {
+        self.context.glx().and_then(|ctx| ctx.x11_window())
+    }
+
+    #[inline]
+    fn glx_visual_id(&self) -> Option<glutin_glx_sys::VisualID> {
+        self.context.glx().map(|ctx| ctx.visual_id())
+    }
+
+    #[inline]
+    fn uses_legacy_glx_visual(&self) -> bool {
+        self.context
+            .glx()
+            .map_or(false, |ctx| ctx.uses_legacy_visual())
+    }
+
+    #[inline]
+    fn is_direct(&self) -> bool {
+        self.context.glx().map_or(true, |ctx| ctx.is_direct())
+    }
+
+    #[inline]
+    fn sync_values(&self) -> Result<GlxSyncValues, ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .sync_values()
+    }
+
+    #[inline]
+    fn swap_buffers_msc(
+        &self,
+        target_msc: u64,
+        divisor: u64,
+        remainder: u64,
+    ) -> Result<u64, ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .swap_buffers_msc(target_msc, divisor, remainder)
+    }
+
+    #[inline]
+    fn wait_for_msc(
+        &self,
+        target_msc: u64,
+        divisor: u64,
+        remainder: u64,
+    ) -> Result<GlxSyncValues, ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .wait_for_msc(target_msc, divisor, remainder)
+    }
+
+    #[inline]
+    fn video_sync_counter(&self) -> Result<u32, ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .video_sync_counter()
+    }
+
+    #[inline]
+    fn wait_video_sync(
+        &self,
+        divisor: u32,
+        remainder: u32,
+    ) -> Result<u32, ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .wait_video_sync(divisor, remainder)
+    }
+
+    #[inline]
+    fn query_max_swap_groups(&self) -> Result<SwapGroupLimits, ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .query_max_swap_groups()
+    }
+
+    #[inline]
+    fn join_swap_group(&self, group: u32) -> Result<(), ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .join_swap_group(group)
+    }
+
+    #[inline]
+    fn bind_swap_barrier(
+        &self,
+        group: u32,
+        barrier: u32,
+    ) -> Result<(), ContextError> {
+        self.context
+            .glx()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .bind_swap_barrier(group, barrier)
+    }
+}
+
 /// A unix-specific extension for the [`ContextBuilder`] which allows
 /// assembling [`RawContext<T>`]s.
 ///
@@ -381,6 +2006,97 @@ pub trait RawContextExt {
     ) -> Result<crate::RawContext<NotCurrent>, CreationError>
     where
         Self: Sized;
+
+    /// Creates a context rendering to the provided X11 pixmap. Like for
+    /// windows, GLX is used for OpenGL if it is available, and EGL otherwise.
+    /// Only configs compatible with the pixmap, with `GLX_PIXMAP_BIT` in
+    /// their `GLX_DRAWABLE_TYPE` and a visual of the depth of the pixmap, or
+    /// with `EGL_PIXMAP_BIT` in their `EGL_SURFACE_TYPE`, are considered.
+    /// With GLX, [`GlxContextExt::glx_drawable`] returns the GLXPixmap.
+    ///
+    /// Pixmaps are single-buffered, so there are no buffers to swap: call
+    /// [`wait_gl`] or `glFinish` before reading the pixmap from X11 or
+    /// another process.
+    ///
+    /// Unsafe behaviour might happen if you:
+    ///   - Provide us with invalid parameters.
+    ///   - The pixmap is freed before the context
+    ///
+    /// [`wait_gl`]: ../../struct.Context.html#method.wait_gl
+    /// [`GlxContextExt::glx_drawable`]:
+    /// trait.GlxContextExt.html#tymethod.glx_drawable
+    unsafe fn build_raw_x11_pixmap_context(
+        self,
+        xconn: Arc<XConnection>,
+        pixmap: raw::c_ulong,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized;
+
+    /// Chooses the visual of the config that would be used for a window, so
+    /// that you can create your own X11 window with it, along with a
+    /// colormap for it, before calling [`build_raw_x11_context`].
+    ///
+    /// If `transparent` is true, a 32-bit visual with an alpha channel is
+    /// preferred. With EGL, where the visual of a config is given by its
+    /// `EGL_NATIVE_VISUAL_ID`, that is the only way to get a transparent
+    /// window. If no config has such a visual, a warning is logged and the
+    /// returned visual has a depth other than 32.
+    ///
+    /// [`build_raw_x11_context`]: #tymethod.build_raw_x11_context
+    fn choose_x11_visual(
+        &self,
+        xconn: &Arc<XConnection>,
+        screen_id: raw::c_int,
+        transparent: bool,
+    ) -> Result<glutin_glx_sys::XVisualInfo, CreationError>;
+
+    /// Creates a raw context rendering to a GBM surface, for drawing through
+    /// DRM/KMS without a display server. The display is created from the
+    /// `gbm_device` with `EGL_KHR_platform_gbm` or `EGL_MESA_platform_gbm`.
+    ///
+    /// `format` is the format the `gbm_surface` was created with, e.g.
+    /// `GBM_FORMAT_XRGB8888`. Only configs whose `EGL_NATIVE_VISUAL_ID`
+    /// matches it are considered.
+    ///
+    /// Unsafe behaviour might happen if you:
+    ///   - Provide us with invalid parameters.
+    ///   - The gbm_surface/gbm_device is destroyed before the context
+    unsafe fn build_raw_gbm_context(
+        self,
+        gbm_device: *mut raw::c_void,
+        gbm_surface: *mut raw::c_void,
+        format: u32,
+    ) -> Result<crate::RawContext<NotCurrent>, CreationError>
+    where
+        Self: Sized;
+
+    /// Creates a raw context presenting to a DRM plane of the given EGL
+    /// [`Device`] through an EGLStream, for drawing through DRM/KMS on drivers
+    /// without GBM, like NVIDIA's proprietary one.
+    ///
+    /// The frames swapped with [`swap_buffers`] are presented on the output
+    /// layer of `plane`, or on the first output layer if `plane` is `None`.
+    /// The DRM master must have set a mode on the CRTC of the plane
+    /// beforehand.
+    ///
+    /// Returns a [`CreationError::NotSupported`] if the device lacks
+    /// `EGL_EXT_output_drm`, `EGL_KHR_stream_producer_eglsurface` or another
+    /// extension needed for EGLStreams, and a [`CreationError::OsError`] if it
+    /// has no matching output layer.
+    ///
+    /// [`Device`]: struct.Device.html
+    /// [`swap_buffers`]: ../../struct.ContextWrapper.html#method.swap_buffers
+    /// [`CreationError::NotSupported`]: ../../enum.CreationError.html#variant.NotSupported
+    /// [`CreationError::OsError`]: ../../enum.CreationError.html#variant.OsError
+    fn build_raw_egl_stream_context(
+        self,
+        device: &egl::Device,
+        plane: Option<u32>,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<crate::RawContext<NotCurrent>, CreationError>
+    where
+        Self: Sized;
 }
 
 impl<'a, T: ContextCurrentState> RawContextExt
@@ -450,4 +2166,96 @@ impl<'a, T: ContextCurrentState> RawContextExt
                 window: (),
             })
     }
+
+    #[inline]
+    unsafe fn build_raw_x11_pixmap_context(
+        self,
+        xconn: Arc<XConnection>,
+        pixmap: raw::c_ulong,
+    ) -> Result<crate::Context<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        let crate::ContextBuilder { pf_reqs, gl_attr } = self;
+        let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
+        Context::is_compatible(&gl_attr.sharing, ContextType::X11)?;
+        let gl_attr = gl_attr.clone().map_sharing(|ctx| match *ctx {
+            Context::X11(ref ctx) => ctx,
+            _ => unreachable!(),
+        });
+        x11::Context::new_pixmap(xconn, pixmap, &pf_reqs, &gl_attr)
+            .map(|context| Context::X11(context))
+            .map(|context| crate::Context {
+                context,
+                phantom: PhantomData,
+            })
+    }
+
+    #[inline]
+    fn choose_x11_visual(
+        &self,
+        xconn: &Arc<XConnection>,
+        screen_id: raw::c_int,
+        transparent: bool,
+    ) -> Result<glutin_glx_sys::XVisualInfo, CreationError> {
+        let gl_attr = self.gl_attr.clone().map_sharing(|ctx| &ctx.context);
+        Context::is_compatible(&gl_attr.sharing, ContextType::X11)?;
+        let gl_attr = gl_attr.map_sharing(|ctx| match *ctx {
+            Context::X11(ref ctx) => ctx,
+            _ => unreachable!(),
+        });
+        x11::Context::choose_visual(
+            xconn,
+            screen_id,
+            &self.pf_reqs,
+            &gl_attr,
+            transparent,
+        )
+    }
+
+    #[inline]
+    unsafe fn build_raw_gbm_context(
+        self,
+        gbm_device: *mut raw::c_void,
+        gbm_surface: *mut raw::c_void,
+        format: u32,
+    ) -> Result<crate::RawContext<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        let crate::ContextBuilder { pf_reqs, gl_attr } = self;
+        let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
+        Context::new_gbm(gbm_device, gbm_surface, format, &pf_reqs, &gl_attr)
+            .map(|context| crate::Context {
+                context,
+                phantom: PhantomData,
+            })
+            .map(|context| crate::RawContext {
+                context,
+                window: (),
+            })
+    }
+
+    #[inline]
+    fn build_raw_egl_stream_context(
+        self,
+        device: &egl::Device,
+        plane: Option<u32>,
+        size: dpi::PhysicalSize<u32>,
+    ) -> Result<crate::RawContext<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        let crate::ContextBuilder { pf_reqs, gl_attr } = self;
+        let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
+        Context::new_egl_stream(device, plane, size, &pf_reqs, &gl_attr)
+            .map(|context| crate::Context {
+                context,
+                phantom: PhantomData,
+            })
+            .map(|context| crate::RawContext {
+                context,
+                window: (),
+            })
+    }
 }
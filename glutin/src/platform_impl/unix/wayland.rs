@@ -1,5 +1,5 @@
 use crate::api::egl::{
-    Context as EglContext, NativeDisplay, SurfaceType as EglSurfaceType,
+    self, Context as EglContext, NativeDisplay, SurfaceType as EglSurfaceType,
 };
 use crate::{
     ContextError, CreationError, GlAttributes, PixelFormat,
@@ -47,6 +47,23 @@ impl Deref for Context {
 }
 
 impl Context {
+    /// Returns the distinct EGL sample counts of the window configs of the
+    /// Wayland display of `el`.
+    pub fn egl_supported_sample_counts<T>(
+        el: &EventLoopWindowTarget<T>,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&EglContext>,
+    ) -> Result<Vec<u16>, CreationError> {
+        let display_ptr = el.wayland_display().unwrap() as *const _;
+        egl::supported_sample_counts(
+            &NativeDisplay::Wayland(Some(display_ptr)),
+            EglSurfaceType::Window,
+            pf_reqs,
+            gl_attr,
+            |_, _| true,
+        )
+    }
+
     #[inline]
     pub fn new_headless<T>(
         el: &EventLoopWindowTarget<T>,
@@ -157,6 +174,11 @@ impl Context {
         (**self).make_current()
     }
 
+    #[inline]
+    pub unsafe fn make_current_surfaceless(&self) -> Result<(), ContextError> {
+        (**self).make_current_surfaceless()
+    }
+
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
         (**self).make_not_current()
@@ -172,6 +194,26 @@ impl Context {
         (**self).get_api()
     }
 
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        (**self).priority()
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        (**self).release_behavior()
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        (**self).color_space()
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> &crate::Extensions {
+        (**self).extensions()
+    }
+
     #[inline]
     pub unsafe fn raw_handle(&self) -> ffi::EGLContext {
         (**self).raw_handle()
@@ -184,6 +226,12 @@ impl Context {
 
     #[inline]
     pub fn resize(&self, width: u32, height: u32) {
+        // minimized windows can report a zero size, which a `wl_egl_window`
+        // can't have, so keep the previous buffers until the next resize
+        if width == 0 || height == 0 {
+            return;
+        }
+
         match self {
             Context::Windowed(_, surface) => {
                 surface.0.resize(width as i32, height as i32, 0, 0)
@@ -202,6 +250,19 @@ impl Context {
         (**self).swap_buffers()
     }
 
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        (**self).buffer_age()
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        (**self).set_damage_region(rects)
+    }
+
     #[inline]
     pub fn swap_buffers_with_damage(
         &self,
@@ -211,8 +272,29 @@ impl Context {
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
-        (**self).swap_buffers_with_damage_supported()
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        (**self).swap_damage_support()
+    }
+
+    #[inline]
+    pub fn set_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        (**self).set_swap_interval(interval)
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> bool {
+        (**self).supports_swap_interval(interval)
+    }
+
+    #[inline]
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
+        (**self).get_swap_interval()
     }
 
     #[inline]
@@ -62,6 +62,7 @@ pub enum Context {
     Surfaceless(ContextInner),
     PBuffer(ContextInner),
     Windowed(ContextInner),
+    Pixmap(ContextInner),
 }
 
 impl Deref for Context {
@@ -72,6 +73,7 @@ impl Deref for Context {
             Context::Surfaceless(ctx) => ctx,
             Context::PBuffer(ctx) => ctx,
             Context::Windowed(ctx) => ctx,
+            Context::Pixmap(ctx) => ctx,
         }
     }
 }
@@ -82,6 +84,7 @@ impl DerefMut for Context {
             Context::Surfaceless(ctx) => ctx,
             Context::PBuffer(ctx) => ctx,
             Context::Windowed(ctx) => ctx,
+            Context::Pixmap(ctx) => ctx,
         }
     }
 }
@@ -89,12 +92,9 @@ impl DerefMut for Context {
 unsafe impl Send for Context {}
 unsafe impl Sync for Context {}
 
-// FIXME:
-// When using egl, all the configs will not support transparency, even if
-// transparency does work with glx.
-//
-// https://bugs.freedesktop.org/show_bug.cgi?id=67676<Paste>
-// I'm on a patch.
+// With EGL, the visual of a config is found through its
+// `EGL_NATIVE_VISUAL_ID`, so transparency works as long as the driver exposes
+// configs with a 32-bit ARGB visual.
 pub fn select_config<T, F>(
     xconn: &Arc<XConnection>,
     transparent: Option<bool>,
@@ -154,7 +154,8 @@ where
         Some(Ok(())) => (),
         Some(Err(Lacks::Transparency)) => warn!("Glutin could not a find fb config with an alpha mask. Transparency may be broken."),
         Some(Err(Lacks::XID)) => panic!(),
-        None => unreachable!(),
+        // no config has a visual on the screen
+        None => (),
     }
 
     chosen_config_id.ok_or(())
@@ -208,8 +209,7 @@ impl Context {
             }
         };
 
-        // Get the screen_id for the window being built.
-        let screen_id = unsafe { (xconn.xlib.XDefaultScreen)(xconn.display) };
+        let screen_id = Self::screen_id(&xconn, pf_reqs)?;
 
         let mut builder_glx_u = None;
         let mut builder_egl_u = None;
@@ -297,6 +297,78 @@ impl Context {
         }
     }
 
+    /// Returns the distinct GLX sample counts of the screen of `pf_reqs`.
+    pub fn glx_supported_sample_counts<T>(
+        el: &EventLoopWindowTarget<T>,
+        pf_reqs: &PixelFormatRequirements,
+    ) -> Result<Vec<u16>, CreationError> {
+        let xconn = match el.xlib_xconnection() {
+            Some(xconn) => xconn,
+            None => {
+                return Err(CreationError::NoBackendAvailable(Box::new(
+                    NoX11Connection,
+                )));
+            }
+        };
+        let screen_id = Self::screen_id(&xconn, pf_reqs)?;
+        crate::api::glx::supported_sample_counts(&xconn, screen_id, pf_reqs)
+    }
+
+    /// Returns the distinct EGL sample counts of the window configs of the
+    /// screen of `pf_reqs`.
+    pub fn egl_supported_sample_counts<T>(
+        el: &EventLoopWindowTarget<T>,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&EglContext>,
+    ) -> Result<Vec<u16>, CreationError> {
+        let xconn = match el.xlib_xconnection() {
+            Some(xconn) => xconn,
+            None => {
+                return Err(CreationError::NoBackendAvailable(Box::new(
+                    NoX11Connection,
+                )));
+            }
+        };
+        let screen_id = Self::screen_id(&xconn, pf_reqs)?;
+        egl::supported_sample_counts(
+            &NativeDisplay::X11(Some(xconn.display as *const _)),
+            EglSurfaceType::Window,
+            pf_reqs,
+            gl_attr,
+            |display, config| {
+                // windows can only use the visuals of their screen
+                let xid =
+                    egl::get_native_visual_id(display, config) as ffi::VisualID;
+                xid != 0
+                    && x11_utils::get_visual_info_from_xid(&xconn, xid).screen
+                        == screen_id
+            },
+        )
+    }
+
+    // The screen to choose the config on.
+    fn screen_id(
+        xconn: &Arc<XConnection>,
+        pf_reqs: &PixelFormatRequirements,
+    ) -> Result<raw::c_int, CreationError> {
+        unsafe {
+            match pf_reqs.x11_screen {
+                Some(screen_id)
+                    if screen_id < 0
+                        || screen_id
+                            >= (xconn.xlib.XScreenCount)(xconn.display) =>
+                {
+                    Err(CreationError::OsError(format!(
+                        "The X display has no screen {}",
+                        screen_id
+                    )))
+                }
+                Some(screen_id) => Ok(screen_id),
+                None => Ok((xconn.xlib.XDefaultScreen)(xconn.display)),
+            }
+        }
+    }
+
     #[inline]
     fn new_first_stage<'a>(
         xconn: &Arc<XConnection>,
@@ -317,7 +389,13 @@ impl Context {
                 if xid == 0 {
                     return None;
                 }
-                Some(x11_utils::get_visual_info_from_xid(xconn, xid))
+                let visual_infos =
+                    x11_utils::get_visual_info_from_xid(xconn, xid);
+                // windows can only use the visuals of their screen
+                if visual_infos.screen != screen_id {
+                    return None;
+                }
+                Some(visual_infos)
             })
             .map(|(c, _)| c)
         };
@@ -457,6 +535,41 @@ impl Context {
         })
     }
 
+    /// Chooses the visual of the config a window context would be built
+    /// with, without building it.
+    pub fn choose_visual(
+        xconn: &Arc<XConnection>,
+        screen_id: raw::c_int,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&Context>,
+        transparent: bool,
+    ) -> Result<ffi::XVisualInfo, CreationError> {
+        Self::try_then_fallback(|fallback| {
+            let mut builder_glx_u = None;
+            let mut builder_egl_u = None;
+            let context = Self::new_first_stage(
+                xconn,
+                pf_reqs,
+                gl_attr,
+                screen_id,
+                &mut builder_glx_u,
+                &mut builder_egl_u,
+                EglSurfaceType::Window,
+                fallback,
+                fallback,
+                Some(transparent),
+            )?;
+
+            Ok(match context {
+                Prototype::Glx(ref p) => p.get_visual_infos().clone(),
+                Prototype::Egl(ref p) => utils::get_visual_info_from_xid(
+                    xconn,
+                    p.get_native_visual_id() as ffi::VisualID,
+                ),
+            })
+        })
+    }
+
     fn new_impl<T>(
         wb: WindowBuilder,
         el: &EventLoopWindowTarget<T>,
@@ -473,8 +586,7 @@ impl Context {
             }
         };
 
-        // Get the screen_id for the window being built.
-        let screen_id = unsafe { (xconn.xlib.XDefaultScreen)(xconn.display) };
+        let screen_id = Self::screen_id(&xconn, pf_reqs)?;
 
         let mut builder_glx_u = None;
         let mut builder_egl_u = None;
@@ -535,6 +647,85 @@ impl Context {
         })
     }
 
+    /// Builds a context rendering to a native pixmap. Like for windows, GLX
+    /// is preferred for OpenGL, and EGL is used for OpenGL ES or if GLX is
+    /// missing.
+    pub fn new_pixmap(
+        xconn: Arc<XConnection>,
+        pixmap: raw::c_ulong,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&Context>,
+    ) -> Result<Self, CreationError> {
+        // pixmaps have no visual, their configs are matched by depth
+        let mut pf_reqs = pf_reqs.clone();
+        pf_reqs.x11_pixmap = Some(pixmap);
+
+        let use_glx = match gl_attr.sharing.map(|c| &c.context) {
+            Some(X11Context::Glx(_)) => true,
+            Some(X11Context::Egl(_)) => false,
+            None => match gl_attr.version {
+                GlRequest::Specific(Api::OpenGlEs, _) => false,
+                _ => GLX.is_some(),
+            },
+        };
+        let (screen_id, _) = x11_utils::get_pixmap_screen_and_depth(
+            &xconn, pixmap,
+        )
+        .ok_or_else(|| {
+            CreationError::OsError(
+                "The pixmap is not a valid X11 drawable".to_string(),
+            )
+        })?;
+        x11_utils::check_drawable_screen(&pf_reqs, "pixmap", screen_id)?;
+
+        if use_glx {
+            let gl_attr = gl_attr.clone().map_sharing(|c| match c.context {
+                X11Context::Glx(ref c) => c,
+                _ => panic!(),
+            });
+            let context = GlxContext::new(
+                Arc::clone(&xconn),
+                &pf_reqs,
+                &gl_attr,
+                screen_id,
+                EglSurfaceType::Pixmap,
+                None,
+            )?
+            .finish_pixmap(pixmap)?;
+            return Ok(Context::Pixmap(ContextInner {
+                xconn,
+                context: X11Context::Glx(context),
+            }));
+        }
+
+        if EGL.is_none() {
+            return Err(CreationError::NotSupported(
+                "libEGL not present".to_string(),
+            ));
+        }
+
+        let gl_attr = gl_attr.clone().map_sharing(|c| match c.context {
+            X11Context::Egl(ref c) => c,
+            _ => panic!(),
+        });
+
+        let native_display =
+            NativeDisplay::X11(Some(xconn.display as *const _));
+        let context = EglContext::new(
+            &pf_reqs,
+            &gl_attr,
+            native_display,
+            EglSurfaceType::Pixmap,
+            |c, _| Ok(c[0]),
+        )?
+        .finish_pixmap(pixmap)?;
+
+        Ok(Context::Pixmap(ContextInner {
+            xconn,
+            context: X11Context::Egl(context),
+        }))
+    }
+
     fn new_raw_context_impl(
         xconn: &Arc<XConnection>,
         xwin: raw::c_ulong,
@@ -542,21 +733,13 @@ impl Context {
         gl_attr: &GlAttributes<&Context>,
         fallback: bool,
     ) -> Result<Self, CreationError> {
-        let attrs = unsafe {
-            let mut attrs = ::std::mem::zeroed();
-            (xconn.xlib.XGetWindowAttributes)(xconn.display, xwin, &mut attrs);
-            attrs
-        };
-
-        // Not particularly efficient, but it's the only method I can find.
-        let mut screen_id = 0;
-        unsafe {
-            while attrs.screen
-                != (xconn.xlib.XScreenOfDisplay)(xconn.display, screen_id)
-            {
-                screen_id += 1;
-            }
-        }
+        let screen_id =
+            x11_utils::get_window_screen(xconn, xwin).ok_or_else(|| {
+                CreationError::OsError(
+                    "The window is not a valid X11 window".to_string(),
+                )
+            })?;
+        x11_utils::check_drawable_screen(pf_reqs, "window", screen_id)?;
 
         let attrs = {
             let mut attrs = unsafe { std::mem::zeroed() };
@@ -615,6 +798,35 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &ContextInner,
+        read: &ContextInner,
+    ) -> Result<(), ContextError> {
+        match (&self.context, &draw.context, &read.context) {
+            (
+                X11Context::Glx(ctx),
+                X11Context::Glx(draw),
+                X11Context::Glx(read),
+            ) => ctx.make_current_draw_read(draw, read),
+            (
+                X11Context::Egl(ctx),
+                X11Context::Egl(draw),
+                X11Context::Egl(read),
+            ) => ctx.make_current_draw_read(draw, read),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    #[inline]
+    pub unsafe fn make_current_surfaceless(&self) -> Result<(), ContextError> {
+        match self.context {
+            X11Context::Glx(_) => Err(ContextError::FunctionUnavailable),
+            X11Context::Egl(ref ctx) => ctx.make_current_surfaceless(),
+        }
+    }
+
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
         match self.context {
@@ -639,6 +851,38 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        match self.context {
+            X11Context::Glx(_) => None,
+            X11Context::Egl(ref ctx) => ctx.priority(),
+        }
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.release_behavior(),
+            X11Context::Egl(ref ctx) => ctx.release_behavior(),
+        }
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        match self.context {
+            X11Context::Glx(_) => None,
+            X11Context::Egl(ref ctx) => ctx.color_space(),
+        }
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> &crate::Extensions {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.extensions(),
+            X11Context::Egl(ref ctx) => ctx.extensions(),
+        }
+    }
+
     #[inline]
     pub unsafe fn raw_handle(&self) -> &X11Context {
         &self.context
@@ -668,6 +912,52 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.wait_gl(),
+            X11Context::Egl(ref ctx) => ctx.wait_gl(),
+        }
+    }
+
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.wait_native(),
+            X11Context::Egl(ref ctx) => ctx.wait_native(),
+        }
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.buffer_age(),
+            X11Context::Egl(ref ctx) => ctx.buffer_age(),
+        }
+    }
+
+    #[inline]
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        match (self, &self.context) {
+            (Context::PBuffer(_), X11Context::Glx(ref ctx)) => {
+                Ok(ctx.drawable_size())
+            }
+            (_, X11Context::Egl(ref ctx)) => ctx.pbuffer_size(),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        match self.context {
+            X11Context::Glx(_) => Err(ContextError::FunctionUnavailable),
+            X11Context::Egl(ref ctx) => ctx.set_damage_region(rects),
+        }
+    }
+
     #[inline]
     pub fn swap_buffers_with_damage(
         &self,
@@ -682,12 +972,40 @@ impl Context {
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
         match self.context {
-            X11Context::Glx(_) => false,
-            X11Context::Egl(ref ctx) => {
-                ctx.swap_buffers_with_damage_supported()
-            }
+            X11Context::Glx(_) => crate::DamageSupport::None,
+            X11Context::Egl(ref ctx) => ctx.swap_damage_support(),
+        }
+    }
+
+    #[inline]
+    pub fn set_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.set_swap_interval(interval),
+            X11Context::Egl(ref ctx) => ctx.set_swap_interval(interval),
+        }
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> bool {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.supports_swap_interval(interval),
+            X11Context::Egl(ref ctx) => ctx.supports_swap_interval(interval),
+        }
+    }
+
+    #[inline]
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
+        match self.context {
+            X11Context::Glx(ref ctx) => ctx.get_swap_interval(),
+            X11Context::Egl(ref ctx) => ctx.get_swap_interval(),
         }
     }
 
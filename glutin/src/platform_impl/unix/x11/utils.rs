@@ -54,6 +54,11 @@ pub fn examine_visual_info(
 
     unsafe {
         if want_transparency {
+            // only 32-bit ARGB visuals are composited with their alpha
+            if visual_infos.depth != 32 {
+                return Err(Lacks::Transparency);
+            }
+
             let pict_format = (xconn.xrender.XRenderFindVisualFormat)(
                 xconn.display as *mut _,
                 visual_infos.visual,
@@ -71,5 +76,80 @@ pub fn examine_visual_info(
     return Ok(());
 }
 
+/// Returns the screen and the depth of `pixmap`, or `None` if it isn't a
+/// valid drawable.
+pub fn get_pixmap_screen_and_depth(
+    xconn: &Arc<XConnection>,
+    pixmap: ffi::Pixmap,
+) -> Option<(std::os::raw::c_int, u32)> {
+    unsafe {
+        let (mut root, mut x, mut y) = (0, 0, 0);
+        let (mut width, mut height, mut border, mut depth) = (0, 0, 0, 0);
+        let status = (xconn.xlib.XGetGeometry)(
+            xconn.display,
+            pixmap,
+            &mut root,
+            &mut x,
+            &mut y,
+            &mut width,
+            &mut height,
+            &mut border,
+            &mut depth,
+        );
+        if status == 0 || xconn.check_errors().is_err() {
+            return None;
+        }
+
+        let screen_id = (0..(xconn.xlib.XScreenCount)(xconn.display)).find(
+            |&screen_id| {
+                (xconn.xlib.XRootWindow)(xconn.display, screen_id) == root
+            },
+        )?;
+        Some((screen_id, depth))
+    }
+}
+
+/// Returns the screen of `window`, or `None` if it isn't a valid window.
+pub fn get_window_screen(
+    xconn: &Arc<XConnection>,
+    window: ffi::Window,
+) -> Option<std::os::raw::c_int> {
+    unsafe {
+        let mut attrs = std::mem::zeroed();
+        let status = (xconn.xlib.XGetWindowAttributes)(
+            xconn.display,
+            window,
+            &mut attrs,
+        );
+        if status == 0 || xconn.check_errors().is_err() {
+            return None;
+        }
+
+        (0..(xconn.xlib.XScreenCount)(xconn.display)).find(|&screen_id| {
+            (xconn.xlib.XScreenOfDisplay)(xconn.display, screen_id)
+                == attrs.screen
+        })
+    }
+}
+
+/// Checks that a drawable on `drawable_screen` can be used with a config
+/// chosen on the screen `pf_reqs` asks for, if any.
+pub fn check_drawable_screen(
+    pf_reqs: &crate::PixelFormatRequirements,
+    drawable: &str,
+    drawable_screen: std::os::raw::c_int,
+) -> Result<(), crate::CreationError> {
+    match pf_reqs.x11_screen {
+        Some(screen_id) if screen_id != drawable_screen => {
+            Err(crate::CreationError::OsError(format!(
+                "The {} is on X screen {}, but the config was requested on X \
+                 screen {}",
+                drawable, drawable_screen, screen_id
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
 pub use super::select_config;
 pub use crate::api::egl::SurfaceType;
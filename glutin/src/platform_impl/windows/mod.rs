@@ -1,8 +1,9 @@
 #![cfg(target_os = "windows")]
 
 use crate::{
-    Api, ContextCurrentState, ContextError, CreationError, GlAttributes,
-    GlRequest, NotCurrent, PixelFormat, PixelFormatRequirements, Rect,
+    Api, Backend, ContextCurrentState, ContextError, CreationError,
+    GlAttributes, GlRequest, NotCurrent, PixelFormat, PixelFormatRequirements,
+    Rect, StateMask, SwapGroupLimits,
 };
 
 use crate::api::egl::{
@@ -12,7 +13,7 @@ use crate::api::wgl::Context as WglContext;
 use crate::platform::windows::WindowExtWindows;
 
 use glutin_egl_sys as ffi;
-use winapi::shared::windef::{HGLRC, HWND};
+use winapi::shared::windef::{HDC, HGLRC, HWND};
 use winit;
 use winit::dpi;
 use winit::event_loop::EventLoopWindowTarget;
@@ -36,6 +37,12 @@ pub enum Context {
     /// A regular window, but invisible.
     HiddenWindowEgl(Window, EglContext),
     HiddenWindowWgl(Window, WglContext),
+    /// A WGL pbuffer, with the invisible window it was created with.
+    WglPbuffer(
+        // only kept alive, as the pbuffer was created with its device context
+        #[allow(dead_code)] Window,
+        WglContext,
+    ),
     /// An EGL pbuffer.
     EglPbuffer(EglContext),
 }
@@ -52,9 +59,14 @@ impl Context {
         pf_reqs: &PixelFormatRequirements,
         gl_attr: &GlAttributes<&Self>,
     ) -> Result<(Window, Self), CreationError> {
+        // winit enables the blur behind transparent windows, which makes DWM
+        // composite them with their alpha channel
+        let mut pf_reqs = pf_reqs.clone();
+        pf_reqs.wgl_transparent = wb.window.transparent;
+
         let win = wb.build(el)?;
         let hwnd = win.hwnd() as HWND;
-        let ctx = Self::new_raw_context(hwnd, pf_reqs, gl_attr)?;
+        let ctx = Self::new_raw_context(hwnd, &pf_reqs, gl_attr)?;
 
         Ok((win, ctx))
     }
@@ -65,17 +77,23 @@ impl Context {
         pf_reqs: &PixelFormatRequirements,
         gl_attr: &GlAttributes<&Self>,
     ) -> Result<Self, CreationError> {
+        if gl_attr.sharing.is_none() && !pf_reqs.windows_backends.is_empty() {
+            return Self::new_raw_context_with_backends(hwnd, pf_reqs, gl_attr);
+        }
+
         match gl_attr.version {
             GlRequest::Specific(Api::OpenGlEs, (_major, _minor)) => {
                 match (gl_attr.sharing, &*EGL) {
                     // We must use WGL.
                     (Some(&Context::HiddenWindowWgl(_, _)), _)
+                    | (Some(&Context::WglPbuffer(_, _)), _)
                     | (Some(&Context::Wgl(_)), _)
                     | (None, None) => {
                         let gl_attr_wgl =
                             gl_attr.clone().map_sharing(|ctx| match *ctx {
                                 Context::HiddenWindowWgl(_, ref c)
-                                | Context::Wgl(ref c) => c.get_hglrc(),
+                                | Context::WglPbuffer(_, ref c)
+                                | Context::Wgl(ref c) => c,
                                 _ => unreachable!(),
                             });
                         unsafe {
@@ -134,7 +152,8 @@ impl Context {
                 let gl_attr_wgl =
                     gl_attr.clone().map_sharing(|ctx| match *ctx {
                         Context::HiddenWindowWgl(_, ref c)
-                        | Context::Wgl(ref c) => c.get_hglrc(),
+                        | Context::WglPbuffer(_, ref c)
+                        | Context::Wgl(ref c) => c,
                         _ => panic!(),
                     });
                 unsafe {
@@ -145,6 +164,96 @@ impl Context {
         }
     }
 
+    /// Tries the APIs of `pf_reqs.windows_backends` in order, and returns
+    /// the errors of all of them if none works.
+    fn new_raw_context_with_backends(
+        hwnd: HWND,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&Self>,
+    ) -> Result<Self, CreationError> {
+        let mut errors: Option<CreationError> = None;
+        for backend in &pf_reqs.windows_backends {
+            let context = match *backend {
+                Backend::Wgl => unsafe {
+                    WglContext::new(
+                        pf_reqs,
+                        &gl_attr.clone().map_sharing(|_| unreachable!()),
+                        hwnd,
+                    )
+                    .map(Context::Wgl)
+                },
+                Backend::Egl if EGL.is_none() => Err(
+                    CreationError::NotSupported("libEGL not found".to_string()),
+                ),
+                Backend::Egl => EglContext::new(
+                    pf_reqs,
+                    &gl_attr.clone().map_sharing(|_| unreachable!()),
+                    NativeDisplay::Other(Some(std::ptr::null())),
+                    EglSurfaceType::Window,
+                    |c, _| Ok(c[0]),
+                )
+                .and_then(|p| p.finish(hwnd))
+                .map(Context::Egl),
+            };
+            match context {
+                Ok(context) => return Ok(context),
+                Err(err) => {
+                    errors = Some(match errors {
+                        Some(errors) => errors.append(err),
+                        None => err,
+                    })
+                }
+            }
+        }
+        Err(errors.unwrap())
+    }
+
+    /// Returns the API of the context.
+    #[inline]
+    pub fn backend(&self) -> Backend {
+        match *self {
+            Context::Wgl(_)
+            | Context::HiddenWindowWgl(_, _)
+            | Context::WglPbuffer(_, _) => Backend::Wgl,
+            Context::Egl(_)
+            | Context::HiddenWindowEgl(_, _)
+            | Context::EglPbuffer(_) => Backend::Egl,
+        }
+    }
+
+    #[inline]
+    pub fn new_raw_context_from_hdc(
+        hdc: HDC,
+        pf_reqs: &PixelFormatRequirements,
+        gl_attr: &GlAttributes<&Self>,
+        owned: bool,
+    ) -> Result<Self, CreationError> {
+        // EGL needs a window
+        let gl_attr_wgl =
+            match gl_attr.sharing {
+                None
+                | Some(&Context::Wgl(_))
+                | Some(&Context::HiddenWindowWgl(_, _))
+                | Some(&Context::WglPbuffer(_, _)) => {
+                    gl_attr.clone().map_sharing(|ctx| match *ctx {
+                        Context::HiddenWindowWgl(_, ref c)
+                        | Context::WglPbuffer(_, ref c)
+                        | Context::Wgl(ref c) => c,
+                        _ => unreachable!(),
+                    })
+                }
+                _ => return Err(CreationError::NotSupported(
+                    "Contexts built from a device context can't share with \
+                     EGL contexts"
+                        .to_string(),
+                )),
+            };
+        unsafe {
+            WglContext::new_with_hdc(pf_reqs, &gl_attr_wgl, hdc, owned)
+                .map(Context::Wgl)
+        }
+    }
+
     #[inline]
     pub fn new_headless<T>(
         el: &EventLoopWindowTarget<T>,
@@ -155,10 +264,13 @@ impl Context {
         // if EGL is available, we try using EGL first
         // if EGL returns an error, we try the hidden window method
         match (gl_attr.sharing, &*EGL) {
+            // EGL can't choose the GPU
             (None, Some(_))
             | (Some(&Context::Egl(_)), Some(_))
             | (Some(&Context::HiddenWindowEgl(_, _)), Some(_))
-            | (Some(&Context::EglPbuffer(_)), Some(_)) => {
+            | (Some(&Context::EglPbuffer(_)), Some(_))
+                if pf_reqs.gpu.is_none() =>
+            {
                 let gl_attr_egl =
                     gl_attr.clone().map_sharing(|ctx| match *ctx {
                         Context::Egl(ref c)
@@ -188,7 +300,55 @@ impl Context {
         let wb = WindowBuilder::new()
             .with_visible(false)
             .with_inner_size(size);
-        Self::new_windowed(wb, &el, pf_reqs, gl_attr).map(|(win, context)| {
+        let win = wb.build(el)?;
+        let hwnd = win.hwnd() as HWND;
+
+        // if WGL is used, we try a pbuffer before rendering to the window
+        match gl_attr.sharing {
+            None
+            | Some(&Context::Wgl(_))
+            | Some(&Context::HiddenWindowWgl(_, _))
+            | Some(&Context::WglPbuffer(_, _)) => {
+                let gl_attr_wgl =
+                    gl_attr.clone().map_sharing(|ctx| match *ctx {
+                        Context::HiddenWindowWgl(_, ref c)
+                        | Context::WglPbuffer(_, ref c)
+                        | Context::Wgl(ref c) => c,
+                        _ => unreachable!(),
+                    });
+                let context = unsafe {
+                    WglContext::new_pbuffer(
+                        pf_reqs,
+                        &gl_attr_wgl,
+                        hwnd,
+                        size.into(),
+                    )
+                };
+                match context {
+                    Ok(context) => {
+                        return Ok(Context::WglPbuffer(win, context))
+                    }
+                    // the window wouldn't render on the requested GPU, and
+                    // can't be bound as a texture
+                    Err(err)
+                        if pf_reqs.gpu.is_some() || pf_reqs.textureable =>
+                    {
+                        return Err(err)
+                    }
+                    Err(_) => (),
+                }
+            }
+            _ => (),
+        }
+
+        if pf_reqs.textureable {
+            return Err(CreationError::NotSupported(
+                "Textureable pbuffers require EGL or WGL_ARB_render_texture"
+                    .to_string(),
+            ));
+        }
+
+        Self::new_raw_context(hwnd, pf_reqs, gl_attr).map(|context| {
             match context {
                 Context::Egl(context) => Context::HiddenWindowEgl(win, context),
                 Context::Wgl(context) => Context::HiddenWindowWgl(win, context),
@@ -205,21 +365,102 @@ impl Context {
     #[inline]
     pub unsafe fn make_current(&self) -> Result<(), ContextError> {
         match *self {
-            Context::Wgl(ref c) | Context::HiddenWindowWgl(_, ref c) => {
-                c.make_current()
-            }
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.make_current(),
             Context::Egl(ref c)
             | Context::HiddenWindowEgl(_, ref c)
             | Context::EglPbuffer(ref c) => c.make_current(),
         }
     }
 
+    #[inline]
+    pub unsafe fn make_current_draw_read(
+        &self,
+        draw: &Context,
+        read: &Context,
+    ) -> Result<(), ContextError> {
+        match (self.wgl(), draw.wgl(), read.wgl()) {
+            (Some(c), Some(draw), Some(read)) => {
+                return c.make_current_draw_read(draw, read);
+            }
+            _ => (),
+        }
+        match (self.egl(), draw.egl(), read.egl()) {
+            (Some(c), Some(draw), Some(read)) => {
+                c.make_current_draw_read(draw, read)
+            }
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    #[inline]
+    pub fn bind_tex_image(&self) -> Result<(), ContextError> {
+        match *self {
+            Context::WglPbuffer(_, ref c) => c.bind_tex_image(),
+            _ => self
+                .egl()
+                .ok_or(ContextError::FunctionUnavailable)?
+                .bind_tex_image(),
+        }
+    }
+
+    #[inline]
+    pub fn release_tex_image(&self) -> Result<(), ContextError> {
+        match *self {
+            Context::WglPbuffer(_, ref c) => c.release_tex_image(),
+            _ => self
+                .egl()
+                .ok_or(ContextError::FunctionUnavailable)?
+                .release_tex_image(),
+        }
+    }
+
+    #[inline]
+    pub fn copy_state_from(
+        &self,
+        src: &Context,
+        mask: StateMask,
+    ) -> Result<(), ContextError> {
+        match (self.wgl(), src.wgl()) {
+            (Some(dst), Some(src)) => dst.copy_state_from(src, mask),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    #[inline]
+    pub fn pbuffer_size(&self) -> Result<dpi::PhysicalSize<u32>, ContextError> {
+        match *self {
+            Context::WglPbuffer(_, ref c) => c
+                .pbuffer_size()
+                .map(|(width, height)| dpi::PhysicalSize::new(width, height)),
+            _ => self
+                .egl()
+                .ok_or(ContextError::FunctionUnavailable)?
+                .pbuffer_size(),
+        }
+    }
+
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .wait_gl()
+    }
+
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .wait_native()
+    }
+
     #[inline]
     pub unsafe fn make_not_current(&self) -> Result<(), ContextError> {
         match *self {
-            Context::Wgl(ref c) | Context::HiddenWindowWgl(_, ref c) => {
-                c.make_not_current()
-            }
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.make_not_current(),
             Context::Egl(ref c)
             | Context::HiddenWindowEgl(_, ref c)
             | Context::EglPbuffer(ref c) => c.make_not_current(),
@@ -229,9 +470,9 @@ impl Context {
     #[inline]
     pub fn is_current(&self) -> bool {
         match *self {
-            Context::Wgl(ref c) | Context::HiddenWindowWgl(_, ref c) => {
-                c.is_current()
-            }
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.is_current(),
             Context::Egl(ref c)
             | Context::HiddenWindowEgl(_, ref c)
             | Context::EglPbuffer(ref c) => c.is_current(),
@@ -241,9 +482,9 @@ impl Context {
     #[inline]
     pub fn get_proc_address(&self, addr: &str) -> *const core::ffi::c_void {
         match *self {
-            Context::Wgl(ref c) | Context::HiddenWindowWgl(_, ref c) => {
-                c.get_proc_address(addr)
-            }
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.get_proc_address(addr),
             Context::Egl(ref c)
             | Context::HiddenWindowEgl(_, ref c)
             | Context::EglPbuffer(ref c) => c.get_proc_address(addr),
@@ -259,33 +500,174 @@ impl Context {
         }
     }
 
+    #[inline]
+    pub fn swap_buffers_with_frame_id(
+        &self,
+    ) -> Result<crate::FrameId, ContextError> {
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .swap_buffers_with_frame_id()
+    }
+
+    #[inline]
+    pub fn presentation_times(
+        &self,
+        frame: crate::FrameId,
+    ) -> Result<crate::PresentationTimes, ContextError> {
+        self.egl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .presentation_times(frame)
+    }
+
+    #[inline]
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        match *self {
+            Context::Egl(ref c) => c.buffer_age(),
+            _ if !self.is_current() => Err(ContextError::OsError(
+                "The context must be current to query the buffer age"
+                    .to_string(),
+            )),
+            _ => Ok(0),
+        }
+    }
+
+    #[inline]
+    pub fn set_damage_region(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        match *self {
+            Context::Egl(ref c) => c.set_damage_region(rects),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
     #[inline]
     pub fn swap_buffers_with_damage(
         &self,
         rects: &[Rect],
     ) -> Result<(), ContextError> {
-        Err(ContextError::OsError(
-            "buffer damage not suported".to_string(),
-        ))
+        match *self {
+            // WGL has no partial swaps, so the whole buffer is swapped
+            Context::Wgl(ref c) => c.swap_buffers(),
+            Context::Egl(ref c) => c.swap_buffers_with_damage(rects),
+            _ => unreachable!(),
+        }
     }
 
     #[inline]
-    pub fn swap_buffers_with_damage_supported(&self) -> bool {
-        false
+    pub fn swap_damage_support(&self) -> crate::DamageSupport {
+        match *self {
+            Context::Wgl(_) => crate::DamageSupport::Emulated,
+            Context::Egl(ref c) => c.swap_damage_support(),
+            _ => unreachable!(),
+        }
     }
 
     #[inline]
-    pub fn get_api(&self) -> Api {
+    pub fn set_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> Result<crate::SwapInterval, ContextError> {
+        match *self {
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.set_swap_interval(interval),
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => c.set_swap_interval(interval),
+        }
+    }
+
+    #[inline]
+    pub fn supports_swap_interval(
+        &self,
+        interval: crate::SwapInterval,
+    ) -> bool {
         match *self {
-            Context::Wgl(ref c) | Context::HiddenWindowWgl(_, ref c) => {
-                c.get_api()
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => {
+                c.supports_swap_interval(interval)
             }
             Context::Egl(ref c)
             | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => c.supports_swap_interval(interval),
+        }
+    }
+
+    #[inline]
+    pub fn get_swap_interval(&self) -> Option<crate::SwapInterval> {
+        match *self {
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.get_swap_interval(),
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => c.get_swap_interval(),
+        }
+    }
+
+    #[inline]
+    pub fn get_api(&self) -> Api {
+        match *self {
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.get_api(),
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
             | Context::EglPbuffer(ref c) => c.get_api(),
         }
     }
 
+    #[inline]
+    pub fn priority(&self) -> Option<crate::Priority> {
+        match *self {
+            Context::Wgl(_)
+            | Context::HiddenWindowWgl(_, _)
+            | Context::WglPbuffer(_, _) => None,
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => c.priority(),
+        }
+    }
+
+    #[inline]
+    pub fn release_behavior(&self) -> crate::ReleaseBehavior {
+        match *self {
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => c.release_behavior(),
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => c.release_behavior(),
+        }
+    }
+
+    #[inline]
+    pub fn color_space(&self) -> Option<crate::ColorSpace> {
+        match *self {
+            Context::Wgl(_)
+            | Context::HiddenWindowWgl(_, _)
+            | Context::WglPbuffer(_, _) => None,
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => c.color_space(),
+        }
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> Result<&crate::Extensions, ContextError> {
+        match *self {
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => Ok(c.extensions()),
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => Ok(c.extensions()),
+        }
+    }
+
     #[inline]
     pub fn get_pixel_format(&self) -> PixelFormat {
         match *self {
@@ -295,12 +677,30 @@ impl Context {
         }
     }
 
+    pub(crate) fn wgl(&self) -> Option<&WglContext> {
+        match *self {
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn egl(&self) -> Option<&EglContext> {
+        match *self {
+            Context::Egl(ref c)
+            | Context::HiddenWindowEgl(_, ref c)
+            | Context::EglPbuffer(ref c) => Some(c),
+            _ => None,
+        }
+    }
+
     #[inline]
     pub unsafe fn raw_handle(&self) -> RawHandle {
         match *self {
-            Context::Wgl(ref c) | Context::HiddenWindowWgl(_, ref c) => {
-                RawHandle::Wgl(c.get_hglrc())
-            }
+            Context::Wgl(ref c)
+            | Context::HiddenWindowWgl(_, ref c)
+            | Context::WglPbuffer(_, ref c) => RawHandle::Wgl(c.get_hglrc()),
             Context::Egl(ref c)
             | Context::HiddenWindowEgl(_, ref c)
             | Context::EglPbuffer(ref c) => RawHandle::Egl(c.raw_handle()),
@@ -330,6 +730,28 @@ pub trait RawContextExt {
     ) -> Result<crate::RawContext<NotCurrent>, CreationError>
     where
         Self: Sized;
+
+    /// Creates a raw WGL context on the provided device context of a window,
+    /// for example one handed over by a host application. Its pixel format
+    /// is set if it has none, and otherwise must meet the requirements, or
+    /// [`CreationError::PixelFormatAlreadySet`] is returned.
+    ///
+    /// If `owned` is true, the device context is released with `ReleaseDC`
+    /// when the context is dropped. Otherwise it is left to the caller.
+    ///
+    /// Unsafe behaviour might happen if you:
+    ///   - Provide us with invalid parameters.
+    ///   - The device context or its window is destroyed before the context
+    ///
+    /// [`CreationError::PixelFormatAlreadySet`]:
+    /// ../../enum.CreationError.html#variant.PixelFormatAlreadySet
+    unsafe fn build_raw_context_from_hdc(
+        self,
+        hdc: *mut raw::c_void,
+        owned: bool,
+    ) -> Result<crate::RawContext<NotCurrent>, CreationError>
+    where
+        Self: Sized;
 }
 
 impl<'a, T: ContextCurrentState> RawContextExt
@@ -355,4 +777,150 @@ impl<'a, T: ContextCurrentState> RawContextExt
                 window: (),
             })
     }
+
+    #[inline]
+    unsafe fn build_raw_context_from_hdc(
+        self,
+        hdc: *mut raw::c_void,
+        owned: bool,
+    ) -> Result<crate::RawContext<NotCurrent>, CreationError>
+    where
+        Self: Sized,
+    {
+        let crate::ContextBuilder { pf_reqs, gl_attr } = self;
+        let gl_attr = gl_attr.map_sharing(|ctx| &ctx.context);
+        Context::new_raw_context_from_hdc(
+            hdc as *mut _,
+            &pf_reqs,
+            &gl_attr,
+            owned,
+        )
+        .map(|context| crate::Context {
+            context,
+            phantom: PhantomData,
+        })
+        .map(|context| crate::RawContext {
+            context,
+            window: (),
+        })
+    }
+}
+
+/// A windows-specific extension to WGL [`Context`]s.
+///
+/// [`Context`]: ../../struct.Context.html
+pub trait WglContextExt {
+    /// Returns how many swap groups and swap barriers `WGL_NV_swap_group`
+    /// provides for the device context of the context. See
+    /// [`SwapGroupLimits`].
+    ///
+    /// This requires `WGL_NV_swap_group`. If it is missing, or the context
+    /// doesn't use WGL, this and the other swap group functions return
+    /// [`ContextError::FunctionUnavailable`], while a group or barrier out of
+    /// range is reported as [`ContextError::OsError`].
+    ///
+    /// [`SwapGroupLimits`]: ../../struct.SwapGroupLimits.html
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    /// [`ContextError::OsError`]:
+    /// ../../enum.ContextError.html#variant.OsError
+    fn query_max_swap_groups(&self) -> Result<SwapGroupLimits, ContextError>;
+
+    /// Makes the device context of the context join the swap group `group`,
+    /// or leave its swap group if `group` is `0`. The device contexts of a
+    /// swap group swap their buffers together.
+    ///
+    /// The device context leaves its swap group when the context is dropped.
+    fn join_swap_group(&self, group: u32) -> Result<(), ContextError>;
+
+    /// Binds the swap group `group` to the swap barrier `barrier`, or unbinds
+    /// it if `barrier` is `0`. The swap groups bound to a barrier swap their
+    /// buffers together, across systems linked by a G-Sync board.
+    fn bind_swap_barrier(
+        &self,
+        group: u32,
+        barrier: u32,
+    ) -> Result<(), ContextError>;
+
+    /// Returns whether the memory of the pbuffer of a headless context was
+    /// lost, which can happen when the display mode changes. A lost pbuffer
+    /// doesn't hold any rendering anymore and should be recreated.
+    ///
+    /// This requires a pbuffer created with `WGL_ARB_pbuffer`, otherwise
+    /// [`ContextError::FunctionUnavailable`] is returned.
+    ///
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    fn is_pbuffer_lost(&self) -> Result<bool, ContextError>;
+
+    /// Returns whether the pixel format of the window supports composition
+    /// by DWM, which it needs to be blended with the desktop. Pbuffers
+    /// aren't composited.
+    ///
+    /// If the context doesn't use WGL, [`ContextError::FunctionUnavailable`]
+    /// is returned.
+    ///
+    /// [`ContextError::FunctionUnavailable`]:
+    /// ../../enum.ContextError.html#variant.FunctionUnavailable
+    fn supports_composition(&self) -> Result<bool, ContextError>;
+
+    /// Returns whether the context was created through WGL or EGL, for
+    /// example after falling back to another API of
+    /// [`ContextBuilder::with_windows_backends`].
+    ///
+    /// [`ContextBuilder::with_windows_backends`]:
+    /// ../../struct.ContextBuilder.html#method.with_windows_backends
+    fn backend(&self) -> Backend;
+}
+
+impl<T: ContextCurrentState> WglContextExt for crate::Context<T> {
+    #[inline]
+    fn query_max_swap_groups(&self) -> Result<SwapGroupLimits, ContextError> {
+        self.context
+            .wgl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .query_max_swap_groups()
+    }
+
+    #[inline]
+    fn join_swap_group(&self, group: u32) -> Result<(), ContextError> {
+        self.context
+            .wgl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .join_swap_group(group)
+    }
+
+    #[inline]
+    fn bind_swap_barrier(
+        &self,
+        group: u32,
+        barrier: u32,
+    ) -> Result<(), ContextError> {
+        self.context
+            .wgl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .bind_swap_barrier(group, barrier)
+    }
+
+    #[inline]
+    fn is_pbuffer_lost(&self) -> Result<bool, ContextError> {
+        match self.context {
+            Context::WglPbuffer(_, ref c) => c.is_pbuffer_lost(),
+            _ => Err(ContextError::FunctionUnavailable),
+        }
+    }
+
+    #[inline]
+    fn supports_composition(&self) -> Result<bool, ContextError> {
+        Ok(self
+            .context
+            .wgl()
+            .ok_or(ContextError::FunctionUnavailable)?
+            .supports_composition())
+    }
+
+    #[inline]
+    fn backend(&self) -> Backend {
+        self.context.backend()
+    }
 }
@@ -132,13 +132,114 @@ impl<W> ContextWrapper<PossiblyCurrent, W> {
     /// next time the screen is refreshed. However drivers can choose to
     /// override your vsync settings, which means that you can't know in
     /// advance whether `swap_buffers` will block or not.
+    ///
+    /// On EGL, this returns [`ContextError::DisplayLost`] if the display was
+    /// terminated, in which case the context has to be built again.
+    ///
+    /// [`ContextError::DisplayLost`]:
+    /// enum.ContextError.html#variant.DisplayLost
     pub fn swap_buffers(&self) -> Result<(), ContextError> {
         self.context.context.swap_buffers()
     }
 
+    /// Swaps the buffers like [`swap_buffers`], and returns the id of the
+    /// frame that was swapped, to pass to [`presentation_times`] once it has
+    /// been presented.
+    ///
+    /// This uses `EGL_ANDROID_get_frame_timestamps`, or
+    /// `GLX_OML_sync_control` with GLX, and returns
+    /// [`ContextError::FunctionUnavailable`] without swapping if it is not
+    /// available. Timestamps are only recorded from the first call on.
+    ///
+    /// [`swap_buffers`]: #method.swap_buffers
+    /// [`presentation_times`]: #method.presentation_times
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub fn swap_buffers_with_frame_id(&self) -> Result<FrameId, ContextError> {
+        self.context.context.swap_buffers_with_frame_id()
+    }
+
+    /// Returns when a frame swapped with [`swap_buffers_with_frame_id`] was
+    /// requested to be presented, finished rendering and reached the
+    /// display, for frame pacing. See [`PresentationTimes`].
+    ///
+    /// The driver only keeps the timestamps of recent frames, and returns an
+    /// error for frames that are too old. Like
+    /// [`swap_buffers_with_frame_id`], this returns
+    /// [`ContextError::FunctionUnavailable`] if
+    /// `EGL_ANDROID_get_frame_timestamps` is not available.
+    ///
+    /// With GLX, only the display time of the latest completed swap is
+    /// known, and the composite interval is the refresh period of the
+    /// display. Older frames return an error, so this has to be called
+    /// before the next swap completes.
+    ///
+    /// [`swap_buffers_with_frame_id`]: #method.swap_buffers_with_frame_id
+    /// [`PresentationTimes`]: struct.PresentationTimes.html
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub fn presentation_times(
+        &self,
+        frame: FrameId,
+    ) -> Result<PresentationTimes, ContextError> {
+        self.context.context.presentation_times(frame)
+    }
+
+    /// Returns the age of the back buffer, which is the number of frames since
+    /// its contents were last presented, or 0 if its contents are undefined or
+    /// the age is unknown.
+    ///
+    /// Knowing the age lets you only repaint what changed since then. The age
+    /// is only meaningful once the context is current, and before drawing
+    /// anything to the back buffer.
+    ///
+    /// This uses `EGL_EXT_buffer_age` or `GLX_EXT_buffer_age`, and always
+    /// returns 0 on other platforms. With GLX, it also returns 0 if the
+    /// window of the context isn't the current draw drawable of the calling
+    /// thread.
+    ///
+    /// Returns an error if the context isn't current on the calling thread.
+    pub fn buffer_age(&self) -> Result<u32, ContextError> {
+        self.context.context.buffer_age()
+    }
+
+    /// Tells the driver which parts of the back buffer will be redrawn this
+    /// frame, so that tiled renderers can skip loading the rest. The
+    /// rectangles use a bottom-left origin, see [`Rect`].
+    ///
+    /// This must be called at most once per frame, while the context is
+    /// current and before drawing anything, typically right after
+    /// [`buffer_age`]. Pixels outside of the region have undefined contents
+    /// after drawing.
+    ///
+    /// This uses `EGL_KHR_partial_update` and returns
+    /// [`ContextError::FunctionUnavailable`] if it is not available. Partial
+    /// updates don't work with preserved swaps, so this fails on contexts
+    /// created with [`with_preserved_swap`].
+    ///
+    /// [`Rect`]: struct.Rect.html
+    /// [`buffer_age`]: #method.buffer_age
+    /// [`with_preserved_swap`]:
+    /// struct.ContextBuilder.html#method.with_preserved_swap
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub fn set_damage_region(
+        &self,
+        rects: &[Rect],
+    ) -> Result<(), ContextError> {
+        self.context.context.set_damage_region(rects)
+    }
+
     /// Swaps the buffers in case of double or triple buffering using specified
     /// damage rects.
     ///
+    /// The rectangles use a bottom-left origin, see [`Rect`]. This uses
+    /// `EGL_KHR_swap_buffers_with_damage` or `EGL_EXT_swap_buffers_with_damage`
+    /// and returns [`ContextError::FunctionUnavailable`] if neither is
+    /// available, in which case you should call [`swap_buffers`] instead.
+    /// WGL has no equivalent and swaps the whole buffer, see
+    /// [`swap_damage_support`].
+    ///
     /// You should call this function every time you have finished rendering, or
     /// the image may not be displayed on the screen.
     ///
@@ -146,6 +247,12 @@ impl<W> ContextWrapper<PossiblyCurrent, W> {
     /// next time the screen is refreshed. However drivers can choose to
     /// override your vsync settings, which means that you can't know in
     /// advance whether `swap_buffers` will block or not.
+    ///
+    /// [`Rect`]: struct.Rect.html
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    /// [`swap_buffers`]: #method.swap_buffers
+    /// [`swap_damage_support`]: #method.swap_damage_support
     pub fn swap_buffers_with_damage(
         &self,
         rects: &[Rect],
@@ -157,7 +264,67 @@ impl<W> ContextWrapper<PossiblyCurrent, W> {
     /// function returns false, any call to swap_buffers_with_damage will
     /// return an error.
     pub fn swap_buffers_with_damage_supported(&self) -> bool {
-        self.context.context.swap_buffers_with_damage_supported()
+        self.swap_damage_support() != DamageSupport::None
+    }
+
+    /// Returns how [`swap_buffers_with_damage`] honors the damage rects. See
+    /// [`DamageSupport`].
+    ///
+    /// [`swap_buffers_with_damage`]: #method.swap_buffers_with_damage
+    /// [`DamageSupport`]: enum.DamageSupport.html
+    pub fn swap_damage_support(&self) -> DamageSupport {
+        self.context.context.swap_damage_support()
+    }
+
+    /// Sets how [`swap_buffers`] synchronizes with the display, without
+    /// recreating the surface. The context must be current. See
+    /// [`SwapInterval`].
+    ///
+    /// Intervals outside of the range supported by the surface are clamped,
+    /// and the interval that was actually set is returned. Returns
+    /// [`ContextError::FunctionUnavailable`] if the backend can't honor the
+    /// kind of `interval`, such as [`AdaptiveWait`] without the tear
+    /// extensions, rather than falling back to another mode. Use
+    /// [`supports_swap_interval`] to check beforehand.
+    ///
+    /// With WGL, the interval is remembered for the window and applied by
+    /// [`swap_buffers`] to the current context, so that a context drawing to
+    /// several windows with [`make_current_draw_read`] keeps the interval of
+    /// each. CGL only supports intervals of 0 and 1, and sets the interval of
+    /// the context itself. The other backends set the interval of the surface
+    /// of the context.
+    ///
+    /// [`swap_buffers`]: #method.swap_buffers
+    /// [`make_current_draw_read`]: #method.make_current_draw_read
+    /// [`SwapInterval`]: enum.SwapInterval.html
+    /// [`AdaptiveWait`]: enum.SwapInterval.html#variant.AdaptiveWait
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    /// [`supports_swap_interval`]: #method.supports_swap_interval
+    pub fn set_swap_interval(
+        &self,
+        interval: SwapInterval,
+    ) -> Result<SwapInterval, ContextError> {
+        self.context.context.set_swap_interval(interval)
+    }
+
+    /// Returns the swap interval in effect, or `None` if the backend can't
+    /// report it.
+    ///
+    /// EGL can't query the swap interval, so there this is the last one set
+    /// with [`set_swap_interval`] or at creation.
+    ///
+    /// [`set_swap_interval`]: #method.set_swap_interval
+    pub fn get_swap_interval(&self) -> Option<SwapInterval> {
+        self.context.context.get_swap_interval()
+    }
+
+    /// Returns whether [`set_swap_interval`] supports `interval` with the
+    /// current config and surface without clamping it.
+    ///
+    /// [`set_swap_interval`]: #method.set_swap_interval
+    pub fn supports_swap_interval(&self, interval: SwapInterval) -> bool {
+        self.context.context.supports_swap_interval(interval)
     }
 
     /// Returns the pixel format of the main framebuffer of the context.
@@ -174,6 +341,19 @@ impl<W> ContextWrapper<PossiblyCurrent, W> {
     /// that is received with a [`LogicalSize`] and convert it to a
     /// [`PhysicalSize`] and pass it into this function.
     ///
+    /// On Wayland, this resizes the `wl_egl_window`, and the new size is used
+    /// from the next frame on, so call it before drawing and calling
+    /// [`swap_buffers`]. When using a fractional scale factor, pass the
+    /// rounded [`PhysicalSize`] of the window, not a rescaled
+    /// [`LogicalSize`], or the compositor will scale the output. Zero sizes,
+    /// which minimized windows can report, are ignored. On macOS this updates
+    /// the `NSOpenGLContext`, and attaches it to the view of the window again
+    /// if it lost it, for example when the window moved to another screen.
+    /// AppKit only allows this on the main thread, so from other threads the
+    /// update is queued for the main thread's run loop. On other platforms it
+    /// does nothing.
+    ///
+    /// [`swap_buffers`]: #method.swap_buffers
     /// [`LogicalSize`]: dpi/struct.LogicalSize.html
     /// [`PhysicalSize`]: dpi/struct.PhysicalSize.html
     /// [`Resized`]: event/enum.WindowEvent.html#variant.Resized
@@ -257,6 +437,66 @@ impl<T: ContextCurrentState, W> ContextWrapper<T, W> {
         }
     }
 
+    /// Like [`make_current`], but draws to the surface of `draw` and reads
+    /// from the surface of `read`, which is used by `glReadPixels`,
+    /// `glCopyPixels` and as the source of `glBlitFramebuffer`. This avoids
+    /// going through framebuffer objects to copy between two windows.
+    ///
+    /// `None` stands for the surface of this context, and passing `None` for
+    /// both is the same as calling [`make_current`]. For example, to copy
+    /// from another window into this one:
+    ///
+    /// ```no_run
+    /// # fn main() {
+    /// # let el = glutin::event_loop::EventLoop::new();
+    /// # let wb = glutin::window::WindowBuilder::new();
+    /// # let windowed_context = glutin::ContextBuilder::new()
+    /// #    .build_windowed(wb.clone(), &el)
+    /// #    .unwrap();
+    /// # let other = glutin::ContextBuilder::new()
+    /// #    .with_shared_lists(windowed_context.context())
+    /// #    .build_windowed(wb, &el)
+    /// #    .unwrap();
+    /// let windowed_context = unsafe {
+    ///     windowed_context
+    ///         .make_current_draw_read(None, Some(other.context()))
+    ///         .unwrap()
+    /// };
+    /// # }
+    /// ```
+    ///
+    /// Supported with EGL and GLX. For different draw and read surfaces, GLX
+    /// needs GLX 1.3 and a context that wasn't created with a legacy visual,
+    /// and returns [`ContextError::FunctionUnavailable`] otherwise, while WGL
+    /// needs `WGL_ARB_make_current_read`, and returns
+    /// [`ContextError::OsError`] naming it otherwise. With CGL, EAGL,
+    /// OsMesa and WebGL, [`ContextError::FunctionUnavailable`] is returned
+    /// and the context is left alone.
+    ///
+    /// The surfaces must be compatible with this context, which usually
+    /// means they were created with the same pixel format on the same
+    /// display. This is unsafe for the same reasons as [`make_current`], and
+    /// because `draw` and `read` must outlive the time this context is
+    /// current with their surfaces.
+    ///
+    /// [`make_current`]: struct.ContextWrapper.html#method.make_current
+    /// [`ContextError::OsError`]: enum.ContextError.html#variant.OsError
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub unsafe fn make_current_draw_read<T2: ContextCurrentState>(
+        self,
+        draw: Option<&Context<T2>>,
+        read: Option<&Context<T2>>,
+    ) -> Result<ContextWrapper<PossiblyCurrent, W>, (Self, ContextError)> {
+        let window = self.window;
+        match self.context.make_current_draw_read(draw, read) {
+            Ok(context) => Ok(ContextWrapper { window, context }),
+            Err((context, err)) => {
+                Err((ContextWrapper { window, context }, err))
+            }
+        }
+    }
+
     /// If this context is current, makes this context not current. If this
     /// context is not current however, this function does nothing.
     ///
@@ -322,6 +562,49 @@ impl<T: ContextCurrentState, W> ContextWrapper<T, W> {
     pub fn get_api(&self) -> Api {
         self.context.get_api()
     }
+
+    /// Returns the scheduling priority the driver granted the context, which
+    /// may be lower than the one passed to [`with_priority`].
+    ///
+    /// Returns `None` if the backend can't report it. See [`Priority`].
+    ///
+    /// [`with_priority`]: struct.ContextBuilder.html#method.with_priority
+    /// [`Priority`]: enum.Priority.html
+    pub fn priority(&self) -> Option<Priority> {
+        self.context.priority()
+    }
+
+    /// Returns what happens to the context when it stops being current. If
+    /// this is [`ReleaseBehavior::None`], you must flush manually before
+    /// switching contexts.
+    ///
+    /// [`ReleaseBehavior::None`]: enum.ReleaseBehavior.html#variant.None
+    pub fn release_behavior(&self) -> ReleaseBehavior {
+        self.context.release_behavior()
+    }
+
+    /// Returns the color space of the surface, as reported by the driver.
+    ///
+    /// Returns `None` if the backend can't report it. See [`ColorSpace`].
+    ///
+    /// [`ColorSpace`]: enum.ColorSpace.html
+    pub fn color_space(&self) -> Option<ColorSpace> {
+        self.context.color_space()
+    }
+
+    /// Returns the EGL, GLX or WGL extensions supported by the display the
+    /// context was created on. They are queried once at creation, so this is
+    /// cheap to call. See [`Extensions`].
+    ///
+    /// Returns [`ContextError::FunctionUnavailable`] on backends without
+    /// such extensions, that is OsMesa, CGL, EAGL and WebGL.
+    ///
+    /// [`Extensions`]: struct.Extensions.html
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    pub fn extensions(&self) -> Result<&Extensions, ContextError> {
+        self.context.extensions()
+    }
 }
 
 impl<W> ContextWrapper<PossiblyCurrent, W> {
@@ -330,6 +613,81 @@ impl<W> ContextWrapper<PossiblyCurrent, W> {
     pub fn get_proc_address(&self, addr: &str) -> *const core::ffi::c_void {
         self.context.get_proc_address(addr)
     }
+
+    /// Waits for OpenGL rendering to complete, so that native rendering to
+    /// the same window, for example with Xlib, can start. This calls
+    /// `eglWaitClient` or `glXWaitGL`.
+    ///
+    /// Returns an error if the context is not current, and
+    /// [`ContextError::FunctionUnavailable`] on backends other than EGL and
+    /// GLX.
+    ///
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    #[inline]
+    pub fn wait_gl(&self) -> Result<(), ContextError> {
+        self.context.wait_gl()
+    }
+
+    /// Waits for native rendering to the window to complete, so that OpenGL
+    /// rendering can start. This calls `eglWaitNative` or `glXWaitX`.
+    ///
+    /// Returns an error if the context is not current, and
+    /// [`ContextError::FunctionUnavailable`] on backends other than EGL and
+    /// GLX.
+    ///
+    /// [`ContextError::FunctionUnavailable`]:
+    /// enum.ContextError.html#variant.FunctionUnavailable
+    #[inline]
+    pub fn wait_native(&self) -> Result<(), ContextError> {
+        self.context.wait_native()
+    }
+
+    /// Returns whether a graphics reset happened since the context was
+    /// created, by calling `glGetGraphicsResetStatus` or one of its `KHR`,
+    /// `ARB` and `EXT` variants through [`get_proc_address`]. The context
+    /// must be current.
+    ///
+    /// The core function is used with OpenGL 4.5 and OpenGL ES 3.2, and the
+    /// variants when `GL_KHR_robustness`, `GL_ARB_robustness` or
+    /// `GL_EXT_robustness` are in the extensions of the context. Otherwise,
+    /// this returns [`ResetStatus::NoError`]. See [`ResetStatus`].
+    ///
+    /// [`get_proc_address`]: struct.ContextWrapper.html#method.get_proc_address
+    /// [`ResetStatus::NoError`]: enum.ResetStatus.html#variant.NoError
+    /// [`ResetStatus`]: enum.ResetStatus.html
+    #[inline]
+    pub fn reset_status(&self) -> ResetStatus {
+        self.context.reset_status()
+    }
+
+    /// Returns true if the context was lost to a graphics reset. It must then
+    /// be recreated, along with all of its resources.
+    ///
+    /// This is a shorthand for comparing [`reset_status`] against
+    /// [`ResetStatus::NoError`].
+    ///
+    /// [`reset_status`]: struct.ContextWrapper.html#method.reset_status
+    /// [`ResetStatus::NoError`]: enum.ResetStatus.html#variant.NoError
+    #[inline]
+    pub fn is_lost(&self) -> bool {
+        self.context.is_lost()
+    }
+
+    /// Returns true if the driver created a `GL_KHR_no_error` context, which
+    /// it may not do even if [`Robustness::NoError`] was requested.
+    ///
+    /// This reads `GL_CONTEXT_FLAG_NO_ERROR_BIT` from `GL_CONTEXT_FLAGS`
+    /// with `glGetIntegerv`, through [`get_proc_address`]. Contexts older
+    /// than OpenGL 3.0 or OpenGL ES 3.2 have no such flags, and always return
+    /// false.
+    ///
+    /// [`Robustness::NoError`]: enum.Robustness.html#variant.NoError
+    /// [`get_proc_address`]: struct.ContextWrapper.html#method.get_proc_address
+    #[inline]
+    pub fn is_no_error(&self) -> bool {
+        self.context.is_no_error()
+    }
 }
 
 impl<T: ContextCurrentState, W> std::ops::Deref for ContextWrapper<T, W> {
@@ -350,8 +708,21 @@ impl<'a, T: ContextCurrentState> ContextBuilder<'a, T> {
     ///    happens
     ///  because the underlying platform doesn't support a requested feature.
     ///
+    /// If the window is built with `WindowBuilder::with_transparent`, the
+    /// context gets a pixel format with an alpha channel, which the
+    /// compositor uses to blend the window with the desktop. On Windows, this
+    /// requires a pixel format with 8 alpha bits that supports composition,
+    /// and building the context fails with
+    /// [`CreationError::NoAvailablePixelFormat`] if there is none. DWM
+    /// expects premultiplied alpha, so the color channels must already be
+    /// multiplied by the alpha channel, for example by clearing to
+    /// `(r * a, g * a, b * a, a)` and blending with `GL_ONE,
+    /// GL_ONE_MINUS_SRC_ALPHA`.
+    ///
     /// [`WindowedContext<T>`]: type.WindowedContext.html
     /// [`Context`]: struct.Context.html
+    /// [`CreationError::NoAvailablePixelFormat`]:
+    /// enum.CreationError.html#variant.NoAvailablePixelFormat
     pub fn build_windowed<TE>(
         self,
         wb: WindowBuilder,
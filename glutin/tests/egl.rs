@@ -0,0 +1,183 @@
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+
+use glutin::dpi::PhysicalSize;
+use glutin::platform::unix::{
+    egl_device_supported_sample_counts, Device, EglContextExt,
+    HeadlessContextExt,
+};
+use glutin::{Api, Context, ContextBuilder, GlRequest, NotCurrent};
+
+use std::ffi::CStr;
+use std::os::raw;
+
+const GL_VERSION: u32 = 0x1F02;
+
+// Most CI machines only have the software device of Mesa, if any, so the
+// tests pass without one.
+fn device() -> Option<Device> {
+    match Device::enumerate() {
+        Ok(ref devices) if !devices.is_empty() => Some(devices[0]),
+        Ok(_) => {
+            eprintln!("skipping, no EGL device is available");
+            None
+        }
+        Err(err) => {
+            eprintln!("skipping, EGL devices are not available: {}", err);
+            None
+        }
+    }
+}
+
+// Software configs have a minimum swap interval of 1 and no hardware
+// acceleration.
+fn builder() -> ContextBuilder<'static, NotCurrent> {
+    ContextBuilder::new()
+        .with_hardware_acceleration(None)
+        .with_vsync(true)
+}
+
+// Makes the context current and returns its `GL_VERSION` string.
+fn gl_version(context: Context<NotCurrent>) -> String {
+    let context = unsafe { context.make_current() }.unwrap();
+    let get_string: unsafe extern "system" fn(u32) -> *const raw::c_char =
+        unsafe { std::mem::transmute(context.get_proc_address("glGetString")) };
+    let version = unsafe { CStr::from_ptr(get_string(GL_VERSION)) };
+    version.to_string_lossy().into_owned()
+}
+
+fn has_extension(device: &Device, extension: &str) -> bool {
+    let supported = device
+        .display_info()
+        .map_or(false, |info| info.has_extension(extension));
+    if !supported {
+        eprintln!("skipping, {} is not supported", extension);
+    }
+    supported
+}
+
+// An RGBA8 and an RGB565 pbuffer.
+fn differently_formatted_pbuffers(
+    device: &Device,
+    no_config: bool,
+) -> (Context<NotCurrent>, Context<NotCurrent>) {
+    let size = PhysicalSize::new(16, 16);
+    let rgba8 = builder()
+        .with_no_config(no_config)
+        .with_pixel_format(24, 8)
+        .build_headless_with_device(device, size)
+        .unwrap();
+    let rgb565 = builder()
+        .with_pixel_format(16, 0)
+        .build_headless_with_device(device, size)
+        .unwrap();
+    (rgba8, rgb565)
+}
+
+#[test]
+fn pbuffers_build_on_configs_without_a_pixel_limit() {
+    let device = match device() {
+        Some(device) => device,
+        None => return,
+    };
+
+    let context = builder()
+        .build_headless_with_device(&device, PhysicalSize::new(64, 64))
+        .unwrap();
+    unsafe { context.make_current() }.unwrap();
+}
+
+#[test]
+fn device_sample_counts_build_pbuffers() {
+    let device = match device() {
+        Some(device) => device,
+        None => return,
+    };
+
+    let counts =
+        egl_device_supported_sample_counts(&builder(), &device).unwrap();
+    assert!(!counts.is_empty());
+    for &count in &counts {
+        builder()
+            .with_multisampling_exact(count)
+            .build_headless_with_device(&device, PhysicalSize::new(16, 16))
+            .unwrap();
+    }
+}
+
+#[test]
+fn no_config_contexts_bind_differently_formatted_surfaces() {
+    let device = match device() {
+        Some(device) => device,
+        None => return,
+    };
+    if !has_extension(&device, "EGL_KHR_no_config_context") {
+        return;
+    }
+
+    let (context, other) = differently_formatted_pbuffers(&device, true);
+
+    let context = unsafe { context.make_current_with_surface_of(&other) }
+        .map_err(|(_, err)| err)
+        .unwrap();
+    assert!(context.is_current());
+    let context = unsafe { context.make_current() }.unwrap();
+    assert!(context.is_current());
+}
+
+#[test]
+fn configured_contexts_reject_differently_formatted_surfaces() {
+    let device = match device() {
+        Some(device) => device,
+        None => return,
+    };
+
+    let (context, other) = differently_formatted_pbuffers(&device, false);
+    match unsafe { context.make_current_with_surface_of(&other) } {
+        Ok(_) => panic!("bound a surface of another config"),
+        Err((context, _)) => assert!(!context.is_current()),
+    }
+}
+
+#[test]
+fn gles3_requests_get_gles3_configs() {
+    let device = match device() {
+        Some(device) => device,
+        None => return,
+    };
+
+    let context = match builder()
+        .with_gl(GlRequest::Specific(Api::OpenGlEs, (3, 0)))
+        .build_surfaceless_with_device(&device)
+    {
+        Ok(context) => context,
+        Err(err) => {
+            eprintln!("skipping, OpenGL ES 3 is not supported: {}", err);
+            return;
+        }
+    };
+    assert_eq!(context.get_api(), Api::OpenGlEs);
+    let version = gl_version(context);
+    assert!(version.starts_with("OpenGL ES 3."), "{}", version);
+}
+
+#[test]
+fn desktop_requests_bind_the_opengl_api() {
+    let device = match device() {
+        Some(device) => device,
+        None => return,
+    };
+
+    let context = builder()
+        .with_gl(GlRequest::Specific(Api::OpenGl, (2, 1)))
+        .build_surfaceless_with_device(&device)
+        .unwrap();
+    assert_eq!(context.get_api(), Api::OpenGl);
+    let version = gl_version(context);
+    assert!(!version.starts_with("OpenGL ES"), "{}", version);
+}
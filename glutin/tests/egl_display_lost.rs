@@ -0,0 +1,55 @@
+// Terminating the display would break the contexts of other tests sharing
+// it, so this test has its own process.
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+
+use glutin::platform::unix::{Device, HeadlessContextExt};
+use glutin::platform::ContextTraitExt;
+use glutin::{ContextBuilder, ContextError};
+
+use std::os::raw;
+
+type EglTerminate = unsafe extern "C" fn(*const raw::c_void) -> u32;
+
+#[test]
+fn terminated_displays_are_reported_and_reinitialized() {
+    let device = match Device::enumerate() {
+        Ok(ref devices) if !devices.is_empty() => devices[0],
+        _ => {
+            eprintln!("skipping, no EGL device is available");
+            return;
+        }
+    };
+    let builder = || {
+        ContextBuilder::new()
+            .with_hardware_acceleration(None)
+            .with_vsync(true)
+    };
+
+    let context = builder().build_surfaceless_with_device(&device).unwrap();
+    let context = unsafe { context.make_current() }.unwrap();
+    let context = unsafe { context.make_not_current() }.unwrap();
+
+    // terminate the display behind the back of glutin
+    let egl = libloading::Library::new("libEGL.so.1").unwrap();
+    unsafe {
+        let terminate = egl.get::<EglTerminate>(b"eglTerminate\0").unwrap();
+        let display = context.get_egl_display().unwrap();
+        assert_ne!(terminate(display), 0);
+    }
+
+    match unsafe { context.make_current() } {
+        Err((_, ContextError::DisplayLost)) => (),
+        Err((_, err)) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("made a context of a terminated display current"),
+    }
+
+    // building a new context initializes the display again
+    let context = builder().build_surfaceless_with_device(&device).unwrap();
+    unsafe { context.make_current() }.unwrap();
+}
@@ -0,0 +1,91 @@
+// The process-wide EGL and GLX function tables are loaded on first use, which
+// these tests make happen on several threads at once, so they have their own
+// process.
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+
+use glutin::dpi::PhysicalSize;
+use glutin::event_loop::EventLoop;
+use glutin::platform::unix::{Device, EventLoopExtUnix, HeadlessContextExt};
+use glutin::ContextBuilder;
+
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+const THREADS: usize = 8;
+
+#[test]
+fn egl_contexts_are_created_concurrently_on_first_use() {
+    let barrier = Arc::new(Barrier::new(THREADS));
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                let device = match Device::enumerate() {
+                    Ok(ref devices) if !devices.is_empty() => devices[0],
+                    _ => return false,
+                };
+                for _ in 0..2 {
+                    let context = ContextBuilder::new()
+                        .with_hardware_acceleration(None)
+                        .with_vsync(true)
+                        .build_surfaceless_with_device(&device)
+                        .unwrap();
+                    let context = unsafe { context.make_current() }.unwrap();
+                    assert!(!context.get_proc_address("glClear").is_null());
+                }
+                true
+            })
+        })
+        .collect();
+
+    let ran = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect::<Vec<_>>();
+    if !ran.iter().all(|&ran| ran) {
+        assert!(ran.iter().all(|&ran| !ran));
+        eprintln!("skipping, no EGL device is available");
+    }
+}
+
+#[test]
+fn glx_contexts_are_created_concurrently_on_first_use() {
+    let barrier = Arc::new(Barrier::new(THREADS));
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                let el = EventLoop::<()>::new_x11_any_thread().ok();
+                barrier.wait();
+                let el = match el {
+                    Some(el) => el,
+                    None => return false,
+                };
+                for _ in 0..2 {
+                    let context = ContextBuilder::new()
+                        .build_headless(&el, PhysicalSize::new(8, 8))
+                        .unwrap();
+                    let context = unsafe { context.make_current() }.unwrap();
+                    assert!(!context.get_proc_address("glClear").is_null());
+                }
+                true
+            })
+        })
+        .collect();
+
+    let ran = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect::<Vec<_>>();
+    if !ran.iter().all(|&ran| ran) {
+        assert!(ran.iter().all(|&ran| !ran));
+        eprintln!("skipping, X11 is not available");
+    }
+}
@@ -0,0 +1,255 @@
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+
+use glutin::dpi::PhysicalSize;
+use glutin::platform::unix::{
+    osmesa_driver_info, HeadlessContextExt, OsMesaAttributes,
+    OsMesaContextExt, RawHandle,
+};
+use glutin::platform::ContextTraitExt;
+use glutin::{
+    Context, ContextBuilder, CreationError, GlProfile, GlRequest,
+    PossiblyCurrent,
+};
+
+use std::thread;
+
+const COLOR_BUFFER_BIT: u32 = 0x0000_4000;
+const GL_CONTEXT_PROFILE_MASK: u32 = 0x9126;
+const GL_CONTEXT_CORE_PROFILE_BIT: i32 = 0x0000_0001;
+
+// The few GL functions the tests render with.
+#[derive(Clone, Copy)]
+struct Gl {
+    clear_color: unsafe extern "system" fn(f32, f32, f32, f32),
+    clear: unsafe extern "system" fn(u32),
+}
+
+impl Gl {
+    fn load(context: &Context<PossiblyCurrent>) -> Gl {
+        unsafe {
+            Gl {
+                clear_color: std::mem::transmute(
+                    context.get_proc_address("glClearColor"),
+                ),
+                clear: std::mem::transmute(context.get_proc_address("glClear")),
+            }
+        }
+    }
+
+    // Clears the current context to the given red value.
+    fn clear(&self, red: u8) {
+        unsafe {
+            (self.clear_color)(red as f32 / 255.0, 0.0, 0.0, 1.0);
+            (self.clear)(COLOR_BUFFER_BIT);
+        }
+    }
+}
+
+// OsMesa is rarely installed, so the tests pass without it.
+fn osmesa_available() -> bool {
+    match osmesa_driver_info() {
+        Ok(_) => true,
+        Err(err) => {
+            eprintln!("skipping, OsMesa is not available: {}", err);
+            false
+        }
+    }
+}
+
+fn build(size: PhysicalSize<u32>) -> Context<PossiblyCurrent> {
+    let context = ContextBuilder::new().build_osmesa(size).unwrap();
+    unsafe { context.make_current() }.unwrap()
+}
+
+#[test]
+fn tiles_render_concurrently() {
+    if !osmesa_available() {
+        return;
+    }
+
+    let size = PhysicalSize::new(32, 32);
+    let threads: Vec<_> = (0..8u8)
+        .map(|tile| {
+            thread::spawn(move || {
+                let mut context = build(size);
+                let gl = Gl::load(&context);
+                let red = tile * 32;
+                let pixels =
+                    context.render_to_image(size, || gl.clear(red)).unwrap();
+                assert_eq!(pixels.len(), 32 * 32 * 4);
+                for pixel in pixels.chunks(4) {
+                    assert!((pixel[0] as i32 - red as i32).abs() <= 1);
+                    assert_eq!(pixel[3], 255);
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+fn render_to_image_restores_the_previous_context() {
+    if !osmesa_available() {
+        return;
+    }
+
+    let size = PhysicalSize::new(8, 8);
+    let previous = build(size);
+    let mut context = build(size);
+    let gl = Gl::load(&context);
+    let previous = unsafe { previous.make_current() }.unwrap();
+
+    context.render_to_image(size, || gl.clear(255)).unwrap();
+    assert!(previous.is_current());
+    assert!(!context.is_current());
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        context.render_to_image(size, || panic!("rendering failed"))
+    }));
+    assert!(panicked.is_err());
+    assert!(previous.is_current());
+}
+
+#[test]
+fn scoped_currency_restores_the_previous_context() {
+    if !osmesa_available() {
+        return;
+    }
+
+    let size = PhysicalSize::new(8, 8);
+    let previous = build(size);
+    let mut context = build(size);
+    let previous = unsafe { previous.make_current() }.unwrap();
+
+    {
+        let current = context.make_current_scoped().unwrap();
+        assert!(current.is_current());
+        assert!(!previous.is_current());
+    }
+    assert!(previous.is_current());
+}
+
+fn strict() -> OsMesaAttributes {
+    OsMesaAttributes {
+        reject_unsupported_flags: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn strict_mode_rejects_unsupported_flags() {
+    if !osmesa_available() {
+        return;
+    }
+
+    let size = PhysicalSize::new(8, 8);
+    let debug = ContextBuilder::new()
+        .with_gl_debug_flag(true)
+        .build_osmesa_with_attributes(size, strict());
+    match debug {
+        Err(CreationError::NotSupported(_)) => (),
+        other => panic!("debug flag not rejected: {:?}", other.map(|_| ())),
+    }
+
+    let forward_compatible = ContextBuilder::new()
+        .with_gl_debug_flag(false)
+        .with_gl_forward_compatible_flag(true)
+        .build_osmesa_with_attributes(size, strict());
+    match forward_compatible {
+        Err(CreationError::NotSupported(_)) => (),
+        other => panic!(
+            "forward compatible flag not rejected: {:?}",
+            other.map(|_| ())
+        ),
+    }
+}
+
+#[test]
+fn strict_mode_only_builds_core_profiles() {
+    if !osmesa_available() {
+        return;
+    }
+
+    let context = ContextBuilder::new()
+        .with_gl_debug_flag(false)
+        .with_gl(GlRequest::Specific(glutin::Api::OpenGl, (3, 3)))
+        .with_gl_profile(GlProfile::Core)
+        .build_osmesa_with_attributes(PhysicalSize::new(8, 8), strict());
+    let context = match context {
+        Ok(context) => unsafe { context.make_current() }.unwrap(),
+        // drivers without 3.3 core fail creation outright
+        Err(CreationError::NotSupported(_)) | Err(CreationError::OsError(_)) => {
+            return;
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+    };
+
+    let get_integerv: unsafe extern "system" fn(u32, *mut i32) =
+        unsafe { std::mem::transmute(context.get_proc_address("glGetIntegerv")) };
+    let mut mask = 0;
+    unsafe { get_integerv(GL_CONTEXT_PROFILE_MASK, &mut mask) };
+    assert_ne!(mask & GL_CONTEXT_CORE_PROFILE_BIT, 0);
+}
+
+#[test]
+fn lenient_mode_ignores_unsupported_flags() {
+    if !osmesa_available() {
+        return;
+    }
+
+    ContextBuilder::new()
+        .with_gl_debug_flag(true)
+        .with_gl_forward_compatible_flag(true)
+        .build_osmesa_with_attributes(
+            PhysicalSize::new(8, 8),
+            OsMesaAttributes::default(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn raw_handles_round_trip_without_a_double_free() {
+    if !osmesa_available() {
+        return;
+    }
+
+    let size = PhysicalSize::new(8, 8);
+    let mut owner = build(size);
+    let raw = match unsafe { owner.raw_handle() } {
+        RawHandle::Egl(raw) => raw,
+        RawHandle::Glx(_) => unreachable!(),
+    };
+
+    {
+        let wrapped = unsafe {
+            ContextBuilder::new().build_raw_osmesa_context(
+                raw as *mut _,
+                false,
+                size,
+                OsMesaAttributes::default(),
+            )
+        }
+        .unwrap();
+        let wrapped = unsafe { wrapped.make_current() }.unwrap();
+        assert!(wrapped.is_current());
+        assert!(!wrapped.get_proc_address("glClear").is_null());
+    }
+
+    // the wrapper left the context to its owner, which still renders
+    let owner_raw = match unsafe { owner.raw_handle() } {
+        RawHandle::Egl(raw) => raw,
+        RawHandle::Glx(_) => unreachable!(),
+    };
+    assert_eq!(owner_raw, raw);
+    let gl = Gl::load(&owner);
+    let pixels = owner.render_to_image(size, || gl.clear(255)).unwrap();
+    assert!(pixels.chunks(4).all(|pixel| pixel[0] == 255));
+}
@@ -0,0 +1,91 @@
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+
+use glutin::dpi::PhysicalSize;
+use glutin::event_loop::EventLoop;
+use glutin::platform::unix::EventLoopExtUnix;
+use glutin::window::WindowBuilder;
+use glutin::ContextBuilder;
+
+use std::os::raw;
+
+const COLOR_BUFFER_BIT: u32 = 0x0000_4000;
+const EGL_HEIGHT: i32 = 0x3056;
+const EGL_WIDTH: i32 = 0x3057;
+const EGL_DRAW: i32 = 0x3059;
+
+type GetCurrent = unsafe extern "C" fn() -> *const raw::c_void;
+type GetCurrentSurface = unsafe extern "C" fn(i32) -> *const raw::c_void;
+type QuerySurface = unsafe extern "C" fn(
+    *const raw::c_void,
+    *const raw::c_void,
+    i32,
+    *mut i32,
+) -> u32;
+
+// The tests run without a compositor on most CI machines, so they pass
+// without one. Headless compositors like `weston --backend=headless-backend.so`
+// can run them.
+fn event_loop() -> Option<EventLoop<()>> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        eprintln!("skipping, Wayland is not available");
+        return None;
+    }
+    Some(EventLoop::new_wayland_any_thread())
+}
+
+// Returns the size of the current EGL draw surface.
+fn current_surface_size(egl: &libloading::Library) -> (i32, i32) {
+    unsafe {
+        let get_display = egl.get::<GetCurrent>(b"eglGetCurrentDisplay\0");
+        let get_surface =
+            egl.get::<GetCurrentSurface>(b"eglGetCurrentSurface\0");
+        let query = egl.get::<QuerySurface>(b"eglQuerySurface\0").unwrap();
+        let display = get_display.unwrap()();
+        let surface = get_surface.unwrap()(EGL_DRAW);
+        let (mut width, mut height) = (0, 0);
+        assert_ne!(query(display, surface, EGL_WIDTH, &mut width), 0);
+        assert_ne!(query(display, surface, EGL_HEIGHT, &mut height), 0);
+        (width, height)
+    }
+}
+
+#[test]
+fn resizing_resizes_the_egl_window() {
+    let el = match event_loop() {
+        Some(el) => el,
+        None => return,
+    };
+
+    let wb = WindowBuilder::new()
+        .with_inner_size(PhysicalSize::new(32, 32))
+        .with_visible(false);
+    let context = ContextBuilder::new().build_windowed(wb, &el).unwrap();
+    let context = unsafe { context.make_current() }.unwrap();
+    let clear: unsafe extern "system" fn(u32) =
+        unsafe { std::mem::transmute(context.get_proc_address("glClear")) };
+    let finish: unsafe extern "system" fn() =
+        unsafe { std::mem::transmute(context.get_proc_address("glFinish")) };
+    let draw = || unsafe {
+        clear(COLOR_BUFFER_BIT);
+        finish();
+    };
+    let egl = libloading::Library::new("libEGL.so.1").unwrap();
+
+    // zero sizes keep the previous buffers
+    context.resize(PhysicalSize::new(0, 0));
+    draw();
+    assert_eq!(current_surface_size(&egl), (32, 32));
+    context.swap_buffers().unwrap();
+
+    // the new size is used from the next frame on
+    context.resize(PhysicalSize::new(64, 48));
+    draw();
+    assert_eq!(current_surface_size(&egl), (64, 48));
+    context.swap_buffers().unwrap();
+}
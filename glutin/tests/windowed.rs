@@ -0,0 +1,74 @@
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+
+use glutin::event_loop::EventLoop;
+use glutin::platform::unix::{EventLoopExtUnix, GlxContextExt};
+use glutin::window::WindowBuilder;
+use glutin::{ContextBuilder, ContextError, PossiblyCurrent, WindowedContext};
+
+// The tests run without a display on most CI machines, so they pass without
+// one.
+fn event_loop() -> Option<EventLoop<()>> {
+    match EventLoop::new_x11_any_thread() {
+        Ok(el) => Some(el),
+        Err(err) => {
+            eprintln!("skipping, X11 is not available: {:?}", err);
+            None
+        }
+    }
+}
+
+fn build(el: &EventLoop<()>) -> WindowedContext<PossiblyCurrent> {
+    let wb = WindowBuilder::new().with_visible(false);
+    let context = ContextBuilder::new().build_windowed(wb, el).unwrap();
+    unsafe { context.make_current() }.unwrap()
+}
+
+#[test]
+fn buffer_age_requires_a_current_context() {
+    let el = match event_loop() {
+        Some(el) => el,
+        None => return,
+    };
+
+    let first = build(&el);
+    first.buffer_age().unwrap();
+
+    let second = build(&el);
+    assert!(second.is_current());
+    assert!(!first.is_current());
+    assert!(first.buffer_age().is_err());
+    second.buffer_age().unwrap();
+}
+
+#[test]
+fn glx_sync_control_counters_advance_with_swaps() {
+    let el = match event_loop() {
+        Some(el) => el,
+        None => return,
+    };
+
+    let context = build(&el);
+    let before = match context.sync_values() {
+        Ok(values) => values,
+        Err(ContextError::FunctionUnavailable) => {
+            eprintln!("skipping, GLX_OML_sync_control is not supported");
+            return;
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+    };
+
+    // timing depends on the display, so only the counters are checked
+    let sbc = context.swap_buffers_msc(0, 0, 0).unwrap();
+    assert!(sbc > before.sbc);
+
+    // the target already passed, so this returns right away
+    let after = context.wait_for_msc(before.msc, 0, 0).unwrap();
+    assert!(after.msc >= before.msc);
+    assert!(after.ust >= before.ust);
+}
@@ -0,0 +1,38 @@
+// The EGL library is loaded once per process, and this test makes it fail to
+// load, so it has its own process.
+#![cfg(target_os = "windows")]
+
+use glutin::event_loop::EventLoop;
+use glutin::platform::windows::{EglDisplayBuilder, EventLoopExtWindows};
+use glutin::window::WindowBuilder;
+use glutin::{Api, Backend, ContextBuilder, CreationError, GlRequest};
+
+#[test]
+fn missing_egl_is_reported_with_the_other_backends() {
+    EglDisplayBuilder::new()
+        .with_library_paths(&["glutin-missing/libEGL.dll"])
+        .install()
+        .unwrap();
+    let el = EventLoop::<()>::new_any_thread();
+
+    // no driver supports this version, so WGL fails as well
+    let wb = WindowBuilder::new().with_visible(false);
+    let result = ContextBuilder::new()
+        .with_gl(GlRequest::Specific(Api::OpenGl, (99, 0)))
+        .with_windows_backends(&[Backend::Wgl, Backend::Egl])
+        .build_windowed(wb, &el);
+
+    match result {
+        Err(CreationError::CreationErrors(ref errors)) => {
+            assert_eq!(errors.len(), 2);
+            assert!(errors.iter().any(|err| match **err {
+                CreationError::NotSupported(ref msg) => {
+                    msg == "libEGL not found"
+                }
+                _ => false,
+            }));
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("built an OpenGL 99.0 context"),
+    }
+}
@@ -0,0 +1,155 @@
+#![cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+
+use glutin::platform::unix::x11::XConnection;
+use glutin::platform::unix::RawContextExt;
+use glutin::{Api, ContextBuilder, GlRequest};
+
+use std::os::raw;
+use std::sync::Arc;
+
+const TRUE_COLOR: raw::c_int = 4;
+const Z_PIXMAP: raw::c_int = 2;
+const ALL_PLANES: raw::c_ulong = !0;
+const COLOR_BUFFER_BIT: u32 = 0x0000_4000;
+
+// The tests run without a display on most CI machines, so they pass without
+// one.
+fn xconn() -> Option<Arc<XConnection>> {
+    match XConnection::new(None) {
+        Ok(xconn) => Some(Arc::new(xconn)),
+        Err(err) => {
+            eprintln!("skipping, X11 is not available: {:?}", err);
+            None
+        }
+    }
+}
+
+fn default_screen(xconn: &XConnection) -> raw::c_int {
+    unsafe { (xconn.xlib.XDefaultScreen)(xconn.display) }
+}
+
+fn has_argb_visual(xconn: &XConnection, screen: raw::c_int) -> bool {
+    let mut info = unsafe { std::mem::zeroed() };
+    let found = unsafe {
+        (xconn.xlib.XMatchVisualInfo)(
+            xconn.display,
+            screen,
+            32,
+            TRUE_COLOR,
+            &mut info,
+        )
+    } != 0;
+    if !found {
+        eprintln!("skipping, the screen has no 32-bit visual");
+    }
+    found
+}
+
+#[test]
+fn transparent_visuals_have_a_depth_of_32() {
+    let xconn = match xconn() {
+        Some(xconn) => xconn,
+        None => return,
+    };
+    let screen = default_screen(&xconn);
+    if !has_argb_visual(&xconn, screen) {
+        return;
+    }
+
+    // OpenGL uses GLX if available, OpenGL ES always uses EGL
+    let requests = [
+        GlRequest::Latest,
+        GlRequest::Specific(Api::OpenGlEs, (2, 0)),
+    ];
+    for &request in &requests {
+        let builder = ContextBuilder::new()
+            .with_gl(request)
+            .with_hardware_acceleration(None);
+        let visual = match builder.choose_x11_visual(&xconn, screen, true) {
+            Ok(visual) => visual,
+            Err(err) => {
+                eprintln!("skipping {:?}: {}", request, err);
+                continue;
+            }
+        };
+        assert_eq!(visual.depth, 32, "{:?}", request);
+    }
+}
+
+#[test]
+fn pixmaps_are_rendered_to_without_swapping() {
+    let xconn = match xconn() {
+        Some(xconn) => xconn,
+        None => return,
+    };
+    let screen = default_screen(&xconn);
+    let (width, height) = (16, 16);
+    let pixmap = unsafe {
+        (xconn.xlib.XCreatePixmap)(
+            xconn.display,
+            (xconn.xlib.XDefaultRootWindow)(xconn.display),
+            width,
+            height,
+            (xconn.xlib.XDefaultDepth)(xconn.display, screen) as raw::c_uint,
+        )
+    };
+
+    // OpenGL uses GLX if available, OpenGL ES always uses EGL
+    let requests = [
+        GlRequest::Latest,
+        GlRequest::Specific(Api::OpenGlEs, (2, 0)),
+    ];
+    for &request in &requests {
+        let builder = ContextBuilder::new()
+            .with_gl(request)
+            .with_hardware_acceleration(None);
+        let context = match unsafe {
+            builder.build_raw_x11_pixmap_context(xconn.clone(), pixmap)
+        } {
+            Ok(context) => context,
+            Err(err) => {
+                eprintln!("skipping {:?}: {}", request, err);
+                continue;
+            }
+        };
+        let context = unsafe { context.make_current() }.unwrap();
+        unsafe {
+            let clear_color: unsafe extern "system" fn(f32, f32, f32, f32) =
+                std::mem::transmute(context.get_proc_address("glClearColor"));
+            let clear: unsafe extern "system" fn(u32) =
+                std::mem::transmute(context.get_proc_address("glClear"));
+            clear_color(1.0, 1.0, 1.0, 1.0);
+            clear(COLOR_BUFFER_BIT);
+        }
+        // pixmaps are single-buffered, waiting is all it takes
+        context.wait_gl().unwrap();
+
+        let pixel = unsafe {
+            let image = (xconn.xlib.XGetImage)(
+                xconn.display,
+                pixmap,
+                0,
+                0,
+                width,
+                height,
+                ALL_PLANES,
+                Z_PIXMAP,
+            );
+            assert!(!image.is_null());
+            let pixel = (xconn.xlib.XGetPixel)(image, 8, 8);
+            (xconn.xlib.XDestroyImage)(image);
+            pixel
+        };
+        assert_eq!(pixel & 0xFF_FFFF, 0xFF_FFFF, "{:?}", request);
+    }
+
+    unsafe {
+        (xconn.xlib.XFreePixmap)(xconn.display, pixmap);
+    }
+}
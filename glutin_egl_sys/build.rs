@@ -28,6 +28,7 @@ fn main() {
                 "EGL_KHR_create_context",
                 "EGL_EXT_create_context_robustness",
                 "EGL_KHR_create_context_no_error",
+                "EGL_KHR_context_flush_control",
                 "EGL_KHR_platform_x11",
                 "EGL_KHR_platform_android",
                 "EGL_KHR_platform_wayland",
@@ -37,7 +38,36 @@ fn main() {
                 "EGL_MESA_platform_gbm",
                 "EGL_EXT_platform_wayland",
                 "EGL_EXT_platform_device",
+                "EGL_EXT_device_base",
+                "EGL_EXT_device_enumeration",
+                "EGL_EXT_device_query",
+                "EGL_EXT_device_drm",
+                "EGL_EXT_output_base",
+                "EGL_EXT_output_drm",
+                "EGL_KHR_stream",
+                "EGL_KHR_stream_producer_eglsurface",
+                "EGL_EXT_stream_consumer_egloutput",
                 "EGL_KHR_swap_buffers_with_damage",
+                "EGL_EXT_swap_buffers_with_damage",
+                "EGL_KHR_partial_update",
+                "EGL_IMG_context_priority",
+                "EGL_EXT_protected_content",
+                "EGL_EXT_pixel_format_float",
+                "EGL_KHR_gl_colorspace",
+                "EGL_EXT_gl_colorspace_scrgb_linear",
+                "EGL_EXT_gl_colorspace_display_p3",
+                "EGL_KHR_no_config_context",
+                "EGL_KHR_mutable_render_buffer",
+                "EGL_KHR_fence_sync",
+                "EGL_KHR_wait_sync",
+                "EGL_ANDROID_native_fence_sync",
+                "EGL_KHR_image_base",
+                "EGL_KHR_gl_texture_2D_image",
+                "EGL_KHR_gl_renderbuffer_image",
+                "EGL_MESA_image_dma_buf_export",
+                "EGL_EXT_image_dma_buf_import",
+                "EGL_EXT_image_dma_buf_import_modifiers",
+                "EGL_EXT_buffer_age",
             ],
         );
 
@@ -0,0 +1,161 @@
+#[cfg(target_os = "windows")]
+mod support;
+
+fn main() {
+    #[cfg(not(target_os = "windows"))]
+    unimplemented!();
+    #[cfg(target_os = "windows")]
+    this_example::main();
+}
+
+#[cfg(target_os = "windows")]
+mod this_example {
+    use super::support;
+    use glutin::event::{Event, WindowEvent};
+    use glutin::event_loop::{ControlFlow, EventLoop};
+    use glutin::platform::windows::{
+        ContextBuilderExtWindows, WindowBuilderExtWindows, WindowExtWindows,
+    };
+    use glutin::window::WindowBuilder;
+    use glutin::ContextBuilder;
+    use winapi::shared::minwindef::{
+        HIWORD, LOWORD, LPARAM, LRESULT, UINT, WPARAM,
+    };
+    use winapi::shared::windef::HWND;
+    use winapi::um::libloaderapi::GetModuleHandleW;
+    use winapi::um::winuser;
+
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+
+    fn wide(s: &str) -> Vec<u16> {
+        OsStr::new(s).encode_wide().chain(Some(0)).collect()
+    }
+
+    // Keeps the child window filling the client area of the parent.
+    unsafe extern "system" fn parent_proc(
+        hwnd: HWND,
+        msg: UINT,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if msg == winuser::WM_SIZE {
+            let child = winuser::GetWindow(hwnd, winuser::GW_CHILD);
+            if !child.is_null() {
+                let width = LOWORD(lparam as u32) as i32;
+                let height = HIWORD(lparam as u32) as i32;
+                winuser::MoveWindow(child, 0, 0, width, height, 1);
+            }
+            return 0;
+        }
+        winuser::DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    // A plain Win32 window, like the one of a WinForms or Qt host. It clips
+    // its children so that erasing its background doesn't paint over them.
+    unsafe fn create_parent() -> HWND {
+        let class_name = wide("GlutinChildWindowParent");
+        let instance = GetModuleHandleW(std::ptr::null());
+        let class = winuser::WNDCLASSEXW {
+            cbSize: std::mem::size_of::<winuser::WNDCLASSEXW>() as UINT,
+            style: winuser::CS_HREDRAW | winuser::CS_VREDRAW,
+            lpfnWndProc: Some(parent_proc),
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: instance,
+            hIcon: std::ptr::null_mut(),
+            hCursor: winuser::LoadCursorW(
+                std::ptr::null_mut(),
+                winuser::IDC_ARROW,
+            ),
+            hbrBackground: (winuser::COLOR_WINDOW + 1) as _,
+            lpszMenuName: std::ptr::null(),
+            lpszClassName: class_name.as_ptr(),
+            hIconSm: std::ptr::null_mut(),
+        };
+        winuser::RegisterClassExW(&class);
+
+        let parent = winuser::CreateWindowExW(
+            0,
+            class_name.as_ptr(),
+            wide("A plain Win32 parent").as_ptr(),
+            winuser::WS_OVERLAPPEDWINDOW
+                | winuser::WS_CLIPCHILDREN
+                | winuser::WS_VISIBLE,
+            winuser::CW_USEDEFAULT,
+            winuser::CW_USEDEFAULT,
+            800,
+            600,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            instance,
+            std::ptr::null_mut(),
+        );
+        assert!(!parent.is_null());
+        parent
+    }
+
+    pub fn main() {
+        // The event loop also dispatches the messages of the parent, since
+        // it belongs to the same thread.
+        let el = EventLoop::new();
+        let parent = unsafe { create_parent() };
+
+        // Child windows of winit have the WS_CHILD and WS_CLIPSIBLINGS styles.
+        let wb = WindowBuilder::new()
+            .with_decorations(false)
+            .with_parent_window(parent);
+        let windowed_context = ContextBuilder::new()
+            .with_wgl_child_window(true)
+            .build_windowed(wb, &el)
+            .unwrap();
+
+        let windowed_context =
+            unsafe { windowed_context.make_current().unwrap() };
+
+        println!(
+            "Pixel format of the window's GL context: {:?}",
+            windowed_context.get_pixel_format()
+        );
+
+        unsafe {
+            let mut rect = std::mem::zeroed();
+            winuser::GetClientRect(parent, &mut rect);
+            winuser::MoveWindow(
+                windowed_context.window().hwnd() as HWND,
+                0,
+                0,
+                rect.right,
+                rect.bottom,
+                1,
+            );
+        }
+
+        let gl = support::load(&windowed_context.context());
+
+        el.run(move |event, _, control_flow| {
+            println!("{:?}", event);
+            *control_flow = ControlFlow::Wait;
+
+            match event {
+                Event::LoopDestroyed => return,
+                Event::WindowEvent { event, .. } => match event {
+                    WindowEvent::Resized(physical_size) => {
+                        windowed_context.resize(physical_size);
+                        windowed_context.window().request_redraw();
+                    }
+                    // Closing the parent destroys the child as well.
+                    WindowEvent::Destroyed => {
+                        *control_flow = ControlFlow::Exit
+                    }
+                    _ => (),
+                },
+                Event::RedrawRequested(_) => {
+                    gl.draw_frame([1.0, 0.5, 0.7, 1.0]);
+                    windowed_context.swap_buffers().unwrap();
+                }
+                _ => (),
+            }
+        });
+    }
+}
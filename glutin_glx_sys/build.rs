@@ -31,14 +31,25 @@ fn main() {
                 "GLX_ARB_create_context",
                 "GLX_ARB_create_context_profile",
                 "GLX_ARB_create_context_robustness",
+                "GLX_ARB_robustness_application_isolation",
+                "GLX_ARB_robustness_share_group_isolation",
+                "GLX_NV_multisample_coverage",
+                "GLX_ARB_create_context_no_error",
                 "GLX_ARB_context_flush_control",
                 "GLX_ARB_fbconfig_float",
                 "GLX_ARB_framebuffer_sRGB",
                 "GLX_EXT_framebuffer_sRGB",
                 "GLX_ARB_multisample",
+                "GLX_OML_swap_method",
                 "GLX_EXT_swap_control",
+                "GLX_EXT_swap_control_tear",
                 "GLX_SGI_swap_control",
                 "GLX_MESA_swap_control",
+                "GLX_EXT_buffer_age",
+                "GLX_OML_sync_control",
+                "GLX_SGI_video_sync",
+                "GLX_MESA_query_renderer",
+                "GLX_NV_swap_group",
             ],
         )
         .write_bindings(gl_generator::StructGenerator, &mut file)
@@ -23,19 +23,32 @@ fn main() {
             Profile::Core,
             Fallbacks::All,
             [
+                "WGL_AMD_gpu_association",
                 "WGL_ARB_create_context",
                 "WGL_ARB_create_context_profile",
                 "WGL_ARB_create_context_robustness",
+                "WGL_ARB_robustness_application_isolation",
+                "WGL_ARB_robustness_share_group_isolation",
+                "WGL_NV_multisample_coverage",
+                "WGL_ARB_create_context_no_error",
                 "WGL_ARB_context_flush_control",
                 "WGL_ARB_extensions_string",
+                "WGL_ARB_make_current_read",
                 "WGL_ARB_framebuffer_sRGB",
                 "WGL_ARB_multisample",
+                "WGL_ARB_pbuffer",
                 "WGL_ARB_pixel_format",
                 "WGL_ARB_pixel_format_float",
+                "WGL_ARB_render_texture",
+                "WGL_NV_DX_interop",
+                "WGL_NV_DX_interop2",
+                "WGL_NV_gpu_affinity",
                 "WGL_EXT_create_context_es2_profile",
                 "WGL_EXT_extensions_string",
                 "WGL_EXT_framebuffer_sRGB",
                 "WGL_EXT_swap_control",
+                "WGL_EXT_swap_control_tear",
+                "WGL_NV_swap_group",
             ],
         )
         .write_bindings(gl_generator::StructGenerator, &mut file)